@@ -0,0 +1,90 @@
+// Universe generation benchmark
+// Run with `cargo bench --bench universe_generation`. Generates universes of a
+// few sizes and reports the mean time of each generation phase, then compares
+// serial and parallel system generation.
+
+use std::time::{Duration, Instant};
+
+use space_trader::models::universe::{GenerationStats, Universe, UniverseConfig};
+
+const WARMUP_RUNS: u32 = 2;
+const MEASURED_RUNS: u32 = 10;
+const SYSTEM_COUNTS: [usize; 4] = [20, 100, 500, 1000];
+const PARALLEL_COMPARISON_SYSTEMS: usize = 500;
+
+fn generate(system_count: usize, seed: u64) -> GenerationStats {
+    generate_with_threshold(system_count, seed, UniverseConfig::default().parallel_threshold)
+}
+
+fn generate_with_threshold(system_count: usize, seed: u64, parallel_threshold: usize) -> GenerationStats {
+    let config = UniverseConfig {
+        seed,
+        system_count,
+        parallel_threshold,
+        ..UniverseConfig::default()
+    };
+    Universe::with_config(config)
+        .expect("benchmark config is valid")
+        .generation_stats()
+        .clone()
+}
+
+fn mean(total: Duration) -> Duration {
+    total / MEASURED_RUNS
+}
+
+fn main() {
+    println!("{:>8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        "systems", "placement", "fields", "stations", "markets", "lanes", "total");
+
+    for &system_count in SYSTEM_COUNTS.iter() {
+        for run in 0..WARMUP_RUNS {
+            generate(system_count, run as u64);
+        }
+
+        let mut sum = GenerationStats::default();
+        let started = Instant::now();
+        for run in 0..MEASURED_RUNS {
+            let stats = generate(system_count, 1000 + run as u64);
+            sum.system_placement += stats.system_placement;
+            sum.resource_fields += stats.resource_fields;
+            sum.stations += stats.stations;
+            sum.market_items += stats.market_items;
+            sum.jump_lanes += stats.jump_lanes;
+            sum.total += stats.total;
+        }
+        let wall = started.elapsed();
+
+        println!("{:>8} {:>12?} {:>12?} {:>12?} {:>12?} {:>12?} {:>12?}",
+            system_count,
+            mean(sum.system_placement),
+            mean(sum.resource_fields),
+            mean(sum.stations),
+            mean(sum.market_items),
+            mean(sum.jump_lanes),
+            mean(sum.total));
+        println!("{:>8} {} runs in {:?}", "", MEASURED_RUNS, wall);
+    }
+
+    compare_serial_parallel();
+}
+
+// Wall time of the per-system phases, leaving out the jump lanes which are
+// built serially either way
+fn compare_serial_parallel() {
+    println!();
+    println!("serial vs parallel at {} systems", PARALLEL_COMPARISON_SYSTEMS);
+
+    for (label, threshold) in [("serial", usize::MAX), ("parallel", 0)] {
+        for run in 0..WARMUP_RUNS {
+            generate_with_threshold(PARALLEL_COMPARISON_SYSTEMS, run as u64, threshold);
+        }
+
+        let mut systems = Duration::ZERO;
+        for run in 0..MEASURED_RUNS {
+            let stats = generate_with_threshold(PARALLEL_COMPARISON_SYSTEMS, 1000 + run as u64, threshold);
+            systems += stats.total - stats.jump_lanes;
+        }
+        println!("{:>8} {:>12?}", label, mean(systems));
+    }
+}
@@ -11,7 +11,8 @@ use space_trader::debug::client_server::{ConnectionHealth, NetworkSimulator};
 use space_trader::debug::network::NetworkDiagnostics;
 use space_trader::debug::error_analysis;
 
-use space_trader::network::client::GameClient;
+use space_trader::network::connection::ClientLink;
+use space_trader::network::tls::ClientTls;
 use space_trader::network::protocol::Message;
 use space_trader::game::Game;
 
@@ -84,25 +85,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("Connecting to server at {}:{}...", server_host, port);
     
-    // Create a client and connect to the server
-    let mut client = GameClient::new(username.clone());
+    // The link keeps the connection up, reconnecting with backoff and
+    // holding actions made while the server is unreachable
+    let client_id = Uuid::new_v4();
+    let (mut link, status_rx) = ClientLink::new(
+        format!("{}:{}", server_host, port),
+        client_id,
+        username.clone(),
+        password,
+    );
+    
+    // Opt in to TLS by naming the certificate to trust: the server's own
+    // self-signed one, or the CA that issued it
+    if let Ok(cert_path) = env::var("SERVER_TLS_CERT") {
+        link.use_tls(ClientTls::new(&cert_path, &server_host)?);
+        println!("Using TLS, trusting {}", cert_path);
+    }
     
     // Try to connect to the server
-    match client.connect(&server_host, Some(port), password).await {
-        Ok(_) => {
+    match link.connect().await {
+        Ok((connection, welcome)) => {
             println!("Connected to server. Starting game in online mode...");
-            
-            // TODO: Set up game state synchronization with server
+            app.set_identity(client_id, username.clone());
+            app.set_connection_status(status_rx);
+            app.predict_actions();
+            let _ = tx_ui.send(welcome).await;
+            let link_task = tokio::spawn(link.run(connection, rx_network, tx_ui));
             
             // Start the TUI application
             if let Err(err) = app.run() {
                 eprintln!("Application error: {}", err);
             }
             
-            // Disconnect from server when done
-            if let Err(err) = client.disconnect().await {
-                eprintln!("Error disconnecting: {}", err);
-            }
+            // Dropping the app closes the outgoing channel, which has the
+            // link say goodbye to the server
+            drop(app);
+            let _ = link_task.await;
         }
         Err(err) => {
             println!("Failed to connect to server: {}", err);
@@ -2,7 +2,7 @@ use std::env;
 use std::io::{self, Write};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, Mutex};
 use tokio::signal;
 use tokio::time::interval;
 use dotenv::dotenv;
@@ -15,7 +15,9 @@ use space_trader::debug::error_analysis;
 use space_trader::game::Game;
 use space_trader::models::universe::Universe;
 use space_trader::network::server::GameServer;
-use space_trader::network::protocol::Message;
+use space_trader::network::protocol::{AdminCommand, GameConfig, Message};
+use space_trader::network::console::{self, ConsoleCommand, CONSOLE_HELP};
+use space_trader::network::tls::TlsConfig;
 
 // Initialize the debug system
 fn init_debug_system() {
@@ -49,24 +51,51 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let network_report = NetworkDiagnostics::network_environment_report();
     log_debug!("Network environment report:\n{}", network_report);
     
-    // Get server port from environment or use default
-    let port_str = env::var("SERVER_PORT").unwrap_or_else(|_| {
-        prompt("Enter server port (default: 7890): ")
-            .unwrap_or_else(|_| "7890".to_string())
-    });
+    // Settings come from the config file if one was given, otherwise from
+    // the environment or prompts
+    let config_path = GameConfig::path_from_env_or_args();
+    let mut config = match &config_path {
+        Some(path) => {
+            let config = GameConfig::load(path)?;
+            println!("Loaded server config from {}", path);
+            config
+        }
+        None => GameConfig::default(),
+    };
     
-    let port = port_str.parse::<u16>().unwrap_or(7890);
+    // Environment variables override the config file
+    if let Ok(port_str) = env::var("SERVER_PORT") {
+        config.port = port_str.parse::<u16>().unwrap_or(config.port);
+    } else if config_path.is_none() {
+        let default_port = config.port;
+        config.port = prompt(&format!("Enter server port (default: {}): ", default_port))
+            .ok()
+            .and_then(|s| s.parse::<u16>().ok())
+            .unwrap_or(default_port);
+    }
     
-    // Get server password from environment (optional)
-    let password = env::var("SERVER_PASSWORD").ok().or_else(|| {
-        prompt("Set server password (leave empty for no password): ").ok()
-            .filter(|s| !s.is_empty())
-    });
+    if let Ok(password) = env::var("SERVER_PASSWORD") {
+        config.password = Some(password).filter(|s| !s.is_empty());
+    } else if config_path.is_none() {
+        config.password = prompt("Set server password (leave empty for no password): ").ok()
+            .filter(|s| !s.is_empty());
+    }
+    
+    // Encrypt connections when given a certificate and key; without them
+    // the server speaks plain TCP, which is fine for local play
+    if let (Ok(cert_path), Ok(key_path)) = (env::var("SERVER_TLS_CERT"), env::var("SERVER_TLS_KEY")) {
+        config.tls = Some(TlsConfig { cert_path, key_path });
+    }
+    
+    config.validate()?;
+    let port = config.port;
+    let economy_tick = Duration::from_millis(config.economy_tick_ms);
     
     println!("Starting ASTR Space Trader Game Server on port {}", port);
     
     // Create game universe
     let mut game = Game::new();
+    game.universe = Universe::with_config(config.universe_config())?;
     
     // Skip character creation in server mode
     if game.current_screen == space_trader::game::GameScreen::CharacterCreation {
@@ -91,11 +120,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let game_state = Arc::new(Mutex::new(game));
     
     // Create and start the game server
-    let server = GameServer::new(password, game_state.clone()).await;
+    let server = Arc::new(GameServer::new(config, game_state.clone()).await);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
     
     // Spawn server task
+    let console_server = server.clone();
     let server_task = tokio::spawn(async move {
-        if let Err(e) = server.start(Some(port)).await {
+        if let Err(e) = server.start(Some(port), shutdown_rx).await {
             eprintln!("Server error: {}", e);
         }
     });
@@ -103,7 +134,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Spawn universe simulation task (runs the game simulation on the server)
     let game_state_clone = game_state.clone();
     let universe_task = tokio::spawn(async move {
-        let mut interval = interval(Duration::from_millis(100));
+        let mut interval = interval(economy_tick);
         
         loop {
             interval.tick().await;
@@ -123,7 +154,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
     
     // Spawn REPL for server commands
-    let game_state_clone = game_state.clone();
     let command_task = tokio::spawn(async move {
         println!("Server console ready. Type 'help' for commands.");
         
@@ -136,37 +166,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 continue;
             }
             
-            let input = input.trim().to_lowercase();
+            let command = match console::parse(&input) {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("{}", e);
+                    println!("Type 'help' for available commands");
+                    continue;
+                }
+            };
             
-            match input.as_str() {
-                "help" => {
+            match command {
+                ConsoleCommand::Help => {
                     println!("Available commands:");
-                    println!("  status    - Show server status and connected players");
-                    println!("  save      - Save game state");
-                    println!("  clients   - List connected clients");
-                    println!("  debug     - Show debug information and perform diagnostics");
-                    println!("  stop      - Stop the server");
-                    println!("  help      - Show this help");
+                    for (usage, description) in CONSOLE_HELP {
+                        println!("  {:<20} - {}", usage, description);
+                    }
                 },
-                "status" => {
-                    let game = game_state_clone.lock().await;
+                ConsoleCommand::Status => {
                     println!("Server status: Running");
-                    println!("Game time: {}", game.time_system.get_formatted_time());
-                    println!("Universe size: {} star systems", game.universe.get_systems_count());
-                    // Add more status info as needed
+                    for line in console_server.status().await.lines() {
+                        println!("{}", line);
+                    }
+                },
+                ConsoleCommand::Save => {
+                    match console_server.save_all().await {
+                        Ok(()) => println!("Game, economy and accounts saved."),
+                        Err(e) => println!("{}", e),
+                    }
+                },
+                ConsoleCommand::Clients => {
+                    let clients = console_server.client_summaries().await;
+                    if clients.is_empty() {
+                        println!("No clients connected.");
+                    }
+                    for client in clients {
+                        println!(
+                            "  {}  {:<20} {:<21} {:<16} idle {}s",
+                            client.id,
+                            client.username,
+                            client.addr,
+                            client.account.as_deref().unwrap_or("-"),
+                            client.idle.as_secs()
+                        );
+                    }
                 },
-                "save" => {
-                    let mut game = game_state_clone.lock().await;
-                    match game.save_state() {
-                        Ok(_) => println!("Game state saved successfully."),
-                        Err(e) => println!("Error saving game state: {}", e),
+                ConsoleCommand::Kick { client, reason } => {
+                    let result = match console_server.find_client(&client).await {
+                        Ok(target) => console_server.run_console_command(AdminCommand::KickClient { target, reason }).await,
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Ok(message) | Err(message) => println!("{}", message),
                     }
                 },
-                "clients" => {
-                    println!("Connected clients: [Feature not implemented]");
-                    // This would show connected clients in a real implementation
+                ConsoleCommand::Broadcast(message) => {
+                    match console_server.run_console_command(AdminCommand::Announce { message }).await {
+                        Ok(message) | Err(message) => println!("{}", message),
+                    }
                 },
-                "debug" => {
+                ConsoleCommand::Debug => {
                     println!("Debug menu:");
                     println!("1. System information");
                     println!("2. Network diagnostics");
@@ -193,7 +252,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             println!("{}", NetworkDiagnostics::network_environment_report());
                             
                             // Check local port availability
-                            let port = 7890; // Default port
                             let is_available = NetworkDiagnostics::check_port_available(port);
                             println!("Port {} is {}available", port, if is_available { "" } else { "not " });
                         },
@@ -236,17 +294,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
                 },
-                "stop" => {
+                ConsoleCommand::Stop => {
                     println!("Stopping server...");
                     break;
                 },
-                "" => {
-                    // Skip empty lines
-                },
-                _ => {
-                    println!("Unknown command: {}", input);
-                    println!("Type 'help' for available commands");
-                }
             }
         }
     });
@@ -271,14 +322,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("Shutting down server...");
     
-    // Cancel tasks
-    server_task.abort();
+    // Stop the simulation, then let the server notify clients and save
     universe_task.abort();
-    
-    // Save final game state
-    let mut game = game_state.lock().await;
-    if let Err(e) = game.save_state() {
-        eprintln!("Error saving final game state: {}", e);
+    let _ = shutdown_tx.send(());
+    if let Err(e) = server_task.await {
+        eprintln!("Server task failed during shutdown: {}", e);
     }
     
     println!("Server shutdown complete");
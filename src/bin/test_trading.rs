@@ -1,7 +1,9 @@
 use std::time::Duration;
 use std::thread;
+use space_trader::models::player::Player;
 use space_trader::models::universe::Universe;
 use space_trader::systems::trading::TradingSystem;
+use space_trader::systems::time::TimeSystem;
 
 fn main() {
     println!("Starting Automated Trading System Test");
@@ -10,6 +12,8 @@ fn main() {
     // Create universe and trading system
     let mut universe = Universe::new();
     let mut trading_system = TradingSystem::new();
+    let mut time_system = TimeSystem::new();
+    let mut player = Player::new("Tester");
     
     println!("\nRunning automated trading test for 60 seconds...");
     println!("(Market prices will update every 5 seconds)");
@@ -20,8 +24,11 @@ fn main() {
     for i in 1..=12 {
         println!("Test iteration {} (t+{}s)", i, i * 5);
         
+        // Advance the game clock enough to force a market tick
+        time_system.update(Duration::from_secs(10));
+        
         // Update the trading system to check for order execution
-        let executed_orders = trading_system.update(&mut universe, Duration::from_secs(10)); // Force update
+        let executed_orders = trading_system.update(&mut universe, &mut player, &time_system);
         
         // If no orders were executed this time, print a status message
         if executed_orders.is_empty() {
@@ -1,13 +1,13 @@
 // Error analysis module for Space Trader game
 // This module provides tools for tracking, analyzing, and fixing errors
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Mutex;
 use std::time::{Duration, SystemTime};
 use std::fmt;
 
 // Import our logging macros
-use crate::{log_info, log_debug, log_error};
+use crate::{log_info, log_debug, log_warn, log_error};
 
 // Define an error record structure
 #[derive(Debug, Clone)]
@@ -40,9 +40,83 @@ impl fmt::Display for ErrorRecord {
 // Maximum number of errors to store
 const MAX_ERRORS: usize = 1000;
 
+// Sliding window that error rates are measured over
+pub const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+// Errors per window allowed for (module, type) pairs without their own threshold
+pub const DEFAULT_MAX_PER_WINDOW: usize = 20;
+
+// An error type counts as runaway once it's recorded more than
+// `max_per_window` times within RATE_WINDOW
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorThreshold {
+    pub module: String,
+    pub error_type: String,
+    pub max_per_window: usize,
+}
+
+// Raised by check_thresholds for an error type over its threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdAlert {
+    pub module: String,
+    pub error_type: String,
+    pub count: usize,
+    pub max_per_window: usize,
+}
+
+impl fmt::Display for ThresholdAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{} occurred {} times in the last {}s (threshold {})",
+            self.module, self.error_type, self.count, RATE_WINDOW.as_secs(), self.max_per_window)
+    }
+}
+
+// Error rate bookkeeping: recent timestamps per (module, type), the
+// configured thresholds, and which pairs are currently alerting
+struct ErrorRates {
+    recent: HashMap<(String, String), VecDeque<SystemTime>>,
+    thresholds: Vec<ErrorThreshold>,
+    alerting: HashSet<(String, String)>,
+}
+
+impl ErrorRates {
+    fn new() -> Self {
+        ErrorRates {
+            recent: HashMap::new(),
+            // Repeated render failures mean the UI is stuck in a broken state
+            thresholds: vec![ErrorThreshold {
+                module: "ui".to_string(),
+                error_type: "render_error".to_string(),
+                max_per_window: 5,
+            }],
+            alerting: HashSet::new(),
+        }
+    }
+
+    fn threshold_for(&self, module: &str, error_type: &str) -> usize {
+        self.thresholds.iter()
+            .find(|t| t.module == module && t.error_type == error_type)
+            .map_or(DEFAULT_MAX_PER_WINDOW, |t| t.max_per_window)
+    }
+
+    // Forget timestamps that have left the window
+    fn prune(&mut self, now: SystemTime) {
+        for times in self.recent.values_mut() {
+            while let Some(oldest) = times.front() {
+                match now.duration_since(*oldest) {
+                    Ok(age) if age > RATE_WINDOW => { times.pop_front(); },
+                    _ => break,
+                }
+            }
+        }
+        self.recent.retain(|_, times| !times.is_empty());
+    }
+}
+
 // Global error queue
 lazy_static! {
     static ref ERROR_QUEUE: Mutex<VecDeque<ErrorRecord>> = Mutex::new(VecDeque::with_capacity(MAX_ERRORS));
+    static ref ERROR_RATES: Mutex<ErrorRates> = Mutex::new(ErrorRates::new());
 }
 
 // Record an error
@@ -62,6 +136,15 @@ pub fn record_error(
     
     log_error!("{}", error);
     
+    {
+        let mut rates = ERROR_RATES.lock().unwrap();
+        rates.prune(error.timestamp);
+        rates.recent
+            .entry((error.module.clone(), error.error_type.clone()))
+            .or_insert_with(VecDeque::new)
+            .push_back(error.timestamp);
+    }
+    
     let mut queue = ERROR_QUEUE.lock().unwrap();
     
     // Add to the queue, removing older entries if needed
@@ -116,6 +199,10 @@ pub fn get_recent_errors(duration: Duration) -> Vec<ErrorRecord> {
 pub fn clear_errors() {
     let mut queue = ERROR_QUEUE.lock().unwrap();
     queue.clear();
+    
+    let mut rates = ERROR_RATES.lock().unwrap();
+    rates.recent.clear();
+    rates.alerting.clear();
 }
 
 // Generate an error report
@@ -288,6 +375,63 @@ pub fn analyze_error_patterns() -> String {
     report
 }
 
+// Set how many errors of a type are allowed per RATE_WINDOW before
+// check_thresholds raises an alert
+#[allow(dead_code)]
+pub fn set_threshold(module: &str, error_type: &str, max_per_window: usize) {
+    let mut rates = ERROR_RATES.lock().unwrap();
+    rates.thresholds.retain(|t| !(t.module == module && t.error_type == error_type));
+    rates.thresholds.push(ErrorThreshold {
+        module: module.to_string(),
+        error_type: error_type.to_string(),
+        max_per_window,
+    });
+}
+
+// Number of errors of a type recorded within the last RATE_WINDOW
+#[allow(dead_code)]
+pub fn error_rate(module: &str, error_type: &str) -> usize {
+    let mut rates = ERROR_RATES.lock().unwrap();
+    rates.prune(SystemTime::now());
+    rates.recent
+        .get(&(module.to_string(), error_type.to_string()))
+        .map_or(0, |times| times.len())
+}
+
+// Find error types over their threshold and log a warning for each one that
+// has newly crossed it. Returns every type currently over, alerted or not.
+// Meant to be called periodically, e.g. from the main loop.
+pub fn check_thresholds() -> Vec<ThresholdAlert> {
+    let mut rates = ERROR_RATES.lock().unwrap();
+    rates.prune(SystemTime::now());
+    
+    let mut alerts: Vec<ThresholdAlert> = rates.recent.iter()
+        .filter_map(|((module, error_type), times)| {
+            let max_per_window = rates.threshold_for(module, error_type);
+            (times.len() > max_per_window).then(|| ThresholdAlert {
+                module: module.clone(),
+                error_type: error_type.clone(),
+                count: times.len(),
+                max_per_window,
+            })
+        })
+        .collect();
+    alerts.sort_by(|a, b| b.count.cmp(&a.count));
+    
+    // Warn once per crossing; a type that drops back under can alert again
+    let over: HashSet<(String, String)> = alerts.iter()
+        .map(|alert| (alert.module.clone(), alert.error_type.clone()))
+        .collect();
+    for alert in &alerts {
+        if !rates.alerting.contains(&(alert.module.clone(), alert.error_type.clone())) {
+            log_warn!("Error rate alert: {}", alert);
+        }
+    }
+    rates.alerting = over;
+    
+    alerts
+}
+
 // Convenience method to register a simple error
 pub fn register_simple_error(module: &str, message: &str) {
     record_error(module, "error", message, None);
@@ -296,4 +440,22 @@ pub fn register_simple_error(module: &str, message: &str) {
 // Helper function to get error count
 pub fn get_error_count() -> usize {
     ERROR_QUEUE.lock().unwrap().len()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_alerts_when_rate_exceeded() {
+        set_threshold("threshold_test", "flaky", 2);
+        let over = || check_thresholds().into_iter().any(|alert| alert.module == "threshold_test");
+
+        record_error("threshold_test", "flaky", "first", None);
+        record_error("threshold_test", "flaky", "second", None);
+        assert_eq!(error_rate("threshold_test", "flaky"), 2);
+        assert!(!over());
+
+        record_error("threshold_test", "flaky", "third", None);
+        assert!(over());
+    }
+}
@@ -0,0 +1,161 @@
+// Game-state integrity checks
+// Finds broken invariants left behind by save corruption or by server
+// handlers that change state without the usual checks
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::debug::{self, error_analysis, LogLevel};
+use crate::models::player::Player;
+use crate::models::universe::Universe;
+
+// Credit balances above this are assumed to come from an overflow or a bad edit
+pub const MAX_SANE_CREDITS: u32 = 1_000_000_000;
+
+// A single broken invariant
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityWarning {
+    FuelOverCapacity { current: u32, capacity: u32 },
+    HullOverMaximum { hull: u32, max_hull: u32 },
+    CreditsOutOfRange(u32),
+    EmptyInventoryStack(String),
+    InventoryOverCapacity { used: u32, capacity: u32 },
+    UnknownCurrentSystem(String),
+    OrderForUnknownSystem { order_id: String, system_id: String },
+    DuplicateCharacterId(String),
+}
+
+impl fmt::Display for IntegrityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityWarning::FuelOverCapacity { current, capacity } => {
+                write!(f, "Fuel {} exceeds tank capacity {}", current, capacity)
+            },
+            IntegrityWarning::HullOverMaximum { hull, max_hull } => {
+                write!(f, "Hull {} exceeds maximum {}", hull, max_hull)
+            },
+            IntegrityWarning::CreditsOutOfRange(credits) => {
+                write!(f, "Credits {} above sane limit {}", credits, MAX_SANE_CREDITS)
+            },
+            IntegrityWarning::EmptyInventoryStack(name) => {
+                write!(f, "Inventory holds an empty stack of {}", name)
+            },
+            IntegrityWarning::InventoryOverCapacity { used, capacity } => {
+                write!(f, "Cargo {} t exceeds hold capacity {} t", used, capacity)
+            },
+            IntegrityWarning::UnknownCurrentSystem(id) => {
+                write!(f, "Current system {} does not exist in the universe", id)
+            },
+            IntegrityWarning::OrderForUnknownSystem { order_id, system_id } => {
+                write!(f, "Order {} targets unknown system {}", order_id, system_id)
+            },
+            IntegrityWarning::DuplicateCharacterId(id) => {
+                write!(f, "Character id {} is used more than once", id)
+            },
+        }
+    }
+}
+
+// Invariants of a single player against the universe they play in
+pub fn validate_player(player: &Player, universe: &Universe) -> Vec<IntegrityWarning> {
+    let mut warnings = Vec::new();
+    let ship = &player.ship;
+
+    if ship.current_fuel > ship.fuel_capacity {
+        warnings.push(IntegrityWarning::FuelOverCapacity { current: ship.current_fuel, capacity: ship.fuel_capacity });
+    }
+    if ship.hull > ship.max_hull {
+        warnings.push(IntegrityWarning::HullOverMaximum { hull: ship.hull, max_hull: ship.max_hull });
+    }
+    if player.credits > MAX_SANE_CREDITS {
+        warnings.push(IntegrityWarning::CreditsOutOfRange(player.credits));
+    }
+
+    let mut empty_stacks: Vec<String> = player.inventory.items.iter()
+        .filter(|(_, quantity)| **quantity == 0)
+        .map(|(item, _)| item.name.clone())
+        .collect();
+    empty_stacks.sort();
+    warnings.extend(empty_stacks.into_iter().map(IntegrityWarning::EmptyInventoryStack));
+
+    let used = player.inventory.used_capacity();
+    if used > player.inventory.capacity {
+        warnings.push(IntegrityWarning::InventoryOverCapacity { used, capacity: player.inventory.capacity });
+    }
+
+    if universe.get_system(&player.current_system.id).is_none() {
+        warnings.push(IntegrityWarning::UnknownCurrentSystem(player.current_system.id.clone()));
+    }
+
+    warnings
+}
+
+// Every order in the universe's markets must point at a real system
+pub fn validate_orders(universe: &Universe) -> Vec<IntegrityWarning> {
+    let mut system_ids = universe.get_all_system_ids();
+    system_ids.sort();
+
+    system_ids.iter()
+        .filter_map(|id| universe.get_market(id))
+        .flat_map(|market| market.trade_orders.into_iter())
+        .filter(|order| universe.get_system(&order.system_id).is_none())
+        .map(|order| IntegrityWarning::OrderForUnknownSystem {
+            order_id: order.id.to_string(),
+            system_id: order.system_id,
+        })
+        .collect()
+}
+
+// No two characters may share an id
+pub fn validate_character_ids<'a>(players: impl IntoIterator<Item = &'a Player>) -> Vec<IntegrityWarning> {
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    let mut warnings = Vec::new();
+
+    for player in players {
+        if !seen.insert(player.id.as_str()) && reported.insert(player.id.as_str()) {
+            warnings.push(IntegrityWarning::DuplicateCharacterId(player.id.clone()));
+        }
+    }
+
+    warnings
+}
+
+// Log warnings found during `context` (e.g. "load") and record them for
+// error analysis
+pub fn report(context: &str, warnings: &[IntegrityWarning]) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    debug::log_internal(LogLevel::Warning, "integrity", &format!(
+        "{} integrity warning(s) after {}", warnings.len(), context
+    ));
+    for warning in warnings {
+        error_analysis::record_error("integrity", context, &warning.to_string(), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::{Item, ItemType};
+
+    #[test]
+    fn test_broken_player_state_is_reported() {
+        let universe = Universe::new();
+        let mut player = Player::new("Test");
+        assert!(validate_player(&player, &universe).is_empty());
+
+        player.ship.current_fuel = player.ship.fuel_capacity + 1;
+        player.inventory.items.insert(Item::new("Ghost Ore", 10, 1, ItemType::Product), 0);
+        player.current_system.id = "nowhere".to_string();
+
+        let warnings = validate_player(&player, &universe);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.contains(&IntegrityWarning::EmptyInventoryStack("Ghost Ore".to_string())));
+
+        let twin = player.clone();
+        assert_eq!(validate_character_ids([&player, &twin]).len(), 1);
+    }
+}
@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::time::Duration;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::backtrace::Backtrace;
-use std::fs::{OpenOptions, create_dir_all};
+use std::fs::{self, File, OpenOptions, create_dir_all};
 use std::io::Write;
 use std::path::Path;
 use std::fmt;
@@ -18,6 +18,8 @@ pub mod network;
 pub mod error_analysis;
 pub mod client_server;
 pub mod examples;
+pub mod integrity;
+pub mod replay;
 
 // Define log levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -41,18 +43,89 @@ impl fmt::Display for LogLevel {
     }
 }
 
+// Log files are rotated once they reach this size
+pub const DEFAULT_MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+
+// Rotated log files kept (game.log.1 is the newest)
+pub const DEFAULT_LOG_BACKUPS: usize = 3;
+
 // Global logger configuration
 struct Logger {
     file_path: Option<String>,
+    // Open log file and how many bytes it holds, kept between writes
+    file: Option<File>,
+    file_size: u64,
+    max_file_size: u64,
+    max_backups: usize,
     print_to_console: bool,
     global_level: LogLevel,
     module_levels: HashMap<String, LogLevel>,
 }
 
+impl Logger {
+    // (Re)open the configured log file for appending
+    fn open_file(&mut self) {
+        self.file = None;
+        self.file_size = 0;
+        
+        if let Some(path) = &self.file_path {
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => {
+                    self.file_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+                    self.file = Some(file);
+                },
+                Err(e) => eprintln!("Failed to open log file: {}", e),
+            }
+        }
+    }
+    
+    fn write_line(&mut self, line: &str) {
+        if self.file_size >= self.max_file_size {
+            if let Some(path) = self.file_path.clone() {
+                self.file = None;
+                if let Err(e) = rotate_log_files(&path, self.max_backups) {
+                    eprintln!("Failed to rotate log file: {}", e);
+                }
+                self.open_file();
+            }
+        }
+        
+        if let Some(file) = &mut self.file {
+            match writeln!(file, "{}", line) {
+                Ok(()) => self.file_size += line.len() as u64 + 1,
+                Err(e) => eprintln!("Failed to write to log file: {}", e),
+            }
+        }
+    }
+}
+
+// Shift `path.1` .. `path.{backups - 1}` up by one, dropping the oldest, and
+// move `path` to `path.1`. With no backups the log is simply discarded.
+fn rotate_log_files(path: &str, backups: usize) -> std::io::Result<()> {
+    if backups == 0 {
+        return fs::remove_file(path);
+    }
+    
+    let backup = |n: usize| format!("{}.{}", path, n);
+    if Path::new(&backup(backups)).exists() {
+        fs::remove_file(backup(backups))?;
+    }
+    for n in (1..backups).rev() {
+        if Path::new(&backup(n)).exists() {
+            fs::rename(backup(n), backup(n + 1))?;
+        }
+    }
+    fs::rename(path, backup(1))
+}
+
 // Create a global logger instance
 lazy_static! {
     static ref LOGGER: Mutex<Logger> = Mutex::new(Logger {
         file_path: None,
+        file: None,
+        file_size: 0,
+        max_file_size: DEFAULT_MAX_LOG_SIZE,
+        max_backups: DEFAULT_LOG_BACKUPS,
         print_to_console: true,
         global_level: LogLevel::Info,
         module_levels: HashMap::new(),
@@ -84,6 +157,7 @@ pub fn init(file_path: Option<&str>, print_to_console: bool, global_level: LogLe
             }
         }
     }
+    logger.open_file();
     
     // Set custom panic handler
     panic::set_hook(Box::new(|panic_info| {
@@ -118,6 +192,14 @@ pub fn init(file_path: Option<&str>, print_to_console: bool, global_level: LogLe
     }));
 }
 
+// Set the size at which the log file is rotated and how many old files to keep
+#[allow(dead_code)]
+pub fn set_log_rotation(max_file_size: u64, max_backups: usize) {
+    let mut logger = LOGGER.lock().unwrap();
+    logger.max_file_size = max_file_size;
+    logger.max_backups = max_backups;
+}
+
 // Set logging level for a specific module
 pub fn set_module_level(module: &str, level: LogLevel) {
     let mut logger = LOGGER.lock().unwrap();
@@ -144,7 +226,7 @@ pub fn get_log_level_from_env() -> LogLevel {
 
 // Internal logging function (made public for macro access)
 pub fn log_internal(level: LogLevel, module: &str, message: &str) {
-    let logger = LOGGER.lock().unwrap();
+    let mut logger = LOGGER.lock().unwrap();
     
     // Check if this log should be processed based on the module's level
     let module_level = logger.module_levels.get(module).unwrap_or(&logger.global_level);
@@ -168,23 +250,7 @@ pub fn log_internal(level: LogLevel, module: &str, message: &str) {
     }
     
     // Write to file if configured
-    if let Some(file_path) = &logger.file_path {
-        let file_result = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path);
-            
-        match file_result {
-            Ok(mut file) => {
-                if let Err(e) = writeln!(file, "{}", formatted_message) {
-                    eprintln!("Failed to write to log file: {}", e);
-                }
-            },
-            Err(e) => {
-                eprintln!("Failed to open log file: {}", e);
-            }
-        }
-    }
+    logger.write_line(&formatted_message);
 }
 
 // Record timing information
@@ -335,4 +401,28 @@ pub fn warning(msg: &str) {
 
 pub fn trace(msg: &str) {
     log_internal(LogLevel::Trace, "main", msg);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_newest_backups() {
+        let dir = std::env::temp_dir().join(format!("space_trader_log_test_{}", std::process::id()));
+        create_dir_all(&dir).unwrap();
+        let path = dir.join("game.log").to_string_lossy().into_owned();
+
+        for generation in 1..=3 {
+            fs::write(&path, format!("generation {}", generation)).unwrap();
+            rotate_log_files(&path, 2).unwrap();
+        }
+
+        assert!(!Path::new(&path).exists());
+        assert_eq!(fs::read_to_string(format!("{}.1", path)).unwrap(), "generation 3");
+        assert_eq!(fs::read_to_string(format!("{}.2", path)).unwrap(), "generation 2");
+        assert!(!Path::new(&format!("{}.3", path)).exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,28 +1,81 @@
 use std::error::Error;
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
-use crossterm::event::{KeyEvent, KeyCode};
+use crossterm::event::{KeyEvent, KeyCode, KeyModifiers, MouseEvent, MouseEventKind, MouseButton};
 use serde::{Serialize, Deserialize};
-use crate::utils::serde::{instant_serde, option_instant_serde};
+use crate::utils::serde::instant_serde;
 
 use crate::models::{
-    player::Player,
-    universe::Universe,
+    player::{Player, TransactionCategory},
+    crew::{self, CrewRole},
+    universe::StarSystem,
+    universe::{Universe, UniverseConfig, StartLocation},
     faction::{FactionType, Storyline},
-    market::OrderType,
+    skills::SkillCategory,
+    market::{Market, OrderKind, OrderType, TradeOrder, AlertDirection, PriceAlert},
+    item::{Item, ItemType, InventorySort},
+    ship::{Ship, ShipModule},
+    currency::FactionCurrency,
+    insurance::{InsurancePolicy, POLICY_TERM_DAYS},
 };
 use crate::systems::{
     navigation::NavigationSystem,
-    trading::TradingSystem,
+    trading::{TradingSystem, TradeEstimate, BulkSale, JUNK_VALUE_THRESHOLD},
+    economy::EconomySystem,
     mining::MiningSystem,
-    crafting::CraftingSystem,
+    crafting::{CraftingSystem, RESEARCH_COST, RESEARCH_HOURS},
     time::TimeSystem,
+    encounter::EncounterOutcome,
+    tutorial::{TutorialState, TutorialStep},
+    routine::{TradeRoutine, RoutineStep, RoutineRun, StepProgress},
+    scheduler::{Scheduler, ScheduledEvent},
 };
-use crate::utils::save_load::{save_game, load_game};
+use crate::utils::save_load::{self, save_game, load_game, save_exists, MetaSync, SaveMeta, SaveSummary, Summarize};
+use crate::ui::LayoutRects;
+use crate::debug::integrity::{self, IntegrityWarning};
+use crate::ui::widgets::system_map;
+use crate::ui::widgets::confirm_dialog::ConfirmDialog;
+use crate::network::protocol::PlayerHoldings;
+
+// Station service prices
+const REPAIR_PRICE_PER_HULL_POINT: u32 = 15;
+// Charged when a salvage crew has to recover a destroyed ship
+const RESCUE_FEE: u32 = 5000;
+// Oldest messages are dropped once the log holds this many
+const MESSAGE_LOG_CAPACITY: usize = 50;
+// How long the jump animation plays when a jump starts
+const TRAVEL_ANIMATION_DURATION: Duration = Duration::from_millis(1500);
+// Real time between animation frames
+const ANIMATION_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+// Selling a unit worth at least this much asks for confirmation first
+const HIGH_VALUE_SELL_THRESHOLD: u32 = 1000;
+// Longest stretch the player can wait at a station in one go
+const MAX_WAIT_HOURS: u64 = 72;
+// Game time advanced per step while waiting, one market tick
+const WAIT_STEP_SECONDS: u64 = 300;
+// Where the debug key writes market data for spreadsheets
+const MARKET_EXPORT_FILE: &str = "market_export.csv";
+// Game time between checks of the player's price alerts
+const PRICE_ALERT_CHECK_SECONDS: u64 = 60;
+// Game time before a finished crafting job that didn't fit in the hold is
+// offered again
+const CRAFTING_RETRY_SECONDS: u64 = 300;
+// Jumps' worth of fuel, to the nearest system, below which fuel counts as low
+const LOW_FUEL_JUMPS: u32 = 2;
+// Share of the hold that counts as full for the cargo warning
+const CARGO_FULL_FRACTION: f32 = 0.95;
+// Credits changed into scrip per use of the station exchange
+pub const EXCHANGE_LOT: u32 = 100;
+// Longest stretch of real time a replay simulates in one step, about a frame
+const REPLAY_STEP: Duration = Duration::from_millis(50);
+// How far below its peak price a trailing stop placed from the orders screen sells
+const TRAILING_STOP_PERCENT: f32 = 10.0;
 
 
 
 #[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
 pub enum GameScreen {
+    StartMenu, // Continue, new game or load, before any game is running
     MainMenu,
     CharacterCreation,
     Navigation,
@@ -34,10 +87,132 @@ pub enum GameScreen {
     Character, // New screen for character and skills information
     Orders,    // Trade order management screen
     StationServices, // Screen for station services including refueling
+    News,      // Market events and how they move prices
     Help,
     Quit,
 }
 
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MessageKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+    Milestone, // Level-ups and storyline progress; pinned until dismissed
+}
+
+fn encounter_message_kind(outcome: &EncounterOutcome) -> MessageKind {
+    match outcome {
+        EncounterOutcome::SafePassage => MessageKind::Info,
+        EncounterOutcome::Escaped => MessageKind::Info,
+        EncounterOutcome::CombatWon { .. } => MessageKind::Success,
+        EncounterOutcome::Robbed { .. } => MessageKind::Warning,
+    }
+}
+
+// What happened while the player waited at a station
+#[derive(Debug, Clone, Default)]
+pub struct WaitSummary {
+    pub hours: u64,
+    pub executed_orders: Vec<TradeOrder>,
+    pub crafted: Vec<String>,
+}
+
+impl WaitSummary {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.executed_orders.is_empty() {
+            parts.push(format!("{} order(s) executed", self.executed_orders.len()));
+        }
+        if !self.crafted.is_empty() {
+            parts.push(format!("crafted {}", self.crafted.join(", ")));
+        }
+        if parts.is_empty() {
+            parts.push("nothing completed".to_string());
+        }
+        format!("Waited {} hour(s): {}", self.hours, parts.join("; "))
+    }
+}
+
+// What an entry on the start menu does
+#[derive(Debug, Clone, PartialEq)]
+pub enum StartOption {
+    Continue,       // The default save
+    NewGame,
+    Load(String),   // A named save slot
+}
+
+// An entry on the start menu, with why it can't be chosen when it can't
+// and the save's metadata for a preview
+#[derive(Debug, Clone)]
+pub struct StartEntry {
+    pub option: StartOption,
+    pub unavailable: Option<String>,
+    pub meta: Option<SaveMeta>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StartMenu {
+    pub entries: Vec<StartEntry>,
+    pub selected: usize,
+    // Seed typed for a new game; blank picks one at random
+    pub seed_input: Option<String>,
+}
+
+impl StartMenu {
+    // Offer every save on disk, disabling any that are missing or don't load
+    fn scan() -> Self {
+        let continue_entry = match save_load::save_metadata() {
+            Some(meta) => StartEntry {
+                unavailable: Self::check_save(&meta, load_game),
+                option: StartOption::Continue,
+                meta: Some(meta),
+            },
+            None => StartEntry {
+                option: StartOption::Continue,
+                unavailable: Some("no save yet".to_string()),
+                meta: None,
+            },
+        };
+        
+        let mut entries = vec![
+            continue_entry,
+            StartEntry { option: StartOption::NewGame, unavailable: None, meta: None },
+        ];
+        entries.extend(save_load::list_save_metadata().into_iter().map(|meta| {
+            let slot = meta.slot.clone();
+            StartEntry {
+                unavailable: Self::check_save(&meta, || save_load::load_game_from(&slot)),
+                option: StartOption::Load(slot),
+                meta: Some(meta),
+            }
+        }));
+        
+        let selected = entries.iter().position(|entry| entry.unavailable.is_none()).unwrap_or(0);
+        StartMenu { entries, selected, seed_input: None }
+    }
+    
+    // Why a save can't be loaded, if it can't. Metadata that matches the
+    // body is trusted; otherwise the whole save is loaded to find out.
+    fn check_save(meta: &SaveMeta, load: impl FnOnce() -> Result<Game, Box<dyn Error>>) -> Option<String> {
+        if meta.sync == MetaSync::InSync {
+            return (meta.version > save_load::SAVE_VERSION)
+                .then(|| "saved by a newer version".to_string());
+        }
+        load().err().map(|_| "save is unreadable".to_string())
+    }
+}
+
+// Irreversible operations that wait on a ConfirmDialog
+#[derive(PartialEq, Clone, Debug)]
+pub enum PendingAction {
+    CancelSelectedOrder,
+    NewGame,
+    SellItem(usize),
+    BulkSell(BulkSale),
+    Jettison(Item, u32),
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Game {
     pub player: Player,
@@ -53,14 +228,109 @@ pub struct Game {
     pub last_update: Instant,
     pub game_over: bool,
     pub quit_confirmed: bool,
-    pub message: Option<String>,
-    #[serde(with = "option_instant_serde", skip_serializing_if = "Option::is_none")]
-    pub message_time: Option<Instant>,
+    // Recent messages, newest last. Not saved with the game.
+    #[serde(skip)]
+    pub message_log: VecDeque<(Instant, String, MessageKind)>,
+    // Milestone messages not yet dismissed, oldest first
+    #[serde(skip)]
+    pub milestones: VecDeque<String>,
+    // Skill levels when last checked, to spot level-ups
+    #[serde(skip)]
+    known_skill_levels: Vec<(SkillCategory, u8)>,
+    // Debug builds can re-check state integrity every tick; the last
+    // warnings found are kept so each new problem is reported once
+    #[serde(skip)]
+    check_integrity_each_tick: bool,
+    #[serde(skip)]
+    last_integrity_warnings: Vec<IntegrityWarning>,
+    // Open confirmation prompt; takes all input until answered
+    #[serde(skip)]
+    pub confirm_dialog: Option<ConfirmDialog>,
     // Animation related fields
     #[serde(default)]
     pub animation_frame: u64,
     #[serde(default)]
     pub show_animation_effects: bool,
+    // Real time since the last animation frame
+    #[serde(skip)]
+    animation_elapsed: Duration,
+    // Body or resource field under the system map's inspect cursor
+    #[serde(skip)]
+    pub inspected_body: usize,
+    // Jump animation in progress: when it started and where the ship is headed
+    #[serde(skip)]
+    pub travel_animation: Option<(Instant, String)>,
+    // Item shown in the examine popup, by name
+    #[serde(skip)]
+    pub item_detail: Option<String>,
+    // Highlighted row of the cargo manifest
+    #[serde(skip)]
+    pub inventory_selected: usize,
+    // How the cargo manifest is ordered and filtered, and whether the
+    // filter is being typed
+    #[serde(skip)]
+    pub inventory_sort: InventorySort,
+    #[serde(skip)]
+    pub inventory_filter: String,
+    #[serde(skip)]
+    pub inventory_filter_editing: bool,
+    // Estimate for the market row under the cursor: buying one unit, or
+    // selling the whole stack in sell mode
+    #[serde(skip)]
+    pub trade_estimate: Option<TradeEstimate>,
+    // Hours typed at the station's wait prompt, while it is open
+    #[serde(skip)]
+    pub wait_input: Option<String>,
+    // Units typed at the inventory's jettison prompt, while it is open
+    #[serde(skip)]
+    pub jettison_input: Option<String>,
+    // Price alert being set up from the market: direction and typed threshold
+    #[serde(skip)]
+    pub alert_input: Option<(AlertDirection, String)>,
+    // Repeat flag for the alert being set up
+    #[serde(skip)]
+    pub alert_repeat: bool,
+    // Timed events waiting on the game clock. Rebuilt from the crafting
+    // jobs on load rather than saved.
+    #[serde(skip)]
+    scheduler: Scheduler,
+    // Steps the player has taken, collected for a networked client to send
+    // to the server. None when playing offline.
+    #[serde(skip)]
+    reported_steps: Option<Vec<RoutineStep>>,
+    
+    // Whether the low fuel and full hold warnings have been given; each is
+    // given once and re-armed when the condition clears
+    #[serde(skip)]
+    low_fuel_warned: bool,
+    #[serde(skip)]
+    cargo_full_warned: bool,
+    // System and fuel level the low fuel check last ran for
+    #[serde(skip)]
+    fuel_checked_at: Option<(String, u32)>,
+    
+    // Save slot this game is written to; None for the default save
+    #[serde(skip)]
+    pub save_slot: Option<String>,
+    #[serde(skip)]
+    pub start_menu: StartMenu,
+    // Where a character created in this game starts
+    #[serde(skip)]
+    pub start_location: StartLocation,
+    
+    // Guided start; off for saves made before it existed
+    #[serde(default)]
+    pub tutorial: TutorialState,
+    
+    // Trade routine kept from the last recording
+    #[serde(default)]
+    pub routine: Option<TradeRoutine>,
+    // Routine being recorded from the player's actions
+    #[serde(skip)]
+    pub routine_recording: Option<TradeRoutine>,
+    // Routine being played back
+    #[serde(skip)]
+    pub routine_run: Option<RoutineRun>,
     
     // Character creation related fields
     #[serde(default)]
@@ -74,63 +344,394 @@ pub struct Game {
     
     // Character info screen related fields
     #[serde(default)]
-    pub character_info_tab: usize, // 0=skills, 1=reputation, 2=assets, 3=background
+    pub character_info_tab: usize, // 0=skills, 1=reputation, 2=assets, 3=background, 4=stats
     
     // Orders screen related fields
     #[serde(default)]
     pub orders_view_active: bool, // true=viewing active orders, false=viewing completed orders
+    
+    // Last game day crew wages were paid for
+    #[serde(default)]
+    pub last_crew_upkeep_day: u64,
+    
+    // Whether character creation has been finished. Saves from before this
+    // flag existed always had a character.
+    #[serde(default = "default_character_created")]
+    pub character_created: bool,
+    
+    // While paused the game clock, travel and markets stand still
+    #[serde(default)]
+    pub paused: bool,
+}
+
+fn default_character_created() -> bool {
+    true
+}
+
+impl Summarize for Game {
+    fn save_summary(&self) -> SaveSummary {
+        SaveSummary {
+            character_name: self.player.character.name.clone(),
+            credits: self.player.credits,
+            play_time_secs: self.time_system.play_time().as_secs(),
+        }
+    }
+}
+
+// Per-tick integrity checks are for debug builds, and only when asked for
+// with SPACE_TRADER_CHECK_INTEGRITY since they rebuild every market
+fn integrity_checks_enabled() -> bool {
+    cfg!(debug_assertions) && std::env::var("SPACE_TRADER_CHECK_INTEGRITY").is_ok()
 }
 
 impl Game {
+    // Used by the server and client; the standalone game opens on the start menu
+    #[allow(dead_code)]
     pub fn new() -> Self {
         // Try to load saved game, or create a new one
         match load_game() {
-            Ok(game) => game,
-            Err(_) => {
-                let player = Player::new("Commander");
-                let universe = Universe::new();
-                
-                Self {
-                    player,
-                    universe,
-                    current_screen: GameScreen::CharacterCreation, // Start with character creation
-                    previous_screen: GameScreen::MainMenu,
-                    navigation_system: NavigationSystem::new(),
-                    trading_system: TradingSystem::new(),
-                    mining_system: MiningSystem::new(),
-                    crafting_system: CraftingSystem::new(),
-                    time_system: TimeSystem::new(),
-                    last_update: Instant::now(),
-                    game_over: false,
-                    quit_confirmed: false,
-                    message: None,
-                    message_time: None,
-                    animation_frame: 0,
-                    show_animation_effects: true,
-                    // Initialize character creation fields
-                    character_name: String::new(),
-                    selected_faction: 0,
-                    selected_storyline: 0,
-                    creation_stage: 0,
-                    character_info_tab: 0,
-                    orders_view_active: true,
-                }
-            }
+            Ok(game) => Self::resume(game),
+            Err(_) => Self::fresh(Universe::new()),
         }
     }
+    
+    // Open on the start menu so the player chooses whether to continue,
+    // start over or load a slot
+    pub fn with_start_menu() -> Self {
+        let mut game = Self::fresh(Universe::new());
+        game.current_screen = GameScreen::StartMenu;
+        game.start_menu = StartMenu::scan();
+        game
+    }
+    
+    // A fresh game in a universe generated from `seed`, ignoring any save.
+    // The save on disk is left alone until the new character is created.
+    pub fn new_with_seed(seed: u64) -> Result<Self, String> {
+        let config = UniverseConfig { seed, ..UniverseConfig::default() };
+        Ok(Self::fresh(Universe::with_config(config)?))
+    }
+    
+    // A game at character creation in `universe`
+    fn fresh(universe: Universe) -> Self {
+        let player = Player::new("Commander");
+        let mut crafting_system = CraftingSystem::new();
+        crafting_system.setup_basic_blueprints();
+        
+        let mut game = Self {
+            player,
+            universe,
+            current_screen: GameScreen::CharacterCreation, // Start with character creation
+            previous_screen: GameScreen::MainMenu,
+            navigation_system: NavigationSystem::new(),
+            trading_system: TradingSystem::new(),
+            mining_system: MiningSystem::new(),
+            crafting_system,
+            time_system: TimeSystem::new(),
+            last_update: Instant::now(),
+            game_over: false,
+            quit_confirmed: false,
+            message_log: VecDeque::new(),
+            milestones: VecDeque::new(),
+            known_skill_levels: Vec::new(),
+            check_integrity_each_tick: integrity_checks_enabled(),
+            last_integrity_warnings: Vec::new(),
+            confirm_dialog: None,
+            animation_frame: 0,
+            show_animation_effects: true,
+            animation_elapsed: Duration::from_secs(0),
+            travel_animation: None,
+            inspected_body: 0,
+            item_detail: None,
+            inventory_selected: 0,
+            inventory_sort: InventorySort::default(),
+            inventory_filter: String::new(),
+            inventory_filter_editing: false,
+            trade_estimate: None,
+            wait_input: None,
+            jettison_input: None,
+            alert_input: None,
+            alert_repeat: false,
+            scheduler: Scheduler::new(),
+            reported_steps: None,
+            low_fuel_warned: false,
+            cargo_full_warned: false,
+            fuel_checked_at: None,
+            save_slot: None,
+            start_menu: StartMenu::default(),
+            start_location: StartLocation::default(),
+            tutorial: TutorialState::new(),
+            routine: None,
+            routine_recording: None,
+            routine_run: None,
+            // Initialize character creation fields
+            character_name: String::new(),
+            selected_faction: 0,
+            selected_storyline: 0,
+            creation_stage: 0,
+            character_info_tab: 0,
+            orders_view_active: true,
+            last_crew_upkeep_day: 1,
+            character_created: false,
+            paused: false,
+        };
+        game.rebuild_schedule();
+        game
+    }
+
+    // Check the invariants a consistent game state should hold. Nothing is
+    // repaired; problems are only described.
+    pub fn validate(&self) -> Vec<IntegrityWarning> {
+        let mut warnings = integrity::validate_player(&self.player, &self.universe);
+        warnings.extend(integrity::validate_orders(&self.universe));
+        warnings
+    }
 
+    // Put a loaded save back on a playable screen. The save may have been
+    // written from the quit prompt or from the creation screen.
+    fn resume(mut game: Game) -> Self {
+        game.relocate_if_stranded();
+        game.backfill_blueprints();
+        game.rebuild_schedule();
+        integrity::report("load", &game.validate());
+        game.check_integrity_each_tick = integrity_checks_enabled();
+        game.last_update = Instant::now();
+        game.quit_confirmed = false;
+        game.game_over = false;
+        
+        if !game.character_created {
+            game.current_screen = GameScreen::CharacterCreation;
+        } else if matches!(game.current_screen, GameScreen::StartMenu | GameScreen::CharacterCreation | GameScreen::Quit) {
+            game.current_screen = GameScreen::MainMenu;
+        }
+        game.previous_screen = GameScreen::MainMenu;
+        game
+    }
+    
+    // A save can outlive the universe it was made in. Refresh the player's
+    // copy of their system from the universe, or if the system is gone,
+    // move them to the nearest one that exists.
+    fn relocate_if_stranded(&mut self) {
+        if self.navigation_system.forget_unknown_systems(&self.universe) {
+            self.push_message(MessageKind::Warning, "Your plotted jump led to a system that no longer exists and was cancelled".to_string());
+        }
+        
+        let stale = &self.player.current_system;
+        if let Some(system) = self.universe.get_system(&stale.id) {
+            self.player.current_system = system.clone();
+            return;
+        }
+        let relocated = self.universe.nearest_system(stale.x, stale.y)
+            .unwrap_or_else(|| self.universe.origin_system())
+            .clone();
+        let message = format!("{} is no longer on the charts. Your ship was relocated to {}", stale.name, relocated.name);
+        self.push_message(MessageKind::Warning, message);
+        self.player.record_visit(&relocated.id);
+        self.player.is_docked = false;
+        self.player.current_system = relocated;
+    }
+    
+    // Schedule the recurring checks and the end of every crafting job
+    // already running
+    fn rebuild_schedule(&mut self) {
+        self.scheduler.clear();
+        let now = self.time_system.game_time_seconds();
+        self.scheduler.schedule(now + PRICE_ALERT_CHECK_SECONDS, ScheduledEvent::PriceAlertCheck);
+        for job in self.crafting_system.active_jobs.values() {
+            self.scheduler.schedule(job.end_time, ScheduledEvent::CraftingJobsDue);
+        }
+    }
+    
+    // Saves from before blueprint research have no blueprints to craft
+    // from, and characters who know none. Give them the basic set and
+    // their faction's starting blueprints.
+    fn backfill_blueprints(&mut self) {
+        if self.crafting_system.blueprints.is_empty() {
+            self.crafting_system.setup_basic_blueprints();
+        }
+        if self.character_created && self.player.known_blueprints.is_empty() {
+            self.player.known_blueprints = self.player.character.faction.starting_loadout().known_blueprints;
+        }
+    }
+    
     pub fn update(&mut self) -> Result<(), Box<dyn Error>> {
         let now = Instant::now();
         let delta_time = now.duration_since(self.last_update);
         self.last_update = now;
 
-        // Update game systems
+        self.advance(delta_time);
+        
+        // Update animation frame
+        if self.show_animation_effects {
+            // Advance the frame counter every 100ms of real time
+            self.animation_elapsed += delta_time;
+            while self.animation_elapsed >= ANIMATION_FRAME_INTERVAL {
+                self.animation_elapsed -= ANIMATION_FRAME_INTERVAL;
+                self.animation_frame += 1;
+            }
+        }
+        
+        if let Some((started, _)) = &self.travel_animation {
+            if now.duration_since(*started) >= TRAVEL_ANIMATION_DURATION {
+                self.travel_animation = None;
+            }
+        }
+
+        Ok(())
+    }
+    
+    // Everything a tick does apart from animation: the simulation, unless
+    // paused, and the checks that follow it
+    fn advance(&mut self, delta_time: Duration) {
+        if !self.paused {
+            self.update_simulation(delta_time);
+        }
+        
+        if self.character_created {
+            if !self.paused {
+                self.advance_routine();
+            }
+            self.check_ship_warnings();
+            self.advance_tutorial();
+            self.announce_achievements();
+        }
+        
+        if self.check_integrity_each_tick {
+            let warnings = self.validate();
+            if warnings != self.last_integrity_warnings {
+                integrity::report("tick", &warnings);
+                self.last_integrity_warnings = warnings;
+            }
+        }
+    }
+    
+    // Tick the game until its clock reaches `game_seconds`, in frame-sized
+    // steps of simulated real time. Replays use this in place of waiting.
+    pub fn run_until(&mut self, game_seconds: u64) {
+        while !self.paused && self.time_system.game_time_seconds() < game_seconds {
+            let remaining = game_seconds - self.time_system.game_time_seconds();
+            let step = self.time_system.real_time_for(remaining).clamp(Duration::from_millis(1), REPLAY_STEP);
+            self.advance(step);
+        }
+    }
+    
+    fn start_travel_animation(&mut self, destination_name: &str) {
+        if self.show_animation_effects {
+            self.travel_animation = Some((Instant::now(), destination_name.to_string()));
+        }
+    }
+    
+    // Fraction of the jump animation played and the destination name, while
+    // the animation is running
+    pub fn travel_animation_progress(&self) -> Option<(f32, &str)> {
+        self.travel_animation.as_ref().map(|(started, destination)| {
+            let progress = started.elapsed().as_secs_f32() / TRAVEL_ANIMATION_DURATION.as_secs_f32();
+            (progress.min(1.0), destination.as_str())
+        })
+    }
+    
+    pub fn toggle_animation_effects(&mut self) {
+        self.show_animation_effects = !self.show_animation_effects;
+        if !self.show_animation_effects {
+            self.travel_animation = None;
+        }
+        self.show_formatted_message(format!("Animation effects {}",
+            if self.show_animation_effects { "on" } else { "off" }));
+    }
+    
+    // Advance the clock and everything driven by it
+    fn update_simulation(&mut self, delta_time: Duration) {
         self.time_system.update(delta_time);
-        self.navigation_system.update(&mut self.player, &self.universe, &self.time_system, delta_time);
+        if let Some(outcome) = self.navigation_system.update(&mut self.player, &self.universe, &self.time_system, delta_time) {
+            // The next hop of a plotted course just started
+            if let Some(name) = self.navigation_system.get_destination().map(|system| system.name.clone()) {
+                self.start_travel_animation(&name);
+            }
+            if outcome != EncounterOutcome::SafePassage {
+                self.push_message(encounter_message_kind(&outcome), outcome.describe());
+            }
+        }
+        // Pirates may have left the ship a wreck
+        if self.player.ship.is_destroyed() {
+            self.rescue_destroyed_ship();
+        }
+        let (executed_orders, crafted) = self.run_timed_systems();
+        self.announce_executed_orders(&executed_orders);
+        if !crafted.is_empty() {
+            self.push_message(MessageKind::Success, format!("Crafting complete: {}", crafted.join(", ")));
+        }
+    }
+    
+    // Everything that catches up with the game clock once it has moved:
+    // upkeep, progress checks, markets and orders, then whatever scheduled
+    // events have come due. Returns the orders that executed and the
+    // crafting jobs delivered.
+    fn run_timed_systems(&mut self) -> (Vec<TradeOrder>, Vec<String>) {
+        self.pay_crew_upkeep();
+        for message in self.player.check_storyline_progress(&self.time_system.clock()) {
+            self.push_milestone(message);
+        }
+        self.announce_level_ups();
+        
+        let executed_orders = self.trading_system.update(&mut self.universe, &mut self.player, &self.time_system);
+        
+        let now = self.time_system.game_time_seconds();
+        let mut crafted = Vec::new();
+        let mut events = self.scheduler.drain_due(now);
+        // Several jobs ending together need only one delivery
+        events.sort();
+        events.dedup();
+        for event in events {
+            match event {
+                ScheduledEvent::CraftingJobsDue => crafted = self.deliver_crafting_jobs(now),
+                ScheduledEvent::PriceAlertCheck => {
+                    self.check_price_alerts();
+                    self.scheduler.schedule(now + PRICE_ALERT_CHECK_SECONDS, ScheduledEvent::PriceAlertCheck);
+                },
+            }
+        }
+        (executed_orders, crafted)
+    }
+    
+    // Hand over finished crafting jobs. A job that doesn't fit in the hold
+    // stays finished and is tried again a little later.
+    fn deliver_crafting_jobs(&mut self, now: u64) -> Vec<String> {
+        let delivered = self.crafting_system.deliver_completed_jobs(&mut self.player, now);
+        if self.crafting_system.has_finished_jobs(&self.player.id, now) {
+            self.scheduler.schedule(now + CRAFTING_RETRY_SECONDS, ScheduledEvent::CraftingJobsDue);
+        }
+        delivered
+    }
+    
+    // Tell the player about any alerts whose threshold prices have crossed
+    fn check_price_alerts(&mut self) {
+        if self.player.price_alerts.is_empty() {
+            return;
+        }
         
-        // Update trading system and check for executed orders
-        let executed_orders = self.trading_system.update(&mut self.universe, delta_time);
+        let mut system_ids: Vec<String> = self.player.price_alerts.iter()
+            .map(|alert| alert.system_id.clone())
+            .collect();
+        system_ids.sort();
+        system_ids.dedup();
         
+        for system_id in system_ids {
+            let market = match self.universe.get_market(&system_id) {
+                Some(market) => market,
+                None => continue,
+            };
+            let system_name = self.universe.get_system(&system_id)
+                .map(|system| system.name.clone())
+                .unwrap_or(system_id);
+            for (alert, price) in self.player.check_price_alerts(&market) {
+                self.push_message(MessageKind::Warning, format!(
+                    "Price alert: {} at {} is {} cr ({} {})",
+                    alert.item_name, system_name, price, alert.direction.label(), alert.threshold
+                ));
+            }
+        }
+    }
+    
+    fn announce_executed_orders(&mut self, executed_orders: &[TradeOrder]) {
         // Show notification if there are executed orders
         if !executed_orders.is_empty() {
             // Build a notification message for the player
@@ -140,14 +741,20 @@ impl Game {
                     message.push_str(", ");
                 }
                 
+                // The market can move past the trigger before the order
+                // fills, so report what it filled at next to the target
+                let price = match order.executed_price {
+                    Some(price) => format!("{} credits (target {})", price, order.target_price),
+                    None => format!("target {} credits", order.target_price),
+                };
                 match order.order_type {
                     OrderType::Buy => {
-                        message.push_str(&format!("Bought {} {} at {} credits", 
-                            order.quantity, order.item_name, order.target_price));
+                        message.push_str(&format!("Bought {} {} at {}", 
+                            order.quantity, order.item_name, price));
                     },
                     OrderType::Sell => {
-                        message.push_str(&format!("Sold {} {} at {} credits", 
-                            order.quantity, order.item_name, order.target_price));
+                        message.push_str(&format!("Sold {} {} at {}", 
+                            order.quantity, order.item_name, price));
                     }
                 }
                 
@@ -159,94 +766,1070 @@ impl Game {
             }
             
             // Display the notification
-            self.show_message(&message);
+            self.push_message(MessageKind::Success, message);
+        }
+    }
+    
+    // Let `hours` of game time pass while docked, in market-tick steps so
+    // prices, orders and crafting resolve as they would in real time
+    pub fn wait(&mut self, hours: u64) -> Result<WaitSummary, String> {
+        if !self.navigation_system.is_docked(&self.player) {
+            return Err("You must be docked at a station to wait".to_string());
+        }
+        if hours == 0 || hours > MAX_WAIT_HOURS {
+            return Err(format!("You can wait between 1 and {} hours", MAX_WAIT_HOURS));
         }
         
-        // Update animation frame
-        if self.show_animation_effects {
-            // Update animation frame counter approximately every 100ms
-            if delta_time.as_millis() > 100 {
-                self.animation_frame += 1;
+        let mut summary = WaitSummary { hours, ..WaitSummary::default() };
+        let mut remaining = hours * 3600;
+        while remaining > 0 {
+            let step = remaining.min(WAIT_STEP_SECONDS);
+            self.time_system.advance_game_seconds(step);
+            remaining -= step;
+            
+            let (executed_orders, crafted) = self.run_timed_systems();
+            summary.executed_orders.extend(executed_orders);
+            summary.crafted.extend(crafted);
+        }
+        Ok(summary)
+    }
+    
+    // Warn once when fuel runs low or the hold is nearly full, and again
+    // only after the condition has cleared and come back
+    fn check_ship_warnings(&mut self) {
+        let ship = &self.player.ship;
+        let fuel_key = (self.player.current_system.id.clone(), ship.current_fuel);
+        // Finding the nearest jump scans every system, so only redo it when
+        // fuel or location has changed
+        if self.fuel_checked_at.as_ref() != Some(&fuel_key) {
+            self.fuel_checked_at = Some(fuel_key);
+            let fuel = ship.current_fuel;
+            match self.navigation_system.cheapest_jump_fuel(&self.universe, &self.player) {
+                Some(jump) if fuel < jump * LOW_FUEL_JUMPS => {
+                    if !self.low_fuel_warned {
+                        self.low_fuel_warned = true;
+                        let message = if fuel < jump {
+                            format!("Fuel critical: {} left, the nearest jump needs {}. Refuel before you're stranded", fuel, jump)
+                        } else {
+                            format!("Fuel low: {} left, the nearest jump needs {}", fuel, jump)
+                        };
+                        self.push_message(MessageKind::Warning, message);
+                    }
+                },
+                _ => self.low_fuel_warned = false,
+            }
+        }
+        
+        let capacity = self.player.ship.cargo_capacity;
+        let used = self.player.inventory.used_capacity();
+        if capacity > 0 && used as f32 >= capacity as f32 * CARGO_FULL_FRACTION {
+            if !self.cargo_full_warned {
+                self.cargo_full_warned = true;
+                self.push_message(MessageKind::Warning, format!("Cargo hold nearly full: {}/{}", used, capacity));
+            }
+        } else {
+            self.cargo_full_warned = false;
+        }
+    }
+    
+    // Tick off tutorial steps the player has just done
+    // Start playing `routine` back. Its steps run as the game ticks, each
+    // jump waited out, until one fails or the routine is stopped.
+    pub fn run_routine(&mut self, routine: TradeRoutine) -> Result<(), String> {
+        if routine.is_empty() {
+            return Err("The routine has no steps".to_string());
+        }
+        if self.routine_recording.is_some() {
+            return Err("Finish recording before running a routine".to_string());
+        }
+        self.routine_run = Some(RoutineRun::new(routine));
+        self.advance_routine();
+        Ok(())
+    }
+    
+    pub fn stop_routine(&mut self) {
+        if let Some(run) = self.routine_run.take() {
+            self.show_formatted_message(run.report("stopped"));
+        }
+    }
+    
+    // Add a step to the routine being recorded, if there is one, and to the
+    // steps waiting to be reported to the server
+    fn record_routine_step(&mut self, step: RoutineStep) {
+        if let Some(reported) = self.reported_steps.as_mut() {
+            reported.push(step.clone());
+        }
+        if let Some(routine) = self.routine_recording.as_mut() {
+            routine.record(step);
+        }
+    }
+    
+    // Start collecting the player's steps for a server to confirm
+    pub fn report_actions(&mut self) {
+        self.reported_steps.get_or_insert_with(Vec::new);
+    }
+    
+    // Steps taken since the last call, oldest first
+    pub fn take_reported_actions(&mut self) -> Vec<RoutineStep> {
+        self.reported_steps.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+    
+    // Undo steps the server turned down: put the player back as they were
+    // and, if one of the steps was a jump, call it off
+    pub fn roll_back_player(&mut self, player: Player, abandon_jump: bool, reason: String) {
+        self.player = player;
+        if abandon_jump {
+            self.navigation_system.abort_travel();
+            self.travel_animation = None;
+        }
+        self.push_message(MessageKind::Warning, reason);
+    }
+    
+    // Take the server's word for the ship's state where the local copy
+    // drifted from it
+    pub fn adopt_server_ship(&mut self, ship: Ship) {
+        self.player.ship = ship;
+        self.show_message("Ship state corrected to match the server");
+    }
+    
+    // Likewise for balances and cargo
+    pub fn adopt_server_holdings(&mut self, holdings: PlayerHoldings) {
+        self.player.credits = holdings.credits;
+        self.player.currencies = holdings.currencies.into_iter().collect();
+        self.player.inventory = holdings.cargo;
+        self.show_message("Credits and cargo corrected to match the server");
+    }
+    
+    // Run every step of the current routine that can finish now. At most
+    // one lap per tick, so a repeating routine with no jumps can't spin.
+    fn advance_routine(&mut self) {
+        let mut budget = match &self.routine_run {
+            Some(run) => run.routine.steps.len(),
+            None => return,
+        };
+        while budget > 0 {
+            let step = match self.routine_run.as_ref().and_then(|run| run.current()) {
+                Some(step) => step.clone(),
+                None => return,
+            };
+            match self.run_routine_step(&step) {
+                StepProgress::Waiting => return,
+                StepProgress::Failed(reason) => {
+                    if let Some(run) = self.routine_run.take() {
+                        self.push_message(MessageKind::Error, format!(
+                            "{}: {} failed: {}", run.report("stopped"), step.describe(), reason
+                        ));
+                    }
+                    return;
+                },
+                StepProgress::Done => {
+                    let finished = self.routine_run.as_mut().map_or(true, |run| !run.advance());
+                    if finished {
+                        if let Some(run) = self.routine_run.take() {
+                            self.push_message(MessageKind::Success, run.report("finished"));
+                        }
+                        return;
+                    }
+                },
+            }
+            budget -= 1;
+        }
+    }
+    
+    // Try one routine step with the same systems the player's keys use
+    fn run_routine_step(&mut self, step: &RoutineStep) -> StepProgress {
+        if self.navigation_system.is_traveling() {
+            return StepProgress::Waiting;
+        }
+        let docked = self.navigation_system.is_docked(&self.player);
+        match step {
+            RoutineStep::Travel(system_id) if *system_id == self.player.current_system.id => {
+                if !docked {
+                    if !self.navigation_system.can_dock(&self.player) {
+                        return StepProgress::Failed(format!("No station to dock at in {}", self.player.current_system.name));
+                    }
+                    self.navigation_system.dock(&mut self.player);
+                }
+                StepProgress::Done
+            },
+            RoutineStep::Travel(system_id) => {
+                if docked {
+                    self.record_market_snapshot();
+                    self.navigation_system.undock(&mut self.player);
+                }
+                match self.navigation_system.set_course(&mut self.player, &self.universe, system_id, &self.time_system.clock()) {
+                    Ok((_, outcome)) => {
+                        let name = self.universe.get_system(system_id)
+                            .map(|system| system.name.clone())
+                            .unwrap_or_else(|| system_id.clone());
+                        self.start_travel_animation(&name);
+                        if outcome != EncounterOutcome::SafePassage {
+                            self.push_message(encounter_message_kind(&outcome), format!("En route to {}: {}", name, outcome.describe()));
+                        }
+                        StepProgress::Waiting
+                    },
+                    Err(e) => StepProgress::Failed(e),
+                }
+            },
+            _ if !docked && !matches!(step, RoutineStep::Mine { .. }) => {
+                StepProgress::Failed("Not docked at a station".to_string())
+            },
+            RoutineStep::Buy { item, quantity } => {
+                let items = self.universe.get_market_items_for_system(self.player.current_system.id.clone());
+                let index = match items.iter().position(|(market_item, _)| market_item.name == *item) {
+                    Some(index) => index,
+                    None => return StepProgress::Failed(format!("{} is not sold here", item)),
+                };
+                for _ in 0..*quantity {
+                    let held = self.player.inventory.get_item_quantity(item);
+                    let result = self.trading_system.buy_item(&mut self.player, &self.universe, index, &self.time_system.clock());
+                    if self.player.inventory.get_item_quantity(item) == held {
+                        return StepProgress::Failed(result.unwrap_or_else(|| format!("Could not buy {}", item)));
+                    }
+                }
+                StepProgress::Done
+            },
+            RoutineStep::Sell { item, quantity } => {
+                for sold in 0..*quantity {
+                    let index = match self.player.inventory.items.keys().position(|held| held.name == *item) {
+                        Some(index) => index,
+                        None if sold == 0 => return StepProgress::Failed(format!("No {} to sell", item)),
+                        None => break,
+                    };
+                    self.trading_system.sell_item(&mut self.player, &self.universe, index, &self.time_system.clock());
+                }
+                StepProgress::Done
+            },
+            RoutineStep::Mine { resource, attempts } => {
+                let fields = self.mining_system.get_resources_for_system(&self.player.current_system.id);
+                if fields.first().map_or(true, |field| *resource >= field.resources.len()) {
+                    return StepProgress::Failed("Nothing to mine here".to_string());
+                }
+                for _ in 0..*attempts {
+                    if self.player.inventory.remaining_capacity() == 0 {
+                        break;
+                    }
+                    self.mining_system.mine_resource(&mut self.player, *resource);
+                    if self.player.ship.is_destroyed() {
+                        self.rescue_destroyed_ship();
+                        return StepProgress::Failed("Ship destroyed while mining".to_string());
+                    }
+                }
+                StepProgress::Done
+            },
+            RoutineStep::Refuel => {
+                self.refuel_ship();
+                if self.player.ship.current_fuel < self.player.ship.fuel_capacity {
+                    return StepProgress::Failed("Not enough credits to refuel".to_string());
+                }
+                StepProgress::Done
+            },
+        }
+    }
+    
+    // Start recording a routine, or keep the one being recorded
+    fn toggle_routine_recording(&mut self) {
+        match self.routine_recording.take() {
+            Some(routine) if routine.steps.len() > 1 => {
+                self.show_formatted_message(format!("Routine saved with {} steps. Press P to run it", routine.steps.len()));
+                self.routine = Some(routine);
+            },
+            Some(_) => self.show_message("Nothing recorded; routine discarded"),
+            None if self.routine_run.is_some() => self.show_message("Stop the running routine first"),
+            None => {
+                // Routines start and, when repeated, end where they were recorded
+                let mut routine = TradeRoutine { repeat: true, ..TradeRoutine::default() };
+                routine.record(RoutineStep::Travel(self.player.current_system.id.clone()));
+                self.routine_recording = Some(routine);
+                self.show_message("Recording routine: jump, trade, mine and refuel as usual, then press R to save");
+            },
+        }
+    }
+    
+    fn toggle_routine_run(&mut self) {
+        if self.routine_run.is_some() {
+            self.stop_routine();
+            return;
+        }
+        match self.routine.clone() {
+            Some(routine) => match self.run_routine(routine) {
+                Ok(()) => self.show_message("Running routine. Press P to stop"),
+                Err(e) => self.push_message(MessageKind::Error, e),
+            },
+            None => self.show_message("No routine recorded yet. Press R to record one"),
+        }
+    }
+    
+    fn announce_achievements(&mut self) {
+        for achievement in self.player.stats.check_achievements() {
+            self.push_milestone(format!("Achievement unlocked: {}", achievement.describe()));
+        }
+    }
+    
+    fn advance_tutorial(&mut self) {
+        for step in self.tutorial.observe(&self.player) {
+            self.push_message(MessageKind::Success, format!("Tutorial: {}", step.completed_message()));
+            if self.tutorial.step == TutorialStep::Done {
+                self.push_message(MessageKind::Success, format!("Tutorial: {}", TutorialStep::Done.completed_message()));
+            }
+        }
+    }
+    
+    // What the tutorial asks of the player next, worded for the screen
+    // they're on. None once the tutorial is finished or skipped.
+    pub fn tutorial_hint(&self) -> Option<String> {
+        if !self.tutorial.is_active() || !self.character_created {
+            return None;
+        }
+        let screen = &self.current_screen;
+        let docked = self.navigation_system.is_docked(&self.player);
+        let hint = match self.tutorial.step {
+            TutorialStep::Travel => match screen {
+                GameScreen::MainMenu => "Press N to open navigation",
+                GameScreen::Navigation => "Press the number of a nearby system to jump there",
+                _ => "Press M for the main menu, then N to navigate",
+            },
+            TutorialStep::Dock => match screen {
+                GameScreen::Navigation if self.navigation_system.can_dock(&self.player) => "Press D to dock at the station here",
+                GameScreen::Navigation => "There's no station here - jump to a system that has one",
+                GameScreen::MainMenu => "Press N to open navigation, then D to dock",
+                _ => "Press M for the main menu, then N to navigate and D to dock",
+            },
+            TutorialStep::Buy => match screen {
+                _ if !docked => "Dock at a station to trade: N to navigate, then D",
+                GameScreen::Market if self.trading_system.is_buy_mode() => "Press an item's number to buy one unit",
+                GameScreen::Market => "Press B to switch the market to buying",
+                GameScreen::MainMenu => "Press M to open the market",
+                _ => "Press M for the main menu, then M to open the market",
+            },
+            TutorialStep::Mine => match screen {
+                GameScreen::Mining => "Press 1-3 to mine one of the fields listed",
+                GameScreen::MainMenu => "Press R to open mining",
+                _ => "Press M for the main menu, then R to mine",
+            },
+            TutorialStep::Done => return None,
+        };
+        Some(hint.to_string())
+    }
+    
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused {
+            self.push_message(MessageKind::Warning, "Game paused - press Ctrl+P to resume".to_string());
+        } else {
+            self.show_message("Game resumed");
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) {
+        if self.confirm_dialog.is_some() {
+            self.handle_confirm_input(key);
+            return;
+        }
+        if self.item_detail.is_some() {
+            // Any of these close the examine popup; everything else is ignored
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('m')) {
+                self.item_detail = None;
+            }
+            return;
+        }
+        
+        if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.toggle_pause();
+            return;
+        }
+        if key.code == KeyCode::Char('e') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.toggle_animation_effects();
+            return;
+        }
+        if key.code == KeyCode::Char('x') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.milestones.pop_front();
+            return;
+        }
+        if key.code == KeyCode::Char('t') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            if self.tutorial.is_active() {
+                self.tutorial.skip();
+                self.show_message("Tutorial skipped");
             }
+            return;
+        }
+        
+        match self.current_screen {
+            GameScreen::StartMenu => self.handle_start_menu_input(key),
+            GameScreen::MainMenu => self.handle_main_menu_input(key),
+            GameScreen::CharacterCreation => self.handle_character_creation_input(key),
+            GameScreen::Navigation => self.handle_navigation_input(key),
+            GameScreen::Market => self.handle_market_input(key),
+            GameScreen::Ship => self.handle_ship_input(key),
+            GameScreen::Mining => self.handle_mining_input(key),
+            GameScreen::Crafting => self.handle_crafting_input(key),
+            GameScreen::Inventory => self.handle_inventory_input(key),
+            GameScreen::Character => self.handle_character_input(key),
+            GameScreen::Orders => self.handle_orders_input(key),
+            GameScreen::StationServices => self.handle_station_services_input(key),
+            GameScreen::News => self.handle_news_input(key),
+            GameScreen::Help => self.handle_help_input(key),
+            GameScreen::Quit => self.handle_quit_input(key),
+        }
+    }
+    
+    // Clicks act like the key shown on the clicked item; the scroll wheel
+    // moves list selections like the arrow keys
+    pub fn handle_mouse(&mut self, event: MouseEvent, layout_rects: &LayoutRects) {
+        let key = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => layout_rects.key_at(event.column, event.row),
+            MouseEventKind::ScrollUp => Some(KeyCode::Up),
+            MouseEventKind::ScrollDown => Some(KeyCode::Down),
+            _ => None,
+        };
+        
+        if let Some(code) = key {
+            self.handle_input(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+    
+    // Ask the player to confirm `action` before it runs
+    fn request_confirmation(&mut self, message: String, action: PendingAction) {
+        self.confirm_dialog = Some(ConfirmDialog::new(message, action));
+    }
+    
+    fn handle_confirm_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if let Some(dialog) = self.confirm_dialog.take() {
+                    self.execute_pending_action(dialog.action);
+                }
+            },
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.confirm_dialog = None;
+                self.show_message("Cancelled");
+            },
+            _ => {}
+        }
+    }
+    
+    fn execute_pending_action(&mut self, action: PendingAction) {
+        match action {
+            PendingAction::CancelSelectedOrder => {
+                match self.trading_system.cancel_selected_order(&mut self.player) {
+                    Ok(_) => self.show_message("Order cancelled successfully"),
+                    Err(e) => self.push_message(MessageKind::Error, format!("Error cancelling order: {}", e)),
+                }
+            },
+            PendingAction::NewGame => {
+                let seed = rand::random();
+                match Self::new_with_seed(seed) {
+                    Ok(game) => {
+                        let start_location = std::mem::take(&mut self.start_location);
+                        *self = game;
+                        self.start_location = start_location;
+                        self.show_formatted_message(format!("New universe generated from seed {}", seed));
+                    },
+                    Err(e) => self.push_message(MessageKind::Error, e),
+                }
+                return;
+            },
+            PendingAction::SellItem(item_idx) => {
+                let sold = self.player.inventory.items.iter().nth(item_idx)
+                    .map(|(item, held)| (item.name.clone(), *held));
+                if let Some(result) = self.trading_system.sell_item(&mut self.player, &self.universe, item_idx, &self.time_system.clock()) {
+                    self.show_formatted_message(result);
+                }
+                if let Some((name, _)) = sold.filter(|(name, held)| self.player.inventory.get_item_quantity(name) < *held) {
+                    self.record_routine_step(RoutineStep::Sell { item: name, quantity: 1 });
+                }
+            },
+            PendingAction::BulkSell(sale) => {
+                let clock = self.time_system.clock();
+                match self.trading_system.bulk_sell(&mut self.player, &mut self.universe, &sale, &clock) {
+                    Ok(summary) => {
+                        for (item, quantity, _) in &summary.lines {
+                            self.record_routine_step(RoutineStep::Sell { item: item.name.clone(), quantity: *quantity });
+                        }
+                        self.show_formatted_message(format!(
+                            "Sold {} units in {} stacks for {} cr",
+                            summary.total_quantity(), summary.lines.len(), summary.total_revenue()
+                        ));
+                    },
+                    Err(e) => self.push_message(MessageKind::Error, e),
+                }
+            },
+            PendingAction::Jettison(item, quantity) => {
+                let removed = self.player.inventory.discard(&item, quantity);
+                let inventory = &self.player.inventory;
+                let message = format!(
+                    "Jettisoned {} {}. Hold now {}/{} t",
+                    removed, item.name, inventory.used_capacity(), inventory.capacity
+                );
+                self.show_formatted_message(message);
+            },
+        }
+        if self.current_screen == GameScreen::Market {
+            self.refresh_trade_estimate();
+        }
+    }
+    
+    // Quote a bulk sale and ask before going through with it
+    fn request_bulk_sale(&mut self, sale: BulkSale) {
+        let clock = self.time_system.clock();
+        match self.trading_system.quote_bulk_sale(&self.player, &self.universe, &sale, &clock) {
+            Ok(summary) => self.request_confirmation(
+                format!(
+                    "Sell {} ({} units in {} stacks) for {} cr?",
+                    sale.describe(), summary.total_quantity(), summary.lines.len(), summary.total_revenue()
+                ),
+                PendingAction::BulkSell(sale),
+            ),
+            Err(e) => self.push_message(MessageKind::Error, e),
+        }
+    }
+    
+    fn refresh_trade_estimate(&mut self) {
+        let system_id = self.player.current_system.id.clone();
+        let selected = if self.trading_system.is_buy_mode() {
+            self.universe.get_market_items_for_system(system_id.clone())
+                .get(self.trading_system.get_inspected_item())
+                .map(|(item, _)| (item.name.clone(), 1))
+        } else {
+            self.selected_cargo_item()
+                .map(|item| {
+                    let held = self.player.inventory.get_item_quantity(&item.name);
+                    (item.name, held)
+                })
+        };
+        
+        self.trade_estimate = match (selected, self.universe.get_market(&system_id)) {
+            (Some((name, quantity)), Some(market)) => {
+                let economy = EconomySystem::from_universe(&self.universe);
+                let is_buy = self.trading_system.is_buy_mode();
+                self.trading_system.estimate_trade(&self.player, &market, &economy, &name, quantity, is_buy).ok()
+            },
+            _ => None,
+        };
+    }
+    
+    // Name of the market item shown in the detail panel in buy mode
+    fn inspected_market_item(&self) -> Option<String> {
+        self.universe.get_market_items_for_system(self.player.current_system.id.clone())
+            .get(self.trading_system.get_inspected_item())
+            .map(|(item, _)| item.name.clone())
+    }
+    
+    fn handle_alert_input(&mut self, key: KeyEvent) {
+        let (direction, input) = match self.alert_input.as_mut() {
+            Some(alert_input) => alert_input,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() && input.len() < 9 => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            },
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right => *direction = direction.toggle(),
+            KeyCode::Char('r') => self.alert_repeat = !self.alert_repeat,
+            KeyCode::Enter => {
+                let direction = *direction;
+                let threshold = input.parse().unwrap_or(0);
+                self.alert_input = None;
+                if threshold == 0 {
+                    self.show_message("Enter a price for the alert");
+                    return;
+                }
+                if let Some(name) = self.inspected_market_item() {
+                    let alert = PriceAlert::new(&self.player.current_system.id, &name, direction, threshold, self.alert_repeat);
+                    self.show_formatted_message(format!("Alert set: {}", alert.describe()));
+                    self.player.price_alerts.push(alert);
+                }
+            },
+            KeyCode::Esc => self.alert_input = None,
+            _ => {}
+        }
+    }
+    
+    // Cargo row highlighted in the market's sell mode
+    fn selected_cargo_item(&self) -> Option<Item> {
+        self.player.inventory.items.keys()
+            .nth(self.trading_system.get_inspected_item())
+            .cloned()
+    }
+    
+    fn handle_station_services_input(&mut self, key: KeyEvent) {
+        if self.wait_input.is_some() {
+            self.handle_wait_input(key);
+            return;
+        }
+        
+        match key.code {
+            // Refuel ship
+            KeyCode::Char('2') => {
+                self.refuel_ship();
+            },
+            // Repair hull
+            KeyCode::Char('r') => {
+                self.repair_ship();
+            },
+            // Insure the ship against destruction
+            KeyCode::Char('i') => self.buy_insurance(),
+            // Pay for a research attempt at a research station
+            KeyCode::Char('e') => self.research_blueprint(),
+            // Hire one of the candidates on offer
+            KeyCode::Char('a') | KeyCode::Char('b') | KeyCode::Char('c') => {
+                let candidate_idx = match key.code {
+                    KeyCode::Char(c) => (c as u8 - b'a') as usize,
+                    _ => 0,
+                };
+                self.hire_crew_member(candidate_idx);
+            },
+            // Pass time at the station
+            KeyCode::Char('w') => {
+                if self.navigation_system.is_docked(&self.player) {
+                    self.wait_input = Some(String::new());
+                } else {
+                    self.show_message("You must be docked at a station to wait");
+                }
+            },
+            // Change credits into the local scrip, or all local scrip back
+            KeyCode::Char('x') => self.exchange_for_scrip(EXCHANGE_LOT),
+            KeyCode::Char('X') => self.exchange_for_credits(),
+            KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
+            _ => {}
+        }
+    }
+    
+    // The scrip markets in the current system price in, if any
+    pub fn local_scrip(&self) -> Option<&FactionCurrency> {
+        self.universe.market_definition().currency_for(&self.player.current_system.id)
+    }
+    
+    fn exchange_for_scrip(&mut self, credits: u32) {
+        let scrip = match self.local_scrip() {
+            Some(scrip) if self.navigation_system.is_docked(&self.player) => scrip.clone(),
+            _ => {
+                self.show_message("There is no currency exchange here");
+                return;
+            },
+        };
+        let received = scrip.buy_quote(credits);
+        let description = format!("Exchanged for {}", scrip.currency().format(received));
+        if self.player.apply_transaction(-(credits as i64), TransactionCategory::Trade, &description, &self.time_system.clock()).is_err() {
+            self.push_message(MessageKind::Warning, format!("Not enough credits. The exchange takes {} credits at a time", credits));
+            return;
+        }
+        let _ = self.player.adjust_balance(&scrip.currency(), received as i64, TransactionCategory::Trade, &description, &self.time_system.clock());
+        self.show_formatted_message(format!("Exchanged {} credits for {}", credits, scrip.currency().format(received)));
+    }
+    
+    fn exchange_for_credits(&mut self) {
+        let scrip = match self.local_scrip() {
+            Some(scrip) if self.navigation_system.is_docked(&self.player) => scrip.clone(),
+            _ => {
+                self.show_message("There is no currency exchange here");
+                return;
+            },
+        };
+        let held = self.player.balance(&scrip.currency());
+        let received = scrip.sell_quote(held);
+        if received == 0 {
+            self.show_formatted_message(format!("You have no {} worth exchanging", scrip.currency().symbol()));
+            return;
+        }
+        let description = format!("Exchanged {}", scrip.currency().format(held));
+        let _ = self.player.adjust_balance(&scrip.currency(), -(held as i64), TransactionCategory::Trade, &description, &self.time_system.clock());
+        let _ = self.player.apply_transaction(received as i64, TransactionCategory::Trade, &description, &self.time_system.clock());
+        self.show_formatted_message(format!("Exchanged {} for {} credits", scrip.currency().format(held), received));
+    }
+    
+    fn handle_wait_input(&mut self, key: KeyEvent) {
+        let input = match self.wait_input.as_mut() {
+            Some(input) => input,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() && input.len() < 3 => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            },
+            KeyCode::Enter => {
+                let hours = input.parse().unwrap_or(0);
+                self.wait_input = None;
+                match self.wait(hours) {
+                    Ok(summary) => {
+                        self.show_formatted_message(summary.describe());
+                        self.announce_executed_orders(&summary.executed_orders);
+                    },
+                    Err(e) => self.push_message(MessageKind::Warning, e),
+                }
+            },
+            KeyCode::Esc => self.wait_input = None,
+            _ => {}
+        }
+    }
+    
+    // Refuel the player's ship
+    fn refuel_ship(&mut self) {
+        // Check if player is docked
+        if !self.navigation_system.is_docked(&self.player) {
+            self.show_message("You must be docked at a station to refuel");
+            return;
+        }
+        
+        // Check if ship is already at full fuel
+        if self.player.ship.current_fuel >= self.player.ship.fuel_capacity {
+            self.show_message("Ship fuel tank is already full");
+            return;
+        }
+        
+        // Calculate refuel amount and cost
+        let missing_fuel = self.player.ship.fuel_capacity - self.player.ship.current_fuel;
+        let fuel_price = 25; // 25 credits per unit
+        let total_cost = missing_fuel as u32 * fuel_price;
+        
+        // Deduct credits and refill fuel
+        let description = format!("Refueled {} units", missing_fuel);
+        if self.player.apply_transaction(-(total_cost as i64), TransactionCategory::Refuel, &description, &self.time_system.clock()).is_err() {
+            self.push_message(MessageKind::Warning, format!("Not enough credits. Refueling costs {} credits", total_cost));
+            return;
+        }
+        self.player.ship.current_fuel = self.player.ship.fuel_capacity;
+        self.record_routine_step(RoutineStep::Refuel);
+        
+        self.show_formatted_message(format!(
+            "Ship refueled for {} credits. Fuel now at {}/{}", 
+            total_cost, 
+            self.player.ship.current_fuel, 
+            self.player.ship.fuel_capacity
+        ));
+    }
+
+    // Repair the player's hull to full
+    fn repair_ship(&mut self) {
+        // Check if player is docked
+        if !self.navigation_system.is_docked(&self.player) {
+            self.show_message("You must be docked at a station to repair");
+            return;
+        }
+        
+        let damage = self.player.ship.hull_damage();
+        if damage == 0 {
+            self.show_message("Hull is already at full integrity");
+            return;
+        }
+        
+        // Calculate repair cost, with a discount for crew engineers
+        let engineer_discount = self.player.ship.crew_bonus(&CrewRole::Engineer);
+        let total_cost = ((damage * REPAIR_PRICE_PER_HULL_POINT) as f32 * (1.0 - engineer_discount)) as u32;
+        
+        // Deduct credits and restore hull
+        let description = format!("Repaired {} hull", damage);
+        if self.player.apply_transaction(-(total_cost as i64), TransactionCategory::Repair, &description, &self.time_system.clock()).is_err() {
+            self.push_message(MessageKind::Warning, format!("Not enough credits. Repairs cost {} credits", total_cost));
+            return;
+        }
+        self.player.ship.repair(damage);
+        
+        self.show_formatted_message(format!(
+            "Hull repaired for {} credits. Integrity now at {}/{}", 
+            total_cost, 
+            self.player.ship.hull, 
+            self.player.ship.max_hull
+        ));
+    }
+    
+    // Buy a policy on the current ship. A policy still in force has to run
+    // out before another can be bought.
+    fn buy_insurance(&mut self) {
+        if !self.navigation_system.is_docked(&self.player) {
+            self.show_message("You must be docked at a station to buy insurance");
+            return;
+        }
+        
+        let today = self.time_system.get_game_day();
+        if let Some(policy) = self.player.insurance.as_ref().filter(|policy| policy.is_active(today)) {
+            self.show_formatted_message(format!("Your ship is already insured until day {}", policy.expires_on_day));
+            return;
+        }
+        
+        let policy = InsurancePolicy::quote(&self.player.ship, today);
+        let description = format!("Insured {} for {} days", self.player.ship.name, POLICY_TERM_DAYS);
+        if self.player.apply_transaction(-(policy.premium as i64), TransactionCategory::Insurance, &description, &self.time_system.clock()).is_err() {
+            self.push_message(MessageKind::Warning, format!("Not enough credits. The premium is {} credits", policy.premium));
+            return;
+        }
+        
+        self.show_formatted_message(format!(
+            "Ship insured for {} credits until day {}. Deductible on a claim: {} credits",
+            policy.premium, policy.expires_on_day, policy.deductible
+        ));
+        self.player.insurance = Some(policy);
+    }
+    
+    // Spend credits and station time on a chance of learning a blueprint
+    fn research_blueprint(&mut self) {
+        let offers_research = self.player.current_system.stations.first()
+            .map_or(false, |station| station.services.contains(&"Blueprint Research".to_string()));
+        if !offers_research || !self.navigation_system.is_docked(&self.player) {
+            self.show_message("This station has no research laboratory");
+            return;
+        }
+        if self.crafting_system.unknown_blueprints(&self.player).is_empty() {
+            self.show_message("There are no blueprints left for you to research");
+            return;
         }
         
-        // Clear temporary messages after 3 seconds
-        if let Some(message_time) = self.message_time {
-            if now.duration_since(message_time) > Duration::from_secs(3) {
-                self.message = None;
-                self.message_time = None;
-            }
+        if self.player.apply_transaction(-(RESEARCH_COST as i64), TransactionCategory::Research, "Blueprint research", &self.time_system.clock()).is_err() {
+            self.push_message(MessageKind::Warning, format!("Not enough credits. Research costs {} credits", RESEARCH_COST));
+            return;
         }
-
-        Ok(())
-    }
-
-    pub fn handle_input(&mut self, key: KeyEvent) {
-        match self.current_screen {
-            GameScreen::MainMenu => self.handle_main_menu_input(key),
-            GameScreen::CharacterCreation => self.handle_character_creation_input(key),
-            GameScreen::Navigation => self.handle_navigation_input(key),
-            GameScreen::Market => self.handle_market_input(key),
-            GameScreen::Ship => self.handle_ship_input(key),
-            GameScreen::Mining => self.handle_mining_input(key),
-            GameScreen::Crafting => self.handle_crafting_input(key),
-            GameScreen::Inventory => self.handle_inventory_input(key),
-            GameScreen::Character => self.handle_character_input(key),
-            GameScreen::Orders => self.handle_orders_input(key),
-            GameScreen::StationServices => self.handle_station_services_input(key),
-            GameScreen::Help => self.handle_help_input(key),
-            GameScreen::Quit => self.handle_quit_input(key),
+        match self.wait(RESEARCH_HOURS) {
+            Ok(summary) => self.announce_executed_orders(&summary.executed_orders),
+            Err(e) => {
+                self.push_message(MessageKind::Warning, e);
+                return;
+            },
+        }
+        
+        match self.crafting_system.research_blueprint(&mut self.player, &mut rand::thread_rng()) {
+            Ok(Some(name)) => {
+                let message = format!("After {} hours in the lab you worked out the {} blueprint", RESEARCH_HOURS, name);
+                self.push_message(MessageKind::Success, message);
+            },
+            Ok(None) => self.show_formatted_message(format!("{} hours of research turned up nothing new", RESEARCH_HOURS)),
+            Err(e) => self.push_message(MessageKind::Warning, e),
         }
     }
     
-    fn handle_station_services_input(&mut self, key: KeyEvent) {
-        match key.code {
-            // Refuel ship
-            KeyCode::Char('2') => {
-                self.refuel_ship();
+    // Find the nearest reachable system where the player can buy fuel and
+    // set course for it
+    fn set_course_for_fuel(&mut self) {
+        let credits = self.player.credits;
+        let sells_fuel = |system: &StarSystem, market: Option<&Market>| {
+            let has_refueling = system.stations.iter()
+                .any(|station| station.services.contains(&"Refueling".to_string()));
+            let affordable_fuel = market.map_or(false, |market| {
+                market.items.values().any(|market_item| {
+                    market_item.item.item_type == ItemType::Fuel
+                        && market_item.quantity > 0
+                        && market_item.buy_price() <= credits
+                })
+            });
+            has_refueling || affordable_fuel
+        };
+        
+        if sells_fuel(&self.player.current_system, self.universe.get_market(&self.player.current_system.id).as_ref()) {
+            self.show_message("Fuel is available in this system");
+            return;
+        }
+        
+        let destination_id = match self.navigation_system.nearest_system_with(&self.universe, &self.player, sells_fuel) {
+            Some(id) => id,
+            None => {
+                self.push_message(MessageKind::Warning, "No fuel source within reach".to_string());
+                return;
+            }
+        };
+        
+        // Leave the station before jumping out
+        if self.navigation_system.is_docked(&self.player) {
+            self.record_market_snapshot();
+            self.navigation_system.undock(&mut self.player);
+        }
+        
+        match self.navigation_system.set_course(&mut self.player, &self.universe, &destination_id, &self.time_system.clock()) {
+            Ok((route, outcome)) => {
+                self.record_routine_step(RoutineStep::Travel(destination_id.clone()));
+                let name = self.universe.get_system(&destination_id)
+                    .map(|system| system.name.clone())
+                    .unwrap_or(destination_id);
+                self.start_travel_animation(&name);
+                let mut message = format!("Course set for {} ({} jumps) to refuel", name, route.len() - 1);
+                if outcome != EncounterOutcome::SafePassage {
+                    message.push_str(&format!(". {}", outcome.describe()));
+                }
+                self.show_formatted_message(message);
             },
-            KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
-            _ => {}
+            Err(e) => self.push_message(MessageKind::Error, e),
         }
     }
     
-    // Refuel the player's ship
-    fn refuel_ship(&mut self) {
-        // Check if player is docked
+    // Hire a candidate from the current station's recruitment office
+    fn hire_crew_member(&mut self, candidate_idx: usize) {
         if !self.navigation_system.is_docked(&self.player) {
-            self.show_message("You must be docked at a station to refuel");
+            self.show_message("You must be docked at a station to hire crew");
             return;
         }
         
-        // Check if ship is already at full fuel
-        if self.player.ship.current_fuel >= self.player.ship.fuel_capacity {
-            self.show_message("Ship fuel tank is already full");
+        let station = match self.player.current_system.stations.first() {
+            Some(station) if station.services.contains(&"Crew Recruitment".to_string()) => station.clone(),
+            _ => {
+                self.show_message("This station has no recruitment office");
+                return;
+            }
+        };
+        
+        let candidates = crew::recruitment_pool(&station.id, self.time_system.get_game_day());
+        let candidate = match candidates.get(candidate_idx) {
+            Some(candidate) => candidate.clone(),
+            None => return,
+        };
+        
+        if self.player.ship.crew.iter().any(|member| member.name == candidate.name) {
+            self.show_formatted_message(format!("{} has already joined your crew", candidate.name));
             return;
         }
         
-        // Calculate refuel amount and cost
-        let missing_fuel = self.player.ship.fuel_capacity - self.player.ship.current_fuel;
-        let fuel_price = 25; // 25 credits per unit
-        let total_cost = missing_fuel as u32 * fuel_price;
+        if self.player.credits < candidate.hire_cost {
+            self.push_message(MessageKind::Warning, format!("Not enough credits. {} asks {} credits to sign on", 
+                candidate.name, candidate.hire_cost));
+            return;
+        }
         
-        // Check if player has enough credits
-        if self.player.credits < total_cost {
-            self.show_message(&format!("Not enough credits. Refueling costs {} credits", total_cost));
+        let name = candidate.name.clone();
+        let hire_cost = candidate.hire_cost;
+        match self.player.ship.add_crew_member(candidate) {
+            Ok(()) => {
+                // Affordability was checked above
+                let _ = self.player.apply_transaction(-(hire_cost as i64), TransactionCategory::Crew, &format!("Hired {}", name), &self.time_system.clock());
+                self.show_formatted_message(format!("{} joined your crew for {} credits", name, hire_cost));
+            },
+            Err(e) => self.push_message(MessageKind::Error, e),
+        }
+    }
+    
+    // Pay crew wages once per game day. Crew who can't be paid walk off,
+    // most recently hired first.
+    fn pay_crew_upkeep(&mut self) {
+        let today = self.time_system.get_game_day();
+        if today <= self.last_crew_upkeep_day {
             return;
         }
+        let days_owed = (today - self.last_crew_upkeep_day) as u32;
+        self.last_crew_upkeep_day = today;
         
-        // Deduct credits and refill fuel
-        self.player.remove_credits(total_cost);
-        self.player.ship.current_fuel = self.player.ship.fuel_capacity;
+        let mut departed = Vec::new();
+        while !self.player.ship.crew.is_empty() {
+            let wages = self.player.ship.crew_daily_upkeep() * days_owed;
+            let description = format!("Crew wages for {} day(s)", days_owed);
+            if self.player.apply_transaction(-(wages as i64), TransactionCategory::Crew, &description, &self.time_system.clock()).is_ok() {
+                break;
+            }
+            if let Some(member) = self.player.ship.crew.pop() {
+                departed.push(member.name);
+            }
+        }
         
-        self.show_formatted_message(format!(
-            "Ship refueled for {} credits. Fuel now at {}/{}", 
-            total_cost, 
-            self.player.ship.current_fuel, 
-            self.player.ship.fuel_capacity
+        if !departed.is_empty() {
+            self.push_message(MessageKind::Warning, format!("Unpaid crew left the ship: {}", departed.join(", ")));
+        }
+    }
+    
+    // A destroyed ship is towed back in by a salvage crew. The player keeps
+    // the ship but loses their cargo and pays a hefty rescue fee. Insured
+    // ships are replaced in full for the deductible instead.
+    fn rescue_destroyed_ship(&mut self) {
+        let today = self.time_system.get_game_day();
+        if let Some(policy) = self.player.insurance.take().filter(|policy| policy.is_active(today)) {
+            self.settle_insurance_claim(policy);
+            return;
+        }
+        
+        let fee = RESCUE_FEE.min(self.player.credits);
+        let _ = self.player.apply_transaction(-(fee as i64), TransactionCategory::Rescue, "Salvage crew rescue fee", &self.time_system.clock());
+        self.player.inventory.items.clear();
+        self.mining_system.stop_mining();
+        
+        let ship = &mut self.player.ship;
+        ship.hull = (ship.max_hull / 4).max(1);
+        ship.shield = 0;
+        
+        self.push_message(MessageKind::Error, format!(
+            "Your ship was destroyed! A salvage crew towed the wreck in for {} credits. All cargo was lost.",
+            fee
+        ));
+    }
+    
+    // The insurer covers the replacement hull; the player pays the
+    // deductible, or whatever they have towards it
+    fn settle_insurance_claim(&mut self, policy: InsurancePolicy) {
+        let deductible = policy.deductible.min(self.player.credits);
+        let _ = self.player.apply_transaction(-(deductible as i64), TransactionCategory::Insurance, "Insurance claim deductible", &self.time_system.clock());
+        self.player.inventory.items.clear();
+        self.mining_system.stop_mining();
+        
+        let ship = &mut self.player.ship;
+        ship.hull = ship.max_hull;
+        ship.shield = ship.max_shield;
+        
+        self.push_message(MessageKind::Error, format!(
+            "Your ship was destroyed! Your insurer covered {} credits of repairs and you paid the {} credit deductible. All cargo was lost.",
+            policy.payout(), deductible
         ));
     }
 
+    fn handle_start_menu_input(&mut self, key: KeyEvent) {
+        if let Some(seed) = self.start_menu.seed_input.as_mut() {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() && seed.len() < 20 => seed.push(c),
+                KeyCode::Backspace => {
+                    seed.pop();
+                },
+                KeyCode::Enter => {
+                    let seed = seed.parse().unwrap_or_else(|_| rand::random());
+                    match Self::new_with_seed(seed) {
+                        Ok(game) => {
+                            let start_location = std::mem::take(&mut self.start_location);
+                            *self = game;
+                            self.start_location = start_location;
+                        },
+                        Err(e) => {
+                            self.start_menu.seed_input = None;
+                            self.push_message(MessageKind::Error, e);
+                        },
+                    }
+                },
+                KeyCode::Esc => self.start_menu.seed_input = None,
+                _ => {}
+            }
+            return;
+        }
+        
+        let count = self.start_menu.entries.len();
+        match key.code {
+            KeyCode::Up if count > 0 => {
+                self.start_menu.selected = (self.start_menu.selected + count - 1) % count;
+            },
+            KeyCode::Down if count > 0 => {
+                self.start_menu.selected = (self.start_menu.selected + 1) % count;
+            },
+            KeyCode::Enter => {
+                let entry = match self.start_menu.entries.get(self.start_menu.selected) {
+                    Some(entry) if entry.unavailable.is_none() => entry.clone(),
+                    _ => return,
+                };
+                let loaded = match &entry.option {
+                    StartOption::NewGame => {
+                        self.start_menu.seed_input = Some(String::new());
+                        return;
+                    },
+                    StartOption::Continue => load_game::<Game>(),
+                    StartOption::Load(slot) => save_load::load_game_from::<Game>(slot),
+                };
+                match loaded {
+                    Ok(game) => {
+                        *self = Self::resume(game);
+                        if let StartOption::Load(slot) = entry.option {
+                            self.save_slot = Some(slot);
+                        }
+                    },
+                    // The save changed since the menu was built
+                    Err(e) => {
+                        self.start_menu.entries[self.start_menu.selected].unavailable = Some(format!("failed to load: {}", e));
+                    },
+                }
+            },
+            _ => {}
+        }
+    }
+    
     fn handle_main_menu_input(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('n') => self.change_screen(GameScreen::Navigation),
@@ -258,7 +1841,12 @@ impl Game {
             KeyCode::Char('p') => self.change_screen(GameScreen::Character), // 'p' for profile/pilot
             KeyCode::Char('o') => self.change_screen(GameScreen::Orders),    // 'o' for orders
             KeyCode::Char('t') => self.change_screen(GameScreen::StationServices), // 't' for station
+            KeyCode::Char('w') => self.change_screen(GameScreen::News),            // 'w' for newswire
             KeyCode::Char('h') => self.change_screen(GameScreen::Help),
+            KeyCode::Char('g') => self.request_confirmation(
+                "Start a new game? Your current save is kept until the new character is created".to_string(),
+                PendingAction::NewGame,
+            ),
             KeyCode::Char('q') => self.change_screen(GameScreen::Quit),
             _ => {}
         }
@@ -357,11 +1945,19 @@ impl Game {
                         // Create new player with selected options
                         self.player = crate::models::player::Player::with_character(
                             &self.character_name,
-                            faction_type,
+                            faction_type.clone(),
                             storyline
                         );
+                        self.player.start_at(self.universe.start_system(&self.start_location, &faction_type));
+                        
+                        // Another game owns the default save, so this one gets a slot of its own
+                        if self.save_slot.is_none() && save_exists() {
+                            self.save_slot = Some(save_load::unused_slot_name(&self.character_name));
+                        }
                         
-                        // Proceed to main game
+                        // Proceed to main game. Starting skills aren't level-ups.
+                        self.known_skill_levels.clear();
+                        self.character_created = true;
                         self.change_screen(GameScreen::MainMenu);
                     },
                     KeyCode::Char('n') => {
@@ -371,6 +1967,9 @@ impl Game {
                         self.selected_storyline = 0;
                         self.creation_stage = 0;
                     },
+                    KeyCode::Char('t') => {
+                        self.tutorial.enabled = !self.tutorial.enabled;
+                    },
                     KeyCode::Backspace => {
                         // Go back to storyline selection
                         self.creation_stage = 2;
@@ -527,12 +2126,24 @@ impl Game {
                     KeyCode::Char(c) => c.to_digit(10).unwrap() as usize,
                     _ => 0, // Default fallback, though this match arm should never be reached
                 };
-                if let Some(destination) = self.universe.get_nearby_system(num - 1) {
+                if let Some(destination) = self.universe.get_nearby_system(&self.player.current_system, num - 1) {
                     if self.navigation_system.can_travel_to(&self.player, &destination) {
-                        self.navigation_system.travel_to(&mut self.player, destination.clone());
-                        self.show_formatted_message(format!("Traveling to {}", destination.name));
+                        if self.navigation_system.is_docked(&self.player) {
+                            self.record_market_snapshot();
+                        }
+                        let outcome = self.navigation_system.travel_to(&mut self.player, destination.clone(), &self.time_system.clock());
+                        self.record_routine_step(RoutineStep::Travel(destination.id.clone()));
+                        self.start_travel_animation(&destination.name);
+                        match outcome {
+                            EncounterOutcome::SafePassage => {
+                                self.show_formatted_message(format!("Traveling to {}", destination.name));
+                            },
+                            _ => {
+                                self.push_message(encounter_message_kind(&outcome), format!("En route to {}: {}", destination.name, outcome.describe()));
+                            }
+                        }
                     } else {
-                        self.show_message("Cannot travel to that system - too far away");
+                        self.push_message(MessageKind::Warning, "Cannot travel to that system - too far away".to_string());
                     }
                 }
             },
@@ -548,12 +2159,31 @@ impl Game {
             KeyCode::Char('u') => {
                 // Undock from current station
                 if self.navigation_system.is_docked(&self.player) {
+                    self.record_market_snapshot();
                     self.navigation_system.undock(&mut self.player);
                     self.show_message("Undocked from station");
                 } else {
                     self.show_message("Not currently docked");
                 }
             },
+            KeyCode::Char('f') => {
+                // Set course for the nearest system selling fuel
+                self.set_course_for_fuel();
+            },
+            KeyCode::Char('r') => self.toggle_routine_recording(),
+            KeyCode::Char('p') => self.toggle_routine_run(),
+            // Move the system map's inspect cursor
+            KeyCode::Left | KeyCode::Right => {
+                let count = system_map::inspectable_count(&self.player.current_system);
+                if count > 0 {
+                    let current = self.inspected_body % count;
+                    self.inspected_body = if key.code == KeyCode::Right {
+                        (current + 1) % count
+                    } else {
+                        (current + count - 1) % count
+                    };
+                }
+            },
             KeyCode::Char('t') => {
                 // Access station services when docked
                 if self.navigation_system.is_docked(&self.player) {
@@ -568,6 +2198,17 @@ impl Game {
     }
 
     fn handle_market_input(&mut self, key: KeyEvent) {
+        if self.alert_input.is_some() {
+            self.handle_alert_input(key);
+            return;
+        }
+        self.handle_market_key(key);
+        if self.current_screen == GameScreen::Market {
+            self.refresh_trade_estimate();
+        }
+    }
+    
+    fn handle_market_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('b') => {
                 // Buy mode
@@ -587,15 +2228,82 @@ impl Game {
                 
                 // Buy or sell the selected item
                 if self.trading_system.is_buy_mode() {
-                    if let Some(result) = self.trading_system.buy_item(&mut self.player, num - 1) {
+                    let name = self.universe.get_market_items_for_system(self.player.current_system.id.clone())
+                        .get(num - 1)
+                        .map(|(item, _)| item.name.clone());
+                    let held = name.as_ref().map_or(0, |name| self.player.inventory.get_item_quantity(name));
+                    if let Some(result) = self.trading_system.buy_item(&mut self.player, &self.universe, num - 1, &self.time_system.clock()) {
                         self.show_formatted_message(result);
                     }
+                    if let Some(name) = name.filter(|name| self.player.inventory.get_item_quantity(name) > held) {
+                        self.record_routine_step(RoutineStep::Buy { item: name, quantity: 1 });
+                    }
                 } else {
-                    if let Some(result) = self.trading_system.sell_item(&mut self.player, num - 1) {
-                        self.show_formatted_message(result);
+                    // Check before parting with anything valuable
+                    let market = self.universe.get_market(&self.player.current_system.id);
+                    let high_value_item = self.player.inventory.items.iter()
+                        .nth(num - 1)
+                        .map(|(item, _)| (item.name.clone(), TradingSystem::quote(market.as_ref(), item, false)))
+                        .filter(|(_, price)| *price >= HIGH_VALUE_SELL_THRESHOLD);
+                    
+                    match high_value_item {
+                        Some((name, price)) => self.request_confirmation(
+                            format!("Sell 1 {} for {} cr?", name, price),
+                            PendingAction::SellItem(num - 1),
+                        ),
+                        None => self.execute_pending_action(PendingAction::SellItem(num - 1)),
                     }
                 }
             },
+            // Bulk sales of the highlighted cargo, its type, or all junk
+            KeyCode::Char('a') if !self.trading_system.is_buy_mode() => {
+                if let Some(item) = self.selected_cargo_item() {
+                    self.request_bulk_sale(BulkSale::AllOf(item.name));
+                }
+            },
+            KeyCode::Char('t') if !self.trading_system.is_buy_mode() => {
+                if let Some(item) = self.selected_cargo_item() {
+                    self.request_bulk_sale(BulkSale::AllOfType(item.item_type));
+                }
+            },
+            KeyCode::Char('j') if !self.trading_system.is_buy_mode() => {
+                self.request_bulk_sale(BulkSale::JunkBelow(JUNK_VALUE_THRESHOLD));
+            },
+            // Pick the item shown in the detail panel, or the cargo row in sell mode
+            KeyCode::Down | KeyCode::Up => {
+                let count = if self.trading_system.is_buy_mode() {
+                    self.universe.get_market_items_for_system(self.player.current_system.id.clone()).len()
+                } else {
+                    self.player.inventory.items.len()
+                };
+                if key.code == KeyCode::Down {
+                    self.trading_system.inspect_next(count);
+                } else {
+                    self.trading_system.inspect_previous(count);
+                }
+            },
+            KeyCode::Enter => {
+                let items = self.universe.get_market_items_for_system(self.player.current_system.id.clone());
+                if let Some((item, _)) = items.get(self.trading_system.get_inspected_item()) {
+                    self.item_detail = Some(item.name.clone());
+                }
+            },
+            // Set up a price alert on the inspected item, or clear its alerts
+            KeyCode::Char('l') if self.trading_system.is_buy_mode() => {
+                if self.inspected_market_item().is_some() {
+                    self.alert_input = Some((AlertDirection::Below, String::new()));
+                    self.alert_repeat = false;
+                }
+            },
+            KeyCode::Char('L') if self.trading_system.is_buy_mode() => {
+                if let Some(name) = self.inspected_market_item() {
+                    let system_id = self.player.current_system.id.clone();
+                    let before = self.player.price_alerts.len();
+                    self.player.price_alerts.retain(|alert| !(alert.system_id == system_id && alert.item_name == name));
+                    let removed = before - self.player.price_alerts.len();
+                    self.show_formatted_message(format!("Removed {} price alert(s) on {}", removed, name));
+                }
+            },
             KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
             _ => {}
         }
@@ -603,6 +2311,17 @@ impl Game {
 
     fn handle_ship_input(&mut self, key: KeyEvent) {
         match key.code {
+            // Dismiss a crew member
+            KeyCode::Char('1') | KeyCode::Char('2') | KeyCode::Char('3') | KeyCode::Char('4') => {
+                let crew_idx = match key.code {
+                    KeyCode::Char(c) => c.to_digit(10).unwrap() as usize - 1,
+                    _ => 0,
+                };
+                if let Some(member) = self.player.ship.remove_crew_member(crew_idx) {
+                    self.show_formatted_message(format!("{} ({}) has been dismissed", 
+                        member.name, member.role.to_string()));
+                }
+            },
             KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
             _ => {}
         }
@@ -619,6 +2338,11 @@ impl Game {
                 if let Some(result) = self.mining_system.mine_resource(&mut self.player, resource_idx) {
                     self.show_formatted_message(result);
                 }
+                self.record_routine_step(RoutineStep::Mine { resource: resource_idx, attempts: 1 });
+                
+                if self.player.ship.is_destroyed() {
+                    self.rescue_destroyed_ship();
+                }
             },
             KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
             _ => {}
@@ -634,9 +2358,16 @@ impl Game {
                     _ => 0, // Default fallback, though this match arm should never be reached
                 };
                 
-                match self.crafting_system.craft_item(&mut self.player, blueprint_idx) {
-                    Ok(result) => self.show_formatted_message(format!("Started crafting job: {}", result)),
-                    Err(error) => self.show_message(&error),
+                match self.crafting_system.craft_item(&mut self.player, blueprint_idx, self.time_system.game_time_seconds()) {
+                    Ok((job_id, quality)) => {
+                        if let Some(job) = self.crafting_system.active_jobs.get(&job_id) {
+                            self.scheduler.schedule(job.end_time, ScheduledEvent::CraftingJobsDue);
+                        }
+                        let name = &self.crafting_system.blueprints[blueprint_idx].name;
+                        let message = format!("Started crafting {} ({} quality)", name, quality.to_string());
+                        self.show_formatted_message(message);
+                    },
+                    Err(error) => self.push_message(MessageKind::Error, error),
                 }
             },
             KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
@@ -644,13 +2375,146 @@ impl Game {
         }
     }
 
+    // Cargo stacks as listed on the inventory screen
+    pub fn inventory_view(&self) -> Vec<(&Item, u32)> {
+        self.player.inventory.view(self.inventory_sort, &self.inventory_filter)
+    }
+    
+    // Whether keys should go to a text field rather than act as shortcuts
+    pub fn is_typing(&self) -> bool {
+        match self.current_screen {
+            GameScreen::Inventory => self.inventory_filter_editing || self.jettison_input.is_some(),
+            GameScreen::StationServices => self.wait_input.is_some(),
+            GameScreen::Market => self.alert_input.is_some(),
+            GameScreen::StartMenu => self.start_menu.seed_input.is_some(),
+            _ => false,
+        }
+    }
+    
+    fn handle_inventory_filter_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => self.inventory_filter.push(c),
+            KeyCode::Backspace => {
+                self.inventory_filter.pop();
+            },
+            KeyCode::Enter => self.inventory_filter_editing = false,
+            // Esc drops the filter altogether
+            KeyCode::Esc => {
+                self.inventory_filter.clear();
+                self.inventory_filter_editing = false;
+            },
+            _ => {}
+        }
+        self.inventory_selected = 0;
+    }
+    
     fn handle_inventory_input(&mut self, key: KeyEvent) {
+        if self.inventory_filter_editing {
+            self.handle_inventory_filter_input(key);
+            return;
+        }
+        if self.jettison_input.is_some() {
+            self.handle_jettison_input(key);
+            return;
+        }
+        
+        let count = self.inventory_view().len();
         match key.code {
+            KeyCode::Down if count > 0 => {
+                self.inventory_selected = (self.inventory_selected + 1) % count;
+            },
+            KeyCode::Up if count > 0 => {
+                self.inventory_selected = (self.inventory_selected + count - 1) % count;
+            },
+            KeyCode::Enter => {
+                let selected = self.inventory_view()
+                    .get(self.inventory_selected)
+                    .map(|(item, _)| item.name.clone());
+                if let Some(name) = selected {
+                    self.item_detail = Some(name);
+                }
+            },
+            KeyCode::Char('s') => {
+                self.inventory_sort = self.inventory_sort.next();
+                self.inventory_selected = 0;
+            },
+            KeyCode::Char('/') => self.inventory_filter_editing = true,
+            // Dump some of the highlighted stack to free up the hold
+            KeyCode::Char('j') if count > 0 => self.jettison_input = Some(String::new()),
+            KeyCode::Char('f') => self.fit_selected_module(),
             KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
             _ => {}
         }
     }
     
+    // Fit the highlighted item to the ship as a module. Fitting needs a
+    // station's tools.
+    fn fit_selected_module(&mut self) {
+        let (item, _) = match self.selected_inventory_stack() {
+            Some(stack) => stack,
+            None => return,
+        };
+        if !self.navigation_system.is_docked(&self.player) {
+            self.show_message("You must be docked at a station to fit modules");
+            return;
+        }
+        let result = ShipModule::from_item(&item)
+            .and_then(|module| self.player.ship.install_module(module));
+        match result {
+            Ok(()) => {
+                self.player.inventory.discard(&item, 1);
+                let ship = &self.player.ship;
+                let message = format!("Fitted {} ({}/{} module slots used)", item.name, ship.modules.len(), ship.module_slots());
+                self.show_formatted_message(message);
+            },
+            Err(e) => self.push_message(MessageKind::Warning, e),
+        }
+    }
+    
+    // Highlighted inventory row and how many are held
+    pub fn selected_inventory_stack(&self) -> Option<(Item, u32)> {
+        self.inventory_view()
+            .get(self.inventory_selected)
+            .map(|(item, held)| ((*item).clone(), *held))
+    }
+    
+    // Typing a quantity to jettison; a blank quantity is the whole stack.
+    // Nothing goes overboard until the player confirms.
+    fn handle_jettison_input(&mut self, key: KeyEvent) {
+        let input = match self.jettison_input.as_mut() {
+            Some(input) => input,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() && input.len() < 6 => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            },
+            KeyCode::Enter => {
+                let typed = input.parse::<u32>().ok();
+                self.jettison_input = None;
+                let (item, held) = match self.selected_inventory_stack() {
+                    Some(stack) => stack,
+                    None => return,
+                };
+                let quantity = typed.unwrap_or(held).min(held);
+                if quantity == 0 {
+                    self.show_message("Nothing jettisoned");
+                    return;
+                }
+                self.request_confirmation(
+                    format!(
+                        "Jettison {} {} worth about {} cr? It can't be recovered",
+                        quantity, item.name, item.value * quantity
+                    ),
+                    PendingAction::Jettison(item, quantity),
+                );
+            },
+            KeyCode::Esc => self.jettison_input = None,
+            _ => {}
+        }
+    }
+    
     fn handle_character_input(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('m') | KeyCode::Esc => self.change_screen(GameScreen::MainMenu),
@@ -671,10 +2535,21 @@ impl Game {
                 self.character_info_tab = 3;
                 self.show_message("Viewing background information");
             },
+            KeyCode::Char('5') => {
+                self.character_info_tab = 4;
+                self.show_message("Viewing lifetime stats");
+            },
             _ => {}
         }
     }
 
+    fn handle_news_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('m') | KeyCode::Esc => self.change_screen(GameScreen::MainMenu),
+            _ => {}
+        }
+    }
+    
     fn handle_help_input(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('m') => self.change_screen(GameScreen::MainMenu),
@@ -701,46 +2576,11 @@ impl Game {
                         item_name, 
                         quantity, 
                         target_price, 
-                        notes
+                        notes,
+                        &self.time_system.clock()
                     ) {
                         Ok(_) => self.show_formatted_message(format!("Created buy order for {} {}", quantity, item_name)),
-                        Err(e) => self.show_formatted_message(format!("Error creating buy order: {}", e)),
-                    }
-                } else if !self.player.is_docked {
-                    self.show_message("You must be docked at a station to create orders");
-                } else {
-                    self.show_message("You can only create orders in the active orders view");
-                }
-            },
-            KeyCode::Char('s') => {
-                // Create sell order
-                if self.orders_view_active && self.player.is_docked {
-                    // In a real implementation, this would open a form or dialog
-                    // For now, we'll create a sample sell order for an item the player might have
-                    
-                    // Check if player has any items to sell
-                    // First get an item if available
-                    let item_to_sell = self.player.inventory.items.iter().next().map(|(item, _qty)| {
-                        (item.name.clone(), item.value)
-                    });
-                    
-                    if let Some((item_name, item_value)) = item_to_sell {
-                        let quantity = 1; // Keep it simple for testing
-                        let target_price = item_value + 10; // Sell for profit
-                        let notes = "Test sell order";
-                        
-                        match self.trading_system.create_sell_order(
-                            &mut self.player, 
-                            &item_name, 
-                            quantity, 
-                            target_price, 
-                            notes
-                        ) {
-                            Ok(_) => self.show_formatted_message(format!("Created sell order for {} {}", quantity, item_name)),
-                            Err(e) => self.show_formatted_message(format!("Error creating sell order: {}", e)),
-                        }
-                    } else {
-                        self.show_message("You have no items to sell");
+                        Err(e) => self.push_message(MessageKind::Error, format!("Error creating buy order: {}", e)),
                     }
                 } else if !self.player.is_docked {
                     self.show_message("You must be docked at a station to create orders");
@@ -748,12 +2588,20 @@ impl Game {
                     self.show_message("You can only create orders in the active orders view");
                 }
             },
+            KeyCode::Char('s') => self.create_sample_sell_order(OrderKind::Limit),
+            KeyCode::Char('l') => self.create_sample_sell_order(OrderKind::StopLoss),
+            KeyCode::Char('p') => self.create_sample_sell_order(OrderKind::TakeProfit),
+            KeyCode::Char('f') => self.create_sample_sell_order(OrderKind::TrailingStop { percent: TRAILING_STOP_PERCENT }),
             KeyCode::Char('c') => {
                 // Cancel selected order
                 if self.orders_view_active && self.player.is_docked {
-                    match self.trading_system.cancel_selected_order(&mut self.player) {
-                        Ok(_) => self.show_message("Order cancelled successfully"),
-                        Err(e) => self.show_formatted_message(format!("Error cancelling order: {}", e)),
+                    if self.trading_system.get_selected_order_index().is_some() {
+                        self.request_confirmation(
+                            "Cancel the selected order? This cannot be undone.".to_string(),
+                            PendingAction::CancelSelectedOrder,
+                        );
+                    } else {
+                        self.show_message("Select an order to cancel first");
                     }
                 } else if !self.player.is_docked {
                     self.show_message("You must be docked at a station to cancel orders");
@@ -781,6 +2629,52 @@ impl Game {
         }
     }
 
+    // Sell order of the given kind for one unit of the first item in the hold
+    fn create_sample_sell_order(&mut self, kind: OrderKind) {
+        if !self.player.is_docked {
+            self.show_message("You must be docked at a station to create orders");
+            return;
+        }
+        if !self.orders_view_active {
+            self.show_message("You can only create orders in the active orders view");
+            return;
+        }
+        
+        // In a real implementation, this would open a form or dialog
+        // For now, we'll create a sample sell order for an item the player might have
+        let item_to_sell = self.player.inventory.items.iter().next().map(|(item, _qty)| {
+            (item.name.clone(), item.value)
+        });
+        let (item_name, item_value) = match item_to_sell {
+            Some(item) => item,
+            None => {
+                self.show_message("You have no items to sell");
+                return;
+            }
+        };
+        
+        let quantity = 1; // Keep it simple for testing
+        let (target_price, description) = match kind {
+            OrderKind::Limit => (item_value + 10, "sell"), // Sell for profit
+            OrderKind::TakeProfit => (item_value + item_value / 5, "take-profit"),
+            OrderKind::StopLoss => (item_value - item_value / 10, "stop-loss"),
+            OrderKind::TrailingStop { .. } => (item_value, "trailing stop"), // Follows the market instead
+        };
+        
+        match self.trading_system.create_sell_order_of_kind(
+            &mut self.player,
+            &item_name,
+            quantity,
+            kind,
+            target_price,
+            "Test sell order",
+            &self.time_system.clock()
+        ) {
+            Ok(_) => self.show_formatted_message(format!("Created {} order for {} {}", description, quantity, item_name)),
+            Err(e) => self.push_message(MessageKind::Error, format!("Error creating sell order: {}", e)),
+        }
+    }
+
     fn handle_quit_input(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('y') => self.quit_confirmed = true,
@@ -793,6 +2687,11 @@ impl Game {
     }
 
     pub fn confirm_quit(&mut self) -> bool {
+        // Nothing is running yet, so there is nothing to confirm
+        if self.current_screen == GameScreen::StartMenu {
+            self.quit_confirmed = true;
+            return true;
+        }
         if self.current_screen == GameScreen::Quit {
             return self.quit_confirmed;
         }
@@ -802,6 +2701,15 @@ impl Game {
     }
 
     pub fn cancel_action(&mut self) {
+        // Esc closes the examine popup before leaving the screen
+        if self.item_detail.take().is_some() {
+            return;
+        }
+        // A character has to exist before the rest of the game is reachable
+        if self.current_screen == GameScreen::StartMenu
+            || (self.current_screen == GameScreen::CharacterCreation && !self.character_created) {
+            return;
+        }
         if self.current_screen != GameScreen::MainMenu {
             self.change_screen(GameScreen::MainMenu);
         }
@@ -812,22 +2720,95 @@ impl Game {
     }
 
     pub fn save_state(&self) -> Result<(), Box<dyn Error>> {
-        save_game(self)
+        // The placeholder behind the start menu is never worth keeping
+        if self.current_screen == GameScreen::StartMenu {
+            return Ok(());
+        }
+        // A game still at character creation never replaces an existing save
+        let exists = match &self.save_slot {
+            Some(slot) => save_load::slot_exists(slot),
+            None => save_exists(),
+        };
+        if !self.character_created && exists {
+            return Ok(());
+        }
+        match &self.save_slot {
+            Some(slot) => save_load::save_game_to(self, slot),
+            None => save_game(self),
+        }
+    }
+    
+    // Dump every market to CSV for analysis outside the game
+    pub fn export_market_data(&mut self) -> std::io::Result<()> {
+        let economy = EconomySystem::from_universe(&self.universe);
+        economy.export_market_csv(std::path::Path::new(MARKET_EXPORT_FILE))?;
+        self.show_formatted_message(format!(
+            "Exported {} markets to {}", economy.system_markets.len(), MARKET_EXPORT_FILE
+        ));
+        Ok(())
     }
 
     fn change_screen(&mut self, screen: GameScreen) {
         self.previous_screen = self.current_screen.clone();
         self.current_screen = screen;
+        if self.current_screen == GameScreen::Market {
+            self.refresh_trade_estimate();
+        }
+    }
+
+    // Save the current system's market so the next visit can show what changed
+    fn record_market_snapshot(&mut self) {
+        let system_id = self.player.current_system.id.clone();
+        if let Some(market) = self.universe.get_market(&system_id) {
+            self.player.record_market_snapshot(&system_id, market.snapshot());
+        }
+    }
+
+    fn push_message(&mut self, kind: MessageKind, message: String) {
+        if self.message_log.len() >= MESSAGE_LOG_CAPACITY {
+            self.message_log.pop_front();
+        }
+        self.message_log.push_back((Instant::now(), message, kind));
+    }
+
+    // Log a milestone and pin it until the player dismisses it. Several at
+    // once queue up and are shown one after another.
+    fn push_milestone(&mut self, message: String) {
+        self.push_message(MessageKind::Milestone, message.clone());
+        self.milestones.push_back(message);
+    }
+
+    // Pin a milestone for every skill that has gained a level since the last check
+    fn announce_level_ups(&mut self) {
+        let levels: Vec<(SkillCategory, u8)> = self.player.skills.skills.iter()
+            .map(|skill| (skill.category.clone(), skill.level))
+            .collect();
+        
+        let previous = std::mem::replace(&mut self.known_skill_levels, levels.clone());
+        if previous.is_empty() {
+            return;
+        }
+        for (category, level) in levels {
+            let before = previous.iter()
+                .find(|(known, _)| *known == category)
+                .map_or(level, |(_, before)| *before);
+            if level > before {
+                self.push_milestone(format!("{} skill reached level {}", category.to_string(), level));
+            }
+        }
     }
 
     fn show_message(&mut self, message: &str) {
-        self.message = Some(message.to_string());
-        self.message_time = Some(Instant::now());
+        self.push_message(MessageKind::Info, message.to_string());
     }
     
     // Helper method for formatted messages
     fn show_formatted_message(&mut self, message: String) {
-        self.message = Some(message);
-        self.message_time = Some(Instant::now());
+        self.push_message(MessageKind::Info, message);
+    }
+
+    // Most recent messages, newest last
+    pub fn recent_messages(&self, count: usize) -> impl Iterator<Item = &(Instant, String, MessageKind)> {
+        self.message_log.iter().skip(self.message_log.len().saturating_sub(count))
     }
 }
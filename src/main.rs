@@ -18,7 +18,7 @@ use std::env;
 use std::process::Command;
 use dotenv::dotenv;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -28,6 +28,8 @@ use tui::{
 };
 
 use game::Game;
+use models::universe::StartLocation;
+use debug::replay::{Recorder, Recording};
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Initialize debugging system
@@ -50,6 +52,27 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Parse command line arguments for client/server mode
     let args: Vec<String> = env::args().collect();
     
+    // `--seed <n>` starts a fresh game in the universe generated from n
+    let seed = match args.iter().position(|arg| arg == "--seed") {
+        Some(index) => Some(args.get(index + 1)
+            .and_then(|value| value.parse::<u64>().ok())
+            .ok_or("--seed needs a number")?),
+        None => None,
+    };
+    
+    // `--record <file>` writes every key pressed to a file that
+    // `--replay <file>` plays back without a terminal, for bug reports
+    let flag_value = |flag: &str| -> Result<Option<String>, String> {
+        match args.iter().position(|arg| arg == flag) {
+            Some(index) => args.get(index + 1).cloned().map(Some).ok_or(format!("{} needs a file", flag)),
+            None => Ok(None),
+        }
+    };
+    let record = flag_value("--record")?;
+    if let Some(path) = flag_value("--replay")? {
+        return replay(std::path::Path::new(&path));
+    }
+    
     if args.len() > 1 {
         match args[1].as_str() {
             "server" => {
@@ -68,6 +91,25 @@ fn main() -> Result<(), Box<dyn Error>> {
                     .map_err(|e| Box::new(e) as Box<dyn Error>)
                     .map(|_| ());
             }
+            // `export <slot> <file>` and `import <file> <slot>` move saves in and out
+            "export" => {
+                let (slot, path) = match (args.get(2), args.get(3)) {
+                    (Some(slot), Some(path)) => (slot, path),
+                    _ => return Err("usage: export <slot> <file>".into()),
+                };
+                utils::save_load::export_save(slot, std::path::Path::new(path))?;
+                println!("Exported save slot '{}' to {}", slot, path);
+                return Ok(());
+            }
+            "import" => {
+                let (path, slot) = match (args.get(2), args.get(3)) {
+                    (Some(path), Some(slot)) => (path, slot),
+                    _ => return Err("usage: import <file> <slot>".into()),
+                };
+                utils::save_load::import_save::<Game>(std::path::Path::new(path), slot)?;
+                println!("Imported {} as save slot '{}'", path, slot);
+                return Ok(());
+            }
             _ => {
                 // Continue with normal execution or prompt
             }
@@ -121,6 +163,39 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     }
     
+    // A recording needs a seed to replay against, so it always starts a new game
+    let seed = match (&record, seed) {
+        (Some(_), None) => Some(rand::random()),
+        _ => seed,
+    };
+    let mut recorder = match &record {
+        Some(path) => {
+            debug::info(&format!("Recording input to {}", path));
+            Some(Recorder::create(std::path::Path::new(path), seed.unwrap_or_default())?)
+        },
+        None => None,
+    };
+    
+    // `--start <system id|random|faction>` picks where a new character starts
+    let start_location = args.iter().position(|arg| arg == "--start")
+        .map(|index| args.get(index + 1).map(|value| StartLocation::parse(value)).ok_or("--start needs a system id, 'random' or 'faction'"))
+        .transpose()?;
+    
+    // Create game instance
+    let mut game = match seed {
+        Some(seed) => {
+            debug::info(&format!("Creating new game from seed {}", seed));
+            Game::new_with_seed(seed)?
+        },
+        None => {
+            debug::info("Opening the start menu");
+            Game::with_start_menu()
+        },
+    };
+    if let Some(start_location) = start_location {
+        game.start_location = start_location;
+    }
+    
     // Setup terminal for standalone mode
     debug::info("Initializing terminal UI");
     
@@ -160,13 +235,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     };
 
-    // Create game instance
-    debug::info("Creating new game instance");
-    let mut game = Game::new();
-    
     // Main game loop
     debug::info("Starting game main loop");
-    let res = run_game(&mut terminal, &mut game);
+    let res = run_game(&mut terminal, &mut game, recorder.as_mut());
 
     // Restore terminal
     debug::debug("Cleaning up terminal");
@@ -207,8 +278,10 @@ fn main() -> Result<(), Box<dyn Error>> {
 fn run_game<B: tui::backend::Backend>(
     terminal: &mut Terminal<B>,
     game: &mut Game,
+    mut recorder: Option<&mut Recorder>,
 ) -> Result<(), Box<dyn Error>> {
     debug::info("Starting game main loop in STANDALONE mode");
+    let mut last_threshold_check = std::time::Instant::now();
     
     // Main game loop
     loop {
@@ -235,44 +308,40 @@ fn run_game<B: tui::backend::Backend>(
             }
         }
         
-        // Handle input with error analysis
-        match event::read() {
-            Ok(Event::Key(key)) => {
-                match key.code {
-                    KeyCode::Char('q') => {
-                        if game.confirm_quit() {
-                            debug::info("Player requested quit, saving game state");
-                            // Save game state before exiting
-                            if let Err(e) = game.save_state() {
-                                debug::error(&format!("Failed to save game state during exit: {}", e));
-                            }
-                            break;
+        // Handle input with error analysis. Wait only briefly for it so
+        // animations keep running between key presses.
+        if event::poll(std::time::Duration::from_millis(40)).unwrap_or(false) {
+            match event::read() {
+                Ok(Event::Key(key)) => {
+                    if let Some(recorder) = recorder.as_mut() {
+                        if let Err(e) = recorder.record(game.time_system.game_time_seconds(), &key) {
+                            debug::error(&e);
                         }
-                    },
-                    KeyCode::Esc => {
-                        game.cancel_action();
-                        debug::debug("Player canceled current action");
-                    },
-                    KeyCode::Char('d') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
-                        // Secret debug key combination
-                        debug::debug("Debug mode activated");
-                        let report = debug::error_analysis::generate_error_report();
-                        debug::info(&format!("Error Report:\n{}", report));
-                    },
-                    _ => {
-                        game.handle_input(key);
-                    },
+                    }
+                    if route_key(game, key) {
+                        debug::info("Player requested quit, saving game state");
+                        // Save game state before exiting
+                        if let Err(e) = game.save_state() {
+                            debug::error(&format!("Failed to save game state during exit: {}", e));
+                        }
+                        break;
+                    }
+                },
+                Ok(Event::Mouse(mouse)) => {
+                    // Hit-test against the layout the last frame was drawn with
+                    let rects = ui::layout_rects(game, terminal.size()?);
+                    game.handle_mouse(mouse, &rects);
+                },
+                Ok(_) => {},  // Ignore other events
+                Err(e) => {
+                    debug::error(&format!("Input error: {}", e));
+                    debug::error_analysis::record_error(
+                        "input", 
+                        "read_error", 
+                        &format!("Failed to read input: {}", e),
+                        None
+                    );
                 }
-            },
-            Ok(_) => {},  // Ignore other events
-            Err(e) => {
-                debug::error(&format!("Input error: {}", e));
-                debug::error_analysis::record_error(
-                    "input", 
-                    "read_error", 
-                    &format!("Failed to read input: {}", e),
-                    None
-                );
             }
         }
 
@@ -300,6 +369,12 @@ fn run_game<B: tui::backend::Backend>(
             std::thread::sleep(std::time::Duration::from_millis(2000));
         }
         
+        // Look for error types that are recurring too quickly
+        if last_threshold_check.elapsed() >= std::time::Duration::from_secs(10) {
+            debug::error_analysis::check_thresholds();
+            last_threshold_check = std::time::Instant::now();
+        }
+        
         // Check game over condition
         if game.is_game_over() {
             debug::info("Game over condition reached");
@@ -313,3 +388,68 @@ fn run_game<B: tui::backend::Backend>(
     debug::info("Exiting game main loop");
     Ok(())
 }
+
+// Send a key press where it belongs. Returns true once the player has
+// confirmed they want to quit.
+fn route_key(game: &mut Game, key: KeyEvent) -> bool {
+    match key.code {
+        // Text fields take every key, including the shortcuts below
+        _ if game.is_typing() => {
+            game.handle_input(key);
+        },
+        KeyCode::Char('q') => {
+            return game.confirm_quit();
+        },
+        KeyCode::Esc => {
+            game.cancel_action();
+            debug::debug("Player canceled current action");
+        },
+        KeyCode::Char('d') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+            // Secret debug key combination
+            debug::debug("Debug mode activated");
+            let report = debug::error_analysis::generate_error_report();
+            debug::info(&format!("Error Report:\n{}", report));
+            if let Err(e) = game.export_market_data() {
+                debug::error(&format!("Failed to export market data: {}", e));
+            }
+        },
+        _ => {
+            game.handle_input(key);
+        },
+    }
+    false
+}
+
+// Play a recording back against a new game from its seed and report where
+// it ended up. Nothing is saved.
+fn replay(path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    let recording = Recording::load(path)?;
+    debug::info(&format!("Replaying {} keys from seed {}", recording.keys.len(), recording.seed));
+    let mut game = Game::new_with_seed(recording.seed)?;
+    
+    for (index, recorded) in recording.keys.iter().enumerate() {
+        game.run_until(recorded.game_seconds);
+        let key = recorded.to_key_event()?;
+        debug::debug(&format!("Replay key {}: {:?} on {:?}", index + 1, key.code, game.current_screen));
+        if route_key(&mut game, key) {
+            break;
+        }
+    }
+    
+    println!("Replayed {} keys from seed {}", recording.keys.len(), recording.seed);
+    println!("Screen: {:?}", game.current_screen);
+    println!("Clock: {}", game.time_system.get_clock_string());
+    println!(
+        "{} in {} with {} credits, {}/{} fuel, hull {}/{}",
+        game.player.character.name, game.player.current_system.name, game.player.credits,
+        game.player.ship.current_fuel, game.player.ship.fuel_capacity,
+        game.player.ship.hull, game.player.ship.max_hull,
+    );
+    for (_, message, _) in &game.message_log {
+        println!("  {}", message);
+    }
+    for warning in game.validate() {
+        println!("Integrity: {}", warning);
+    }
+    Ok(())
+}
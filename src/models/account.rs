@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::Path;
+use std::path::PathBuf;
 use uuid::Uuid;
 use bcrypt::{hash, verify, DEFAULT_COST};
 
@@ -17,6 +17,78 @@ pub struct UserAccount {
     pub characters: Vec<String>, // List of character IDs associated with this account
     pub created_at: u64,      // Unix timestamp when account was created
     pub last_login: Option<u64>, // Unix timestamp of last login
+    #[serde(default)]
+    pub reset_token: Option<PasswordResetToken>, // Outstanding password reset, if any
+    #[serde(default)]
+    pub role: AccountRole, // What server administration the account may do
+}
+
+/// Privilege level of an account. Ordered, so a higher role can do
+/// everything a lower one can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AccountRole {
+    Player,
+    Moderator, // May kick clients and make announcements
+    Admin,     // May also reset markets and adjust credits
+}
+
+impl Default for AccountRole {
+    fn default() -> Self {
+        AccountRole::Player
+    }
+}
+
+impl AccountRole {
+    /// Whether this role grants at least the privileges of `required`
+    pub fn allows(&self, required: AccountRole) -> bool {
+        *self >= required
+    }
+}
+
+/// How long a password reset token stays valid
+pub const RESET_TOKEN_TTL_SECS: u64 = 60 * 60;
+
+/// A pending password reset. Only a hash of the token is kept, like the password.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordResetToken {
+    pub token_hash: String,
+    pub expires_at: u64, // Unix timestamp
+}
+
+/// Sends a freshly created reset token to the account owner (email, SMS, ...)
+pub trait ResetTokenDelivery: std::fmt::Debug + Send + Sync {
+    fn deliver(&self, account: &UserAccount, token: &str) -> Result<(), String>;
+}
+
+/// Development delivery that just writes the token to the server log
+#[derive(Debug)]
+pub struct LogTokenDelivery;
+
+impl ResetTokenDelivery for LogTokenDelivery {
+    fn deliver(&self, account: &UserAccount, token: &str) -> Result<(), String> {
+        let recipient = account.email.as_deref().unwrap_or("<no email on file>");
+        crate::debug::log_internal(
+            crate::debug::LogLevel::Info,
+            "account",
+            &format!("Password reset token for {} ({}): {}", account.username, recipient, token),
+        );
+        Ok(())
+    }
+}
+
+fn default_reset_delivery() -> Box<dyn ResetTokenDelivery> {
+    Box::new(LogTokenDelivery)
+}
+
+fn default_accounts_path() -> PathBuf {
+    PathBuf::from("accounts.json")
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
 }
 
 /// Contains all user accounts and provides methods for account management
@@ -24,30 +96,51 @@ pub struct UserAccount {
 pub struct AccountManager {
     accounts: HashMap<String, UserAccount>, // Map username to account
     account_ids: HashMap<String, String>,   // Map account ID to username
+    #[serde(skip, default = "default_reset_delivery")]
+    reset_delivery: Box<dyn ResetTokenDelivery>, // Where reset tokens are sent
+    #[serde(skip, default = "default_accounts_path")]
+    path: PathBuf, // File the accounts are saved to
 }
 
 impl AccountManager {
     /// Create a new empty account manager
     pub fn new() -> Self {
+        Self::with_path(default_accounts_path())
+    }
+    
+    /// Create a new empty account manager that saves to `path`
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
         Self {
             accounts: HashMap::new(),
             account_ids: HashMap::new(),
+            reset_delivery: default_reset_delivery(),
+            path: path.into(),
         }
     }
     
     /// Load account manager from disk, or create new if none exists
     pub fn load() -> Self {
-        let accounts_path = Path::new("accounts.json");
+        Self::load_from(default_accounts_path())
+    }
+    
+    /// Load account manager from the file at `path`, or create a new one
+    /// saving there if none exists
+    pub fn load_from(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let accounts_path = path.as_path();
         
         if accounts_path.exists() {
             match fs::read_to_string(accounts_path) {
                 Ok(content) => {
-                    match serde_json::from_str(&content) {
-                        Ok(accounts) => return accounts,
+                    match serde_json::from_str::<Self>(&content) {
+                        Ok(mut accounts) => {
+                            accounts.path = path;
+                            return accounts;
+                        },
                         Err(e) => {
                             eprintln!("Error parsing accounts file: {}", e);
                             // Create a backup of the corrupted file
-                            if let Err(e) = fs::copy(accounts_path, "accounts.json.bak") {
+                            if let Err(e) = fs::copy(accounts_path, accounts_path.with_extension("json.bak")) {
                                 eprintln!("Failed to backup accounts file: {}", e);
                             }
                         }
@@ -58,12 +151,12 @@ impl AccountManager {
         }
         
         // If we got here, either no file exists or it was corrupted
-        Self::new()
+        Self::with_path(path)
     }
     
     /// Save account manager to disk
     pub fn save(&self) -> Result<(), Box<dyn Error>> {
-        let accounts_path = Path::new("accounts.json");
+        let accounts_path = self.path.as_path();
         let serialized = serde_json::to_string_pretty(self)?;
         
         let mut file = File::create(accounts_path)?;
@@ -105,6 +198,8 @@ impl AccountManager {
             characters: Vec::new(),
             created_at: now,
             last_login: None,
+            reset_token: None,
+            role: AccountRole::Player,
         };
         
         // Add to our maps
@@ -240,6 +335,87 @@ impl AccountManager {
         }
     }
     
+    /// Role of an account, if it exists
+    pub fn get_role(&self, username: &str) -> Option<AccountRole> {
+        self.accounts.get(username).map(|account| account.role)
+    }
+    
+    /// Promote or demote an account
+    pub fn set_role(&mut self, username: &str, role: AccountRole) -> Result<(), AccountError> {
+        let account = self.accounts.get_mut(username).ok_or(AccountError::AccountNotFound)?;
+        account.role = role;
+        
+        if let Err(e) = self.save() {
+            eprintln!("Error saving accounts after changing role: {}", e);
+        }
+        
+        Ok(())
+    }
+    
+    /// Replace the way reset tokens reach their owners (logged by default)
+    pub fn set_reset_delivery(&mut self, delivery: Box<dyn ResetTokenDelivery>) {
+        self.reset_delivery = delivery;
+    }
+    
+    /// Start a password reset: store a new single-use token on the account,
+    /// replacing any earlier one, and hand it to the configured delivery
+    pub fn create_reset_token(&mut self, username: &str) -> Result<String, AccountError> {
+        self.create_reset_token_at(username, unix_now())
+    }
+    
+    fn create_reset_token_at(&mut self, username: &str, now: u64) -> Result<String, AccountError> {
+        let account = self.accounts.get_mut(username).ok_or(AccountError::AccountNotFound)?;
+        
+        let token = Uuid::new_v4().simple().to_string();
+        let token_hash = hash(&token, DEFAULT_COST).map_err(|_| AccountError::HashingFailed)?;
+        account.reset_token = Some(PasswordResetToken {
+            token_hash,
+            expires_at: now + RESET_TOKEN_TTL_SECS,
+        });
+        
+        if let Err(e) = self.reset_delivery.deliver(account, &token) {
+            eprintln!("Error delivering reset token to {}: {}", username, e);
+        }
+        
+        if let Err(e) = self.save() {
+            eprintln!("Error saving accounts after creating reset token: {}", e);
+        }
+        
+        Ok(token)
+    }
+    
+    /// Finish a password reset. The token is used up whether or not it had
+    /// expired; a wrong token leaves it in place.
+    pub fn consume_reset_token(&mut self, username: &str, token: &str, new_password: &str) -> Result<(), AccountError> {
+        self.consume_reset_token_at(username, token, new_password, unix_now())
+    }
+    
+    fn consume_reset_token_at(&mut self, username: &str, token: &str, new_password: &str, now: u64) -> Result<(), AccountError> {
+        let account = self.accounts.get_mut(username).ok_or(AccountError::AccountNotFound)?;
+        let reset = account.reset_token.clone().ok_or(AccountError::InvalidResetToken)?;
+        
+        if now >= reset.expires_at {
+            account.reset_token = None;
+            if let Err(e) = self.save() {
+                eprintln!("Error saving accounts after expiring reset token: {}", e);
+            }
+            return Err(AccountError::ResetTokenExpired);
+        }
+        
+        if !verify(token, &reset.token_hash).unwrap_or(false) {
+            return Err(AccountError::InvalidResetToken);
+        }
+        
+        account.password_hash = hash(new_password, DEFAULT_COST).map_err(|_| AccountError::HashingFailed)?;
+        account.reset_token = None;
+        
+        if let Err(e) = self.save() {
+            eprintln!("Error saving accounts after password reset: {}", e);
+        }
+        
+        Ok(())
+    }
+    
     /// Delete an account (requires password confirmation)
     pub fn delete_account(&mut self, username: &str, password: &str) -> Result<(), AccountError> {
         // First authenticate to confirm deletion
@@ -277,6 +453,12 @@ pub enum AccountError {
     
     #[error("Failed to hash password")]
     HashingFailed,
+    
+    #[error("Invalid password reset token")]
+    InvalidResetToken,
+    
+    #[error("Password reset token has expired")]
+    ResetTokenExpired,
 }
 
 // Unit tests for account manager
@@ -284,9 +466,28 @@ pub enum AccountError {
 mod tests {
     use super::*;
     
+    // A directory of the test's own in the temp dir, removed (with the
+    // accounts file and its backup) when the test ends
+    struct TempDir(PathBuf);
+    
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+    
+    // Account manager saving into its own temp directory, so tests never
+    // touch the server's accounts.json
+    fn temp_manager() -> (TempDir, AccountManager) {
+        let dir = TempDir(std::env::temp_dir().join(format!("accounts-{}", Uuid::new_v4())));
+        fs::create_dir_all(&dir.0).unwrap();
+        let manager = AccountManager::with_path(dir.0.join("accounts.json"));
+        (dir, manager)
+    }
+    
     #[test]
     fn test_register_and_authenticate() {
-        let mut manager = AccountManager::new();
+        let (_dir, mut manager) = temp_manager();
         
         // Register a new account
         let account = manager.register_account("testuser", "password123", None).unwrap();
@@ -301,9 +502,39 @@ mod tests {
         assert!(auth_fail.is_err());
     }
     
+    #[test]
+    fn test_reset_token_is_single_use_and_expires() {
+        let (_dir, mut manager) = temp_manager();
+        manager.register_account("resetuser", "oldpassword", None).unwrap();
+        
+        // A used token can't be replayed
+        let token = manager.create_reset_token_at("resetuser", 1000).unwrap();
+        assert!(manager.consume_reset_token_at("resetuser", "wrong", "newpassword", 1001).is_err());
+        manager.consume_reset_token_at("resetuser", &token, "newpassword", 1001).unwrap();
+        assert!(manager.authenticate("resetuser", "newpassword").is_ok());
+        assert!(manager.consume_reset_token_at("resetuser", &token, "again", 1002).is_err());
+        
+        // An expired token is rejected
+        let token = manager.create_reset_token_at("resetuser", 1000).unwrap();
+        let result = manager.consume_reset_token_at("resetuser", &token, "later", 1000 + RESET_TOKEN_TTL_SECS);
+        assert!(matches!(result, Err(AccountError::ResetTokenExpired)));
+    }
+    
+    #[test]
+    fn test_new_accounts_are_players() {
+        let (_dir, mut manager) = temp_manager();
+        manager.register_account("roleuser", "password", None).unwrap();
+        assert_eq!(manager.get_role("roleuser"), Some(AccountRole::Player));
+        
+        manager.set_role("roleuser", AccountRole::Moderator).unwrap();
+        let role = manager.get_role("roleuser").unwrap();
+        assert!(role.allows(AccountRole::Player));
+        assert!(!role.allows(AccountRole::Admin));
+    }
+    
     #[test]
     fn test_username_exists() {
-        let mut manager = AccountManager::new();
+        let (_dir, mut manager) = temp_manager();
         
         // Register a new account
         manager.register_account("existinguser", "password123", None).unwrap();
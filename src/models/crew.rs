@@ -0,0 +1,86 @@
+use serde::{Serialize, Deserialize};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CrewRole {
+    Navigator, // Plots efficient jumps, reducing fuel use
+    Engineer,  // Keeps the ship patched up, reducing repair costs
+    Miner,     // Runs the mining rigs, increasing yield
+}
+
+impl CrewRole {
+    pub fn to_string(&self) -> String {
+        match self {
+            CrewRole::Navigator => "Navigator".to_string(),
+            CrewRole::Engineer => "Engineer".to_string(),
+            CrewRole::Miner => "Miner".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewMember {
+    pub name: String,
+    pub role: CrewRole,
+    pub skill_level: u8,  // 1-5
+    pub hire_cost: u32,   // One-off signing fee
+    pub daily_upkeep: u32, // Wages paid every game day
+}
+
+#[allow(dead_code)]
+impl CrewMember {
+    pub fn new(name: &str, role: CrewRole, skill_level: u8) -> Self {
+        let skill_level = skill_level.max(1).min(5);
+
+        CrewMember {
+            name: name.to_string(),
+            role,
+            skill_level,
+            hire_cost: 500 * skill_level as u32,
+            daily_upkeep: 40 * skill_level as u32,
+        }
+    }
+
+    // Fractional bonus this crew member gives in their role (5% per skill level)
+    pub fn bonus(&self) -> f32 {
+        self.skill_level as f32 * 0.05
+    }
+
+    // Effect shown in the UI, e.g. "-10% fuel use"
+    pub fn bonus_description(&self) -> String {
+        let pct = self.bonus() * 100.0;
+        match self.role {
+            CrewRole::Navigator => format!("-{:.0}% fuel use", pct),
+            CrewRole::Engineer => format!("-{:.0}% repair cost", pct),
+            CrewRole::Miner => format!("+{:.0}% mining yield", pct),
+        }
+    }
+}
+
+const FIRST_NAMES: [&str; 10] = [
+    "Mara", "Jonas", "Ilsa", "Dex", "Priya", "Tomas", "Yuki", "Rook", "Ada", "Coll",
+];
+
+const LAST_NAMES: [&str; 10] = [
+    "Voss", "Okafor", "Reyes", "Lindqvist", "Tanaka", "Mercer", "Haldane", "Idris", "Kowal", "Brandt",
+];
+
+// Candidates available for hire at a station on a given game day. The pool
+// is derived from the station and day so it stays stable while docked.
+pub fn recruitment_pool(station_id: &str, day: u64) -> Vec<CrewMember> {
+    let seed = station_id.bytes()
+        .fold(day, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
+    let mut rng = StdRng::seed_from_u64(seed);
+    let roles = [CrewRole::Navigator, CrewRole::Engineer, CrewRole::Miner];
+
+    (0..3).map(|_| {
+        let name = format!(
+            "{} {}",
+            FIRST_NAMES[rng.gen_range(0..FIRST_NAMES.len())],
+            LAST_NAMES[rng.gen_range(0..LAST_NAMES.len())]
+        );
+        let role = roles[rng.gen_range(0..roles.len())].clone();
+        CrewMember::new(&name, role, rng.gen_range(1..=5))
+    }).collect()
+}
@@ -0,0 +1,84 @@
+// Money other than credits
+// Factions may issue their own scrip, and markets in systems they control
+// can price in it. Station exchanges convert between credits and the local
+// scrip, keeping a spread on every conversion.
+
+use serde::{Serialize, Deserialize};
+
+use crate::models::faction::FactionType;
+
+// Share of every exchange the station keeps
+pub const EXCHANGE_SPREAD: f32 = 0.08;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    Credits,
+    Scrip(FactionType),
+}
+
+#[allow(dead_code)]
+impl Currency {
+    // Short unit shown after amounts
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Credits => "cr",
+            Currency::Scrip(FactionType::Traders) => "UTF scrip",
+            Currency::Scrip(FactionType::Miners) => "MC scrip",
+            Currency::Scrip(FactionType::Military) => "GSF scrip",
+            Currency::Scrip(FactionType::Scientists) => "SA scrip",
+        }
+    }
+
+    pub fn format(&self, amount: u32) -> String {
+        format!("{} {}", amount, self.symbol())
+    }
+}
+
+// A faction's scrip, what one unit is worth in credits, and the systems
+// whose markets price in it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FactionCurrency {
+    pub faction: FactionType,
+    pub credits_per_unit: f32,
+    #[serde(default)]
+    pub systems: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl FactionCurrency {
+    pub fn currency(&self) -> Currency {
+        Currency::Scrip(self.faction.clone())
+    }
+
+    // A credit price restated in this scrip, never below 1
+    pub fn from_credits(&self, credits: u32) -> u32 {
+        ((credits as f32 / self.credits_per_unit).round() as u32).max(1)
+    }
+
+    // Scrip received at the exchange for `credits`, after the spread
+    pub fn buy_quote(&self, credits: u32) -> u32 {
+        (credits as f32 * (1.0 - EXCHANGE_SPREAD) / self.credits_per_unit) as u32
+    }
+
+    // Credits received at the exchange for `scrip`, after the spread
+    pub fn sell_quote(&self, scrip: u32) -> u32 {
+        (scrip as f32 * self.credits_per_unit * (1.0 - EXCHANGE_SPREAD)) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_through_exchange_loses_the_spread_twice() {
+        let scrip = FactionCurrency { faction: FactionType::Miners, credits_per_unit: 2.0, systems: Vec::new() };
+        assert_eq!(scrip.from_credits(500), 250);
+        assert_eq!(scrip.from_credits(1), 1);
+
+        let bought = scrip.buy_quote(1000);
+        assert_eq!(bought, 460);
+        assert_eq!(scrip.sell_quote(bought), 846);
+    }
+}
@@ -1,8 +1,11 @@
 use serde::{Serialize, Deserialize};
-use crate::models::ship::{Ship, ShipType};
+use crate::models::ship::{ModuleType, Ship, ShipModule, ShipType};
+use crate::models::item::{Item, ItemType, ResourceType};
+use crate::models::blueprint::{Blueprint, BlueprintCategory, BlueprintIngredient, BlueprintType};
 use crate::models::skills::SkillCategory;
+use crate::systems::crafting::{blueprint_id, BlueprintId};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum FactionType {
     Traders,      // Focus on trading and commerce
     Miners,       // Focus on resource extraction
@@ -30,6 +33,71 @@ impl FactionType {
         }
     }
     
+    // Credits, equipment and cargo a new character of this faction starts
+    // with, on top of the faction's starting ship
+    pub fn starting_loadout(&self) -> StartingLoadout {
+        match self {
+            FactionType::Traders => StartingLoadout {
+                credits: 8000,
+                modules: Vec::new(),
+                hull_bonus: 0,
+                cargo: Vec::new(),
+                blueprints: Vec::new(),
+                known_blueprints: vec![blueprint_id("Standard Fuel")],
+            },
+            FactionType::Miners => StartingLoadout {
+                credits: 4000,
+                modules: vec![ShipModule::new("Mining Laser", ModuleType::MiningLaser, 5)],
+                hull_bonus: 0,
+                cargo: vec![
+                    (Item::new("Iron", 60, 1, ItemType::Resource(ResourceType::Mineral)), 20),
+                    (Item::new("Copper", 80, 1, ItemType::Resource(ResourceType::Mineral)), 10),
+                ],
+                blueprints: Vec::new(),
+                known_blueprints: vec![blueprint_id("Standard Fuel"), blueprint_id("Basic Mineral Alloy")],
+            },
+            FactionType::Military => StartingLoadout {
+                credits: 4000,
+                modules: vec![ShipModule::new("Pulse Laser", ModuleType::Weapon, 8)],
+                hull_bonus: 40,
+                cargo: Vec::new(),
+                blueprints: Vec::new(),
+                known_blueprints: vec![blueprint_id("Standard Fuel"), blueprint_id("Basic Shield Generator")],
+            },
+            FactionType::Scientists => StartingLoadout {
+                credits: 4500,
+                modules: vec![ShipModule::new("Tuned Drive", ModuleType::Engine, 10)],
+                hull_bonus: 0,
+                cargo: Vec::new(),
+                blueprints: vec![Blueprint::new(
+                    "Survey Scanner",
+                    "Sensor package for charting resource fields",
+                    BlueprintCategory::ShipPart,
+                    BlueprintType::Original,
+                    vec![
+                        BlueprintIngredient {
+                            item_name: "Computer Chip".to_string(),
+                            item_type: ItemType::Component,
+                            quantity: 2,
+                        },
+                        BlueprintIngredient {
+                            item_name: "Copper".to_string(),
+                            item_type: ItemType::Resource(ResourceType::Mineral),
+                            quantity: 5,
+                        },
+                    ],
+                    "Survey Scanner",
+                    ItemType::Equipment,
+                    1,
+                    2,
+                    3,
+                    None,
+                )],
+                known_blueprints: vec![blueprint_id("Standard Fuel"), blueprint_id("Standard Power Cell")],
+            },
+        }
+    }
+    
     pub fn starting_ship(&self) -> Ship {
         match self {
             FactionType::Traders => Ship::new(
@@ -72,6 +140,17 @@ impl FactionType {
     }
 }
 
+// Faction-specific starting state, see FactionType::starting_loadout
+#[derive(Debug, Clone)]
+pub struct StartingLoadout {
+    pub credits: u32,
+    pub modules: Vec<ShipModule>,
+    pub hull_bonus: u32, // Added to the starting ship's maximum hull
+    pub cargo: Vec<(Item, u32)>,
+    pub blueprints: Vec<Blueprint>,
+    pub known_blueprints: Vec<BlueprintId>, // Crafting blueprints learned from the start
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Storyline {
     pub id: String,
@@ -83,6 +162,57 @@ pub struct Storyline {
     pub total_steps: usize,
     pub completed: bool,
     pub starting_skills: Vec<(SkillCategory, u32)>, // Starting skill categories and levels
+    // One objective per step; completing the current one advances the storyline
+    #[serde(default)]
+    pub objectives: Vec<StorylineObjective>,
+}
+
+// Something the player has to achieve, checked against their current state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ObjectiveGoal {
+    Credits(u32),
+    SystemsVisited(usize),
+    UnitsMined(u32),
+    Reputation(FactionType, i32),
+}
+
+impl ObjectiveGoal {
+    pub fn describe(&self) -> String {
+        match self {
+            ObjectiveGoal::Credits(amount) => format!("Accumulate {} credits", amount),
+            ObjectiveGoal::SystemsVisited(count) => format!("Visit {} systems", count),
+            ObjectiveGoal::UnitsMined(units) => format!("Mine {} units", units),
+            ObjectiveGoal::Reputation(faction, standing) => {
+                format!("Reach {} standing with the {}", standing, faction.to_string())
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorylineObjective {
+    pub goal: ObjectiveGoal,
+    pub reward_credits: u32,
+}
+
+// Objectives for each step of a faction's storylines, getting harder as the
+// story goes on. Each faction alternates between two kinds of goal.
+pub fn default_objectives(faction: &FactionType, total_steps: usize) -> Vec<StorylineObjective> {
+    (1..=total_steps).map(|step| {
+        let n = step as u32;
+        let odd = step % 2 == 1;
+        let goal = match faction {
+            FactionType::Traders if odd => ObjectiveGoal::Credits(10_000 * n),
+            FactionType::Traders => ObjectiveGoal::SystemsVisited(2 * step),
+            FactionType::Miners if odd => ObjectiveGoal::UnitsMined(100 * n),
+            FactionType::Miners => ObjectiveGoal::Credits(5_000 * n),
+            FactionType::Military if odd => ObjectiveGoal::Reputation(FactionType::Military, (10 * step as i32).min(100)),
+            FactionType::Military => ObjectiveGoal::SystemsVisited(2 * step),
+            FactionType::Scientists if odd => ObjectiveGoal::SystemsVisited(3 * step),
+            FactionType::Scientists => ObjectiveGoal::UnitsMined(50 * n),
+        };
+        StorylineObjective { goal, reward_credits: 500 * n }
+    }).collect()
 }
 
 #[allow(dead_code)]
@@ -91,6 +221,7 @@ impl Storyline {
         // Default implementation for backward compatibility
         Self {
             id: id.to_string(),
+            objectives: default_objectives(&faction, total_steps),
             faction,
             name: name.to_string(),
             description: description.to_string(),
@@ -114,6 +245,7 @@ impl Storyline {
     ) -> Self {
         Self {
             id: id.to_string(),
+            objectives: default_objectives(&faction, total_steps),
             faction,
             name: name.to_string(),
             description: description.to_string(),
@@ -138,6 +270,14 @@ impl Storyline {
         true
     }
     
+    // Objective for the step the storyline is on, if it isn't finished
+    pub fn current_objective(&self) -> Option<&StorylineObjective> {
+        if self.completed {
+            return None;
+        }
+        self.objectives.get(self.progress)
+    }
+    
     pub fn get_progress_percentage(&self) -> f32 {
         if self.total_steps == 0 {
             return 0.0;
@@ -0,0 +1,70 @@
+// Ship insurance
+// A policy is bought at a station for a share of the ship's value and runs
+// for a fixed number of game days. If the ship is destroyed while it is in
+// force, the insurer replaces the hull in full for the deductible instead of
+// the player paying a salvage crew. A claim uses the policy up.
+
+use serde::{Serialize, Deserialize};
+use crate::models::ship::Ship;
+
+// Share of the ship's value charged for one term of cover
+const PREMIUM_RATE: f32 = 0.05;
+// Share of the insured value the player still pays on a claim
+const DEDUCTIBLE_RATE: f32 = 0.10;
+// How long a policy runs
+pub const POLICY_TERM_DAYS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InsurancePolicy {
+    // Ship value when the policy was written
+    pub insured_value: u32,
+    pub premium: u32,
+    pub deductible: u32,
+    // Last game day the policy covers
+    pub expires_on_day: u64,
+}
+
+#[allow(dead_code)]
+impl InsurancePolicy {
+    // Terms offered for `ship` on game day `today`
+    pub fn quote(ship: &Ship, today: u64) -> Self {
+        let insured_value = ship.value();
+        InsurancePolicy {
+            insured_value,
+            premium: (insured_value as f32 * PREMIUM_RATE).round() as u32,
+            deductible: (insured_value as f32 * DEDUCTIBLE_RATE).round() as u32,
+            expires_on_day: today + POLICY_TERM_DAYS,
+        }
+    }
+
+    pub fn is_active(&self, today: u64) -> bool {
+        today <= self.expires_on_day
+    }
+
+    // What the insurer puts in after the deductible
+    pub fn payout(&self) -> u32 {
+        self.insured_value.saturating_sub(self.deductible)
+    }
+
+    pub fn days_left(&self, today: u64) -> u64 {
+        self.expires_on_day.saturating_sub(today)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ship::ShipType;
+
+    #[test]
+    fn test_policy_terms_and_expiry() {
+        let ship = Ship::default("Test", ShipType::Freighter);
+        let policy = InsurancePolicy::quote(&ship, 10);
+
+        assert_eq!(policy.insured_value, ship.value());
+        assert!(policy.premium > 0 && policy.premium < policy.deductible);
+        assert_eq!(policy.payout() + policy.deductible, policy.insured_value);
+        assert!(policy.is_active(10 + POLICY_TERM_DAYS));
+        assert!(!policy.is_active(11 + POLICY_TERM_DAYS));
+    }
+}
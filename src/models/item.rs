@@ -1,5 +1,9 @@
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use crate::utils::serde::stack_list_serde;
+use crate::models::ship::ModuleType;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ResourceType {
@@ -23,12 +27,145 @@ pub enum ItemType {
     Fuel,        // Ship fuel for travel
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+impl ItemType {
+    pub fn to_string(&self) -> String {
+        match self {
+            ItemType::Resource(resource) => format!("{:?} Resource", resource),
+            ItemType::Component => "Component".to_string(),
+            ItemType::Product => "Product".to_string(),
+            ItemType::Blueprint => "Blueprint".to_string(),
+            ItemType::Equipment => "Equipment".to_string(),
+            ItemType::ShipModule => "Ship Module".to_string(),
+            ItemType::Fuel => "Fuel".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+#[allow(dead_code)]
+impl Rarity {
+    pub fn to_string(&self) -> String {
+        match self {
+            Rarity::Common => "Common".to_string(),
+            Rarity::Uncommon => "Uncommon".to_string(),
+            Rarity::Rare => "Rare".to_string(),
+            Rarity::Epic => "Epic".to_string(),
+            Rarity::Legendary => "Legendary".to_string(),
+        }
+    }
+
+    // How much more a unit is worth than its common equivalent
+    pub fn value_multiplier(&self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Uncommon => 1.5,
+            Rarity::Rare => 2.5,
+            Rarity::Epic => 5.0,
+            Rarity::Legendary => 10.0,
+        }
+    }
+
+    // Fraction of the usual stock a market carries at this rarity
+    pub fn market_availability(&self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Uncommon => 0.6,
+            Rarity::Rare => 0.3,
+            Rarity::Epic => 0.1,
+            Rarity::Legendary => 0.02,
+        }
+    }
+}
+
+// What an installable item does once fitted to a ship
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleStats {
+    pub module_type: ModuleType,
+    pub rating: u32,
+}
+
+// Per-instance properties of equipment and ship modules. Plain goods leave
+// every field empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemAttributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<ModuleStats>,
+    // Wear left before the item stops working
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub durability: Option<u32>,
+    // Uses left, for consumable equipment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charges: Option<u32>,
+}
+
+#[allow(dead_code)]
+impl ItemAttributes {
+    pub fn module(module_type: ModuleType, rating: u32) -> Self {
+        ItemAttributes { module: Some(ModuleStats { module_type, rating }), ..Self::default() }
+    }
+
+    pub fn with_durability(mut self, durability: u32) -> Self {
+        self.durability = Some(durability);
+        self
+    }
+
+    pub fn with_charges(mut self, charges: u32) -> Self {
+        self.charges = Some(charges);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// Two items are the same, and share an inventory stack, when they have the
+// same name, type and rarity. Value isn't part of it: the same ore mined in
+// different amounts or bought at different prices is still one good.
+// Unique items carry an id and never stack with anything. Attributes
+// aren't part of it either; items that differ in them should be unique.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub name: String,
-    pub value: u32,      // Base value in credits
+    pub value: u32,      // Value in credits, including any rarity premium
     pub weight: u32,     // Weight in cargo units
     pub item_type: ItemType,
+    #[serde(default)]
+    pub rarity: Rarity,
+    // Set on one-of-a-kind items only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unique_id: Option<String>,
+    #[serde(default, skip_serializing_if = "ItemAttributes::is_empty")]
+    pub attributes: ItemAttributes,
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.item_type == other.item_type
+            && self.rarity == other.rarity
+            && self.unique_id == other.unique_id
+    }
+}
+
+impl Eq for Item {}
+
+impl Hash for Item {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.item_type.hash(state);
+        self.rarity.hash(state);
+        self.unique_id.hash(state);
+    }
 }
 
 #[allow(dead_code)]
@@ -39,12 +176,77 @@ impl Item {
             value,
             weight,
             item_type,
+            rarity: Rarity::Common,
+            unique_id: None,
+            attributes: ItemAttributes::default(),
+        }
+    }
+
+    pub fn with_attributes(mut self, attributes: ItemAttributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    // Make this a one-of-a-kind item that stacks with nothing else
+    pub fn unique(mut self) -> Self {
+        self.unique_id = Some(Uuid::new_v4().to_string());
+        self
+    }
+
+    pub fn is_stackable(&self) -> bool {
+        self.unique_id.is_none()
+    }
+
+    // Make this a rarer grade of the item, scaling its common value
+    pub fn with_rarity(mut self, rarity: Rarity) -> Self {
+        self.value = (self.value as f32 * rarity.value_multiplier() / self.rarity.value_multiplier()) as u32;
+        self.rarity = rarity;
+        self
+    }
+
+    // Name with the rarity appended for anything better than common
+    pub fn display_name(&self) -> String {
+        if self.rarity == Rarity::Common {
+            self.name.clone()
+        } else {
+            format!("{} [{}]", self.name, self.rarity.to_string())
+        }
+    }
+}
+
+// Orders the cargo manifest can be listed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InventorySort {
+    #[default]
+    Name,
+    Quantity,  // Largest stacks first
+    Value,     // Most valuable stacks first
+    Type,
+}
+
+impl InventorySort {
+    pub fn next(self) -> Self {
+        match self {
+            InventorySort::Name => InventorySort::Quantity,
+            InventorySort::Quantity => InventorySort::Value,
+            InventorySort::Value => InventorySort::Type,
+            InventorySort::Type => InventorySort::Name,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InventorySort::Name => "Name",
+            InventorySort::Quantity => "Quantity",
+            InventorySort::Value => "Value",
+            InventorySort::Type => "Type",
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Inventory {
+    #[serde(with = "stack_list_serde")]
     pub items: HashMap<Item, u32>,
     pub capacity: u32,
 }
@@ -94,6 +296,20 @@ impl Inventory {
         None
     }
 
+    // Throw away up to `quantity` of one exact stack. Returns how many went.
+    pub fn discard(&mut self, item: &Item, quantity: u32) -> u32 {
+        let held = match self.items.get_mut(item) {
+            Some(held) => held,
+            None => return 0,
+        };
+        let removed = quantity.min(*held);
+        *held -= removed;
+        if *held == 0 {
+            self.items.remove(item);
+        }
+        removed
+    }
+
     pub fn has_item(&self, item_name: &str, quantity: u32) -> bool {
         self.items.iter()
             .any(|(item, qty)| item.name == item_name && *qty >= quantity)
@@ -115,4 +331,53 @@ impl Inventory {
     pub fn remaining_capacity(&self) -> u32 {
         self.capacity - self.used_capacity()
     }
+
+    // Stacks in a stable order for listing, alphabetical by name
+    pub fn sorted_items(&self) -> Vec<(&Item, u32)> {
+        let mut items: Vec<(&Item, u32)> = self.items.iter()
+            .map(|(item, quantity)| (item, *quantity))
+            .collect();
+        items.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        items
+    }
+
+    // Stacks whose name contains `filter` (ignoring case), in `sort` order.
+    // Ties fall back to the name so the order is stable.
+    pub fn view(&self, sort: InventorySort, filter: &str) -> Vec<(&Item, u32)> {
+        let filter = filter.to_lowercase();
+        let mut items: Vec<(&Item, u32)> = self.sorted_items().into_iter()
+            .filter(|(item, _)| item.display_name().to_lowercase().contains(&filter))
+            .collect();
+
+        match sort {
+            InventorySort::Name => {},
+            InventorySort::Quantity => items.sort_by(|a, b| b.1.cmp(&a.1)),
+            InventorySort::Value => items.sort_by(|a, b| (b.0.value * b.1).cmp(&(a.0.value * a.1))),
+            InventorySort::Type => items.sort_by(|a, b| a.0.item_type.to_string().cmp(&b.0.item_type.to_string())),
+        }
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goods_stack_regardless_of_value() {
+        let mut inventory = Inventory::new(100);
+        inventory.add_item(Item::new("Iron", 55, 1, ItemType::Resource(ResourceType::Mineral)), 2);
+        inventory.add_item(Item::new("Iron", 61, 1, ItemType::Resource(ResourceType::Mineral)), 3);
+        assert_eq!(inventory.items.len(), 1);
+        assert_eq!(inventory.get_item_quantity("Iron"), 5);
+
+        let module = Item::new("Pulse Laser", 900, 2, ItemType::ShipModule);
+        inventory.add_item(module.clone().unique(), 1);
+        inventory.add_item(module.unique(), 1);
+        assert_eq!(inventory.items.len(), 3);
+
+        let json = serde_json::to_string(&inventory).unwrap();
+        let loaded: Inventory = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.items, inventory.items);
+    }
 }
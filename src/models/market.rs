@@ -1,9 +1,13 @@
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
-use std::time::Duration;
+use rand::Rng;
+use rand::rngs::StdRng;
 use uuid::Uuid;
 
 use crate::models::item::{Item, ItemType};
+use crate::models::currency::Currency;
+use crate::utils::clock::GameClock;
+use crate::models::player::{Player, TransactionCategory};
 
 // Economic factors that affect market prices
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,6 +31,17 @@ pub enum OrderType {
     Sell        // Sell order
 }
 
+impl OrderType {
+    // Whether `current_price` meets an order's target: at or below it for
+    // buys, at or above it for sells
+    pub fn price_reached(&self, current_price: u32, target_price: u32) -> bool {
+        match self {
+            OrderType::Buy => current_price <= target_price,
+            OrderType::Sell => current_price >= target_price,
+        }
+    }
+}
+
 // Status of the order
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum OrderStatus {
@@ -37,6 +52,22 @@ pub enum OrderStatus {
     Expired     // Order expired (time limit reached)
 }
 
+// What makes an order execute. Everything but a limit order is a sell that
+// protects or cashes in goods the player holds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum OrderKind {
+    Limit,                         // Buy at or below the target, sell at or above it
+    StopLoss,                      // Sell once the price falls to the target
+    TakeProfit,                    // Sell once the price rises to the target
+    TrailingStop { percent: f32 }, // Sell once the price falls this far below its peak
+}
+
+impl Default for OrderKind {
+    fn default() -> Self {
+        OrderKind::Limit
+    }
+}
+
 // Trade order for automatic buying/selling based on price conditions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeOrder {
@@ -52,9 +83,180 @@ pub struct TradeOrder {
     pub created_at: u64,           // When the order was created (timestamp)
     pub expires_at: Option<u64>,   // When the order expires (timestamp, optional)
     pub executed_at: Option<u64>,  // When the order was executed (timestamp, if completed)
+    #[serde(default)]
+    pub executed_price: Option<u32>, // Unit price the order actually filled at, if completed
+    #[serde(default)]
+    pub kind: OrderKind,           // What triggers the order
+    #[serde(default)]
+    pub peak_price: Option<u32>,   // Highest price seen since a trailing stop was placed (see track_peak)
     pub notes: String,             // Additional notes/comments for the order
 }
 
+impl TradeOrder {
+    // Whether the order should execute at `current_price`. Trailing stops
+    // first follow a new peak up, so this takes the order mutably.
+    pub fn check_trigger(&mut self, current_price: u32) -> bool {
+        match self.kind {
+            OrderKind::Limit => self.order_type.price_reached(current_price, self.target_price),
+            OrderKind::StopLoss => current_price <= self.target_price,
+            OrderKind::TakeProfit => current_price >= self.target_price,
+            OrderKind::TrailingStop { .. } => {
+                self.track_peak(current_price);
+                current_price <= self.target_price
+            }
+        }
+    }
+    
+    // Move a trailing stop's peak, and its stop price with it, up to
+    // `current_price`. The peak only sees the prices it is shown: the
+    // market tick shows it every price (Market::track_trailing_stops), and
+    // process_orders shows it the price when its owner's orders are checked.
+    pub fn track_peak(&mut self, current_price: u32) {
+        if let OrderKind::TrailingStop { percent } = self.kind {
+            let peak = self.peak_price.map_or(current_price, |peak| peak.max(current_price));
+            self.peak_price = Some(peak);
+            self.target_price = trailing_stop_price(peak, percent);
+        }
+    }
+    
+    // Short name for order lists
+    pub fn kind_label(&self) -> &'static str {
+        match (self.kind, &self.order_type) {
+            (OrderKind::Limit, OrderType::Buy) => "BUY",
+            (OrderKind::Limit, OrderType::Sell) => "SELL",
+            (OrderKind::StopLoss, _) => "STOP",
+            (OrderKind::TakeProfit, _) => "TAKE",
+            (OrderKind::TrailingStop { .. }, _) => "TRAIL",
+        }
+    }
+}
+
+// Where a trailing stop `percent` below `peak` sits
+fn trailing_stop_price(peak: u32, percent: f32) -> u32 {
+    (peak as f32 * (1.0 - percent.clamp(0.0, 100.0) / 100.0)) as u32
+}
+
+// Which way a price has to cross an alert's threshold
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AlertDirection {
+    Above, // Watches the bid, for a good time to sell
+    Below, // Watches the ask, for a good time to buy
+}
+
+impl AlertDirection {
+    // The order an alert stands in for, so it triggers like one would
+    fn order_type(&self) -> OrderType {
+        match self {
+            AlertDirection::Above => OrderType::Sell,
+            AlertDirection::Below => OrderType::Buy,
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            AlertDirection::Above => AlertDirection::Below,
+            AlertDirection::Below => AlertDirection::Above,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertDirection::Above => "above",
+            AlertDirection::Below => "below",
+        }
+    }
+}
+
+// A price level the player wants to hear about, without trading. One-shot
+// alerts are dropped once they fire; repeating ones re-arm when the price
+// moves back across the threshold.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceAlert {
+    pub system_id: String,
+    pub item_name: String,
+    pub direction: AlertDirection,
+    pub threshold: u32,
+    pub repeat: bool,
+    pub armed: bool,
+}
+
+#[allow(dead_code)]
+impl PriceAlert {
+    pub fn new(system_id: &str, item_name: &str, direction: AlertDirection, threshold: u32, repeat: bool) -> Self {
+        PriceAlert {
+            system_id: system_id.to_string(),
+            item_name: item_name.to_string(),
+            direction,
+            threshold,
+            repeat,
+            armed: true,
+        }
+    }
+
+    // The side of the spread this alert watches
+    pub fn watched_price(&self, item: &MarketItem) -> u32 {
+        item.price_for(&self.direction.order_type())
+    }
+
+    pub fn is_triggered(&self, price: u32) -> bool {
+        self.direction.order_type().price_reached(price, self.threshold)
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} {} {} cr{}", self.item_name, self.direction.label(), self.threshold,
+            if self.repeat { " (repeating)" } else { "" })
+    }
+}
+
+// Prices and stock recorded when the player last left a market:
+// (item name, price, quantity)
+pub type MarketSnapshot = Vec<(String, u32, u32)>;
+
+// Change in one item between a snapshot and the current market.
+// `None` on the old side means the item is new, on the new side that it's gone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceDelta {
+    pub item_name: String,
+    pub old_price: Option<u32>,
+    pub new_price: Option<u32>,
+    pub old_quantity: Option<u32>,
+    pub new_quantity: Option<u32>,
+}
+
+#[allow(dead_code)]
+impl PriceDelta {
+    pub fn is_new(&self) -> bool {
+        self.old_price.is_none()
+    }
+
+    pub fn is_gone(&self) -> bool {
+        self.new_price.is_none()
+    }
+
+    // Price change in percent, if the item was listed on both visits
+    pub fn price_change_percent(&self) -> Option<f32> {
+        match (self.old_price, self.new_price) {
+            (Some(old), Some(new)) if old > 0 => Some((new as f32 - old as f32) / old as f32 * 100.0),
+            _ => None,
+        }
+    }
+
+    pub fn quantity_change(&self) -> i64 {
+        self.new_quantity.unwrap_or(0) as i64 - self.old_quantity.unwrap_or(0) as i64
+    }
+
+    // Short description for the market header, e.g. "Iron +12%"
+    pub fn summary(&self) -> String {
+        if self.is_new() {
+            format!("{} new", self.item_name)
+        } else if self.is_gone() {
+            format!("{} gone", self.item_name)
+        } else {
+            format!("{} {:+.0}%", self.item_name, self.price_change_percent().unwrap_or(0.0))
+        }
+    }
+}
+
 // Record of price changes for displaying trends
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceHistory {
@@ -62,6 +264,47 @@ pub struct PriceHistory {
     pub price: u32,
 }
 
+// Game seconds over which player trade volume adds up
+const VOLUME_WINDOW_SECS: u64 = 3600;
+const PRICE_DAY_SECS: u64 = 86400;
+// Volume in one window at which trades move the price half as much as usual
+const HALF_IMPACT_VOLUME: f32 = 50.0;
+// Most a single trade may move the price
+const MAX_TRADE_PRICE_MOVE: f32 = 0.2;
+// Most trades may move the price away from where it opened the game day
+const MAX_DAILY_PRICE_MOVE: f32 = 0.5;
+
+// Gap between buying and selling prices as a share of the mid price. Thin
+// stock and volatile prices widen it from the minimum.
+const MIN_SPREAD: f32 = 0.02;
+const MAX_SPREAD: f32 = 0.5;
+// Stock level at which the thin-market widening is halved
+const LIQUID_STOCK: f32 = 100.0;
+
+// Recent player trading in one item, used to stop a single trader pumping
+// or dumping a thin market
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeVolume {
+    pub window_start: u64,
+    pub units: u32,          // Units traded since window_start
+    pub day_start: u64,
+    pub day_open_price: u32, // Price when the current game day began; 0 until the first trade
+}
+
+impl TradeVolume {
+    fn record(&mut self, units: u32, now: u64, current_price: u32) {
+        if now < self.window_start || now - self.window_start >= VOLUME_WINDOW_SECS {
+            self.window_start = now;
+            self.units = 0;
+        }
+        if self.day_open_price == 0 || now < self.day_start || now - self.day_start >= PRICE_DAY_SECS {
+            self.day_start = now - now % PRICE_DAY_SECS;
+            self.day_open_price = current_price;
+        }
+        self.units = self.units.saturating_add(units);
+    }
+}
+
 // Enhanced market item with more economic factors
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketItem {
@@ -75,6 +318,86 @@ pub struct MarketItem {
     pub price_history: Vec<PriceHistory>, // Track recent price points
     pub production_rate: u32,      // How many units are produced per cycle
     pub consumption_rate: u32,     // How many units are consumed per cycle
+    #[serde(default)]
+    pub trade_volume: TradeVolume,
+}
+
+#[allow(dead_code)]
+impl MarketItem {
+    // Spread around `current_price`, which is the mid price
+    pub fn spread(&self) -> f32 {
+        let thinness = LIQUID_STOCK / (LIQUID_STOCK + self.quantity as f32);
+        (MIN_SPREAD + (0.06 + self.price_volatility * 0.4) * thinness).min(MAX_SPREAD)
+    }
+
+    // What a trader pays the market per unit (the ask)
+    pub fn buy_price(&self) -> u32 {
+        (self.current_price as f32 * (1.0 + self.spread() / 2.0)).ceil() as u32
+    }
+
+    // What the market pays a trader per unit (the bid)
+    pub fn sell_price(&self) -> u32 {
+        (self.current_price as f32 * (1.0 - self.spread() / 2.0)) as u32
+    }
+
+    // The side of the spread an order of `order_type` trades at
+    pub fn price_for(&self, order_type: &OrderType) -> u32 {
+        match order_type {
+            OrderType::Buy => self.buy_price(),
+            OrderType::Sell => self.sell_price(),
+        }
+    }
+
+    // Share of its usual effect the next trade has on the price: 1.0 in a
+    // quiet window, falling as more units change hands
+    pub fn price_impact(&self) -> f32 {
+        1.0 / (1.0 + self.trade_volume.units as f32 / HALF_IMPACT_VOLUME)
+    }
+
+    // Shift supply by `supply_step` (e.g. -0.02 after a buy) and reprice
+    // after players trade `units`. Heavy recent volume dampens the move,
+    // and the price never strays too far from the day's opening price.
+    fn apply_trade(&mut self, units: u32, supply_step: f32, clock: &GameClock) {
+        self.trade_volume.record(units, clock.now(), self.current_price);
+        let impact = self.price_impact();
+
+        let supply = self.supply_level * (1.0 + supply_step * impact);
+        self.supply_level = if supply_step < 0.0 { supply.max(0.5) } else { supply.min(1.5) };
+
+        // Calculate new price based on base price and economic factors
+        let supply_factor = 2.0 - self.supply_level; // Invert supply (less supply = higher price)
+        let target = self.base_price as f32 * supply_factor * self.demand_level;
+
+        let current = self.current_price as f32;
+        let max_change = current * MAX_TRADE_PRICE_MOVE * impact;
+        let moved = (current + (target - current) * impact).clamp(current - max_change, current + max_change);
+
+        let open = self.trade_volume.day_open_price as f32;
+        let daily = moved.clamp(open * (1.0 - MAX_DAILY_PRICE_MOVE), open * (1.0 + MAX_DAILY_PRICE_MOVE));
+        self.current_price = daily.round() as u32;
+
+        // Also update the item's value to match
+        self.item.value = self.current_price;
+
+        self.price_history.push(PriceHistory {
+            timestamp: clock.now(),
+            price: self.current_price,
+        });
+
+        // Keep history size manageable (last 10 price points)
+        if self.price_history.len() > 10 {
+            self.price_history.remove(0);
+        }
+    }
+}
+
+// What a trade would cost or pay at the market as it stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeQuote {
+    pub unit_price: u32,
+    pub subtotal: u32,  // Units times the unit price
+    pub tax: u32,
+    pub total: u32,     // Paid for a buy or received for a sale, tax included
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -88,6 +411,21 @@ pub enum MarketType {
     Military,       // Military outpost, weapons and defense equipment
 }
 
+impl MarketType {
+    // What the market sells cheaply and buys dear, in a few words
+    pub fn specialty(&self) -> &'static str {
+        match self {
+            MarketType::Trading => "balanced prices",
+            MarketType::Industrial => "cheap components, buys ore",
+            MarketType::Mining => "cheap ore, buys components",
+            MarketType::Agricultural => "cheap food and organics",
+            MarketType::HighTech => "cheap modules and equipment",
+            MarketType::Black => "illegal goods, volatile prices",
+            MarketType::Military => "cheap equipment",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Market {
     pub system_id: String,
@@ -97,6 +435,21 @@ pub struct Market {
     pub market_type: MarketType,
     pub tax_rate: f32,             // Local tax rate applied to transactions
     pub trade_orders: Vec<TradeOrder>, // Active trade orders in this market
+    #[serde(default)]
+    pub currency: Currency,        // What prices here are quoted and paid in
+    #[serde(default = "default_credits_per_unit")]
+    pub credits_per_unit: f32,     // Value of one unit of `currency` in credits
+    #[serde(default)]
+    pub maker_spread: Option<f32>, // Spread an NPC market maker sells core commodities at once stock runs out; None without one
+}
+
+// Goods an NPC market maker stands behind: raw resources and fuel
+pub fn is_core_commodity(item_type: &ItemType) -> bool {
+    matches!(item_type, ItemType::Resource(_) | ItemType::Fuel)
+}
+
+fn default_credits_per_unit() -> f32 {
+    1.0
 }
 
 #[allow(dead_code)]
@@ -110,6 +463,9 @@ impl Market {
             market_type: MarketType::Trading, // Default to trading market
             tax_rate: 0.05, // 5% default tax rate
             trade_orders: Vec::new(), // No orders initially
+            currency: Currency::Credits,
+            credits_per_unit: 1.0,
+            maker_spread: None,
         }
     }
 
@@ -131,7 +487,18 @@ impl Market {
         market
     }
 
-    pub fn add_item(&mut self, item: Item, quantity: u32, base_price: u32, volatility: f32) {
+    // Key a grade of an item is stocked under: its name for common goods,
+    // name and rarity for rarer grades, which trade as their own line
+    pub fn stock_key(item: &Item) -> String {
+        item.display_name()
+    }
+
+    // `base_price` and `quantity` are for a common-grade item; rarer grades
+    // are priced up and stocked more thinly
+    pub fn add_item(&mut self, item: Item, quantity: u32, base_price: u32, volatility: f32, clock: &GameClock) {
+        let base_price = (base_price as f32 * item.rarity.value_multiplier()) as u32;
+        let quantity = ((quantity as f32 * item.rarity.market_availability()).ceil() as u32).min(quantity);
+        
         // Adjust volatility based on market type and item type
         let adjusted_volatility = match self.market_type {
             MarketType::Black => volatility * 2.0,  // Black markets are twice as volatile
@@ -190,10 +557,7 @@ impl Market {
             _ => base_price,
         };
         
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
         
         let market_item = MarketItem {
             item: item.clone(),
@@ -209,78 +573,74 @@ impl Market {
             }],
             production_rate: production,
             consumption_rate: consumption,
+            trade_volume: TradeVolume::default(),
         };
         
-        self.items.insert(item.name.clone(), market_item);
+        self.items.insert(Self::stock_key(&item), market_item);
+    }
+
+    // The market maker's ask for `item`, never better than the market's
+    // own. None if no maker serves this market or it doesn't trade the item.
+    fn maker_ask(&self, item: &MarketItem) -> Option<u32> {
+        let spread = self.maker_spread?;
+        if !is_core_commodity(&item.item.item_type) {
+            return None;
+        }
+        let ask = (item.current_price as f32 * (1.0 + spread / 2.0)).ceil() as u32;
+        Some(ask.max(item.buy_price()))
     }
 
-    pub fn buy_item(&mut self, item_name: &str, quantity: u32) -> Option<(Item, u32, u32)> {
-        // First check if we have the item and enough quantity
+    // Cost of buying `quantity` of `item_name` at the current ask plus
+    // local tax, without touching the market. None if it isn't sold here;
+    // stock isn't checked. Units past the stock are priced at the market
+    // maker's ask where one would sell them.
+    pub fn quote_buy(&self, item_name: &str, quantity: u32) -> Option<TradeQuote> {
+        let item = self.items.get(item_name)?;
+        let from_maker = quantity.saturating_sub(item.quantity);
+        let (unit_price, subtotal) = match self.maker_ask(item) {
+            Some(ask) if from_maker > 0 => {
+                let subtotal = item.buy_price() * (quantity - from_maker) + ask * from_maker;
+                ((subtotal as f32 / quantity as f32).ceil() as u32, subtotal)
+            },
+            _ => (item.buy_price(), item.buy_price() * quantity),
+        };
+        let tax = (subtotal as f32 * self.tax_rate) as u32;
+        Some(TradeQuote { unit_price, subtotal, tax, total: subtotal + tax })
+    }
+
+    // What selling `quantity` of `item` here would pay, without touching
+    // the market. Sales aren't taxed.
+    pub fn quote_sell(&self, item: &Item, quantity: u32) -> TradeQuote {
+        let unit_price = self.sell_price(item);
+        let subtotal = unit_price * quantity;
+        TradeQuote { unit_price, subtotal, tax: 0, total: subtotal }
+    }
+
+    pub fn buy_item(&mut self, item_name: &str, quantity: u32, clock: &GameClock) -> Option<(Item, u32, u32)> {
+        // First check if we have the item and enough quantity, or a market
+        // maker to make up the difference
         let can_fulfill = self.items.get(item_name)
-            .map(|item| item.quantity >= quantity)
+            .map(|item| item.quantity >= quantity || self.maker_ask(item).is_some())
             .unwrap_or(false);
             
         if !can_fulfill {
             return None;
         }
         
-        // Get the current price and calculate costs
-        let (base_cost, current_price) = {
-            let item = self.items.get(item_name).unwrap(); // Safe because we checked above
-            let price = item.current_price;
-            (price * quantity, price)
-        };
-        
-        let tax = (base_cost as f32 * self.tax_rate) as u32;
-        let total_cost = base_cost + tax;
+        // Price the purchase before it moves the market
+        let quote = self.quote_buy(item_name, quantity)?;
+        let total_cost = quote.total;
         
         // Now modify the item
         if let Some(market_item) = self.items.get_mut(item_name) {
-            // Reduce available quantity
-            market_item.quantity -= quantity;
+            // Reduce available quantity; the maker supplies any shortfall
+            market_item.quantity = market_item.quantity.saturating_sub(quantity);
             
             // After purchase, decrease supply (more scarcity)
-            market_item.supply_level = (market_item.supply_level * 0.98).max(0.5);
-            
-            // Calculate new price based on supply and demand
-            let supply_factor = 2.0 - market_item.supply_level; // Invert supply (less supply = higher price)
-            let demand_factor = market_item.demand_level;
-            
-            // Calculate new price based on base price and economic factors
-            let new_price = (market_item.base_price as f32 * 
-                          supply_factor * 
-                          demand_factor) as u32;
-            
-            // Ensure price doesn't change too drastically
-            let max_change_pct = 0.2; // Max 20% change from current price
-            let current = market_item.current_price as f32;
-            let max_up = current * (1.0 + max_change_pct);
-            let max_down = current * (1.0 - max_change_pct);
-            
-            market_item.current_price = new_price.max(max_down as u32).min(max_up as u32);
-            
-            // Also update the item's value to match
-            market_item.item.value = market_item.current_price;
-            
-            // Record the price history
-            let current_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or(Duration::from_secs(0))
-                .as_secs();
-                
-            market_item.price_history.push(PriceHistory {
-                timestamp: current_time,
-                price: market_item.current_price,
-            });
-            
-            // Keep history size manageable (last 10 price points)
-            if market_item.price_history.len() > 10 {
-                market_item.price_history.remove(0);
-            }
+            market_item.apply_trade(quantity, -0.02, clock);
             
-            // Clone the item to return it
-            let mut item = market_item.item.clone();
-            item.value = current_price; // Set the value to the price at time of purchase
+            // The goods keep their catalogue value; what was paid is in the total
+            let item = market_item.item.clone();
             
             return Some((item, quantity, total_cost));
         }
@@ -288,80 +648,53 @@ impl Market {
         None // Should never reach here but needed for completeness
     }
 
-    pub fn sell_item(&mut self, item: Item, quantity: u32) -> u32 {
-        // Calculate sell price (base value minus market margin)
-        let (sell_price, exists_in_market) = match self.items.get(&item.name) {
+    // What the market pays per unit of `item`, at its grade
+    pub fn sell_price(&self, item: &Item) -> u32 {
+        if let Some(market_item) = self.items.get(&Self::stock_key(item)) {
+            // Items the market trades sell at its bid
+            return market_item.sell_price();
+        }
+        match self.items.get(&item.name) {
+            // Another grade is traded here: scale its bid by the grades' values
             Some(market_item) => {
-                // If item exists in market, use its current price with a sell margin
-                let margin = match self.market_type {
-                    MarketType::Black => 0.15, // Black markets take 15% margin
-                    MarketType::Trading => 0.05, // Trading hubs only take 5%
-                    _ => 0.10, // Standard 10% margin
-                };
-                ((market_item.current_price as f32 * (1.0 - margin)) as u32, true)
+                let ratio = item.rarity.value_multiplier() / market_item.item.rarity.value_multiplier();
+                (market_item.sell_price() as f32 * ratio) as u32
             },
             None => {
                 // If item doesn't exist, use its intrinsic value
-                ((item.value as f32 * 0.85) as u32, false) // Standard 15% markdown
+                (item.value as f32 * 0.85 / self.credits_per_unit) as u32 // Standard 15% markdown
             }
-        };
-        
-        let revenue = sell_price * quantity;
+        }
+    }
+
+    pub fn sell_item(&mut self, item: Item, quantity: u32, clock: &GameClock) -> u32 {
+        let revenue = self.quote_sell(&item, quantity).total;
+        let key = Self::stock_key(&item);
+        let exists_in_market = self.items.contains_key(&key);
         
         if exists_in_market {
             // Get a mutable reference to the item
-            if let Some(market_item) = self.items.get_mut(&item.name) {
+            if let Some(market_item) = self.items.get_mut(&key) {
                 // Increase available quantity
                 market_item.quantity += quantity;
                 
                 // After sale, increase supply and decrease price slightly
-                market_item.supply_level = (market_item.supply_level * 1.02).min(1.5);
-                
-                // Update the price directly here instead of calling self.update_item_price
-                // to avoid a second mutable borrow
-                let supply_factor = 2.0 - market_item.supply_level; // Invert supply (less supply = higher price)
-                let demand_factor = market_item.demand_level;
-                
-                // Calculate new price based on base price and economic factors
-                let new_price = (market_item.base_price as f32 * 
-                              supply_factor * 
-                              demand_factor) as u32;
-                
-                // Ensure price doesn't change too drastically
-                let max_change_pct = 0.2; // Max 20% change from current price
-                let current = market_item.current_price as f32;
-                let max_up = current * (1.0 + max_change_pct);
-                let max_down = current * (1.0 - max_change_pct);
-                
-                market_item.current_price = new_price.max(max_down as u32).min(max_up as u32);
-                
-                // Also update the item's value to match
-                market_item.item.value = market_item.current_price;
-                
-                // Record the price history
-                let current_time = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or(Duration::from_secs(0))
-                    .as_secs();
-                    
-                market_item.price_history.push(PriceHistory {
-                    timestamp: current_time,
-                    price: market_item.current_price,
-                });
-                
-                // Keep history size manageable (last 10 price points)
-                if market_item.price_history.len() > 10 {
-                    market_item.price_history.remove(0);
-                }
+                market_item.apply_trade(quantity, 0.02, clock);
             }
         } else {
             // Add new item to market if it wasn't there before
             self.add_item(
                 item.clone(), 
                 quantity, 
-                (item.value as f32 * 0.9) as u32, // Base price at 90% of item value
-                0.1 // Default volatility
+                // Base price at 90% of the item's common-grade value
+                (item.value as f32 * 0.9 / item.rarity.value_multiplier()) as u32,
+                0.1, // Default volatility
+                clock,
             );
+            // What was sold is what's in stock, however thinly the grade is usually carried
+            if let Some(market_item) = self.items.get_mut(&key) {
+                market_item.quantity = quantity;
+            }
         }
         
         revenue
@@ -369,7 +702,7 @@ impl Market {
 
     // Update the price of a specific item based on supply and demand
     #[allow(dead_code)]
-    fn update_item_price(&mut self, item_name: &str) {
+    fn update_item_price(&mut self, item_name: &str, clock: &GameClock) {
         if let Some(market_item) = self.items.get_mut(item_name) {
             // Calculate price adjustment based on supply and demand
             let supply_factor = 2.0 - market_item.supply_level; // Invert supply (less supply = higher price)
@@ -392,10 +725,7 @@ impl Market {
             market_item.item.value = market_item.current_price;
             
             // Record the price history
-            let current_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or(Duration::from_secs(0))
-                .as_secs();
+            let current_time = clock.now();
                 
             market_item.price_history.push(PriceHistory {
                 timestamp: current_time,
@@ -409,8 +739,10 @@ impl Market {
         }
     }
 
-    pub fn update_market(&mut self, game_time: u64) {
-        self.last_update = game_time;
+    // All randomness comes from the caller's RNG so that a seeded economy
+    // evolves the same way every run
+    pub fn update_market(&mut self, clock: &GameClock, rng: &mut StdRng) {
+        self.last_update = clock.now();
         
         // Process economic events
         for event in &self.local_events.clone() { // Clone to avoid borrowing issues
@@ -423,8 +755,16 @@ impl Market {
         
         // First pass: update supply, demand, and consumption
         {
-            let items = &mut self.items;
-            for (item_name, market_item) in items.iter_mut() {
+            // Walk items in a stable order so RNG draws line up between runs
+            let mut item_names: Vec<String> = self.items.keys().cloned().collect();
+            item_names.sort();
+            
+            for item_name in item_names {
+                let market_item = match self.items.get_mut(&item_name) {
+                    Some(market_item) => market_item,
+                    None => continue,
+                };
+                
                 // Simulate production
                 if market_item.production_rate > 0 {
                     market_item.quantity += market_item.production_rate;
@@ -446,8 +786,8 @@ impl Market {
                 }
                 
                 // Apply random market fluctuation based on volatility
-                let random_factor = rand::random::<f32>() * market_item.price_volatility * 0.2;
-                if rand::random::<bool>() {
+                let random_factor = rng.gen::<f32>() * market_item.price_volatility * 0.2;
+                if rng.gen::<bool>() {
                     market_item.supply_level = (market_item.supply_level * (1.0 + random_factor)).min(2.0);
                 } else {
                     market_item.supply_level = (market_item.supply_level * (1.0 - random_factor)).max(0.5);
@@ -471,17 +811,14 @@ impl Market {
                 let new_price = new_price.max(max_down as u32).min(max_up as u32);
                 
                 // Store the item_name and new price for the second pass
-                item_updates.push((item_name.clone(), new_price));
+                item_updates.push((item_name, new_price));
                 
                 // Update the item's value
                 market_item.current_price = new_price;
                 market_item.item.value = new_price;
                 
                 // Record the price history
-                let current_time = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or(Duration::from_secs(0))
-                    .as_secs();
+                let current_time = clock.now();
                     
                 market_item.price_history.push(PriceHistory {
                     timestamp: current_time,
@@ -495,69 +832,15 @@ impl Market {
             }
         }
         
-        // Update trade orders - check if any orders should be executed based on current prices
-        let items_copy = self.items.clone(); // Clone to avoid borrowing issues
-        let order_current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
-            
-        for order in &mut self.trade_orders {
-            if order.status != OrderStatus::Active {
-                continue;
-            }
-            
-            // Get the current market price for the item
-            if let Some(market_item) = items_copy.get(&order.item_name) {
-                let current_price = market_item.current_price;
-                
-                match order.order_type {
-                    OrderType::Buy => {
-                        // Execute buy orders when price falls below or equals target price
-                        if current_price <= order.target_price {
-                            order.status = OrderStatus::Completed;
-                            order.executed_at = Some(order_current_time);
-                            
-                            // In a real implementation, we would transfer money and goods here
-                            // For now, we just mark the order as completed
-                        }
-                    },
-                    OrderType::Sell => {
-                        // Execute sell orders when price rises above or equals target price
-                        if current_price >= order.target_price {
-                            order.status = OrderStatus::Completed;
-                            order.executed_at = Some(order_current_time);
-                            
-                            // In a real implementation, we would transfer money and goods here
-                            // For now, we just mark the order as completed
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Mark expired orders
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
-            
-        for order in &mut self.trade_orders {
-            if order.status != OrderStatus::Active {
-                continue;
-            }
-            
-            // Check for expiration
-            if let Some(expires_at) = order.expires_at {
-                if current_time > expires_at {
-                    order.status = OrderStatus::Cancelled;
-                }
-            }
-        }
+        // Trade orders are left alone here: process_orders is the one place
+        // they complete or expire, since completing one means settling it
+        // against its owner's credits and hold. Trailing stops still follow
+        // the new prices, so an offline owner's stop doesn't lag behind.
+        self.track_trailing_stops();
         
         // Randomly generate new economic events (5% chance per update)
-        if rand::random::<f32>() < 0.05 {
-            self.generate_random_event();
+        if rng.gen::<f32>() < 0.05 {
+            self.generate_random_event(rng);
         }
         
         // Clear old events (events only last for a limited time)
@@ -641,18 +924,19 @@ impl Market {
         }
     }
     
-    fn generate_random_event(&mut self) {
+    fn generate_random_event(&mut self, rng: &mut StdRng) {
         // Get a random item from the market
         if self.items.is_empty() {
             return;
         }
         
-        let item_names: Vec<String> = self.items.keys().cloned().collect();
-        let random_index = rand::random::<usize>() % item_names.len();
+        let mut item_names: Vec<String> = self.items.keys().cloned().collect();
+        item_names.sort();
+        let random_index = rng.gen_range(0..item_names.len());
         let random_item = &item_names[random_index];
         
         // Generate a random event type
-        let event_roll = rand::random::<f32>();
+        let event_roll = rng.gen::<f32>();
         let event = if event_roll < 0.15 {
             EconomicEvent::Shortage(random_item.clone())
         } else if event_roll < 0.30 {
@@ -679,10 +963,74 @@ impl Market {
     }
     
     // Get price trend information for an item
+    // Record current prices and stock so a later visit can be compared
+    pub fn snapshot(&self) -> MarketSnapshot {
+        let mut snapshot: MarketSnapshot = self.items.iter()
+            .map(|(name, market_item)| (name.clone(), market_item.current_price, market_item.quantity))
+            .collect();
+        snapshot.sort();
+        snapshot
+    }
+
+    // Compare the market against an earlier snapshot. Only items whose price
+    // or stock changed (or that appeared/disappeared) are reported.
+    pub fn diff_against(&self, snapshot: &[(String, u32, u32)]) -> Vec<PriceDelta> {
+        let mut deltas = Vec::new();
+
+        for (item_name, old_price, old_quantity) in snapshot {
+            match self.items.get(item_name) {
+                Some(market_item) => {
+                    if market_item.current_price != *old_price || market_item.quantity != *old_quantity {
+                        deltas.push(PriceDelta {
+                            item_name: item_name.clone(),
+                            old_price: Some(*old_price),
+                            new_price: Some(market_item.current_price),
+                            old_quantity: Some(*old_quantity),
+                            new_quantity: Some(market_item.quantity),
+                        });
+                    }
+                },
+                None => {
+                    deltas.push(PriceDelta {
+                        item_name: item_name.clone(),
+                        old_price: Some(*old_price),
+                        new_price: None,
+                        old_quantity: Some(*old_quantity),
+                        new_quantity: None,
+                    });
+                }
+            }
+        }
+
+        // Items that weren't there last time
+        for (item_name, market_item) in &self.items {
+            if !snapshot.iter().any(|(name, _, _)| name == item_name) {
+                deltas.push(PriceDelta {
+                    item_name: item_name.clone(),
+                    old_price: None,
+                    new_price: Some(market_item.current_price),
+                    old_quantity: None,
+                    new_quantity: Some(market_item.quantity),
+                });
+            }
+        }
+
+        deltas.sort_by(|a, b| a.item_name.cmp(&b.item_name));
+        deltas
+    }
+
+    // Recorded prices for an item, oldest first
+    pub fn get_price_history(&self, item_name: &str) -> Vec<u32> {
+        self.items.get(item_name)
+            .map(|item| item.price_history.iter().map(|point| point.price).collect())
+            .unwrap_or_default()
+    }
+    
     pub fn get_price_trend(&self, item_name: &str) -> Option<(f32, String)> {
         if let Some(market_item) = self.items.get(item_name) {
+            // Not enough history to call a trend
             if market_item.price_history.len() < 2 {
-                return Some((0.0, "Stable".to_string()));
+                return None;
             }
             
             let latest = market_item.price_history.last().unwrap().price as f32;
@@ -715,16 +1063,13 @@ impl Market {
     // === Trade Order Methods ===
     
     // Create a new buy order
-    pub fn create_buy_order(&mut self, player_id: &str, item_name: &str, quantity: u32, target_price: u32, expires_at: Option<u64>, notes: &str) -> Option<Uuid> {
+    pub fn create_buy_order(&mut self, player_id: &str, item_name: &str, quantity: u32, target_price: u32, expires_at: Option<u64>, notes: &str, clock: &GameClock) -> Option<Uuid> {
         // Verify item exists in market
         if !self.items.contains_key(item_name) {
             return None;
         }
         
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         let order_id = Uuid::new_v4();
         
@@ -741,6 +1086,9 @@ impl Market {
             created_at: current_time,
             expires_at,
             executed_at: None,
+            executed_price: None,
+            kind: OrderKind::Limit,
+            peak_price: None,
             notes: notes.to_string(),
         };
         
@@ -749,18 +1097,22 @@ impl Market {
     }
     
     // Create a new sell order
-    pub fn create_sell_order(&mut self, player_id: &str, item_name: &str, quantity: u32, target_price: u32, expires_at: Option<u64>, notes: &str) -> Option<Uuid> {
+    pub fn create_sell_order(&mut self, player_id: &str, item_name: &str, quantity: u32, target_price: u32, expires_at: Option<u64>, notes: &str, clock: &GameClock) -> Option<Uuid> {
+        self.create_sell_order_of_kind(player_id, item_name, quantity, OrderKind::Limit, target_price, expires_at, notes, clock)
+    }
+    
+    // Create a sell order that executes the way `kind` says. A trailing
+    // stop ignores `target_price` and starts its percentage below the
+    // current bid, or below `target_price` if the market doesn't trade the item.
+    pub fn create_sell_order_of_kind(&mut self, player_id: &str, item_name: &str, quantity: u32, kind: OrderKind, target_price: u32, expires_at: Option<u64>, notes: &str, clock: &GameClock) -> Option<Uuid> {
         // For sell orders, we don't need to verify if the item exists in the market
         // since the player could be selling something not currently available
         
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         let order_id = Uuid::new_v4();
         
-        let order = TradeOrder {
+        let mut order = TradeOrder {
             id: order_id,
             player_id: player_id.to_string(),
             system_id: self.system_id.clone(),
@@ -768,14 +1120,26 @@ impl Market {
             order_type: OrderType::Sell,
             quantity,
             target_price,
-            price_condition: "above".to_string(),
+            price_condition: match kind {
+                OrderKind::Limit | OrderKind::TakeProfit => "above",
+                OrderKind::StopLoss | OrderKind::TrailingStop { .. } => "below",
+            }.to_string(),
             status: OrderStatus::Active,
             created_at: current_time,
             expires_at,
             executed_at: None,
+            executed_price: None,
+            kind,
+            peak_price: None,
             notes: notes.to_string(),
         };
         
+        // A trailing stop starts out following the current bid
+        if let OrderKind::TrailingStop { .. } = kind {
+            let peak = self.items.get(item_name).map_or(target_price, |item| item.sell_price());
+            order.check_trigger(peak);
+        }
+        
         self.trade_orders.push(order);
         Some(order_id)
     }
@@ -799,21 +1163,34 @@ impl Market {
             .collect()
     }
     
-    // Process all active orders and execute any that meet their conditions
-    pub fn process_orders(&mut self, player_inventory: &mut HashMap<Item, u32>, player_credits: &mut u32) -> Vec<TradeOrder> {
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+    // Follow the current bid with every active trailing stop, whether or
+    // not its owner is around to have their orders processed
+    pub fn track_trailing_stops(&mut self) {
+        for order in self.trade_orders.iter_mut().filter(|order| order.status == OrderStatus::Active) {
+            if let Some(item) = self.items.get(&order.item_name) {
+                order.track_peak(item.price_for(&order.order_type));
+            }
+        }
+    }
+    
+    // Execute the active orders `player` placed that meet their conditions,
+    // settling each against the player's hold and, through the ledger, their
+    // balance in this market's currency. An order only completes once it has
+    // settled.
+    pub fn process_orders(&mut self, player: &mut Player, clock: &GameClock) -> Vec<TradeOrder> {
+        let current_time = clock.now();
+        let player_id = player.id.as_str();
+        let balance = player.balance(&self.currency);
             
         // First, collect all orders that need to be processed
         let mut orders_to_process = Vec::new();
         let mut orders_to_mark_expired = Vec::new();
         
-        // Gather info without mutably borrowing self
-        for (order_idx, order) in self.trade_orders.iter().enumerate() {
-            // Skip if not active
-            if order.status != OrderStatus::Active {
+        // Gather info before touching the market; checking a trailing stop
+        // can move its stop price up
+        for (order_idx, order) in self.trade_orders.iter_mut().enumerate() {
+            // Skip if not active or someone else's
+            if order.status != OrderStatus::Active || order.player_id != player_id {
                 continue;
             }
             
@@ -827,38 +1204,33 @@ impl Market {
             
             // Get current price of the item
             if let Some(market_item) = self.items.get(&order.item_name) {
-                let current_price = market_item.current_price;
+                let current_price = market_item.price_for(&order.order_type);
                 let market_quantity = market_item.quantity;
                 
                 match order.order_type {
                     OrderType::Buy => {
                         // Execute if price falls below target price
-                        if current_price <= order.target_price {
+                        if order.check_trigger(current_price) {
                             // Calculate total cost
                             let base_cost = current_price * order.quantity;
                             let tax = (base_cost as f32 * self.tax_rate) as u32;
                             let total_cost = base_cost + tax;
                             
                             // Check if the player has enough credits and market has enough quantity
-                            if *player_credits >= total_cost && market_quantity >= order.quantity {
-                                orders_to_process.push((
-                                    order_idx,
-                                    order.clone(),
-                                    total_cost,
-                                    None
-                                ));
+                            if balance >= total_cost && market_quantity >= order.quantity {
+                                orders_to_process.push((order_idx, order.clone(), None));
                             }
                         }
                     },
                     OrderType::Sell => {
-                        // Execute if price rises above target price
-                        if current_price >= order.target_price {
+                        // Execute once the order's trigger is reached
+                        if order.check_trigger(current_price) {
                             // Find matching item in player inventory
                             let mut item_to_sell = None;
                             let mut available_quantity = 0;
                             
                             // Check if player has the item to sell
-                            for (item, quantity) in player_inventory.iter() {
+                            for (item, quantity) in player.inventory.items.iter() {
                                 if item.name == order.item_name {
                                     item_to_sell = Some(item.clone());
                                     available_quantity = *quantity;
@@ -869,15 +1241,7 @@ impl Market {
                             if let Some(item) = item_to_sell {
                                 // Check if player has enough quantity
                                 if available_quantity >= order.quantity {
-                                    // Calculate expected revenue (will be more accurate when we actually sell)
-                                    let expected_revenue = (current_price as f32 * 0.9) as u32 * order.quantity;
-                                    
-                                    orders_to_process.push((
-                                        order_idx,
-                                        order.clone(),
-                                        expected_revenue,
-                                        Some(item)
-                                    ));
+                                    orders_to_process.push((order_idx, order.clone(), Some(item)));
                                 }
                             }
                         }
@@ -889,7 +1253,7 @@ impl Market {
         // Now mark expired orders
         for order_idx in orders_to_mark_expired {
             if let Some(order) = self.trade_orders.get_mut(order_idx) {
-                order.status = OrderStatus::Cancelled;
+                order.status = OrderStatus::Expired;
             }
         }
         
@@ -898,21 +1262,37 @@ impl Market {
         
         // Now process the orders that meet the conditions
         // We can do this without borrowing self.trade_orders at the same time
-        for (order_idx, order, price, item_to_sell) in orders_to_process {
+        for (order_idx, order, item_to_sell) in orders_to_process {
             match order.order_type {
                 OrderType::Buy => {
+                    // Fill at the price now, which earlier fills this round
+                    // may have moved since the order triggered
+                    let quote = match self.quote_buy(&order.item_name, order.quantity) {
+                        Some(quote) if quote.total <= player.balance(&self.currency) => quote,
+                        _ => continue,
+                    };
+                    
+                    // An order the hold can't take fails rather than overfill it
+                    let weight = self.items.get(&order.item_name).map_or(0, |market_item| market_item.item.weight);
+                    if player.inventory.remaining_capacity() < weight * order.quantity {
+                        if let Some(order) = self.trade_orders.get_mut(order_idx) {
+                            order.status = OrderStatus::Failed;
+                        }
+                        continue;
+                    }
+                    
                     // Execute the buy order - this will modify market state
-                    if let Some((item, quantity, _)) = self.buy_item(&order.item_name, order.quantity) {
-                        // Deduct cost from player credits
-                        *player_credits -= price;
-                        
-                        // Add item to player inventory
-                        *player_inventory.entry(item).or_insert(0) += quantity;
+                    if let Some((item, quantity, total_cost)) = self.buy_item(&order.item_name, order.quantity, clock) {
+                        // Charge the player, then add the item to their hold
+                        let description = format!("Buy order filled: {} {}", quantity, item.name);
+                        let _ = player.adjust_balance(&self.currency, -(total_cost as i64), TransactionCategory::Trade, &description, clock);
+                        player.inventory.add_item(item, quantity);
                         
                         // Mark order as completed - but we need to do it after all processing
                         if let Some(order) = self.trade_orders.get_mut(order_idx) {
                             order.status = OrderStatus::Completed;
                             order.executed_at = Some(current_time);
+                            order.executed_price = Some(quote.unit_price);
                             
                             // Track the completed order
                             executed_orders.push(order.clone());
@@ -922,23 +1302,18 @@ impl Market {
                 OrderType::Sell => {
                     if let Some(item) = item_to_sell {
                         // Execute the sell order
-                        let revenue = self.sell_item(item.clone(), order.quantity);
+                        let revenue = self.sell_item(item.clone(), order.quantity, clock);
                         
-                        // Add revenue to player credits
-                        *player_credits += revenue;
-                        
-                        // Reduce player inventory
-                        if let Some(qty) = player_inventory.get_mut(&item) {
-                            *qty -= order.quantity;
-                            if *qty == 0 {
-                                player_inventory.remove(&item);
-                            }
-                        }
+                        // Pay the player and take the goods out of their hold
+                        let description = format!("Sell order filled: {} {}", order.quantity, item.name);
+                        let _ = player.adjust_balance(&self.currency, revenue as i64, TransactionCategory::Trade, &description, clock);
+                        player.inventory.remove_item(&item.name, order.quantity);
                         
                         // Mark order as completed
                         if let Some(order) = self.trade_orders.get_mut(order_idx) {
                             order.status = OrderStatus::Completed;
                             order.executed_at = Some(current_time);
+                            order.executed_price = Some(revenue / order.quantity.max(1));
                             
                             // Track the completed order
                             executed_orders.push(order.clone());
@@ -952,3 +1327,201 @@ impl Market {
         executed_orders
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::{Item, ItemType, ResourceType};
+
+    #[test]
+    fn test_spread_widens_for_thin_volatile_goods() {
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 1000, 100, 0.05, &clock);
+        market.add_item(Item::new("Xenite", 100, 1, ItemType::Resource(ResourceType::Exotic)), 5, 100, 0.8, &clock);
+
+        let iron = &market.items["Iron"];
+        let xenite = &market.items["Xenite"];
+        assert!(iron.sell_price() < iron.current_price && iron.current_price < iron.buy_price());
+        assert!(xenite.spread() > iron.spread());
+
+        // Buying and selling straight back always loses the spread
+        let (item, quantity, cost) = market.buy_item("Iron", 1, &clock).unwrap();
+        assert!(market.sell_item(item, quantity, &clock) < cost);
+    }
+
+    #[test]
+    fn test_repeated_buys_cannot_pump_price_past_daily_cap() {
+        let clock = GameClock::at(1000);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 10_000, 100, 0.1, &clock);
+        let opening = market.items["Iron"].current_price;
+
+        market.buy_item("Iron", 10, &clock).unwrap();
+        let first_move = market.items["Iron"].current_price - opening;
+
+        for _ in 0..200 {
+            market.buy_item("Iron", 10, &clock).unwrap();
+        }
+        let iron = &market.items["Iron"];
+        assert!(iron.price_impact() < 0.1);
+        assert!(iron.current_price as f32 <= opening as f32 * (1.0 + MAX_DAILY_PRICE_MOVE));
+
+        // Late trades in a busy window barely move the price
+        let before = iron.current_price;
+        market.buy_item("Iron", 10, &clock).unwrap();
+        assert!(market.items["Iron"].current_price - before <= first_move);
+    }
+
+    #[test]
+    fn test_quotes_match_trades_and_leave_the_market_alone() {
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 1000, 100, 0.05, &clock);
+        let price = market.items["Iron"].current_price;
+
+        let buy = market.quote_buy("Iron", 10).unwrap();
+        assert_eq!(buy.total, buy.subtotal + buy.tax);
+        assert!(buy.tax > 0);
+        assert_eq!(market.items["Iron"].current_price, price);
+        assert_eq!(market.items["Iron"].quantity, 1000);
+        assert!(market.quote_buy("Gold", 1).is_none());
+
+        let (item, quantity, cost) = market.buy_item("Iron", 10, &clock).unwrap();
+        assert_eq!(cost, buy.total);
+
+        let sell = market.quote_sell(&item, quantity);
+        assert_eq!(market.sell_item(item, quantity, &clock), sell.total);
+    }
+
+    #[test]
+    fn test_filled_orders_record_the_price_they_filled_at() {
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 1000, 100, 0.05, &clock);
+        let target = market.items["Iron"].buy_price() * 2;
+        market.create_buy_order("player", "Iron", 50, target, None, "", &clock).unwrap();
+        market.create_buy_order("player", "Iron", 50, target, None, "", &clock).unwrap();
+
+        let mut player = Player::new("Trader");
+        player.id = "player".to_string();
+        player.credits = 100_000;
+        player.inventory.capacity = 100;
+        let executed = market.process_orders(&mut player, &clock);
+
+        // The first fill moves the price the second one pays
+        assert_eq!(executed.len(), 2);
+        let first = executed[0].executed_price.unwrap();
+        let second = executed[1].executed_price.unwrap();
+        assert!(first < second && second <= target);
+        let paid = 100_000 - player.credits;
+        assert!(paid >= (first + second) * 50);
+        
+        // Both fills went through the ledger
+        let charged: i64 = player.ledger.iter().map(|entry| entry.amount).sum();
+        assert_eq!(charged, -(paid as i64));
+        assert_eq!(player.stats.trades_completed, 2);
+    }
+
+    #[test]
+    fn test_rarer_grades_sell_at_their_own_price_and_stock() {
+        use crate::models::item::Rarity;
+
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        let iron = Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral));
+        market.add_item(iron.clone(), 1000, 100, 0.05, &clock);
+        let rare = iron.clone().with_rarity(Rarity::Rare);
+
+        assert!(market.quote_sell(&rare, 1).unit_price > market.quote_sell(&iron, 1).unit_price * 2);
+        market.sell_item(rare.clone(), 3, &clock);
+        assert_eq!(market.items["Iron"].quantity, 1000);
+        assert_eq!(market.items[&Market::stock_key(&rare)].quantity, 3);
+        assert_eq!(market.items[&Market::stock_key(&rare)].item.rarity, Rarity::Rare);
+    }
+
+    #[test]
+    fn test_orders_fail_when_the_hold_is_full_and_expire_on_time() {
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 10_000, 100, 0.05, &clock);
+        let target = market.items["Iron"].buy_price() * 2;
+        let mut player = Player::new("Trader");
+        player.id = "player".to_string();
+        player.credits = 10_000_000;
+        let too_much = player.inventory.remaining_capacity() + 1;
+        let oversized = market.create_buy_order("player", "Iron", too_much, target, None, "", &clock).unwrap();
+        let expiring = market.create_buy_order("player", "Iron", 1, target, Some(10), "", &clock).unwrap();
+
+        assert!(market.process_orders(&mut player, &GameClock::at(20)).is_empty());
+        let status = |id: Uuid| market.trade_orders.iter().find(|order| order.id == id).unwrap().status.clone();
+        assert_eq!(status(oversized), OrderStatus::Failed);
+        assert_eq!(status(expiring), OrderStatus::Expired);
+        assert_eq!(player.inventory.used_capacity(), 0);
+        assert_eq!(player.credits, 10_000_000);
+    }
+
+    #[test]
+    fn test_orders_only_complete_when_settled_by_their_owner() {
+        use rand::SeedableRng;
+
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 1000, 100, 0.05, &clock);
+        let target = market.items["Iron"].buy_price() * 2;
+        market.create_buy_order("owner", "Iron", 10, target, None, "", &clock).unwrap();
+
+        // A market update doesn't complete orders it can't settle
+        market.update_market(&clock, &mut StdRng::seed_from_u64(1));
+        assert_eq!(market.trade_orders[0].status, OrderStatus::Active);
+
+        // Nor can another player's wallet settle them
+        let mut other = Player::new("Other");
+        other.id = "other".to_string();
+        other.credits = 100_000;
+        assert!(market.process_orders(&mut other, &clock).is_empty());
+        assert_eq!(other.credits, 100_000);
+
+        let mut owner = Player::new("Owner");
+        owner.id = "owner".to_string();
+        owner.credits = 100_000;
+        let executed = market.process_orders(&mut owner, &clock);
+        assert_eq!(executed.len(), 1);
+        assert!(owner.credits < 100_000);
+        assert_eq!(owner.inventory.get_item_quantity("Iron"), 10);
+    }
+
+    #[test]
+    fn test_stop_orders_trigger_on_their_own_conditions() {
+        let clock = GameClock::at(0);
+        let mut market = Market::new("test");
+        market.add_item(Item::new("Iron", 100, 1, ItemType::Resource(ResourceType::Mineral)), 1000, 100, 0.05, &clock);
+        let bid = market.items["Iron"].sell_price();
+
+        // A trailing stop rides the price up and sells once it falls back
+        let trail = market.create_sell_order_of_kind("player", "Iron", 1, OrderKind::TrailingStop { percent: 10.0 }, 0, None, "", &clock).unwrap();
+        let order = market.trade_orders.iter_mut().find(|order| order.id == trail).unwrap();
+        assert_eq!(order.peak_price, Some(bid));
+        assert!(!order.check_trigger(bid * 2));
+        assert_eq!(order.target_price, trailing_stop_price(bid * 2, 10.0));
+        assert!(!order.check_trigger(bid * 2 * 95 / 100));
+        assert!(order.check_trigger(bid * 2 * 85 / 100));
+
+        // It also trails a rally that happens while nobody checks it
+        market.trade_orders.clear();
+        market.create_sell_order_of_kind("player", "Iron", 1, OrderKind::TrailingStop { percent: 10.0 }, 0, None, "", &clock).unwrap();
+        market.items.get_mut("Iron").unwrap().current_price *= 3;
+        let rallied = market.items["Iron"].sell_price();
+        market.track_trailing_stops();
+        assert_eq!(market.trade_orders[0].peak_price, Some(rallied));
+        assert_eq!(market.trade_orders[0].target_price, trailing_stop_price(rallied, 10.0));
+
+        // A stop-loss only sells below its target, a take-profit only above
+        market.create_sell_order_of_kind("player", "Iron", 1, OrderKind::StopLoss, 90, None, "", &clock).unwrap();
+        market.create_sell_order_of_kind("player", "Iron", 1, OrderKind::TakeProfit, 120, None, "", &clock).unwrap();
+        let mut stop = market.trade_orders[1].clone();
+        let mut take = market.trade_orders[2].clone();
+        assert!(!stop.check_trigger(100) && stop.check_trigger(90));
+        assert!(!take.check_trigger(100) && take.check_trigger(120));
+    }
+}
@@ -0,0 +1,259 @@
+// Data-driven market contents
+// Markets are stocked from a definition file: random draws from item groups
+// for every market, plus fixed stock for particular systems or market types.
+// The embedded default reproduces the game's original random markets.
+
+use std::collections::HashMap;
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::models::item::{Item, ItemAttributes, ItemType, Rarity};
+use crate::models::market::MarketType;
+use crate::models::currency::FactionCurrency;
+
+const DEFAULT_DEFINITION: &str = include_str!("default_markets.json");
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_volatility() -> f32 {
+    0.1
+}
+
+// Name and type of an item a random group can draw
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ItemTemplate {
+    pub name: String,
+    pub item_type: ItemType,
+}
+
+// Every market draws `count` items from `items` (repeats allowed), each
+// with a price and quantity from the given inclusive ranges
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StockGroup {
+    pub count: (u32, u32),
+    pub price: (u32, u32),
+    pub quantity: (u32, u32),
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(default)]
+    pub rarity: Rarity,
+    pub items: Vec<ItemTemplate>,
+}
+
+// An item stocked at a set price and quantity
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FixedStock {
+    pub name: String,
+    pub item_type: ItemType,
+    pub base_price: u32,
+    pub quantity: u32,
+    #[serde(default = "default_volatility")]
+    pub volatility: f32,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(default)]
+    pub rarity: Rarity,
+}
+
+impl FixedStock {
+    pub fn to_item(&self) -> Item {
+        Item {
+            name: self.name.clone(),
+            value: self.base_price,
+            weight: self.weight,
+            item_type: self.item_type.clone(),
+            rarity: self.rarity,
+            unique_id: None,
+            attributes: ItemAttributes::default(),
+        }
+    }
+}
+
+// Fixed stock added to every market of one type
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarketTypeStock {
+    pub market_type: MarketType,
+    pub items: Vec<FixedStock>,
+}
+
+// Fixed stock for one system, on top of its random stock or in place of it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SystemStock {
+    #[serde(default)]
+    pub replace_random: bool,
+    pub items: Vec<FixedStock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarketDefinition {
+    pub random_stock: Vec<StockGroup>,
+    #[serde(default)]
+    pub market_types: Vec<MarketTypeStock>,
+    // Keyed by system id
+    #[serde(default)]
+    pub systems: HashMap<String, SystemStock>,
+    // Faction scrip and the systems pricing in it; none means credits everywhere
+    #[serde(default)]
+    pub currencies: Vec<FactionCurrency>,
+}
+
+impl Default for MarketDefinition {
+    fn default() -> Self {
+        Self::parse(DEFAULT_DEFINITION).expect("embedded market definition is valid")
+    }
+}
+
+#[allow(dead_code)]
+impl MarketDefinition {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let definition: MarketDefinition = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid market definition: {}", e))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    // Read and validate a JSON definition file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read market definition {}: {}", path, e))?;
+        Self::parse(&content)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, group) in self.random_stock.iter().enumerate() {
+            if group.items.is_empty() {
+                return Err(format!("Random stock group {} has no items", index));
+            }
+            for (label, (min, max)) in [("count", group.count), ("price", group.price), ("quantity", group.quantity)] {
+                if min > max {
+                    return Err(format!("Random stock group {} has {} range {}-{}", index, label, min, max));
+                }
+            }
+        }
+        for currency in &self.currencies {
+            if currency.credits_per_unit <= 0.0 {
+                return Err(format!("{} must be worth more than 0 credits", currency.currency().symbol()));
+            }
+        }
+        Ok(())
+    }
+
+    // Stock for a freshly generated market in `system_id`. Draws come in the
+    // same order as they always have, so a seed keeps giving the same markets.
+    pub fn generate_stock<R: Rng + ?Sized>(&self, system_id: &str, rng: &mut R) -> Vec<(Item, u32)> {
+        let system = self.systems.get(system_id);
+        let mut items = Vec::new();
+
+        if !system.map_or(false, |system| system.replace_random) {
+            for group in &self.random_stock {
+                let count = draw(group.count, rng);
+                for _ in 0..count {
+                    let template = if group.items.len() == 1 {
+                        &group.items[0]
+                    } else {
+                        &group.items[rng.gen_range(0..group.items.len())]
+                    };
+                    let price = draw(group.price, rng);
+                    let quantity = draw(group.quantity, rng);
+
+                    items.push((Item {
+                        name: template.name.clone(),
+                        value: price,
+                        weight: group.weight,
+                        item_type: template.item_type.clone(),
+                        rarity: group.rarity,
+                        unique_id: None,
+                        attributes: ItemAttributes::default(),
+                    }, quantity));
+                }
+            }
+        }
+
+        // Fixed stock replaces any random draws of the same item
+        for fixed in system.map(|system| system.items.as_slice()).unwrap_or(&[]) {
+            items.retain(|(item, _)| item.name != fixed.name);
+            items.push((fixed.to_item(), fixed.quantity));
+        }
+
+        items
+    }
+
+    // Fixed stock for a market of `market_type` in `system_id`; system
+    // entries come last so they win over market-type ones
+    pub fn fixed_stock_for(&self, system_id: &str, market_type: &MarketType) -> Vec<&FixedStock> {
+        self.market_types.iter()
+            .filter(|stock| &stock.market_type == market_type)
+            .flat_map(|stock| stock.items.iter())
+            .chain(self.systems.get(system_id).into_iter().flat_map(|system| system.items.iter()))
+            .collect()
+    }
+
+    // Scrip the market in `system_id` prices in, if any
+    pub fn currency_for(&self, system_id: &str) -> Option<&FactionCurrency> {
+        self.currencies.iter()
+            .find(|currency| currency.systems.iter().any(|system| system == system_id))
+    }
+
+    // Volatility set for an item in a system's fixed stock
+    pub fn volatility_for(&self, system_id: &str, item_name: &str) -> Option<f32> {
+        self.systems.get(system_id)?
+            .items.iter()
+            .find(|fixed| fixed.name == item_name)
+            .map(|fixed| fixed.volatility)
+    }
+}
+
+// Value from an inclusive range; fixed ranges use no randomness
+fn draw<R: Rng + ?Sized>((min, max): (u32, u32), rng: &mut R) -> u32 {
+    if min == max {
+        min
+    } else {
+        rng.gen_range(min..=max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::universe::{Universe, UniverseConfig};
+    use crate::systems::economy::EconomySystem;
+    use crate::utils::clock::GameClock;
+
+    const SCENARIO: &str = r#"{
+        "random_stock": [
+            { "count": [1, 2], "price": [10, 20], "quantity": [5, 5], "items": [{ "name": "Ice", "item_type": { "Resource": "Ice" } }] }
+        ],
+        "market_types": [
+            { "market_type": "Mining", "items": [{ "name": "Drill Bit", "item_type": "Component", "base_price": 300, "quantity": 12 }] }
+        ],
+        "systems": {
+            "sol": { "replace_random": true, "items": [{ "name": "Relic", "item_type": "Product", "base_price": 5000, "quantity": 1, "volatility": 0.6 }] }
+        }
+    }"#;
+
+    #[test]
+    fn test_definition_file_stocks_universe_and_economy() {
+        let path = std::env::temp_dir().join(format!("markets_test_{}.json", std::process::id()));
+        std::fs::write(&path, SCENARIO).unwrap();
+        let universe = Universe::with_config(UniverseConfig {
+            system_count: 10,
+            market_definition_file: Some(path.to_string_lossy().to_string()),
+            ..UniverseConfig::default()
+        }).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let sol = universe.get_market("sol").unwrap();
+        assert_eq!(sol.items.len(), 1);
+        assert_eq!(sol.items["Relic"].base_price, 5000);
+        assert_eq!(sol.items["Relic"].price_volatility, 0.6);
+
+        let mut economy = EconomySystem::new();
+        economy.initialize_system_market("outpost", MarketType::Mining);
+        economy.apply_market_definition(universe.market_definition(), &GameClock::default());
+        assert_eq!(economy.system_markets["outpost"].items["Drill Bit"].quantity, 12);
+
+        assert!(MarketDefinition::parse(r#"{ "random_stock": [{ "count": [2, 1], "price": [1, 1], "quantity": [1, 1], "items": [] }] }"#).is_err());
+    }
+}
@@ -2,9 +2,15 @@ pub mod player;
 pub mod ship;
 pub mod universe;
 pub mod market;
+pub mod market_definition;
 pub mod item;
 pub mod skills;
 pub mod blueprint;
 pub mod faction;
 pub mod account;
 pub mod player_market;
+pub mod crew;
+pub mod currency;
+pub mod stats;
+pub mod insurance;
+pub mod news;
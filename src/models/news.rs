@@ -0,0 +1,169 @@
+// Market news
+// Economic events move prices for a while and then blow over: a shortage of
+// one good in one system, or a crash felt in every market. Each event is
+// reported when it breaks and stays in the news until it ends, and only
+// running events move prices.
+
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::models::item::{Item, ItemType};
+use crate::models::market::EconomicEvent;
+
+// How long an event moves prices, in game seconds
+pub const EVENT_DURATION: u64 = 24 * 3600;
+// Most reports kept, running or ended; the oldest are dropped first
+pub const NEWS_HISTORY: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketNews {
+    // System the event hit, or None for one felt across the galaxy
+    pub system_id: Option<String>,
+    pub event: EconomicEvent,
+    // Game time the event broke and ends, in seconds
+    pub started_at: u64,
+    pub ends_at: u64,
+}
+
+#[allow(dead_code)]
+impl MarketNews {
+    pub fn new(system_id: Option<String>, event: EconomicEvent, started_at: u64) -> Self {
+        MarketNews {
+            system_id,
+            event,
+            started_at,
+            ends_at: started_at + EVENT_DURATION,
+        }
+    }
+
+    // Roll a random event for one of `system_ids`, or occasionally for the
+    // whole galaxy. Item events pick from `item_names`.
+    pub fn random(system_ids: &[String], item_names: &[String], now: u64, rng: &mut StdRng) -> Option<Self> {
+        let system_id = system_ids.choose(rng)?.clone();
+        let item = item_names.choose(rng).cloned();
+        let roll = rng.gen::<f32>();
+        let (system_id, event) = match (roll, item) {
+            (r, Some(item)) if r < 0.2 => (Some(system_id), EconomicEvent::Shortage(item)),
+            (r, Some(item)) if r < 0.4 => (Some(system_id), EconomicEvent::Surplus(item)),
+            (r, Some(item)) if r < 0.55 => (Some(system_id), EconomicEvent::HighDemand(item)),
+            (r, Some(item)) if r < 0.7 => (Some(system_id), EconomicEvent::LowDemand(item)),
+            (r, _) if r < 0.75 => (Some(system_id), EconomicEvent::TariffIncrease),
+            (r, _) if r < 0.8 => (Some(system_id), EconomicEvent::TariffDecrease),
+            (r, _) if r < 0.85 => (None, EconomicEvent::MarketCrash),
+            (r, _) if r < 0.9 => (None, EconomicEvent::MarketBoom),
+            (r, _) if r < 0.95 => (Some(system_id), EconomicEvent::LocalConflict),
+            _ => (Some(system_id), EconomicEvent::LocalPeace),
+        };
+        Some(Self::new(system_id, event, now))
+    }
+
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.ends_at
+    }
+
+    // Whether the event is felt in `system_id`'s market
+    pub fn affects(&self, system_id: &str) -> bool {
+        self.system_id.as_deref().map_or(true, |id| id == system_id)
+    }
+
+    // The good the event is about, for events about a single good
+    pub fn item_name(&self) -> Option<&str> {
+        match &self.event {
+            EconomicEvent::Shortage(item)
+            | EconomicEvent::Surplus(item)
+            | EconomicEvent::HighDemand(item)
+            | EconomicEvent::LowDemand(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    // Percent the event moves the prices it touches. Conflict and peace
+    // move equipment (possibly military) this way and civilian products
+    // the other.
+    fn percent(&self) -> i32 {
+        match &self.event {
+            EconomicEvent::Shortage(_) | EconomicEvent::MarketBoom => 30,
+            EconomicEvent::Surplus(_) | EconomicEvent::MarketCrash => -30,
+            EconomicEvent::HighDemand(_) | EconomicEvent::LocalConflict => 20,
+            EconomicEvent::LowDemand(_) | EconomicEvent::LocalPeace => -20,
+            EconomicEvent::TariffIncrease => 10,
+            EconomicEvent::TariffDecrease => -10,
+        }
+    }
+
+    // Percent the event moves `item`'s price, 0 for goods it leaves alone
+    pub fn price_effect(&self, item: &Item) -> i32 {
+        let percent = self.percent();
+        match &self.event {
+            EconomicEvent::LocalConflict | EconomicEvent::LocalPeace => match item.item_type {
+                ItemType::Equipment => percent,
+                ItemType::Product => -percent,
+                _ => 0,
+            },
+            _ => match self.item_name() {
+                Some(name) if name != item.name => 0,
+                _ => percent,
+            },
+        }
+    }
+
+    // One-line report, e.g. "Shortage of Titanium in Sirius — prices up 30%".
+    // `system_name` is the display name of the system hit, None for
+    // galaxy-wide events.
+    pub fn headline(&self, system_name: Option<&str>) -> String {
+        let place = match system_name {
+            Some(name) => format!("in {}", name),
+            None => "across the galaxy".to_string(),
+        };
+        let percent = self.percent();
+        let direction = |percent: i32| if percent > 0 { "up" } else { "down" };
+        let story = match &self.event {
+            EconomicEvent::Shortage(item) => format!("Shortage of {} {}", item, place),
+            EconomicEvent::Surplus(item) => format!("Surplus of {} {}", item, place),
+            EconomicEvent::HighDemand(item) => format!("Demand for {} soars {}", item, place),
+            EconomicEvent::LowDemand(item) => format!("Demand for {} slumps {}", item, place),
+            EconomicEvent::TariffIncrease => format!("Tariffs raised {}", place),
+            EconomicEvent::TariffDecrease => format!("Tariffs cut {}", place),
+            EconomicEvent::MarketCrash => format!("Markets crash {}", place),
+            EconomicEvent::MarketBoom => format!("Markets boom {}", place),
+            EconomicEvent::LocalConflict | EconomicEvent::LocalPeace => {
+                let story = if percent > 0 { "Conflict flares" } else { "Peace settles" };
+                return format!(
+                    "{} {} — equipment {} {}%, products {} {}%",
+                    story, place, direction(percent), percent.abs(), direction(-percent), percent.abs()
+                );
+            },
+        };
+        format!("{} — prices {} {}%", story, direction(percent), percent.abs())
+    }
+}
+
+// Price multiplier from every running event in `news` felt in `system_id`
+#[allow(dead_code)]
+pub fn price_factor(news: &[MarketNews], system_id: &str, item: &Item, now: u64) -> f32 {
+    news.iter()
+        .filter(|report| report.is_active(now) && report.affects(system_id))
+        .map(|report| 1.0 + report.price_effect(item) as f32 / 100.0)
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortage_raises_price_until_it_ends() {
+        let titanium = Item::new("Titanium", 100, 1, ItemType::Component);
+        let news = vec![MarketNews::new(
+            Some("sirius".to_string()),
+            EconomicEvent::Shortage("Titanium".to_string()),
+            1000,
+        )];
+
+        assert_eq!(news[0].headline(Some("Sirius")), "Shortage of Titanium in Sirius — prices up 30%");
+        assert!((price_factor(&news, "sirius", &titanium, 1000) - 1.3).abs() < 1e-6);
+        assert_eq!(price_factor(&news, "sol", &titanium, 1000), 1.0);
+        assert_eq!(price_factor(&news, "sirius", &titanium, 1000 + EVENT_DURATION), 1.0);
+    }
+}
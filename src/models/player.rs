@@ -1,12 +1,21 @@
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 
 use crate::models::ship::{Ship, ShipType};
 use crate::models::universe::StarSystem;
-use crate::models::item::Inventory;
-use crate::models::faction::{FactionType, Storyline};
+use crate::models::item::{Inventory, Item};
+use crate::models::currency::Currency;
+use crate::models::stats::PlayerStats;
+use crate::models::insurance::InsurancePolicy;
+use crate::models::faction::{self, FactionType, ObjectiveGoal, Storyline};
 use crate::models::skills::SkillSet;
 use crate::models::blueprint::BlueprintLibrary;
+use crate::models::market::{Market, MarketSnapshot, OrderStatus, OrderType, PriceAlert};
+use crate::systems::economy::EconomySystem;
+use crate::systems::crafting::BlueprintId;
+use crate::utils::serde::entry_list_map_serde;
+use crate::utils::clock::GameClock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Character {
@@ -53,6 +62,83 @@ impl Character {
     }
 }
 
+// Oldest ledger entries are dropped once the ledger grows past this
+const LEDGER_CAPACITY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionCategory {
+    Trade,
+    Refuel,
+    Repair,
+    Crew,
+    Craft,
+    Mission,
+    Bounty,
+    Piracy,
+    Rescue,
+    Escrow, // Held for, or returned from, a player market deal
+    Insurance, // Premiums and claim deductibles
+    Research, // Paid to research stations for blueprint research
+    Adjustment, // Made by a server administrator
+}
+
+impl TransactionCategory {
+    pub fn to_string(&self) -> String {
+        match self {
+            TransactionCategory::Trade => "Trade".to_string(),
+            TransactionCategory::Refuel => "Refuel".to_string(),
+            TransactionCategory::Repair => "Repair".to_string(),
+            TransactionCategory::Crew => "Crew".to_string(),
+            TransactionCategory::Craft => "Craft".to_string(),
+            TransactionCategory::Mission => "Mission".to_string(),
+            TransactionCategory::Bounty => "Bounty".to_string(),
+            TransactionCategory::Piracy => "Piracy".to_string(),
+            TransactionCategory::Rescue => "Rescue".to_string(),
+            TransactionCategory::Escrow => "Escrow".to_string(),
+            TransactionCategory::Insurance => "Insurance".to_string(),
+            TransactionCategory::Research => "Research".to_string(),
+            TransactionCategory::Adjustment => "Adjustment".to_string(),
+        }
+    }
+}
+
+// One change to the player's credits. Income is positive, spending negative.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub timestamp: u64, // Game time, in seconds
+    pub amount: i64,
+    pub category: TransactionCategory,
+    pub description: String,
+}
+
+// Credits and cargo set aside for one player market deal (an accepted bid
+// or contract). Held value can't be spent or sold until the deal settles
+// and goes to the other party, or fails and comes back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowHold {
+    pub deal_id: String,
+    pub credits: u32,
+    pub items: Vec<(Item, u32)>,
+}
+
+// What the player is worth, broken down by where the value sits
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetWorth {
+    pub credits: u64,
+    pub cargo: u64,     // Inventory at fair market price
+    pub listings: u64,  // Unsold units on the player market at their asking price
+    pub escrow: u64,    // Credits and cargo held for unsettled market deals
+    // Credits and cargo promised to active buy/sell orders. Orders don't
+    // hold anything in escrow, so this is already part of credits and cargo.
+    pub order_commitments: u64,
+}
+
+impl NetWorth {
+    pub fn total(&self) -> u64 {
+        self.credits + self.cargo + self.listings + self.escrow
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub id: String,
@@ -65,6 +151,40 @@ pub struct Player {
     pub skills: SkillSet,
     pub blueprints: BlueprintLibrary,
     pub last_update: Option<f64>, // Serializable timestamp for real-time events
+    // Market prices seen when last undocking, keyed by system id
+    #[serde(default)]
+    pub visited_market_snapshots: HashMap<String, MarketSnapshot>,
+    // Standing with each faction; missing factions are neutral (0)
+    #[serde(default)]
+    pub reputation: HashMap<FactionType, i32>,
+    // Every credit change, oldest first
+    #[serde(default)]
+    pub ledger: Vec<LedgerEntry>,
+    // Ids of every system the player has been to, for storyline objectives
+    #[serde(default)]
+    pub visited_systems: HashSet<String>,
+    // Total resource units ever mined
+    #[serde(default)]
+    pub units_mined: u32,
+    // Price levels to be told about, across any markets
+    #[serde(default)]
+    pub price_alerts: Vec<PriceAlert>,
+    // Value reserved for accepted market deals, one hold per deal
+    #[serde(default)]
+    pub escrow: Vec<EscrowHold>,
+    // Balances in faction scrip. Credits stay in `credits`, so a player
+    // who never touches scrip has an empty map.
+    #[serde(default, with = "entry_list_map_serde")]
+    pub currencies: HashMap<Currency, u32>,
+    // Lifetime totals and the achievements they have unlocked
+    #[serde(default)]
+    pub stats: PlayerStats,
+    // Cover against losing the ship, if any has been bought
+    #[serde(default)]
+    pub insurance: Option<InsurancePolicy>,
+    // Crafting blueprints the player can build from
+    #[serde(default)]
+    pub known_blueprints: Vec<BlueprintId>,
 }
 
 #[allow(dead_code)]
@@ -87,6 +207,7 @@ impl Player {
             resource_fields: Vec::new(),
             stations: Vec::new(),
             resources: vec![],
+            connections: Vec::new(),
         };
 
         // Default character with Traders faction
@@ -113,9 +234,123 @@ impl Player {
             skills: SkillSet::new(),
             blueprints: BlueprintLibrary::new(),
             last_update: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs_f64()),
+            visited_market_snapshots: HashMap::new(),
+            reputation: HashMap::new(),
+            ledger: Vec::new(),
+            visited_systems: HashSet::from(["sol".to_string()]),
+            units_mined: 0,
+            price_alerts: Vec::new(),
+            escrow: Vec::new(),
+            currencies: HashMap::new(),
+            stats: PlayerStats::default(),
+            insurance: None,
+            known_blueprints: Vec::new(),
+        }
+    }
+    
+    pub fn record_visit(&mut self, system_id: &str) {
+        self.visited_systems.insert(system_id.to_string());
+    }
+    
+    pub fn record_mined(&mut self, units: u32) {
+        self.units_mined = self.units_mined.saturating_add(units);
+        self.stats.units_mined += units as u64;
+    }
+    
+    // How far the player is towards a goal, as (current, target)
+    pub fn objective_progress(&self, goal: &ObjectiveGoal) -> (i64, i64) {
+        match goal {
+            ObjectiveGoal::Credits(amount) => (self.credits as i64, *amount as i64),
+            ObjectiveGoal::SystemsVisited(count) => (self.visited_systems.len() as i64, *count as i64),
+            ObjectiveGoal::UnitsMined(units) => (self.units_mined as i64, *units as i64),
+            ObjectiveGoal::Reputation(faction, standing) => (self.get_reputation(faction) as i64, *standing as i64),
         }
     }
     
+    // Complete as many storyline objectives as the player now meets, paying
+    // each one's reward. Returns a message for every step completed.
+    pub fn check_storyline_progress(&mut self, clock: &GameClock) -> Vec<String> {
+        let mut messages = Vec::new();
+        
+        // Storylines saved before objectives existed get the defaults
+        if let Some(storyline) = self.character.active_storyline.as_mut() {
+            if storyline.objectives.is_empty() {
+                storyline.objectives = faction::default_objectives(&storyline.faction, storyline.total_steps);
+            }
+        }
+        
+        loop {
+            let objective = match self.character.active_storyline.as_ref().and_then(|s| s.current_objective()) {
+                Some(objective) => objective.clone(),
+                None => break,
+            };
+            let (current, target) = self.objective_progress(&objective.goal);
+            if current < target {
+                break;
+            }
+            
+            let _ = self.apply_transaction(
+                objective.reward_credits as i64,
+                TransactionCategory::Mission,
+                &format!("Storyline objective: {}", objective.goal.describe()),
+                clock,
+            );
+            messages.push(format!(
+                "Objective complete: {} (+{} credits)",
+                objective.goal.describe(), objective.reward_credits
+            ));
+            
+            if let Some(storyline) = self.character.active_storyline.as_mut() {
+                storyline.advance();
+                if storyline.completed {
+                    messages.push(format!("Storyline complete: {}", storyline.name));
+                }
+            }
+        }
+        
+        messages
+    }
+    
+    // Remember a system's market as it was on leaving. Only the latest
+    // snapshot per system is kept.
+    pub fn record_market_snapshot(&mut self, system_id: &str, snapshot: MarketSnapshot) {
+        self.visited_market_snapshots.insert(system_id.to_string(), snapshot);
+    }
+    
+    pub fn get_market_snapshot(&self, system_id: &str) -> Option<&MarketSnapshot> {
+        self.visited_market_snapshots.get(system_id)
+    }
+    
+    // Check the alerts set on `market` against its current prices. Returns
+    // each alert that fired with the price that set it off; one-shot alerts
+    // are removed and repeating ones wait for the price to move back.
+    pub fn check_price_alerts(&mut self, market: &Market) -> Vec<(PriceAlert, u32)> {
+        let mut fired = Vec::new();
+        self.price_alerts.retain_mut(|alert| {
+            if alert.system_id != market.system_id {
+                return true;
+            }
+            let price = match market.items.get(&alert.item_name) {
+                Some(item) => alert.watched_price(item),
+                None => return true,
+            };
+            
+            let triggered = alert.is_triggered(price);
+            if !alert.armed {
+                alert.armed = !triggered;
+                return true;
+            }
+            if !triggered {
+                return true;
+            }
+            
+            fired.push((alert.clone(), price));
+            alert.armed = false;
+            alert.repeat
+        });
+        fired
+    }
+    
     pub fn with_character(name: &str, faction: FactionType, storyline: Storyline) -> Self {
         let mut player = Self::new(name);
         
@@ -126,9 +361,47 @@ impl Player {
         player.ship = faction.starting_ship();
         player.inventory = Inventory::new(player.ship.cargo_capacity);
         
+        // Fit out the ship and fill the hold from the faction's loadout
+        let loadout = faction.starting_loadout();
+        player.credits = loadout.credits;
+        player.ship.max_hull += loadout.hull_bonus;
+        player.ship.hull = player.ship.max_hull;
+        for module in loadout.modules {
+            if let Err(e) = player.ship.install_module(module) {
+                eprintln!("Could not fit starting module: {}", e);
+            }
+        }
+        for (item, quantity) in loadout.cargo {
+            player.inventory.add_item(item, quantity);
+        }
+        for blueprint in loadout.blueprints {
+            player.blueprints.add_blueprint(blueprint);
+        }
+        player.known_blueprints = loadout.known_blueprints;
+        
         player
     }
     
+    pub fn knows_blueprint(&self, id: &str) -> bool {
+        self.known_blueprints.iter().any(|known| known == id)
+    }
+    
+    // Returns false if the blueprint was already known
+    pub fn learn_blueprint(&mut self, id: &str) -> bool {
+        if self.knows_blueprint(id) {
+            return false;
+        }
+        self.known_blueprints.push(id.to_string());
+        true
+    }
+    
+    // Put a new character in their starting system, as its only visit
+    pub fn start_at(&mut self, system: StarSystem) {
+        self.visited_systems = HashSet::from([system.id.clone()]);
+        self.current_system = system;
+        self.is_docked = !self.current_system.stations.is_empty();
+    }
+    
     pub fn update_skills(&mut self) {
         // Update real-time progression for skills
         let current_time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs_f64();
@@ -150,16 +423,277 @@ impl Player {
         }
     }
 
-    pub fn add_credits(&mut self, amount: u32) {
-        self.credits += amount;
+    pub fn get_reputation(&self, faction: &FactionType) -> i32 {
+        *self.reputation.get(faction).unwrap_or(&0)
     }
 
-    pub fn remove_credits(&mut self, amount: u32) -> bool {
-        if amount <= self.credits {
-            self.credits -= amount;
-            true
+    // Reputation is kept within -100..=100
+    pub fn adjust_reputation(&mut self, faction: &FactionType, delta: i32) {
+        let entry = self.reputation.entry(faction.clone()).or_insert(0);
+        *entry = (*entry + delta).max(-100).min(100);
+    }
+
+    // The one place credits change. Spending more than the player has fails
+    // without touching the balance; every applied change is written to the ledger.
+    pub fn apply_transaction(&mut self, amount: i64, category: TransactionCategory, description: &str, clock: &GameClock) -> Result<(), String> {
+        if amount == 0 {
+            return Ok(());
+        }
+        
+        let balance = self.credits as i64 + amount;
+        if balance < 0 {
+            return Err(format!("Not enough credits: need {}, have {}", -amount, self.credits));
+        }
+        self.credits = balance.min(u32::MAX as i64) as u32;
+        self.record_stats(amount, category);
+        
+        self.ledger.push(LedgerEntry {
+            timestamp: clock.now(),
+            amount,
+            category,
+            description: description.to_string(),
+        });
+        if self.ledger.len() > LEDGER_CAPACITY {
+            let excess = self.ledger.len() - LEDGER_CAPACITY;
+            self.ledger.drain(..excess);
+        }
+        Ok(())
+    }
+    
+    // Value of everything the player owns. Items no market trades fall back
+    // to their base value.
+    pub fn net_worth(&self, economy: &EconomySystem) -> NetWorth {
+        let unit_price = |item: &crate::models::item::Item| {
+            economy.calculate_fair_market_price(&item.name).unwrap_or(item.value) as u64
+        };
+        
+        let cargo = self.inventory.items.iter()
+            .map(|(item, quantity)| unit_price(item) * *quantity as u64)
+            .sum();
+        
+        let listings = economy.player_market.listings.values()
+            .filter(|listing| listing.seller_id == self.id)
+            .map(|listing| listing.price_per_unit as u64 * listing.quantity as u64)
+            .sum();
+        
+        let escrow = self.escrow.iter()
+            .map(|hold| {
+                hold.credits as u64 + hold.items.iter()
+                    .map(|(item, quantity)| unit_price(item) * *quantity as u64)
+                    .sum::<u64>()
+            })
+            .sum();
+        
+        let order_commitments = economy.system_markets.values()
+            .flat_map(|market| market.trade_orders.iter())
+            .filter(|order| order.player_id == self.id && order.status == OrderStatus::Active)
+            .map(|order| match order.order_type {
+                OrderType::Buy => order.target_price as u64 * order.quantity as u64,
+                OrderType::Sell => {
+                    let price = economy.calculate_fair_market_price(&order.item_name)
+                        .unwrap_or(order.target_price);
+                    price as u64 * order.quantity as u64
+                },
+            })
+            .sum();
+        
+        NetWorth {
+            credits: self.credits as u64,
+            cargo,
+            listings,
+            escrow,
+            order_commitments,
+        }
+    }
+    
+    // Move credits and cargo out of reach until the deal `deal_id` settles.
+    // Either everything is reserved or nothing is.
+    pub fn reserve_escrow(&mut self, deal_id: &str, credits: u32, items: &[(String, u32)], clock: &GameClock) -> Result<(), String> {
+        if self.escrow.iter().any(|hold| hold.deal_id == deal_id) {
+            return Err("Deal already has value held in escrow".to_string());
+        }
+        if self.credits < credits {
+            return Err(format!("Not enough credits to hold in escrow: need {}, have {}", credits, self.credits));
+        }
+        for (name, quantity) in items {
+            if !self.inventory.has_item(name, *quantity) {
+                return Err(format!("Not enough {} to hold in escrow", name));
+            }
+        }
+        
+        self.apply_transaction(-(credits as i64), TransactionCategory::Escrow, "Held for market deal", clock)?;
+        let items = items.iter()
+            .filter_map(|(name, quantity)| self.inventory.remove_item(name, *quantity).map(|item| (item, *quantity)))
+            .collect();
+        self.escrow.push(EscrowHold { deal_id: deal_id.to_string(), credits, items });
+        Ok(())
+    }
+    
+    // Give back what was held for a deal that fell through. Returned cargo
+    // was the player's own, so it goes back even over capacity.
+    pub fn release_escrow(&mut self, deal_id: &str, clock: &GameClock) -> Option<EscrowHold> {
+        let hold = self.take_escrow(deal_id)?;
+        let _ = self.apply_transaction(hold.credits as i64, TransactionCategory::Escrow, "Released from market deal", clock);
+        for (item, quantity) in &hold.items {
+            *self.inventory.items.entry(item.clone()).or_insert(0) += quantity;
+        }
+        Some(hold)
+    }
+    
+    // Remove the hold for a settled deal; the caller hands it to the other party
+    pub fn take_escrow(&mut self, deal_id: &str) -> Option<EscrowHold> {
+        let index = self.escrow.iter().position(|hold| hold.deal_id == deal_id)?;
+        Some(self.escrow.remove(index))
+    }
+    
+    // Take delivery of value another player held in escrow
+    pub fn receive_escrow(&mut self, hold: EscrowHold, description: &str, clock: &GameClock) {
+        let _ = self.apply_transaction(hold.credits as i64, TransactionCategory::Trade, description, clock);
+        for (item, quantity) in hold.items {
+            *self.inventory.items.entry(item).or_insert(0) += quantity;
+        }
+    }
+    
+    // How much of `currency` the player holds
+    pub fn balance(&self, currency: &Currency) -> u32 {
+        match currency {
+            Currency::Credits => self.credits,
+            _ => self.currencies.get(currency).copied().unwrap_or(0),
+        }
+    }
+    
+    // Add or spend `amount` of `currency`. Credits go through the ledger;
+    // scrip balances just change.
+    pub fn adjust_balance(&mut self, currency: &Currency, amount: i64, category: TransactionCategory, description: &str, clock: &GameClock) -> Result<(), String> {
+        if *currency == Currency::Credits {
+            return self.apply_transaction(amount, category, description, clock);
+        }
+        
+        let held = self.balance(currency);
+        let balance = held as i64 + amount;
+        if balance < 0 {
+            return Err(format!("Not enough {}: need {}, have {}", currency.symbol(), -amount, held));
+        }
+        if balance == 0 {
+            self.currencies.remove(currency);
         } else {
-            false
+            self.currencies.insert(currency.clone(), balance.min(u32::MAX as i64) as u32);
         }
+        // Scrip isn't credits, but a trade paid in it is still a trade
+        self.record_stats(0, category);
+        Ok(())
+    }
+    
+    // Count a balance change towards the lifetime totals. Money coming back
+    // from escrow or put in by an administrator wasn't earned.
+    fn record_stats(&mut self, amount: i64, category: TransactionCategory) {
+        if category == TransactionCategory::Trade {
+            self.stats.trades_completed += 1;
+        }
+        if amount > 0 && !matches!(category, TransactionCategory::Escrow | TransactionCategory::Adjustment) {
+            self.stats.credits_earned += amount as u64;
+        }
+    }
+    
+    // Most recent ledger entries, newest first
+    pub fn recent_transactions(&self, count: usize) -> impl Iterator<Item = &LedgerEntry> {
+        self.ledger.iter().rev().take(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transactions_are_recorded_and_overdrafts_rejected() {
+        let mut player = Player::new("Test");
+        let start = player.credits;
+        let clock = GameClock::at(5000);
+
+        player.apply_transaction(250, TransactionCategory::Trade, "Sold ore", &clock).unwrap();
+        assert!(player.apply_transaction(-(start as i64) - 1000, TransactionCategory::Repair, "Too much", &clock).is_err());
+        player.apply_transaction(-100, TransactionCategory::Refuel, "Fuel", &clock).unwrap();
+
+        assert_eq!(player.credits, start + 150);
+        assert_eq!(player.ledger.len(), 2);
+        let latest = player.recent_transactions(1).next().unwrap();
+        assert_eq!(latest.category, TransactionCategory::Refuel);
+        assert_eq!(latest.timestamp, 5000);
+    }
+
+    #[test]
+    fn test_net_worth_falls_back_to_item_value() {
+        let mut player = Player::new("Test");
+        player.inventory.add_item(crate::models::item::Item::new("Unobtainium", 70, 1, crate::models::item::ItemType::Product), 3);
+
+        let worth = player.net_worth(&EconomySystem::new());
+        assert_eq!(worth.cargo, 210);
+        assert_eq!(worth.total(), player.credits as u64 + 210);
+    }
+
+    #[test]
+    fn test_factions_start_with_distinct_loadouts() {
+        let start = |faction: FactionType| {
+            let storyline = Storyline::new("test", faction.clone(), "Test", "Test", 1);
+            Player::with_character("Test", faction, storyline)
+        };
+        let traders = start(FactionType::Traders);
+        let miners = start(FactionType::Miners);
+        let military = start(FactionType::Military);
+        let scientists = start(FactionType::Scientists);
+
+        assert!(traders.credits > miners.credits.max(military.credits).max(scientists.credits));
+
+        assert!(miners.ship.mining_rating() > miners.ship.mining_power);
+        assert!(miners.inventory.get_item_quantity("Iron") > 0);
+
+        let bare_fighter = FactionType::Military.starting_ship();
+        assert!(military.ship.attack_rating() > bare_fighter.attack_rating());
+        assert!(military.ship.max_hull > bare_fighter.max_hull);
+        assert_eq!(military.ship.hull, military.ship.max_hull);
+
+        assert_eq!(scientists.blueprints.blueprints.len(), 1);
+        assert!(traders.blueprints.blueprints.is_empty());
+    }
+    #[test]
+    fn test_storyline_advances_when_objective_met() {
+        let storyline = Storyline::new("test", FactionType::Miners, "Test", "Test", 2);
+        let mut player = Player::with_character("Test", FactionType::Miners, storyline);
+        let goal = player.character.active_storyline.as_ref().unwrap().current_objective().unwrap().goal.clone();
+        assert_eq!(goal, ObjectiveGoal::UnitsMined(100));
+        assert!(player.check_storyline_progress(&GameClock::default()).is_empty());
+
+        let credits = player.credits;
+        player.record_mined(100);
+        assert_eq!(player.check_storyline_progress(&GameClock::default()).len(), 1);
+        assert_eq!(player.character.active_storyline.as_ref().unwrap().progress, 1);
+        assert_eq!(player.credits, credits + 500);
+    }
+
+    #[test]
+    fn test_price_alerts_fire_once_or_rearm() {
+        use crate::models::item::{Item, ItemType};
+        use crate::models::market::AlertDirection;
+        use crate::utils::clock::GameClock;
+
+        let mut market = Market::new("sol");
+        market.add_item(Item::new("Gold", 100, 1, ItemType::Product), 50, 100, 0.1, &GameClock::default());
+        let ask = market.items["Gold"].buy_price();
+
+        let mut player = Player::new("Test");
+        player.price_alerts.push(PriceAlert::new("sol", "Gold", AlertDirection::Below, ask, false));
+        player.price_alerts.push(PriceAlert::new("sol", "Gold", AlertDirection::Below, ask, true));
+        player.price_alerts.push(PriceAlert::new("sol", "Gold", AlertDirection::Below, ask - 1, false));
+
+        assert_eq!(player.check_price_alerts(&market).len(), 2);
+        assert_eq!(player.price_alerts.len(), 2);
+
+        // The repeating alert stays quiet until the price moves back above it
+        assert!(player.check_price_alerts(&market).is_empty());
+        market.items.get_mut("Gold").unwrap().current_price *= 2;
+        assert!(player.check_price_alerts(&market).is_empty());
+        market.items.get_mut("Gold").unwrap().current_price /= 2;
+        assert_eq!(player.check_price_alerts(&market).len(), 1);
     }
 }
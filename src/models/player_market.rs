@@ -1,10 +1,11 @@
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 use crate::models::item::{Item, ItemType};
 use crate::models::market::OrderStatus;
+use crate::models::player::{Player, TransactionCategory};
+use crate::utils::clock::GameClock;
 
 /// Represents a single market listing created by a player
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -26,6 +27,33 @@ pub struct PlayerMarketListing {
     pub description: String,            // Optional description
     pub tags: Vec<String>,              // Tags for filtering/searching
     pub quantity_sold: u32,             // Track how many units have been sold so far
+    #[serde(default)]
+    pub kind: ListingKind,              // Fixed price sale or timed auction
+    #[serde(default)]
+    pub consigned: bool,                // Goods left with the market; the seller needn't be present
+}
+
+#[allow(dead_code)]
+impl PlayerMarketListing {
+    /// Whether a player may see, bid on or buy this listing
+    pub fn is_visible_to(&self, player_id: &str, player_faction: Option<&str>) -> bool {
+        match &self.visibility {
+            ListingVisibility::Public => true,
+            ListingVisibility::FactionOnly(faction) => player_faction == Some(faction.as_str()),
+            ListingVisibility::PlayerList(players) => players.iter().any(|player| player == player_id),
+        }
+    }
+}
+
+/// How a listing is sold
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum ListingKind {
+    #[default]
+    FixedPrice, // Bought at the asking price, or by an accepted bid if negotiable
+    Auction {
+        ends_at: u64,       // Game time the auction closes and the highest bid wins
+        min_increment: u32, // Each bid must beat the current highest by at least this much per unit
+    },
 }
 
 /// Determines who can see a listing
@@ -127,6 +155,17 @@ pub struct PlayerMarket {
     pub market_fee: f32,         // Transaction fee percentage (e.g., 0.05 for 5%)
     pub price_trends: HashMap<String, Vec<PriceTrend>>, // Track price trends by item
     pub reputation_requirements: bool, // Whether to enforce reputation requirements
+    #[serde(default)]
+    pub consignment_accounts: HashMap<String, ConsignmentAccount>, // Uncollected proceeds and returns by seller ID
+}
+
+/// What the market is holding for a player until they collect it: sale
+/// proceeds after fees, unsold goods from listings that ended, auction lots
+/// won and credits from auction bids that were outbid
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsignmentAccount {
+    pub credits: u32,
+    pub items: Vec<(Item, u32)>,
 }
 
 /// Records price trend data for market analysis
@@ -150,6 +189,7 @@ impl PlayerMarket {
             market_fee: 0.05,  // 5% default fee
             price_trends: HashMap::new(),
             reputation_requirements: true,
+            consignment_accounts: HashMap::new(),
         }
     }
 
@@ -169,12 +209,10 @@ impl PlayerMarket {
         negotiable: bool,
         description: &str,
         tags: Vec<String>,
+        clock: &GameClock,
     ) -> String {
         let id = Uuid::new_v4().to_string();
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
 
         let listing = PlayerMarketListing {
             id: id.clone(),
@@ -194,6 +232,8 @@ impl PlayerMarket {
             description: description.to_string(),
             tags,
             quantity_sold: 0,
+            kind: ListingKind::FixedPrice,
+            consigned: false,
         };
 
         self.listings.insert(id.clone(), listing);
@@ -202,6 +242,140 @@ impl PlayerMarket {
         id
     }
 
+    /// Put carried goods up for a timed, ascending auction. `starting_price`
+    /// is the lowest per-unit bid accepted; the whole lot goes to the highest
+    /// bid at `ends_at`. The lot is consigned, so it can change hands at the
+    /// close whether or not the seller is there
+    pub fn create_auction(
+        &mut self,
+        seller: &mut Player,
+        item_name: &str,
+        quantity: u32,
+        starting_price: u32,
+        system_id: &str,
+        location_id: &str,
+        ends_at: u64,
+        min_increment: u32,
+        min_reputation: i32,
+        visibility: ListingVisibility,
+        description: &str,
+        tags: Vec<String>,
+        clock: &GameClock,
+    ) -> Result<String, String> {
+        let id = self.consign_listing(
+            seller, item_name, quantity, starting_price, system_id, location_id,
+            Some(ends_at), min_reputation, visibility, true, description, tags, clock,
+        )?;
+        if let Some(listing) = self.listings.get_mut(&id) {
+            listing.kind = ListingKind::Auction { ends_at, min_increment: min_increment.max(1) };
+        }
+        Ok(id)
+    }
+
+    /// Whether a bid was placed on an auction lot
+    fn is_auction_bid(&self, bid_id: &str) -> bool {
+        self.bids.get(bid_id)
+            .and_then(|bid| self.listings.get(&bid.listing_id))
+            .map_or(false, |listing| matches!(listing.kind, ListingKind::Auction { .. }))
+    }
+
+    /// Hold credits for a player in their market account until they collect them
+    fn credit_account(&mut self, player_id: &str, credits: u32) {
+        let account = self.consignment_accounts.entry(player_id.to_string()).or_default();
+        account.credits = account.credits.saturating_add(credits);
+    }
+
+    /// The leading pending bid on a listing; ties go to the earlier bid
+    pub fn highest_bid(&self, listing_id: &str) -> Option<&MarketBid> {
+        self.bids.values()
+            .filter(|bid| bid.listing_id == listing_id && bid.status == BidStatus::Pending)
+            .max_by(|a, b| a.bid_amount.cmp(&b.bid_amount).then(b.created_at.cmp(&a.created_at)))
+    }
+
+    /// Consign carried goods to a station's market. The goods leave the
+    /// seller's inventory now and are held by the market, so the listing can
+    /// sell while the seller is elsewhere; proceeds wait in their consignment account
+    pub fn consign_listing(
+        &mut self,
+        seller: &mut Player,
+        item_name: &str,
+        quantity: u32,
+        price_per_unit: u32,
+        system_id: &str,
+        location_id: &str,
+        expiration: Option<u64>,
+        min_reputation: i32,
+        visibility: ListingVisibility,
+        negotiable: bool,
+        description: &str,
+        tags: Vec<String>,
+        clock: &GameClock,
+    ) -> Result<String, String> {
+        if quantity == 0 {
+            return Err("Nothing to consign".to_string());
+        }
+        let item = seller.inventory.remove_item(item_name, quantity)
+            .ok_or_else(|| format!("You don't carry {} {}", quantity, item_name))?;
+        
+        let seller_name = seller.character.name.clone();
+        let id = self.create_listing(
+            &seller.id, &seller_name, item, quantity, price_per_unit, system_id, location_id,
+            expiration, min_reputation, visibility, negotiable, description, tags, clock,
+        );
+        if let Some(listing) = self.listings.get_mut(&id) {
+            listing.consigned = true;
+        }
+        Ok(id)
+    }
+
+    /// Pay out a seller's consignment account: all proceeds, plus as many
+    /// returned goods as fit in their hold. Returns the credits paid and the
+    /// goods delivered; goods that don't fit stay in the account
+    pub fn collect_consignments(&mut self, seller: &mut Player, clock: &GameClock) -> (u32, Vec<(String, u32)>) {
+        let account = match self.consignment_accounts.get_mut(&seller.id) {
+            Some(account) => account,
+            None => return (0, Vec::new()),
+        };
+        
+        let credits = std::mem::take(&mut account.credits);
+        let _ = seller.apply_transaction(credits as i64, TransactionCategory::Trade, "Consignment proceeds", clock);
+        
+        let mut delivered = Vec::new();
+        account.items.retain(|(item, quantity)| {
+            if seller.inventory.add_item(item.clone(), *quantity) {
+                delivered.push((item.name.clone(), *quantity));
+                return false;
+            }
+            true
+        });
+        
+        if account.credits == 0 && account.items.is_empty() {
+            self.consignment_accounts.remove(&seller.id);
+        }
+        (credits, delivered)
+    }
+
+    /// Hand a consigned sale's goods to the buyer out of the market's hold and
+    /// credit the seller's account. The buyer has already paid
+    fn deliver_consigned(&mut self, purchase: &MarketPurchase, item: Item, buyer: &mut Player) {
+        *buyer.inventory.items.entry(item).or_insert(0) += purchase.quantity;
+        self.credit_consignment(purchase);
+    }
+
+    /// Credit a consigned sale to the seller's account, less the market fee
+    fn credit_consignment(&mut self, purchase: &MarketPurchase) {
+        let fee = (purchase.total_price as f32 * self.market_fee) as u32;
+        self.credit_account(&purchase.seller_id, purchase.total_price - fee);
+    }
+
+    /// Move the unsold remainder of a consigned listing back to its seller's account
+    fn return_consigned(accounts: &mut HashMap<String, ConsignmentAccount>, listing: &PlayerMarketListing) {
+        if listing.consigned && listing.quantity > 0 {
+            accounts.entry(listing.seller_id.clone()).or_default()
+                .items.push((listing.item.clone(), listing.quantity));
+        }
+    }
+
     /// Find listings matching search criteria
     pub fn search_listings(
         &self,
@@ -218,19 +392,7 @@ impl PlayerMarket {
         let mut results: Vec<&PlayerMarketListing> = self.listings.values()
             .filter(|listing| {
                 // Filter by visibility first
-                (match &listing.visibility {
-                    ListingVisibility::Public => true,
-                    ListingVisibility::FactionOnly(faction) => {
-                        if let Some(player_faction) = player_faction {
-                            player_faction == faction
-                        } else {
-                            false
-                        }
-                    },
-                    ListingVisibility::PlayerList(players) => {
-                        players.contains(&player_id.to_string())
-                    }
-                }) &&
+                listing.is_visible_to(player_id, player_faction) &&
                 // Then apply other filters
                 (item_name.is_none() || listing.item.name.contains(item_name.unwrap())) &&
                 (item_type.is_none() || &listing.item.item_type == item_type.unwrap()) &&
@@ -266,13 +428,68 @@ impl PlayerMarket {
         results
     }
 
-    /// Purchase from a listing directly
+    /// Purchase from a listing directly. Consigned goods are held by the
+    /// market, so they're bought with `purchase_consigned` instead
     pub fn purchase_listing(
         &mut self,
         listing_id: &str, 
         buyer_id: &str,
+        buyer_faction: Option<&str>,
         quantity: u32,
         buyer_reputation: i32,
+        clock: &GameClock,
+    ) -> Result<MarketPurchase, String> {
+        if self.listings.get(listing_id).map_or(false, |listing| listing.consigned) {
+            return Err("Consigned goods are paid for and collected at the market".to_string());
+        }
+        self.sell_listing(listing_id, buyer_id, buyer_faction, quantity, buyer_reputation, clock)
+    }
+
+    /// Buy consigned goods: the buyer pays and takes delivery from the
+    /// market's hold in one step, and the seller's share goes to their
+    /// consignment account
+    pub fn purchase_consigned(
+        &mut self,
+        listing_id: &str,
+        buyer: &mut Player,
+        buyer_faction: Option<&str>,
+        quantity: u32,
+        buyer_reputation: i32,
+        clock: &GameClock,
+    ) -> Result<MarketPurchase, String> {
+        let listing = self.listings.get(listing_id).ok_or("Listing not found")?;
+        if !listing.consigned {
+            return Err("This listing is not consigned".to_string());
+        }
+        let item = listing.item.clone();
+        let total_price = listing.price_per_unit * quantity;
+        if buyer.credits < total_price {
+            return Err(format!("Not enough credits: need {}, have {}", total_price, buyer.credits));
+        }
+        if buyer.inventory.remaining_capacity() < item.weight * quantity {
+            return Err("Not enough cargo space".to_string());
+        }
+        
+        let buyer_id = buyer.id.clone();
+        let purchase = self.sell_listing(listing_id, &buyer_id, buyer_faction, quantity, buyer_reputation, clock)?;
+        buyer.apply_transaction(
+            -(purchase.total_price as i64), TransactionCategory::Trade,
+            &format!("Bought {} x{}", purchase.item_name, purchase.quantity), clock,
+        )?;
+        self.deliver_consigned(&purchase, item, buyer);
+        Ok(purchase)
+    }
+
+    /// Sell units off a listing and record the sale. Moving credits and goods
+    /// is left to the caller
+    fn sell_listing(
+        &mut self,
+        listing_id: &str, 
+        buyer_id: &str,
+        buyer_faction: Option<&str>,
+        quantity: u32,
+        buyer_reputation: i32,
+        clock: &GameClock,
     ) -> Result<MarketPurchase, String> {
         // Get the listing and validate
         let mut should_remove = false;
@@ -287,6 +504,16 @@ impl PlayerMarket {
                 None => return Err("Listing not found".to_string()),
             };
 
+            // Visibility is enforced here as well as in search, since a
+            // listing id can be learned without being able to see the listing
+            if !listing.is_visible_to(buyer_id, buyer_faction) {
+                return Err("Not authorized: this listing is not offered to you".to_string());
+            }
+
+            if matches!(listing.kind, ListingKind::Auction { .. }) {
+                return Err("Auction listings can only be bid on".to_string());
+            }
+
             // Check quantity
             if listing.quantity < quantity {
                 return Err(format!("Not enough quantity available. Requested: {}, Available: {}", 
@@ -325,10 +552,7 @@ impl PlayerMarket {
         
         // Create purchase record
         let purchase_id = Uuid::new_v4().to_string();
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         let purchase = MarketPurchase {
             id: purchase_id,
@@ -350,7 +574,7 @@ impl PlayerMarket {
         }
         
         // Update price trend data
-        self.update_price_trend(&item_name, price_per_unit, quantity);
+        self.update_price_trend(&item_name, price_per_unit, quantity, clock);
         
         // Add to purchase history
         self.purchase_history.push(purchase.clone());
@@ -358,20 +582,103 @@ impl PlayerMarket {
         Ok(purchase)
     }
 
-    /// Place a bid on a negotiable listing
+    /// Place a bid on a negotiable listing. Auction bids have to be backed
+    /// by credits, so they're placed with `place_auction_bid`
     pub fn place_bid(
         &mut self,
         listing_id: &str,
         bidder_id: &str,
         bidder_name: &str,
+        bidder_faction: Option<&str>,
+        bidder_reputation: i32,
         bid_amount: u32,
         quantity: u32,
         message: &str,
         expires_in_hours: Option<u64>,
+        clock: &GameClock,
     ) -> Result<String, String> {
-        // Check if listing exists and is negotiable
+        if self.listings.get(listing_id).map_or(false, |listing| matches!(listing.kind, ListingKind::Auction { .. })) {
+            return Err("Auction bids must be backed by credits".to_string());
+        }
+        self.record_bid(
+            listing_id, bidder_id, bidder_name, bidder_faction, bidder_reputation,
+            bid_amount, quantity, message, expires_in_hours, clock,
+        )
+    }
+
+    /// Bid on the whole lot of an auction. The market holds the bid's credits
+    /// while it leads; when it's outbid they go to the bidder's market account
+    /// (or straight back, if they outbid themselves), and at the close the
+    /// winning bid pays for the lot
+    pub fn place_auction_bid(
+        &mut self,
+        listing_id: &str,
+        bidder: &mut Player,
+        bidder_faction: Option<&str>,
+        bidder_reputation: i32,
+        bid_amount: u32,
+        message: &str,
+        clock: &GameClock,
+    ) -> Result<String, String> {
+        let listing = self.listings.get(listing_id).ok_or("Listing not found")?;
+        if !matches!(listing.kind, ListingKind::Auction { .. }) {
+            return Err("This listing is not an auction".to_string());
+        }
+        let quantity = listing.quantity;
+        let total = bid_amount * quantity;
+        let previous = self.highest_bid(listing_id).cloned();
+        
+        bidder.apply_transaction(-(total as i64), TransactionCategory::Escrow, "Held for auction bid", clock)?;
+        let bidder_id = bidder.id.clone();
+        let bidder_name = bidder.character.name.clone();
+        let bid_id = match self.record_bid(
+            listing_id, &bidder_id, &bidder_name, bidder_faction, bidder_reputation,
+            bid_amount, quantity, message, None, clock,
+        ) {
+            Ok(bid_id) => bid_id,
+            Err(e) => {
+                let _ = bidder.apply_transaction(total as i64, TransactionCategory::Escrow, "Auction bid refused", clock);
+                return Err(e);
+            },
+        };
+        
+        if let Some(previous) = previous {
+            if let Some(bid) = self.bids.get_mut(&previous.id) {
+                bid.status = BidStatus::Rejected;
+            }
+            if previous.bidder_id == bidder.id {
+                let _ = bidder.apply_transaction(previous.total_amount as i64, TransactionCategory::Escrow, "Released from auction bid", clock);
+            } else {
+                self.credit_account(&previous.bidder_id, previous.total_amount);
+            }
+        }
+        Ok(bid_id)
+    }
+
+    /// Validate and store a bid
+    fn record_bid(
+        &mut self,
+        listing_id: &str,
+        bidder_id: &str,
+        bidder_name: &str,
+        bidder_faction: Option<&str>,
+        bidder_reputation: i32,
+        bid_amount: u32,
+        quantity: u32,
+        message: &str,
+        expires_in_hours: Option<u64>,
+        clock: &GameClock,
+    ) -> Result<String, String> {
+        // Check if listing exists, is offered to the bidder and is negotiable
         let listing = match self.listings.get(listing_id) {
             Some(l) => {
+                if !l.is_visible_to(bidder_id, bidder_faction) {
+                    return Err("Not authorized: this listing is not offered to you".to_string());
+                }
+                if self.reputation_requirements && bidder_reputation < l.minimum_reputation {
+                    return Err(format!("Insufficient reputation. Required: {}, You have: {}", 
+                                      l.minimum_reputation, bidder_reputation));
+                }
                 if !l.negotiable {
                     return Err("This listing does not accept bids".to_string());
                 }
@@ -383,17 +690,30 @@ impl PlayerMarket {
             None => return Err("Listing not found".to_string()),
         };
         
+        let current_time = clock.now();
+        
+        // Auctions sell the whole lot and every bid must beat the leader
+        if let ListingKind::Auction { ends_at, min_increment } = listing.kind {
+            if current_time >= ends_at {
+                return Err("This auction has closed".to_string());
+            }
+            if quantity != listing.quantity {
+                return Err(format!("Auction bids must be for the whole lot of {}", listing.quantity));
+            }
+            let minimum = match self.highest_bid(listing_id) {
+                Some(leader) => leader.bid_amount.saturating_add(min_increment),
+                None => listing.price_per_unit,
+            };
+            if bid_amount < minimum {
+                return Err(format!("Bid too low: the minimum bid is {} per unit", minimum));
+            }
+        }
+        
         // Create the bid
         let bid_id = Uuid::new_v4().to_string();
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
             
         // Calculate expiration time
-        let expires_at = expires_in_hours.map(|hours| {
-            current_time + (hours * 3600) // hours to seconds
-        });
+        let expires_at = expires_in_hours.map(|hours| clock.hours_from_now(hours));
         
         let bid = MarketBid {
             id: bid_id.clone(),
@@ -415,8 +735,25 @@ impl PlayerMarket {
         Ok(bid_id)
     }
 
-    /// Accept a bid on a listing
-    pub fn accept_bid(&mut self, bid_id: &str) -> Result<MarketPurchase, String> {
+    /// Accept a bid on a listing. Bids on consigned goods are settled with
+    /// `accept_bid_escrowed`, since the buyer has to pay as the goods change
+    /// hands, and auctions only close to their highest bid
+    pub fn accept_bid(&mut self, bid_id: &str, clock: &GameClock) -> Result<MarketPurchase, String> {
+        if self.is_auction_bid(bid_id) {
+            return Err("Auctions close to the highest bid when they end".to_string());
+        }
+        let consigned = self.bids.get(bid_id)
+            .and_then(|bid| self.listings.get(&bid.listing_id))
+            .map_or(false, |listing| listing.consigned);
+        if consigned {
+            return Err("Bids on consigned goods must be settled through escrow".to_string());
+        }
+        self.settle_bid(bid_id, clock)
+    }
+
+    /// Mark a bid accepted and sell its units off the listing. Moving credits
+    /// and goods is left to the caller
+    fn settle_bid(&mut self, bid_id: &str, clock: &GameClock) -> Result<MarketPurchase, String> {
         // Get the bid
         let bid = match self.bids.get_mut(bid_id) {
             Some(b) => {
@@ -476,10 +813,7 @@ impl PlayerMarket {
         }
         
         // Process the purchase
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         // Create the purchase record
         let purchase_id = Uuid::new_v4().to_string();
@@ -503,7 +837,7 @@ impl PlayerMarket {
         }
         
         // Update price trend data
-        self.update_price_trend(&item_name, bid.bid_amount, bid.quantity);
+        self.update_price_trend(&item_name, bid.bid_amount, bid.quantity, clock);
         
         // Add to purchase history
         self.purchase_history.push(purchase.clone());
@@ -513,6 +847,9 @@ impl PlayerMarket {
 
     /// Reject a bid
     pub fn reject_bid(&mut self, bid_id: &str) -> Result<(), String> {
+        if self.is_auction_bid(bid_id) {
+            return Err("Auction bids can't be rejected".to_string());
+        }
         // Get the bid and update status
         match self.bids.get_mut(bid_id) {
             Some(bid) => {
@@ -528,6 +865,9 @@ impl PlayerMarket {
 
     /// Cancel own bid (as a bidder)
     pub fn cancel_bid(&mut self, bid_id: &str, bidder_id: &str) -> Result<(), String> {
+        if self.is_auction_bid(bid_id) {
+            return Err("Auction bids can't be withdrawn".to_string());
+        }
         // Get the bid and update status
         match self.bids.get_mut(bid_id) {
             Some(bid) => {
@@ -559,12 +899,10 @@ impl PlayerMarket {
         deadline: Option<u64>,
         terms: Vec<String>,
         is_public: bool,
+        clock: &GameClock,
     ) -> String {
         let contract_id = Uuid::new_v4().to_string();
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         let contract = MarketContract {
             id: contract_id.clone(),
@@ -589,7 +927,7 @@ impl PlayerMarket {
     }
 
     /// Accept a contract
-    pub fn accept_contract(&mut self, contract_id: &str, player_id: &str) -> Result<(), String> {
+    pub fn accept_contract(&mut self, contract_id: &str, player_id: &str, clock: &GameClock) -> Result<(), String> {
         match self.contracts.get_mut(contract_id) {
             Some(contract) => {
                 if contract.status != ContractStatus::Open {
@@ -605,10 +943,7 @@ impl PlayerMarket {
                 contract.status = ContractStatus::InProgress;
                 
                 // Record progress
-                let current_time = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or(Duration::from_secs(0))
-                    .as_secs();
+                let current_time = clock.now();
                     
                 contract.progress.push(ContractProgress {
                     timestamp: current_time,
@@ -630,6 +965,7 @@ impl PlayerMarket {
         player_id: &str,
         message: &str,
         items_delivered: Vec<(String, u32)>,
+        clock: &GameClock,
     ) -> Result<(), String> {
         match self.contracts.get_mut(contract_id) {
             Some(contract) => {
@@ -644,10 +980,7 @@ impl PlayerMarket {
                 }
                 
                 // Record progress
-                let current_time = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or(Duration::from_secs(0))
-                    .as_secs();
+                let current_time = clock.now();
                     
                 contract.progress.push(ContractProgress {
                     timestamp: current_time,
@@ -663,7 +996,7 @@ impl PlayerMarket {
     }
 
     /// Complete a contract
-    pub fn complete_contract(&mut self, contract_id: &str, issuer_id: &str) -> Result<(), String> {
+    pub fn complete_contract(&mut self, contract_id: &str, issuer_id: &str, clock: &GameClock) -> Result<(), String> {
         match self.contracts.get_mut(contract_id) {
             Some(contract) => {
                 // Verify issuer
@@ -679,10 +1012,7 @@ impl PlayerMarket {
                 contract.status = ContractStatus::Completed;
                 
                 // Record completion
-                let current_time = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or(Duration::from_secs(0))
-                    .as_secs();
+                let current_time = clock.now();
                     
                 contract.progress.push(ContractProgress {
                     timestamp: current_time,
@@ -697,12 +1027,144 @@ impl PlayerMarket {
         }
     }
 
+    /// Accept a bid with both sides held in escrow: the bidder's credits and
+    /// the seller's goods are reserved before the bid is accepted, so neither
+    /// can have been spent elsewhere, then handed over in one step. Consigned
+    /// goods are already held by the market, which delivers them itself and
+    /// pays the seller's consignment account
+    pub fn accept_bid_escrowed(
+        &mut self,
+        bid_id: &str,
+        seller: &mut Player,
+        buyer: &mut Player,
+        clock: &GameClock,
+    ) -> Result<MarketPurchase, String> {
+        if self.is_auction_bid(bid_id) {
+            return Err("Auctions close to the highest bid when they end".to_string());
+        }
+        let bid = self.bids.get(bid_id).ok_or("Bid not found")?.clone();
+        let listing = self.listings.get(&bid.listing_id).ok_or("Listing no longer exists")?;
+        if listing.seller_id != seller.id || bid.bidder_id != buyer.id {
+            return Err("Escrow parties do not match this bid".to_string());
+        }
+        if buyer.inventory.remaining_capacity() < listing.item.weight * bid.quantity {
+            return Err("Buyer does not have enough cargo space".to_string());
+        }
+        let goods = vec![(listing.item.name.clone(), bid.quantity)];
+        let consigned = listing.consigned;
+        let item = listing.item.clone();
+        
+        buyer.reserve_escrow(bid_id, bid.total_amount, &[], clock)?;
+        if !consigned {
+            if let Err(e) = seller.reserve_escrow(bid_id, 0, &goods, clock) {
+                buyer.release_escrow(bid_id, clock);
+                return Err(e);
+            }
+        }
+        
+        match self.settle_bid(bid_id, clock) {
+            Ok(purchase) if consigned => {
+                buyer.take_escrow(bid_id);
+                self.deliver_consigned(&purchase, item, buyer);
+                Ok(purchase)
+            },
+            Ok(purchase) => {
+                if let Some(credits) = buyer.take_escrow(bid_id) {
+                    seller.receive_escrow(credits, &format!("Sold {} x{}", purchase.item_name, purchase.quantity), clock);
+                }
+                if let Some(goods) = seller.take_escrow(bid_id) {
+                    buyer.receive_escrow(goods, &format!("Bought {} x{}", purchase.item_name, purchase.quantity), clock);
+                }
+                Ok(purchase)
+            },
+            Err(e) => {
+                buyer.release_escrow(bid_id, clock);
+                seller.release_escrow(bid_id, clock);
+                Err(e)
+            },
+        }
+    }
+
+    /// Accept a contract, holding the issuer's reward in escrow until the
+    /// contract is completed or fails
+    pub fn accept_contract_escrowed(
+        &mut self,
+        contract_id: &str,
+        issuer: &mut Player,
+        player_id: &str,
+        clock: &GameClock,
+    ) -> Result<(), String> {
+        let contract = self.contracts.get(contract_id).ok_or("Contract not found")?;
+        if contract.issuer_id != issuer.id {
+            return Err("Escrow parties do not match this contract".to_string());
+        }
+        let reward_items: Vec<(String, u32)> = contract.reward_items.iter()
+            .map(|(item, quantity)| (item.name.clone(), *quantity))
+            .collect();
+        
+        issuer.reserve_escrow(contract_id, contract.reward_credits, &reward_items, clock)?;
+        self.accept_contract(contract_id, player_id, clock).map_err(|e| {
+            issuer.release_escrow(contract_id, clock);
+            e
+        })
+    }
+
+    /// Complete a contract and pay the assignee the reward held in escrow
+    pub fn complete_contract_escrowed(
+        &mut self,
+        contract_id: &str,
+        issuer: &mut Player,
+        assignee: &mut Player,
+        clock: &GameClock,
+    ) -> Result<(), String> {
+        let contract = self.contracts.get(contract_id).ok_or("Contract not found")?;
+        if !contract.assignee_ids.contains(&assignee.id) {
+            return Err("Escrow parties do not match this contract".to_string());
+        }
+        let reward_weight: u32 = contract.reward_items.iter()
+            .map(|(item, quantity)| item.weight * quantity)
+            .sum();
+        if assignee.inventory.remaining_capacity() < reward_weight {
+            return Err("Not enough cargo space to take the reward".to_string());
+        }
+        
+        self.complete_contract(contract_id, &issuer.id, clock)?;
+        if let Some(reward) = issuer.take_escrow(contract_id) {
+            assignee.receive_escrow(reward, "Contract reward", clock);
+        }
+        Ok(())
+    }
+
+    /// Mark an accepted contract as failed and return the held reward to the issuer
+    pub fn fail_contract(&mut self, contract_id: &str, issuer: &mut Player, reason: &str, clock: &GameClock) -> Result<(), String> {
+        match self.contracts.get_mut(contract_id) {
+            Some(contract) => {
+                if contract.issuer_id != issuer.id {
+                    return Err("Only the contract issuer can mark it as failed".to_string());
+                }
+                
+                if contract.status != ContractStatus::InProgress {
+                    return Err(format!("Cannot fail contract with status: {:?}", contract.status));
+                }
+                
+                contract.status = ContractStatus::Failed;
+                contract.progress.push(ContractProgress {
+                    timestamp: clock.now(),
+                    update_by: issuer.id.clone(),
+                    message: format!("Contract failed: {}", reason),
+                    items_delivered: Vec::new(),
+                });
+                
+                issuer.release_escrow(contract_id, clock);
+                Ok(())
+            },
+            None => Err("Contract not found".to_string()),
+        }
+    }
+
     /// Update price trends
-    fn update_price_trend(&mut self, item_name: &str, price: u32, quantity: u32) {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+    fn update_price_trend(&mut self, item_name: &str, price: u32, quantity: u32, clock: &GameClock) {
+        let current_time = clock.now();
             
         // Get current trend data or create new entry
         let trends = self.price_trends.entry(item_name.to_string())
@@ -744,26 +1206,60 @@ impl PlayerMarket {
         }
     }
 
-    /// Process expired listings and bids
-    pub fn process_expirations(&mut self) -> (Vec<String>, Vec<String>) {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+    /// Change a listing's price and optionally push back its expiry. Only the
+    /// seller may edit; while bids are pending the price may only come down.
+    pub fn update_listing(
+        &mut self,
+        listing_id: &str,
+        seller_id: &str,
+        new_price: u32,
+        new_expiry: Option<u64>,
+    ) -> Result<(), String> {
+        let has_pending_bids = self.bids.values()
+            .any(|bid| bid.listing_id == listing_id && bid.status == BidStatus::Pending);
+        
+        let listing = match self.listings.get_mut(listing_id) {
+            Some(l) => l,
+            None => return Err("Listing not found".to_string()),
+        };
+        
+        if matches!(listing.kind, ListingKind::Auction { .. }) {
+            return Err("Auctions can't be edited once open".to_string());
+        }
+        
+        if listing.seller_id != seller_id {
+            return Err("You can only edit your own listings".to_string());
+        }
+        if new_price == 0 {
+            return Err("Price must be above zero".to_string());
+        }
+        if has_pending_bids && new_price > listing.price_per_unit {
+            return Err("Cannot raise the price while bids are pending".to_string());
+        }
+        if let (Some(new_expiry), Some(expires_at)) = (new_expiry, listing.expires_at) {
+            if new_expiry < expires_at {
+                return Err("New expiry must be later than the current one".to_string());
+            }
+        }
+        
+        listing.price_per_unit = new_price;
+        listing.total_price = new_price * listing.quantity;
+        if new_expiry.is_some() {
+            listing.expires_at = new_expiry;
+        }
+        
+        Ok(())
+    }
+
+    /// Process expired listings and bids, and close auctions that have ended.
+    /// Returns the expired listing ids, expired bid ids and the sales made by
+    /// closed auctions
+    pub fn process_expirations(&mut self, clock: &GameClock) -> (Vec<String>, Vec<String>, Vec<MarketPurchase>) {
+        let current_time = clock.now();
             
         let mut expired_listings = Vec::new();
         let mut expired_bids = Vec::new();
-        
-        // Process expired listings
-        self.listings.retain(|id, listing| {
-            if let Some(expires_at) = listing.expires_at {
-                if current_time > expires_at {
-                    expired_listings.push(id.clone());
-                    return false; // Remove from listings
-                }
-            }
-            true // Keep listing
-        });
+        let mut auction_sales = Vec::new();
         
         // Process expired bids
         for (id, bid) in self.bids.iter_mut() {
@@ -777,7 +1273,53 @@ impl PlayerMarket {
             }
         }
         
-        (expired_listings, expired_bids)
+        // Close ended auctions: the highest standing bid wins the lot and
+        // every other bid on it is rejected. The winner's held credits pay
+        // the seller's account and the lot goes to the winner's account
+        let ended_auctions: Vec<(String, Item)> = self.listings.values()
+            .filter(|listing| matches!(listing.kind, ListingKind::Auction { ends_at, .. } if current_time >= ends_at))
+            .map(|listing| (listing.id.clone(), listing.item.clone()))
+            .collect();
+        for (listing_id, item) in ended_auctions {
+            match self.highest_bid(&listing_id).cloned() {
+                Some(winner) => match self.settle_bid(&winner.id, clock) {
+                    Ok(purchase) => {
+                        self.credit_consignment(&purchase);
+                        self.consignment_accounts.entry(winner.bidder_id.clone()).or_default()
+                            .items.push((item, purchase.quantity));
+                        auction_sales.push(purchase);
+                    },
+                    Err(_) => {
+                        self.credit_account(&winner.bidder_id, winner.total_amount);
+                        expired_listings.push(listing_id.clone());
+                    },
+                },
+                None => expired_listings.push(listing_id.clone()),
+            }
+            for bid in self.bids.values_mut() {
+                if bid.listing_id == listing_id && bid.status == BidStatus::Pending {
+                    bid.status = BidStatus::Rejected;
+                }
+            }
+            if let Some(listing) = self.listings.remove(&listing_id) {
+                Self::return_consigned(&mut self.consignment_accounts, &listing);
+            }
+        }
+        
+        // Process expired listings
+        let accounts = &mut self.consignment_accounts;
+        self.listings.retain(|id, listing| {
+            if let Some(expires_at) = listing.expires_at {
+                if current_time > expires_at {
+                    Self::return_consigned(accounts, listing);
+                    expired_listings.push(id.clone());
+                    return false; // Remove from listings
+                }
+            }
+            true // Keep listing
+        });
+        
+        (expired_listings, expired_bids, auction_sales)
     }
 
     /// Get market statistics for an item
@@ -878,4 +1420,195 @@ pub struct ItemMarketStatistics {
     pub recent_sales_value: u32,
     pub price_change_percent: i32,
     pub price_history: Option<Vec<PriceTrend>>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_listing_cannot_be_bought_or_bid_on_by_id() {
+        let mut market = PlayerMarket::new();
+        let clock = GameClock::default();
+        let listing_id = market.create_listing(
+            "seller", "Seller", Item::new("Relic", 500, 1, ItemType::Product), 5, 900, "sol", "sol",
+            None, 0, ListingVisibility::FactionOnly("Military".to_string()), true, "", Vec::new(), &clock,
+        );
+
+        let bought = market.purchase_listing(&listing_id, "outsider", Some("Traders"), 1, 0, &clock);
+        assert!(bought.unwrap_err().starts_with("Not authorized"));
+        assert!(market.place_bid(&listing_id, "outsider", "Outsider", None, 0, 800, 1, "", None, &clock).is_err());
+        assert_eq!(market.listings[&listing_id].quantity, 5);
+
+        assert!(market.place_bid(&listing_id, "soldier", "Soldier", Some("Military"), 0, 800, 1, "", None, &clock).is_ok());
+        assert!(market.purchase_listing(&listing_id, "soldier", Some("Military"), 1, 0, &clock).is_ok());
+    }
+
+    #[test]
+    fn test_listing_edits_respect_owner_and_pending_bids() {
+        let mut market = PlayerMarket::new();
+        let clock = GameClock::default();
+        let listing_id = market.create_listing(
+            "seller", "Seller", Item::new("Relic", 500, 1, ItemType::Product), 4, 900, "sol", "sol",
+            Some(100), 0, ListingVisibility::Public, true, "", Vec::new(), &clock,
+        );
+
+        assert!(market.update_listing(&listing_id, "someone_else", 800, None).is_err());
+        market.update_listing(&listing_id, "seller", 1000, Some(500)).unwrap();
+        assert_eq!(market.listings[&listing_id].total_price, 4000);
+        assert_eq!(market.listings[&listing_id].expires_at, Some(500));
+        assert!(market.update_listing(&listing_id, "seller", 1000, Some(200)).is_err());
+
+        market.place_bid(&listing_id, "buyer", "Buyer", None, 0, 700, 1, "", None, &clock).unwrap();
+        assert!(market.update_listing(&listing_id, "seller", 1100, None).is_err());
+        assert!(market.update_listing(&listing_id, "seller", 950, None).is_ok());
+    }
+
+    #[test]
+    fn test_accepted_bid_settles_through_escrow() {
+        let mut market = PlayerMarket::new();
+        let clock = GameClock::default();
+        let relic = Item::new("Relic", 500, 1, ItemType::Product);
+        let mut seller = Player::new("Seller");
+        let mut buyer = Player::new("Buyer");
+        seller.inventory.add_item(relic.clone(), 2);
+        let listing_id = market.create_listing(
+            &seller.id, "Seller", relic, 2, 900, "sol", "sol",
+            None, 0, ListingVisibility::Public, true, "", Vec::new(), &clock,
+        );
+        let bid_id = market.place_bid(&listing_id, &buyer.id, "Buyer", None, 0, 800, 2, "", None, &clock).unwrap();
+
+        // The buyer spent the credits after bidding, so the bid can't be honoured
+        let credits = buyer.credits;
+        buyer.credits = 1000;
+        assert!(market.accept_bid_escrowed(&bid_id, &mut seller, &mut buyer, &clock).is_err());
+        assert_eq!(market.bids[&bid_id].status, BidStatus::Pending);
+        assert_eq!(seller.inventory.get_item_quantity("Relic"), 2);
+        assert!(seller.escrow.is_empty() && buyer.escrow.is_empty());
+
+        buyer.credits = credits;
+        let seller_credits = seller.credits;
+        market.accept_bid_escrowed(&bid_id, &mut seller, &mut buyer, &clock).unwrap();
+        assert_eq!(buyer.credits, credits - 1600);
+        assert_eq!(seller.credits, seller_credits + 1600);
+        assert_eq!(buyer.inventory.get_item_quantity("Relic"), 2);
+        assert_eq!(seller.inventory.get_item_quantity("Relic"), 0);
+    }
+
+    #[test]
+    fn test_contract_reward_is_held_until_settled() {
+        let mut market = PlayerMarket::new();
+        let clock = GameClock::default();
+        let mut issuer = Player::new("Issuer");
+        let mut hauler = Player::new("Hauler");
+        let credits = issuer.credits;
+        let contract_id = market.create_contract(
+            &issuer.id, "Haul", "", Vec::new(), 3000, Vec::new(), None, Vec::new(), true, &clock,
+        );
+
+        market.accept_contract_escrowed(&contract_id, &mut issuer, &hauler.id, &clock).unwrap();
+        assert_eq!(issuer.credits, credits - 3000);
+        assert!(issuer.apply_transaction(-(credits as i64), TransactionCategory::Trade, "", &clock).is_err());
+
+        let hauler_credits = hauler.credits;
+        market.complete_contract_escrowed(&contract_id, &mut issuer, &mut hauler, &clock).unwrap();
+        assert_eq!(hauler.credits, hauler_credits + 3000);
+        assert!(issuer.escrow.is_empty());
+
+        let contract_id = market.create_contract(
+            &issuer.id, "Haul", "", Vec::new(), 1000, Vec::new(), None, Vec::new(), true, &clock,
+        );
+        market.accept_contract_escrowed(&contract_id, &mut issuer, &hauler.id, &clock).unwrap();
+        market.fail_contract(&contract_id, &mut issuer, "missed deadline", &clock).unwrap();
+        assert_eq!(issuer.credits, credits - 3000);
+        assert_eq!(market.contracts[&contract_id].status, ContractStatus::Failed);
+    }
+
+    #[test]
+    fn test_auction_enforces_increment_and_settles_highest_bid() {
+        let mut market = PlayerMarket::new();
+        let clock = GameClock::default();
+        let ends_at = clock.hours_from_now(2);
+        let mut seller = Player::new("Seller");
+        let mut a = Player::new("A");
+        let mut b = Player::new("B");
+        seller.inventory.add_item(Item::new("Relic", 500, 1, ItemType::Product), 3);
+        let (a_credits, b_credits) = (a.credits, b.credits);
+        let listing_id = market.create_auction(
+            &mut seller, "Relic", 3, 100, "sol", "sol",
+            ends_at, 10, 0, ListingVisibility::Public, "", Vec::new(), &clock,
+        ).unwrap();
+        assert_eq!(seller.inventory.get_item_quantity("Relic"), 0);
+
+        assert!(market.purchase_listing(&listing_id, &b.id, None, 3, 0, &clock).is_err());
+        assert!(market.place_bid(&listing_id, &a.id, "A", None, 0, 120, 3, "", None, &clock).is_err());
+        assert!(market.place_auction_bid(&listing_id, &mut a, None, 0, 90, "", &clock).is_err());
+        assert_eq!(a.credits, a_credits);
+        let first = market.place_auction_bid(&listing_id, &mut a, None, 0, 120, "", &clock).unwrap();
+        assert_eq!(a.credits, a_credits - 360);
+        assert!(market.cancel_bid(&first, &a.id).is_err());
+        assert!(market.place_auction_bid(&listing_id, &mut b, None, 0, 125, "", &clock).is_err());
+        let winner = market.place_auction_bid(&listing_id, &mut b, None, 0, 130, "", &clock).unwrap();
+        assert_eq!(b.credits, b_credits - 390);
+        assert_eq!(market.bids[&first].status, BidStatus::Rejected);
+        assert_eq!(market.consignment_accounts[&a.id].credits, 360);
+
+        // The seller can't take a bid early, and the lot doesn't close before its end
+        assert!(market.accept_bid(&winner, &clock).is_err());
+        assert!(market.accept_bid_escrowed(&winner, &mut seller, &mut b, &clock).is_err());
+        let (_, _, sales) = market.process_expirations(&clock);
+        assert!(sales.is_empty());
+
+        let (_, _, sales) = market.process_expirations(&GameClock::at(ends_at));
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].buyer_id, b.id);
+        assert_eq!(sales[0].total_price, 390);
+        assert_eq!(market.bids[&winner].status, BidStatus::Accepted);
+        assert!(!market.listings.contains_key(&listing_id));
+
+        // The winner paid when bidding; each side collects what they're owed
+        assert_eq!(market.collect_consignments(&mut b, &clock), (0, vec![("Relic".to_string(), 3)]));
+        assert_eq!(b.credits, b_credits - 390);
+        assert_eq!(b.inventory.get_item_quantity("Relic"), 3);
+        market.collect_consignments(&mut a, &clock);
+        assert_eq!(a.credits, a_credits);
+        let seller_credits = seller.credits;
+        assert_eq!(market.collect_consignments(&mut seller, &clock).0, 371);
+        assert_eq!(seller.credits, seller_credits + 371);
+    }
+
+    #[test]
+    fn test_consigned_goods_sell_and_return_without_the_seller() {
+        let mut market = PlayerMarket::new();
+        let clock = GameClock::default();
+        let mut seller = Player::new("Seller");
+        seller.inventory.add_item(Item::new("Relic", 500, 1, ItemType::Product), 5);
+        let expires_at = clock.hours_from_now(1);
+
+        assert!(market.consign_listing(
+            &mut seller, "Relic", 6, 1000, "sol", "sol", None, 0, ListingVisibility::Public, false, "", Vec::new(), &clock,
+        ).is_err());
+        let listing_id = market.consign_listing(
+            &mut seller, "Relic", 5, 1000, "sol", "sol", Some(expires_at), 0, ListingVisibility::Public, false, "", Vec::new(), &clock,
+        ).unwrap();
+        assert_eq!(seller.inventory.get_item_quantity("Relic"), 0);
+
+        // The buyer pays and takes the goods as the sale credits the seller
+        let mut buyer = Player::new("Buyer");
+        let buyer_credits = buyer.credits;
+        assert!(market.purchase_listing(&listing_id, &buyer.id, None, 2, 0, &clock).is_err());
+        market.purchase_consigned(&listing_id, &mut buyer, None, 2, 0, &clock).unwrap();
+        assert_eq!(buyer.credits, buyer_credits - 2000);
+        assert_eq!(buyer.inventory.get_item_quantity("Relic"), 2);
+        market.process_expirations(&GameClock::at(expires_at + 1));
+        assert!(!market.listings.contains_key(&listing_id));
+
+        let credits = seller.credits;
+        let (paid, returned) = market.collect_consignments(&mut seller, &clock);
+        assert_eq!(paid, 1900);
+        assert_eq!(seller.credits, credits + 1900);
+        assert_eq!(returned, vec![("Relic".to_string(), 3)]);
+        assert_eq!(seller.inventory.get_item_quantity("Relic"), 3);
+        assert!(market.consignment_accounts.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize};
-use crate::models::item::ResourceType;
+use crate::models::item::{Item, ResourceType};
+use crate::models::crew::{CrewMember, CrewRole};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ShipType {
@@ -18,6 +19,63 @@ impl ShipType {
             ShipType::Fighter => "Fighter".to_string(),
         }
     }
+
+    // What a new hull of this type costs
+    pub fn base_value(&self) -> u32 {
+        match self {
+            ShipType::Scout => 40_000,
+            ShipType::Freighter => 90_000,
+            ShipType::Miner => 70_000,
+            ShipType::Fighter => 80_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ModuleType {
+    Weapon, // Adds to attack
+    Shield, // Adds to defense
+    Armor,  // Adds to defense
+    MiningLaser, // Adds to mining power
+    Engine, // Adds to fuel efficiency
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipModule {
+    pub name: String,
+    pub module_type: ModuleType,
+    pub rating: u32,
+    // Wear left, carried over from the item it was fitted from
+    #[serde(default)]
+    pub durability: Option<u32>,
+}
+
+#[allow(dead_code)]
+impl ShipModule {
+    pub fn new(name: &str, module_type: ModuleType, rating: u32) -> Self {
+        ShipModule {
+            name: name.to_string(),
+            module_type,
+            rating,
+            durability: None,
+        }
+    }
+
+    // The module an item turns into when fitted. Only items whose
+    // attributes describe a module can be fitted, and not once worn out.
+    pub fn from_item(item: &Item) -> Result<Self, String> {
+        let stats = item.attributes.module.as_ref()
+            .ok_or_else(|| format!("{} can't be fitted to a ship", item.name))?;
+        if item.attributes.durability == Some(0) {
+            return Err(format!("{} is worn out", item.name));
+        }
+        Ok(ShipModule {
+            name: item.name.clone(),
+            module_type: stats.module_type.clone(),
+            rating: stats.rating,
+            durability: item.attributes.durability,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +100,19 @@ pub struct Ship {
     pub fuel_capacity: u32,        // Maximum fuel capacity
     pub current_fuel: u32,         // Current fuel level
     pub fuel_consumption_rate: f32, // Fuel used per light year of travel
+    // Fuel costs are divided by this, before engine modules (1.0 = stock)
+    #[serde(default = "default_fuel_efficiency")]
+    pub fuel_efficiency: f32,
+    // Hired crew, limited by crew_capacity()
+    #[serde(default)]
+    pub crew: Vec<CrewMember>,
+    // Installed weapon and defense modules, limited by module_slots()
+    #[serde(default)]
+    pub modules: Vec<ShipModule>,
+}
+
+fn default_fuel_efficiency() -> f32 {
+    1.0
 }
 
 #[allow(dead_code)]
@@ -98,6 +169,9 @@ impl Ship {
             fuel_capacity,
             current_fuel: fuel_capacity, // Start with a full tank
             fuel_consumption_rate,
+            fuel_efficiency: default_fuel_efficiency(),
+            crew: Vec::new(),
+            modules: Vec::new(),
         }
     }
     
@@ -110,6 +184,124 @@ impl Ship {
         self.hull = (self.hull + amount).min(self.max_hull);
     }
 
+    // Hull condition as a percentage of max hull (100.0 = undamaged)
+    pub fn hull_integrity(&self) -> f32 {
+        if self.max_hull == 0 {
+            return 0.0;
+        }
+        self.hull as f32 / self.max_hull as f32 * 100.0
+    }
+
+    // Hull points missing, i.e. what a full repair would restore
+    pub fn hull_damage(&self) -> u32 {
+        self.max_hull.saturating_sub(self.hull)
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.hull == 0
+    }
+
+    // Replacement value: the hull plus what the installed modules are worth
+    pub fn value(&self) -> u32 {
+        let modules: u32 = self.modules.iter().map(|module| module.rating * 1_000).sum();
+        self.ship_type.base_value() + modules
+    }
+
+    // How many crew members fit aboard
+    pub fn crew_capacity(&self) -> usize {
+        match self.ship_type {
+            ShipType::Scout => 2,
+            ShipType::Freighter => 4,
+            ShipType::Miner => 3,
+            ShipType::Fighter => 1,
+        }
+    }
+
+    pub fn add_crew_member(&mut self, member: CrewMember) -> Result<(), String> {
+        if self.crew.len() >= self.crew_capacity() {
+            return Err(format!("No room aboard - crew capacity is {}", self.crew_capacity()));
+        }
+        self.crew.push(member);
+        Ok(())
+    }
+
+    pub fn remove_crew_member(&mut self, index: usize) -> Option<CrewMember> {
+        if index < self.crew.len() {
+            Some(self.crew.remove(index))
+        } else {
+            None
+        }
+    }
+
+    // Combined bonus from all crew in a role, capped at 50%
+    pub fn crew_bonus(&self, role: &CrewRole) -> f32 {
+        let total: f32 = self.crew.iter()
+            .filter(|member| &member.role == role)
+            .map(|member| member.bonus())
+            .sum();
+        total.min(0.5)
+    }
+
+    pub fn crew_daily_upkeep(&self) -> u32 {
+        self.crew.iter().map(|member| member.daily_upkeep).sum()
+    }
+
+    pub fn module_slots(&self) -> usize {
+        match self.ship_type {
+            ShipType::Scout => 2,
+            ShipType::Freighter => 2,
+            ShipType::Miner => 2,
+            ShipType::Fighter => 4,
+        }
+    }
+
+    pub fn install_module(&mut self, module: ShipModule) -> Result<(), String> {
+        if self.modules.len() >= self.module_slots() {
+            return Err(format!("No free module slots ({} max)", self.module_slots()));
+        }
+        self.modules.push(module);
+        Ok(())
+    }
+
+    pub fn remove_module(&mut self, index: usize) -> Option<ShipModule> {
+        if index < self.modules.len() {
+            Some(self.modules.remove(index))
+        } else {
+            None
+        }
+    }
+
+    fn module_rating(&self, module_type: &ModuleType) -> u32 {
+        self.modules.iter()
+            .filter(|module| module.module_type == *module_type)
+            .map(|module| module.rating)
+            .sum()
+    }
+
+    // Combat attack: base weapons plus weapon modules, with every crew member
+    // manning a turret
+    pub fn attack_rating(&self) -> u32 {
+        self.weapon_power + self.module_rating(&ModuleType::Weapon) + self.crew.len() as u32 * 2
+    }
+
+    // Combat defense: shield and armor modules, improved by an engineer
+    // keeping systems running
+    pub fn defense_rating(&self) -> u32 {
+        let base = self.module_rating(&ModuleType::Shield) + self.module_rating(&ModuleType::Armor);
+        (base as f32 * (1.0 + self.crew_bonus(&CrewRole::Engineer))) as u32
+    }
+
+    // Mining power: the ship's own rigs plus any mining laser modules
+    pub fn mining_rating(&self) -> u32 {
+        self.mining_power + self.module_rating(&ModuleType::MiningLaser)
+    }
+
+    // Fuel efficiency with engine modules fitted: each rating point is 1%
+    pub fn effective_fuel_efficiency(&self) -> f32 {
+        let engines = self.module_rating(&ModuleType::Engine) as f32 / 100.0;
+        (self.fuel_efficiency * (1.0 + engines)).max(0.1)
+    }
+
     pub fn recharge_shield(&mut self, amount: u32) {
         self.shield = (self.shield + amount).min(self.max_shield);
     }
@@ -173,7 +365,8 @@ impl Ship {
     
     // Calculate fuel required for a jump of given distance
     pub fn calculate_fuel_for_distance(&self, distance: f32) -> u32 {
-        (distance * self.fuel_consumption_rate).ceil() as u32
+        let navigator_savings = self.crew_bonus(&CrewRole::Navigator);
+        (distance * self.fuel_consumption_rate * (1.0 - navigator_savings) / self.effective_fuel_efficiency()).ceil() as u32
     }
     
     // Check if ship has enough fuel for a jump
@@ -208,7 +401,7 @@ impl Ship {
     
     // Get maximum travel range with current fuel
     pub fn get_max_range_with_current_fuel(&self) -> f32 {
-        self.current_fuel as f32 / self.fuel_consumption_rate
+        self.current_fuel as f32 * self.effective_fuel_efficiency() / self.fuel_consumption_rate
     }
     
     // Get fuel status display string
@@ -222,3 +415,23 @@ impl Ship {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::{ItemAttributes, ItemType};
+
+    #[test]
+    fn test_modules_fit_from_item_attributes() {
+        let plain = Item::new("Iron", 50, 1, ItemType::Resource(ResourceType::Mineral));
+        assert!(ShipModule::from_item(&plain).is_err());
+
+        let shield = Item::new("Shield Generator", 2000, 10, ItemType::ShipModule)
+            .with_attributes(ItemAttributes::module(ModuleType::Shield, 10).with_durability(80));
+        let module = ShipModule::from_item(&shield).unwrap();
+        assert_eq!((module.module_type, module.rating, module.durability), (ModuleType::Shield, 10, Some(80)));
+
+        let worn = shield.with_attributes(ItemAttributes::module(ModuleType::Shield, 10).with_durability(0));
+        assert!(ShipModule::from_item(&worn).is_err());
+    }
+}
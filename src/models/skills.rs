@@ -231,6 +231,18 @@ impl SkillSet {
         }
     }
     
+    // Combat-specific methods
+    
+    pub fn get_combat_level(&self) -> u8 {
+        self.get_skill(&SkillCategory::Combat).map(|skill| skill.level).unwrap_or(0)
+    }
+    
+    pub fn gain_combat_experience(&mut self, amount: u32) {
+        if let Some(skill) = self.get_skill_mut(&SkillCategory::Combat) {
+            skill.add_experience(amount as f64);
+        }
+    }
+    
     // Mining-specific methods
     
     // Get the current mining skill level
@@ -0,0 +1,135 @@
+// Lifetime statistics and achievements
+// Totals only ever grow; each system that does something worth counting
+// adds to them. Achievements are thresholds on those totals, unlocked once
+// and kept for good.
+
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    CreditsEarned,
+    UnitsMined,
+    JumpsMade,
+    ItemsCrafted,
+    TradesCompleted,
+    DistanceTraveled,
+}
+
+impl Stat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stat::CreditsEarned => "Credits earned",
+            Stat::UnitsMined => "Units mined",
+            Stat::JumpsMade => "Jumps made",
+            Stat::ItemsCrafted => "Items crafted",
+            Stat::TradesCompleted => "Trades completed",
+            Stat::DistanceTraveled => "Distance traveled (LY)",
+        }
+    }
+}
+
+pub const ALL_STATS: [Stat; 6] = [
+    Stat::CreditsEarned,
+    Stat::UnitsMined,
+    Stat::JumpsMade,
+    Stat::ItemsCrafted,
+    Stat::TradesCompleted,
+    Stat::DistanceTraveled,
+];
+
+pub struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub stat: Stat,
+    pub threshold: u64,
+}
+
+impl Achievement {
+    pub fn describe(&self) -> String {
+        format!("{}: {} {}", self.name, self.stat.label(), self.threshold)
+    }
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement { id: "first_profit", name: "First Profit", stat: Stat::CreditsEarned, threshold: 1_000 },
+    Achievement { id: "merchant", name: "Merchant", stat: Stat::CreditsEarned, threshold: 100_000 },
+    Achievement { id: "tycoon", name: "Tycoon", stat: Stat::CreditsEarned, threshold: 10_000_000 },
+    Achievement { id: "prospector", name: "Prospector", stat: Stat::UnitsMined, threshold: 100 },
+    Achievement { id: "deep_core", name: "Deep Core", stat: Stat::UnitsMined, threshold: 10_000 },
+    Achievement { id: "first_jump", name: "Off the Map", stat: Stat::JumpsMade, threshold: 1 },
+    Achievement { id: "wanderer", name: "Wanderer", stat: Stat::JumpsMade, threshold: 100 },
+    Achievement { id: "long_haul", name: "Long Haul", stat: Stat::DistanceTraveled, threshold: 1_000 },
+    Achievement { id: "artisan", name: "Artisan", stat: Stat::ItemsCrafted, threshold: 50 },
+    Achievement { id: "dealmaker", name: "Dealmaker", stat: Stat::TradesCompleted, threshold: 500 },
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    #[serde(default)]
+    pub credits_earned: u64,
+    #[serde(default)]
+    pub units_mined: u64,
+    #[serde(default)]
+    pub jumps_made: u64,
+    #[serde(default)]
+    pub items_crafted: u64,
+    #[serde(default)]
+    pub trades_completed: u64,
+    #[serde(default)]
+    pub distance_traveled: f64,
+    // Ids of unlocked achievements, in the order they were earned
+    #[serde(default)]
+    pub achievements: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl PlayerStats {
+    pub fn get(&self, stat: Stat) -> u64 {
+        match stat {
+            Stat::CreditsEarned => self.credits_earned,
+            Stat::UnitsMined => self.units_mined,
+            Stat::JumpsMade => self.jumps_made,
+            Stat::ItemsCrafted => self.items_crafted,
+            Stat::TradesCompleted => self.trades_completed,
+            Stat::DistanceTraveled => self.distance_traveled as u64,
+        }
+    }
+
+    pub fn record_jump(&mut self, distance: f32) {
+        self.jumps_made += 1;
+        self.distance_traveled += distance as f64;
+    }
+
+    pub fn has_achievement(&self, id: &str) -> bool {
+        self.achievements.iter().any(|earned| earned == id)
+    }
+
+    // Unlock every achievement whose threshold has now been reached.
+    // Returns the ones unlocked by this call.
+    pub fn check_achievements(&mut self) -> Vec<&'static Achievement> {
+        let unlocked: Vec<&'static Achievement> = ACHIEVEMENTS.iter()
+            .filter(|achievement| !self.has_achievement(achievement.id))
+            .filter(|achievement| self.get(achievement.stat) >= achievement.threshold)
+            .collect();
+        self.achievements.extend(unlocked.iter().map(|achievement| achievement.id.to_string()));
+        unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_achievements_unlock_once() {
+        let mut stats = PlayerStats::default();
+        assert!(stats.check_achievements().is_empty());
+
+        stats.record_jump(4.5);
+        stats.units_mined = 150;
+        let ids: Vec<&str> = stats.check_achievements().iter().map(|achievement| achievement.id).collect();
+        assert_eq!(ids, vec!["prospector", "first_jump"]);
+        assert!(stats.check_achievements().is_empty());
+        assert!(stats.has_achievement("first_jump"));
+    }
+}
@@ -1,11 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use noise::{NoiseFn, Perlin};
+use rayon::prelude::*;
 
 use crate::models::item::{Item, ItemType, ResourceType};
-use crate::models::market::{Market, MarketType, PriceHistory, MarketItem};
+use crate::models::market::{Market, MarketType, PriceHistory, MarketItem, TradeVolume};
+use crate::models::market_definition::MarketDefinition;
+use crate::models::news::{self, MarketNews, NEWS_HISTORY};
+use crate::models::faction::FactionType;
+use crate::utils::clock::GameClock;
+use crate::debug;
+use crate::{time_function, end_timing};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ResourceFieldType {
@@ -125,6 +134,18 @@ impl StationType {
         }
     }
     
+    // The kind of market a station of this type runs
+    pub fn market_type(&self) -> MarketType {
+        match self {
+            StationType::Trading => MarketType::Trading,
+            StationType::Military => MarketType::Military,
+            StationType::Mining => MarketType::Mining,
+            StationType::Research => MarketType::HighTech,
+            StationType::Industrial => MarketType::Industrial,
+            StationType::Habitation => MarketType::Agricultural,
+        }
+    }
+    
     pub fn services(&self) -> Vec<String> {
         match self {
             StationType::Trading => vec![
@@ -205,6 +226,224 @@ pub struct StarSystem {
     pub resource_fields: Vec<ResourceField>,
     pub stations: Vec<Station>,
     pub resources: Vec<(String, u32)>, // Legacy field - general resource abundance
+    // Ids of systems linked to this one by an established jump lane
+    #[serde(default)]
+    pub connections: Vec<String>,
+}
+
+// Parameters controlling procedural universe generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniverseConfig {
+    pub seed: u64,
+    pub system_count: usize,            // Generated systems, not counting Sol
+    pub station_probability: f64,       // Chance a system has stations
+    pub black_hole_probability: f64,    // Chance a system has a black hole
+    pub asteroid_field_probability: f64,
+    pub ice_field_probability: f64,
+    pub gas_field_probability: f64,
+    pub moon_residue_probability: f64,  // Per moon
+    // Chance each system gets an extra lane beyond the spanning tree
+    #[serde(default = "default_extra_lane_probability")]
+    pub extra_lane_probability: f64,
+    // Universes with at least this many systems are generated in parallel
+    #[serde(default = "default_parallel_threshold")]
+    pub parallel_threshold: usize,
+    // JSON file describing market stock; the embedded default when unset
+    #[serde(default)]
+    pub market_definition_file: Option<String>,
+}
+
+fn default_extra_lane_probability() -> f64 {
+    0.4
+}
+
+// Per-system work is small, so threads only pay off for big universes
+fn default_parallel_threshold() -> usize {
+    1000
+}
+
+// Offset between the universe seed and the first system's seed, keeping
+// system seeds clear of the name and lane RNG seeds
+const SYSTEM_SEED_OFFSET: u64 = 1000;
+
+// Chance a market price update breaks a new market event; with updates
+// every few game minutes that is a handful of events a day
+const MARKET_EVENT_CHANCE: f32 = 0.02;
+
+impl Default for UniverseConfig {
+    fn default() -> Self {
+        UniverseConfig {
+            seed: 12345,  // Fixed seed for reproducible universe
+            system_count: SYSTEM_NAMES.len(),
+            station_probability: 0.6,
+            black_hole_probability: 0.05,
+            asteroid_field_probability: 0.75,
+            ice_field_probability: 0.5,
+            gas_field_probability: 0.4,
+            moon_residue_probability: 0.35,
+            extra_lane_probability: default_extra_lane_probability(),
+            parallel_threshold: default_parallel_threshold(),
+            market_definition_file: None,
+        }
+    }
+}
+
+// Where a new character starts out
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum StartLocation {
+    // A system suited to the character's faction
+    #[default]
+    Faction,
+    // Any system with a station other than Sol, fixed by the universe seed
+    Random,
+    System(String),
+}
+
+impl StartLocation {
+    // "faction", "random" or a system id
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "faction" => StartLocation::Faction,
+            "random" => StartLocation::Random,
+            id => StartLocation::System(id.to_string()),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl UniverseConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.system_count < 1 {
+            return Err("system_count must be at least 1".to_string());
+        }
+        
+        let probabilities = [
+            ("station_probability", self.station_probability),
+            ("black_hole_probability", self.black_hole_probability),
+            ("asteroid_field_probability", self.asteroid_field_probability),
+            ("ice_field_probability", self.ice_field_probability),
+            ("gas_field_probability", self.gas_field_probability),
+            ("moon_residue_probability", self.moon_residue_probability),
+            ("extra_lane_probability", self.extra_lane_probability),
+        ];
+        for (name, value) in probabilities.iter() {
+            if !(0.0..=1.0).contains(value) {
+                return Err(format!("{} must be between 0 and 1 (got {})", name, value));
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+// Named systems used first; larger universes get generated names after these
+const SYSTEM_NAMES: [&str; 19] = [
+    "Alpha Centauri", "Barnard's Star", "Wolf 359", "Lalande 21185", 
+    "Sirius", "Luyten 726-8", "Ross 154", "Ross 248", "Epsilon Eridani",
+    "Lacaille 9352", "Procyon", "61 Cygni", "Struve 2398", "Groombridge 34",
+    "Epsilon Indi", "Tau Ceti", "Gliese 892", "Altair", "Gliese 570"
+];
+
+// System ids are derived from names, so two names mapping to the same id
+// would overwrite each other in the systems map
+fn system_id_from_name(name: &str) -> String {
+    name.to_lowercase().replace(" ", "_")
+}
+
+const NAME_ONSETS: [&str; 16] = [
+    "K", "V", "T", "Z", "M", "R", "S", "D", "Dr", "Th", "Br", "Qu", "L", "N", "X", "Ph",
+];
+const NAME_VOWELS: [&str; 8] = ["a", "e", "i", "o", "u", "ae", "io", "y"];
+const NAME_CODAS: [&str; 8] = ["", "n", "r", "s", "x", "th", "l", "k"];
+
+// Pronounceable name built from 2-3 random syllables, e.g. "Threnvax"
+fn generate_system_name(rng: &mut StdRng) -> String {
+    let syllables = rng.gen_range(2..4);
+    let mut name = String::new();
+    
+    for _ in 0..syllables {
+        name.push_str(NAME_ONSETS[rng.gen_range(0..NAME_ONSETS.len())]);
+        name.push_str(NAME_VOWELS[rng.gen_range(0..NAME_VOWELS.len())]);
+        name.push_str(NAME_CODAS[rng.gen_range(0..NAME_CODAS.len())]);
+    }
+    
+    // Only the first letter is capitalised
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(|c| c.to_lowercase())).collect(),
+        None => name,
+    }
+}
+
+// Name for the system at `index`, never reusing an id already in `used_ids`
+fn unique_system_name(index: usize, name_rng: &mut StdRng, used_ids: &mut HashSet<String>) -> String {
+    let mut name = match SYSTEM_NAMES.get(index) {
+        Some(name) => name.to_string(),
+        None => generate_system_name(name_rng),
+    };
+    
+    // Retry a few times, then fall back to a catalogue-style suffix
+    let mut attempts = 0;
+    while used_ids.contains(&system_id_from_name(&name)) {
+        attempts += 1;
+        name = if attempts < 10 {
+            generate_system_name(name_rng)
+        } else {
+            format!("{} {}", generate_system_name(name_rng), index)
+        };
+    }
+    
+    used_ids.insert(system_id_from_name(&name));
+    name
+}
+
+// Time spent in each phase of universe generation. Phases other than the
+// jump lanes run once per system, so their durations are summed over systems;
+// when systems are generated in parallel that sum can exceed `total`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationStats {
+    pub system_placement: Duration,
+    pub resource_fields: Duration,
+    pub stations: Duration,
+    pub market_items: Duration,
+    pub jump_lanes: Duration,
+    pub total: Duration,
+}
+
+#[allow(dead_code)]
+impl GenerationStats {
+    pub fn phases(&self) -> [(&'static str, Duration); 5] {
+        [
+            ("system_placement", self.system_placement),
+            ("resource_fields", self.resource_fields),
+            ("stations", self.stations),
+            ("market_items", self.market_items),
+            ("jump_lanes", self.jump_lanes),
+        ]
+    }
+
+    // Add the per-system phases of `other` to these
+    fn add_phases(&mut self, other: &GenerationStats) {
+        self.system_placement += other.system_placement;
+        self.resource_fields += other.resource_fields;
+        self.stations += other.stations;
+        self.market_items += other.market_items;
+    }
+
+    // Feed the phase totals into the debug timing stats
+    fn record(&self) {
+        for (phase, duration) in self.phases() {
+            debug::record_timing(&format!("universe_generation::{}", phase), duration);
+        }
+    }
+}
+
+// Time since `lap` was last reset, resetting it for the next phase
+fn take_lap(lap: &mut Instant) -> Duration {
+    let now = Instant::now();
+    let elapsed = now - *lap;
+    *lap = now;
+    elapsed
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -212,12 +451,50 @@ pub struct Universe {
     systems: HashMap<String, StarSystem>,
     market_items: HashMap<String, Vec<(Item, u32)>>,
     rng_seed: u64,
+    // What markets are stocked with, kept so resets restock the same way
+    #[serde(default)]
+    market_definition: MarketDefinition,
+    // Game time of the most recent market price update
+    #[serde(default)]
+    last_price_update: u64,
+    // Only known for universes generated in this session
+    #[serde(skip)]
+    generation_stats: GenerationStats,
+    // Changes whenever the universe does, so copies made for clients can
+    // tell when they are stale. Not saved; a loaded universe gets a new one.
+    #[serde(skip, default = "next_revision")]
+    revision: u64,
+    // Market events, running and recently ended, oldest first
+    #[serde(default)]
+    events: Vec<MarketNews>,
+}
+
+// Revisions come from one counter so no two universe states in a process
+// share one, even when a universe is replaced outright
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
 }
 
 #[allow(dead_code)]
 impl Universe {
     pub fn new() -> Self {
-        let seed = 12345;  // Fixed seed for reproducible universe
+        Self::with_config(UniverseConfig::default())
+            .expect("default universe config is valid")
+    }
+
+    pub fn with_config(config: UniverseConfig) -> Result<Self, String> {
+        config.validate()?;
+        let market_definition = match &config.market_definition_file {
+            Some(path) => MarketDefinition::load(path)?,
+            None => MarketDefinition::default(),
+        };
+        
+        let (generation_op, generation_start) = time_function!("universe_generation");
+        let mut stats = GenerationStats::default();
+        
+        let seed = config.seed;
         let mut rng = StdRng::seed_from_u64(seed);
         let perlin = Perlin::new(seed as u32);
         
@@ -229,259 +506,90 @@ impl Universe {
         
         systems.insert(sol.id.clone(), sol);
         
-        // Generate some basic systems
-        let system_names = [
-            "Alpha Centauri", "Barnard's Star", "Wolf 359", "Lalande 21185", 
-            "Sirius", "Luyten 726-8", "Ross 154", "Ross 248", "Epsilon Eridani",
-            "Lacaille 9352", "Procyon", "61 Cygni", "Struve 2398", "Groombridge 34",
-            "Epsilon Indi", "Tau Ceti", "Gliese 892", "Altair", "Gliese 570"
-        ];
+        // Spread larger universes over a wider area to keep density similar
+        let max_distance = (20.0 * (config.system_count as f32 / SYSTEM_NAMES.len() as f32).sqrt()).max(20.0);
         
-        for name in system_names.iter() {
-            // Generate position using noise to create more realistic clusters
-            let angle = rng.gen_range(0.0..std::f64::consts::TAU) as f32;
-            let distance = rng.gen_range(4.0..20.0);
-            
-            let base_x = distance * angle.cos();
-            let base_y = distance * angle.sin();
-            
-            // Add some noise to create more interesting patterns
-            let noise_val = perlin.get([base_x as f64 * 0.1, base_y as f64 * 0.1]) as f32 * 2.0;
-            
-            let x = base_x + noise_val;
-            let y = base_y + noise_val;
-            
-            // Determine if the system has a station (about 60% chance)
-            let has_station = rng.gen_bool(0.6);
-            
-            // Generate available resources for this system
-            let mut resources = Vec::new();
-            let resource_types = ["Iron", "Copper", "Silver", "Gold", "Titanium", "Water", "Hydrogen", "Oxygen"];
-            
-            let resource_count = rng.gen_range(1..4);
-            for _ in 0..resource_count {
-                let resource_idx = rng.gen_range(0..resource_types.len());
-                let resource_name = resource_types[resource_idx].to_string();
-                
-                // Generate abundance based on perlin noise for more realistic resource distribution
-                let abundance_noise = (perlin.get([x as f64 * 0.2, y as f64 * 0.2]) * 50.0 + 50.0) as u32;
-                resources.push((resource_name, abundance_noise.clamp(10, 100)));
-            }
-            
-            // Create a new star system with procedurally generated content
-            let mut celestial_bodies = Vec::new();
-            let mut resource_fields = Vec::new();
-            let mut stations = Vec::new();
-            
-            // Add a star
-            celestial_bodies.push(CelestialBody {
-                id: format!("{}_{}", name.to_lowercase().replace(" ", "_"), "star"),
-                name: format!("{} Star", name),
-                body_type: CelestialBodyType::Star,
-                position_x: 0.0,
-                position_y: 0.0,
-                size: rng.gen_range(0.8..1.5), // Varying star sizes
-            });
-            
-            // Always add a jump gate
-            celestial_bodies.push(CelestialBody {
-                id: format!("{}_{}", name.to_lowercase().replace(" ", "_"), "jump_gate"),
-                name: format!("{} Jump Gate", name),
-                body_type: CelestialBodyType::JumpGate,
-                position_x: rng.gen_range(-10.0..10.0),
-                position_y: rng.gen_range(-10.0..10.0),
-                size: 0.5,
-            });
-            
-            // Add 1-3 planets
-            let planet_count = rng.gen_range(1..4);
-            for i in 0..planet_count {
-                let planet_angle = rng.gen_range(0.0..std::f64::consts::TAU) as f32;
-                let planet_distance = rng.gen_range(2.0..8.0);
-                
-                let planet_x = planet_distance * planet_angle.cos();
-                let planet_y = planet_distance * planet_angle.sin();
-                
-                celestial_bodies.push(CelestialBody {
-                    id: format!("{}_planet_{}", name.to_lowercase().replace(" ", "_"), i+1),
-                    name: format!("{} Planet {}", name, i+1),
-                    body_type: CelestialBodyType::Planet,
-                    position_x: planet_x,
-                    position_y: planet_y,
-                    size: rng.gen_range(0.3..1.2), // Varying planet sizes
-                });
-                
-                // Maybe add a moon to the planet (50% chance)
-                if rng.gen_bool(0.5) {
-                    let moon_offset_x = rng.gen_range(0.2..0.5);
-                    let moon_offset_y = rng.gen_range(0.2..0.5);
-                    
-                    celestial_bodies.push(CelestialBody {
-                        id: format!("{}_moon_{}", name.to_lowercase().replace(" ", "_"), i+1),
-                        name: format!("{} Moon {}", name, i+1),
-                        body_type: CelestialBodyType::Moon,
-                        position_x: planet_x + moon_offset_x,
-                        position_y: planet_y + moon_offset_y,
-                        size: rng.gen_range(0.1..0.3), // Moons are smaller
-                    });
-                    
-                    // Add moon residue for mining (35% chance)
-                    if rng.gen_bool(0.35) {
-                        resource_fields.push(ResourceField {
-                            field_type: ResourceFieldType::MoonResidue,
-                            size: rng.gen_range(30..60),
-                            resources: ResourceFieldType::MoonResidue.primary_resources(),
-                            position_x: planet_x + moon_offset_x * 1.1,
-                            position_y: planet_y + moon_offset_y * 1.1,
-                        });
-                    }
-                }
-            }
-            
-            // Add resource fields
-            
-            // Asteroid field (75% chance)
-            if rng.gen_bool(0.75) {
-                resource_fields.push(ResourceField {
-                    field_type: ResourceFieldType::AsteroidField,
-                    size: rng.gen_range(40..90),
-                    resources: ResourceFieldType::AsteroidField.primary_resources(),
-                    position_x: rng.gen_range(-8.0..8.0),
-                    position_y: rng.gen_range(-8.0..8.0),
-                });
-            }
-            
-            // Ice field (50% chance)
-            if rng.gen_bool(0.5) {
-                resource_fields.push(ResourceField {
-                    field_type: ResourceFieldType::IceField,
-                    size: rng.gen_range(30..80),
-                    resources: ResourceFieldType::IceField.primary_resources(),
-                    position_x: rng.gen_range(-10.0..10.0),
-                    position_y: rng.gen_range(-10.0..10.0),
-                });
-            }
-            
-            // Gas field (40% chance)
-            if rng.gen_bool(0.4) {
-                resource_fields.push(ResourceField {
-                    field_type: ResourceFieldType::GasField,
-                    size: rng.gen_range(50..100),
-                    resources: ResourceFieldType::GasField.primary_resources(),
-                    position_x: rng.gen_range(-6.0..6.0),
-                    position_y: rng.gen_range(-6.0..6.0),
-                });
-            }
-            
-            // Black hole (5% chance)
-            if rng.gen_bool(0.05) {
-                celestial_bodies.push(CelestialBody {
-                    id: format!("{}_black_hole", name.to_lowercase().replace(" ", "_")),
-                    name: format!("{} Black Hole", name),
-                    body_type: CelestialBodyType::BlackHole,
-                    position_x: rng.gen_range(-4.0..4.0),
-                    position_y: rng.gen_range(-4.0..4.0),
-                    size: rng.gen_range(0.5..1.0),
-                });
-                
-                // Black holes have accretion disks for high-level mining
-                resource_fields.push(ResourceField {
-                    field_type: ResourceFieldType::BlackHoleAccretion,
-                    size: rng.gen_range(70..100),
-                    resources: ResourceFieldType::BlackHoleAccretion.primary_resources(),
-                    position_x: rng.gen_range(-4.0..4.0),
-                    position_y: rng.gen_range(-4.0..4.0),
-                });
-            }
-            
-            // Add stations if has_station is true
-            if has_station {
-                // Determine station types based on a weighted system
-                let station_types = [
-                    (StationType::Trading, 0.3),
-                    (StationType::Mining, 0.2),
-                    (StationType::Military, 0.15),
-                    (StationType::Research, 0.1),
-                    (StationType::Industrial, 0.15),
-                    (StationType::Habitation, 0.1),
-                ];
-                
-                // Add 1-2 stations
-                let station_count = rng.gen_range(1..3);
-                let mut station_types_used = Vec::new();
-                
-                for i in 0..station_count {
-                    // Choose station type based on weights
-                    let mut total_weight = 0.0;
-                    for (_, weight) in &station_types {
-                        total_weight += weight;
-                    }
-                    
-                    let mut random_val = rng.gen_range(0.0..total_weight);
-                    let mut selected_type = &StationType::Trading; // Default
-                    
-                    for (station_type, weight) in &station_types {
-                        if random_val < *weight {
-                            selected_type = station_type;
-                            break;
-                        }
-                        random_val -= weight;
-                    }
-                    
-                    // Check if this type was already used
-                    if station_types_used.contains(selected_type) {
-                        continue; // Skip duplicate types
-                    }
-                    
-                    station_types_used.push(selected_type.clone());
-                    
-                    // Add the station
-                    stations.push(Station {
-                        id: format!("{}_{}_station_{}", name.to_lowercase().replace(" ", "_"), selected_type.to_string().to_lowercase().replace(" ", "_"), i+1),
-                        name: format!("{} {}", name, selected_type.to_string()),
-                        station_type: selected_type.clone(),
-                        position_x: rng.gen_range(-6.0..6.0),
-                        position_y: rng.gen_range(-6.0..6.0),
-                        faction: None, // No faction assignment yet
-                        services: selected_type.services(),
-                    });
-                }
-            }
-            
-            // Create the system
-            let system = StarSystem {
-                id: name.to_lowercase().replace(" ", "_"),
-                name: name.to_string(),
-                x,
-                y,
-                celestial_bodies,
-                resource_fields,
-                stations,
-                resources, // Keep the old resources for compatibility
-            };
-            
-            let system_id = system.id.clone();
-            systems.insert(system_id.clone(), system);
-            
-            // Generate market items for systems with stations
-            if has_station {
-                generate_market_items(&mut market_items, &system_id, &mut rng);
-            }
+        // Names come from their own RNG so the layout of the named systems
+        // doesn't depend on how many extra names get generated
+        let mut name_rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+        let mut used_ids: HashSet<String> = systems.keys().cloned().collect();
+        
+        // Generate the star systems
+        let names: Vec<String> = (0..config.system_count)
+            .map(|index| unique_system_name(index, &mut name_rng, &mut used_ids))
+            .collect();
+        
+        // Each system has its own sub-seed, so generating them in parallel
+        // gives the same universe as generating them one by one
+        let generate = |(index, name): (usize, &String)| {
+            generate_system(&config, &market_definition, &perlin, max_distance, index, name)
+        };
+        let generated: Vec<GeneratedSystem> = if config.system_count >= config.parallel_threshold {
+            names.par_iter().enumerate().map(generate).collect()
+        } else {
+            names.iter().enumerate().map(generate).collect()
+        };
+        
+        for generated in generated {
+            stats.add_phases(&generated.stats);
+            market_items.extend(generated.market_items);
+            systems.insert(generated.system.id.clone(), generated.system);
         }
         
         // Also generate market items for Sol
-        generate_market_items(&mut market_items, "sol", &mut rng);
+        let mut lap = Instant::now();
+        generate_market_items(&market_definition, &mut market_items, "sol", &mut rng);
+        stats.market_items += take_lap(&mut lap);
+        
+        // Link everything up with jump lanes
+        let (lane_op, lane_start) = time_function!("universe_generation::jump_lanes");
+        let mut lane_rng = StdRng::seed_from_u64(seed.wrapping_add(2));
+        build_jump_lanes(&mut systems, config.extra_lane_probability, &mut lane_rng);
+        stats.jump_lanes = end_timing!((lane_op, lane_start));
 
-        Universe {
+        stats.total = end_timing!((generation_op, generation_start));
+        stats.record();
+
+        Ok(Universe {
             systems,
             market_items,
             rng_seed: seed,
-        }
+            market_definition,
+            last_price_update: 0,
+            generation_stats: stats,
+            revision: next_revision(),
+            events: Vec::new(),
+        })
+    }
+
+    // Identifies the universe's current state; any change gives a new one
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.rng_seed
+    }
+    
+    pub fn market_definition(&self) -> &MarketDefinition {
+        &self.market_definition
+    }
+
+    // Per-phase timings from generating this universe. All zero for a
+    // universe that was loaded from a save.
+    pub fn generation_stats(&self) -> &GenerationStats {
+        &self.generation_stats
     }
 
     pub fn get_system(&self, id: &str) -> Option<&StarSystem> {
         self.systems.get(id)
     }
+
+    // Whether a jump lane runs directly between two systems
+    pub fn are_connected(&self, a: &str, b: &str) -> bool {
+        self.systems.get(a)
+            .map_or(false, |system| system.connections.iter().any(|id| id == b))
+    }
     
     /// Get the total number of star systems in the universe
     pub fn get_systems_count(&self) -> usize {
@@ -509,15 +617,74 @@ impl Universe {
         systems.into_iter().take(9).collect()
     }
 
-    pub fn get_nearby_system(&self, index: usize) -> Option<StarSystem> {
-        let sol = self.get_system("sol").unwrap();
-        let nearby = self.get_nearby_systems(sol);
-        
-        if index < nearby.len() {
-            Some(nearby[index].clone())
-        } else {
-            None
+    // The `index`th entry of the nearby list for `from`
+    pub fn get_nearby_system(&self, from: &StarSystem, index: usize) -> Option<StarSystem> {
+        self.get_nearby_systems(from).into_iter().nth(index)
+    }
+
+    // The system closest to a point, preferring ones with a station
+    pub fn nearest_system(&self, x: f32, y: f32) -> Option<&StarSystem> {
+        let by_distance = |a: &&StarSystem, b: &&StarSystem| {
+            distance(x, y, a.x, a.y).partial_cmp(&distance(x, y, b.x, b.y)).unwrap()
+        };
+        self.systems.values()
+            .filter(|system| !system.stations.is_empty())
+            .min_by(by_distance)
+            .or_else(|| self.systems.values().min_by(by_distance))
+    }
+
+    // Sol when the universe has it, otherwise the first system with a
+    // station, by id
+    pub fn origin_system(&self) -> &StarSystem {
+        if let Some(sol) = self.systems.get("sol") {
+            return sol;
         }
+        let mut systems: Vec<&StarSystem> = self.systems.values().collect();
+        systems.sort_by(|a, b| a.id.cmp(&b.id));
+        systems.iter()
+            .find(|system| !system.stations.is_empty())
+            .or_else(|| systems.first())
+            .copied()
+            .expect("a universe always has at least one system")
+    }
+
+    // The system a new character of `faction` starts in. Unknown system ids
+    // fall back to the origin.
+    pub fn start_system(&self, location: &StartLocation, faction: &FactionType) -> StarSystem {
+        let origin = self.origin_system();
+        let mut candidates: Vec<&StarSystem> = self.systems.values()
+            .filter(|system| !system.stations.is_empty())
+            .collect();
+        candidates.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let start = match location {
+            StartLocation::System(id) => self.systems.get(id),
+            StartLocation::Random => {
+                candidates.retain(|system| system.id != "sol");
+                let mut rng = StdRng::seed_from_u64(self.rng_seed.wrapping_add(3));
+                (!candidates.is_empty()).then(|| candidates[rng.gen_range(0..candidates.len())])
+            },
+            // Traders start at the origin; everyone else at the nearest
+            // station of their own kind
+            StartLocation::Faction => {
+                let wanted = match faction {
+                    FactionType::Traders => None,
+                    FactionType::Miners => Some(StationType::Mining),
+                    FactionType::Military => Some(StationType::Military),
+                    FactionType::Scientists => Some(StationType::Research),
+                };
+                wanted.and_then(|wanted| {
+                    candidates.into_iter()
+                        .filter(|system| system.stations.iter().any(|station| station.station_type == wanted))
+                        .min_by(|a, b| {
+                            distance(origin.x, origin.y, a.x, a.y)
+                                .partial_cmp(&distance(origin.x, origin.y, b.x, b.y))
+                                .unwrap()
+                        })
+                })
+            },
+        };
+        start.unwrap_or(origin).clone()
     }
 
     pub fn get_market_items_for_system(&self, system_id: String) -> Vec<(Item, u32)> {
@@ -526,14 +693,38 @@ impl Universe {
             .unwrap_or_default()
     }
 
-    pub fn update_market_prices(&mut self) {
+    // (system id, price, quantity) for every market stocking `item_name`, cheapest first
+    pub fn get_price_comparison(&self, item_name: &str) -> Vec<(String, u32, u32)> {
+        let mut results: Vec<(String, u32, u32)> = self.market_items.iter()
+            .filter_map(|(system_id, items)| {
+                items.iter()
+                    .find(|(item, _)| item.name == item_name)
+                    .map(|(item, quantity)| (system_id.clone(), item.value, *quantity))
+            })
+            .collect();
+
+        results.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        results
+    }
+
+    // Update market prices for all systems. `activity` scales the size of the
+    // swings (1.0 = normal trading hours, lower when markets are quiet).
+    pub fn update_market_prices(&mut self, activity: f32, clock: &GameClock) {
         // Update market prices for all systems with some fluctuation
         let mut rng = StdRng::seed_from_u64(self.rng_seed);
+        let swing = 0.1 * activity.max(0.0);
+        self.last_price_update = clock.now();
+        self.revision = next_revision();
+        self.roll_market_event(clock.now());
         
-        for items in self.market_items.values_mut() {
+        for (system_id, items) in self.market_items.iter_mut() {
             for (item, _) in items {
-                // Fluctuate price by up to 10% in either direction
-                let fluctuation = rng.gen_range(0.9..1.1);
+                // Fluctuate price by up to 10% in either direction during normal hours
+                let fluctuation = if swing > 0.0 {
+                    rng.gen_range((1.0 - swing)..(1.0 + swing))
+                } else {
+                    1.0
+                };
                 let base_value = match &item.item_type {
                     ItemType::Resource(res_type) => {
                         match res_type {
@@ -554,41 +745,90 @@ impl Universe {
                     ItemType::Fuel => 150,
                 };
                 
-                item.value = (base_value as f32 * fluctuation) as u32;
+                let event_factor = news::price_factor(&self.events, system_id, item, clock.now());
+                item.value = (base_value as f32 * fluctuation * event_factor) as u32;
+            }
+        }
+    }
+    
+    // Maybe break a new market event, drawn from the time so every copy of
+    // the universe rolls the same one
+    fn roll_market_event(&mut self, now: u64) {
+        let mut rng = StdRng::seed_from_u64(self.rng_seed ^ now.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        if rng.gen::<f32>() >= MARKET_EVENT_CHANCE {
+            return;
+        }
+        
+        let mut system_ids: Vec<String> = self.market_items.keys().cloned().collect();
+        system_ids.sort();
+        let system_id = match system_ids.get(rng.gen_range(0..system_ids.len().max(1))) {
+            Some(system_id) => system_id.clone(),
+            None => return,
+        };
+        let mut item_names: Vec<String> = self.market_items[&system_id].iter()
+            .map(|(item, _)| item.name.clone())
+            .collect();
+        item_names.sort();
+        
+        if let Some(report) = MarketNews::random(&[system_id], &item_names, now, &mut rng) {
+            self.events.push(report);
+            if self.events.len() > NEWS_HISTORY {
+                self.events.remove(0);
             }
         }
     }
     
+    // Market events, running and recently ended, oldest first
+    pub fn market_news(&self) -> &[MarketNews] {
+        &self.events
+    }
+    
+    // Restock a system's market as freshly generated, discarding whatever
+    // trading has done to it since
+    pub fn reset_market(&mut self, system_id: &str) -> Result<(), String> {
+        if !self.market_items.contains_key(system_id) {
+            return Err(format!("No market in system {}", system_id));
+        }
+        
+        let seed = system_id.bytes()
+            .fold(self.rng_seed, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
+        let mut rng = StdRng::seed_from_u64(seed);
+        generate_market_items(&self.market_definition, &mut self.market_items, system_id, &mut rng);
+        self.revision = next_revision();
+        Ok(())
+    }
+    
     // Get a market by system ID
     pub fn get_market(&self, system_id: &str) -> Option<Market> {
         // For now, we'll create a temporary market for the system
         if let Some(_system) = self.systems.get(system_id) {
             // Get items for this system
             if let Some(items) = self.market_items.get(system_id) {
+                // Markets in a faction's territory price in its scrip
+                let scrip = self.market_definition.currency_for(system_id);
                 let market_items: HashMap<String, MarketItem> = items
                     .iter()
                     .map(|(item, quantity)| {
+                        let price = scrip.map_or(item.value, |scrip| scrip.from_credits(item.value));
                         let market_item = MarketItem {
                             item: item.clone(),
                             quantity: *quantity,
-                            base_price: item.value,
-                            current_price: item.value,
+                            base_price: price,
+                            current_price: price,
                             supply_level: 1.0,
                             demand_level: 1.0,
                             production_rate: 5,
                             consumption_rate: 3,
-                            price_volatility: 0.1,
+                            price_volatility: self.market_definition.volatility_for(system_id, &item.name).unwrap_or(0.1),
                             price_history: vec![
                                 PriceHistory {
-                                    timestamp: std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap_or(std::time::Duration::from_secs(0))
-                                        .as_secs(),
-                                    price: item.value,
+                                    timestamp: self.last_price_update,
+                                    price,
                                 }
                             ],
+                            trade_volume: TradeVolume::default(),
                         };
-                        (item.name.clone(), market_item)
+                        (Market::stock_key(item), market_item)
                     })
                     .collect();
                 
@@ -599,7 +839,13 @@ impl Universe {
                     tax_rate: 0.05,
                     last_update: 0,
                     trade_orders: vec![],
-                    local_events: vec![],
+                    local_events: self.events.iter()
+                        .filter(|report| report.is_active(self.last_price_update) && report.affects(system_id))
+                        .map(|report| report.event.clone())
+                        .collect(),
+                    currency: scrip.map(|scrip| scrip.currency()).unwrap_or_default(),
+                    credits_per_unit: scrip.map_or(1.0, |scrip| scrip.credits_per_unit),
+                    maker_spread: None,
                 };
                 
                 return Some(market);
@@ -616,9 +862,24 @@ impl Universe {
     }
     
     // Update a market (replace it with an updated version)
-    pub fn update_market(&mut self, _market: Market) {
-        // In a real implementation, this would update the market in the universe's storage
-        // For now, as a placeholder, we'll do nothing since our market is regenerated each time
+    pub fn update_market(&mut self, market: Market) {
+        // Markets are regenerated from their stock each time, so only the
+        // stock is written back; prices still come from the items' values
+        let items = match self.market_items.get_mut(&market.system_id) {
+            Some(items) => items,
+            None => return,
+        };
+        for (item, quantity) in items.iter_mut() {
+            if let Some(market_item) = market.items.get(&Market::stock_key(item)) {
+                *quantity = market_item.quantity;
+            }
+        }
+        for market_item in market.items.values() {
+            if !items.iter().any(|(item, _)| *item == market_item.item) {
+                items.push((market_item.item.clone(), market_item.quantity));
+            }
+        }
+        self.revision = next_revision();
     }
     
     // Get all system IDs in the universe
@@ -632,6 +893,271 @@ fn distance(x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
     ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
 }
 
+// A single generated system and the market stock of its stations
+struct GeneratedSystem {
+    system: StarSystem,
+    market_items: HashMap<String, Vec<(Item, u32)>>,
+    stats: GenerationStats,
+}
+
+// Seed for the system at `index`, derived from the universe seed so each
+// system can be generated on its own
+fn system_seed(seed: u64, index: usize) -> u64 {
+    seed.wrapping_add(SYSTEM_SEED_OFFSET).wrapping_add(index as u64)
+}
+
+// Generate the system at `index` from its own sub-seed. The result doesn't
+// depend on the other systems, so systems can be generated on any thread.
+fn generate_system(
+    config: &UniverseConfig,
+    markets: &MarketDefinition,
+    perlin: &Perlin,
+    max_distance: f32,
+    index: usize,
+    name: &str,
+) -> GeneratedSystem {
+    let mut rng = StdRng::seed_from_u64(system_seed(config.seed, index));
+    let mut stats = GenerationStats::default();
+    let mut lap = Instant::now();
+    
+    // Generate position using noise to create more realistic clusters
+    let angle = rng.gen_range(0.0..std::f64::consts::TAU) as f32;
+    let distance = rng.gen_range(4.0..max_distance);
+    
+    let base_x = distance * angle.cos();
+    let base_y = distance * angle.sin();
+    
+    // Add some noise to create more interesting patterns
+    let noise_val = perlin.get([base_x as f64 * 0.1, base_y as f64 * 0.1]) as f32 * 2.0;
+    
+    let x = base_x + noise_val;
+    let y = base_y + noise_val;
+    
+    // Determine if the system has a station (about 60% chance)
+    let has_station = rng.gen_bool(config.station_probability);
+    stats.system_placement += take_lap(&mut lap);
+    
+    // Generate available resources for this system
+    let mut resources = Vec::new();
+    let resource_types = ["Iron", "Copper", "Silver", "Gold", "Titanium", "Water", "Hydrogen", "Oxygen"];
+    
+    let resource_count = rng.gen_range(1..4);
+    for _ in 0..resource_count {
+        let resource_idx = rng.gen_range(0..resource_types.len());
+        let resource_name = resource_types[resource_idx].to_string();
+        
+        // Generate abundance based on perlin noise for more realistic resource distribution
+        let abundance_noise = (perlin.get([x as f64 * 0.2, y as f64 * 0.2]) * 50.0 + 50.0) as u32;
+        resources.push((resource_name, abundance_noise.clamp(10, 100)));
+    }
+    
+    // Create a new star system with procedurally generated content
+    let mut celestial_bodies = Vec::new();
+    let mut resource_fields = Vec::new();
+    let mut stations = Vec::new();
+    
+    // Add a star
+    celestial_bodies.push(CelestialBody {
+        id: format!("{}_{}", system_id_from_name(name), "star"),
+        name: format!("{} Star", name),
+        body_type: CelestialBodyType::Star,
+        position_x: 0.0,
+        position_y: 0.0,
+        size: rng.gen_range(0.8..1.5), // Varying star sizes
+    });
+    
+    // Always add a jump gate
+    celestial_bodies.push(CelestialBody {
+        id: format!("{}_{}", system_id_from_name(name), "jump_gate"),
+        name: format!("{} Jump Gate", name),
+        body_type: CelestialBodyType::JumpGate,
+        position_x: rng.gen_range(-10.0..10.0),
+        position_y: rng.gen_range(-10.0..10.0),
+        size: 0.5,
+    });
+    
+    // Add 1-3 planets
+    let planet_count = rng.gen_range(1..4);
+    for i in 0..planet_count {
+        let planet_angle = rng.gen_range(0.0..std::f64::consts::TAU) as f32;
+        let planet_distance = rng.gen_range(2.0..8.0);
+        
+        let planet_x = planet_distance * planet_angle.cos();
+        let planet_y = planet_distance * planet_angle.sin();
+        
+        celestial_bodies.push(CelestialBody {
+            id: format!("{}_planet_{}", system_id_from_name(name), i+1),
+            name: format!("{} Planet {}", name, i+1),
+            body_type: CelestialBodyType::Planet,
+            position_x: planet_x,
+            position_y: planet_y,
+            size: rng.gen_range(0.3..1.2), // Varying planet sizes
+        });
+        
+        // Maybe add a moon to the planet (50% chance)
+        if rng.gen_bool(0.5) {
+            let moon_offset_x = rng.gen_range(0.2..0.5);
+            let moon_offset_y = rng.gen_range(0.2..0.5);
+            
+            celestial_bodies.push(CelestialBody {
+                id: format!("{}_moon_{}", system_id_from_name(name), i+1),
+                name: format!("{} Moon {}", name, i+1),
+                body_type: CelestialBodyType::Moon,
+                position_x: planet_x + moon_offset_x,
+                position_y: planet_y + moon_offset_y,
+                size: rng.gen_range(0.1..0.3), // Moons are smaller
+            });
+            
+            // Add moon residue for mining
+            if rng.gen_bool(config.moon_residue_probability) {
+                resource_fields.push(ResourceField {
+                    field_type: ResourceFieldType::MoonResidue,
+                    size: rng.gen_range(30..60),
+                    resources: ResourceFieldType::MoonResidue.primary_resources(),
+                    position_x: planet_x + moon_offset_x * 1.1,
+                    position_y: planet_y + moon_offset_y * 1.1,
+                });
+            }
+        }
+    }
+    
+    // Add resource fields
+    
+    // Asteroid field
+    if rng.gen_bool(config.asteroid_field_probability) {
+        resource_fields.push(ResourceField {
+            field_type: ResourceFieldType::AsteroidField,
+            size: rng.gen_range(40..90),
+            resources: ResourceFieldType::AsteroidField.primary_resources(),
+            position_x: rng.gen_range(-8.0..8.0),
+            position_y: rng.gen_range(-8.0..8.0),
+        });
+    }
+    
+    // Ice field
+    if rng.gen_bool(config.ice_field_probability) {
+        resource_fields.push(ResourceField {
+            field_type: ResourceFieldType::IceField,
+            size: rng.gen_range(30..80),
+            resources: ResourceFieldType::IceField.primary_resources(),
+            position_x: rng.gen_range(-10.0..10.0),
+            position_y: rng.gen_range(-10.0..10.0),
+        });
+    }
+    
+    // Gas field
+    if rng.gen_bool(config.gas_field_probability) {
+        resource_fields.push(ResourceField {
+            field_type: ResourceFieldType::GasField,
+            size: rng.gen_range(50..100),
+            resources: ResourceFieldType::GasField.primary_resources(),
+            position_x: rng.gen_range(-6.0..6.0),
+            position_y: rng.gen_range(-6.0..6.0),
+        });
+    }
+    
+    // Black hole (rare)
+    if rng.gen_bool(config.black_hole_probability) {
+        celestial_bodies.push(CelestialBody {
+            id: format!("{}_black_hole", system_id_from_name(name)),
+            name: format!("{} Black Hole", name),
+            body_type: CelestialBodyType::BlackHole,
+            position_x: rng.gen_range(-4.0..4.0),
+            position_y: rng.gen_range(-4.0..4.0),
+            size: rng.gen_range(0.5..1.0),
+        });
+        
+        // Black holes have accretion disks for high-level mining
+        resource_fields.push(ResourceField {
+            field_type: ResourceFieldType::BlackHoleAccretion,
+            size: rng.gen_range(70..100),
+            resources: ResourceFieldType::BlackHoleAccretion.primary_resources(),
+            position_x: rng.gen_range(-4.0..4.0),
+            position_y: rng.gen_range(-4.0..4.0),
+        });
+    }
+    
+    stats.resource_fields += take_lap(&mut lap);
+    
+    // Add stations if has_station is true
+    if has_station {
+        // Determine station types based on a weighted system
+        let station_types = [
+            (StationType::Trading, 0.3),
+            (StationType::Mining, 0.2),
+            (StationType::Military, 0.15),
+            (StationType::Research, 0.1),
+            (StationType::Industrial, 0.15),
+            (StationType::Habitation, 0.1),
+        ];
+        
+        // Add 1-2 stations
+        let station_count = rng.gen_range(1..3);
+        let mut station_types_used = Vec::new();
+        
+        for i in 0..station_count {
+            // Choose station type based on weights
+            let mut total_weight = 0.0;
+            for (_, weight) in &station_types {
+                total_weight += weight;
+            }
+            
+            let mut random_val = rng.gen_range(0.0..total_weight);
+            let mut selected_type = &StationType::Trading; // Default
+            
+            for (station_type, weight) in &station_types {
+                if random_val < *weight {
+                    selected_type = station_type;
+                    break;
+                }
+                random_val -= weight;
+            }
+            
+            // Check if this type was already used
+            if station_types_used.contains(selected_type) {
+                continue; // Skip duplicate types
+            }
+            
+            station_types_used.push(selected_type.clone());
+            
+            // Add the station
+            stations.push(Station {
+                id: format!("{}_{}_station_{}", system_id_from_name(name), selected_type.to_string().to_lowercase().replace(" ", "_"), i+1),
+                name: format!("{} {}", name, selected_type.to_string()),
+                station_type: selected_type.clone(),
+                position_x: rng.gen_range(-6.0..6.0),
+                position_y: rng.gen_range(-6.0..6.0),
+                faction: None, // No faction assignment yet
+                services: selected_type.services(),
+            });
+        }
+    }
+    
+    // Create the system
+    let system = StarSystem {
+        id: system_id_from_name(name),
+        name: name.to_string(),
+        x,
+        y,
+        celestial_bodies,
+        resource_fields,
+        stations,
+        resources, // Keep the old resources for compatibility
+        connections: Vec::new(), // Filled in once all systems exist
+    };
+    
+    stats.stations += take_lap(&mut lap);
+    
+    // Generate market items for systems with stations
+    let mut market_items = HashMap::new();
+    if has_station {
+        generate_market_items(markets, &mut market_items, &system.id, &mut rng);
+    }
+    stats.market_items += take_lap(&mut lap);
+    
+    GeneratedSystem { system, market_items, stats }
+}
+
 fn create_sol_system() -> StarSystem {
     // Create celestial bodies for the Sol system
     let mut celestial_bodies = Vec::new();
@@ -839,112 +1365,200 @@ fn create_sol_system() -> StarSystem {
         resource_fields,
         stations,
         resources,
+        connections: Vec::new(),
     }
 }
 
-fn generate_market_items(
-    market_items: &mut HashMap<String, Vec<(Item, u32)>>,
-    system_id: &str, 
-    rng: &mut StdRng
-) {
-    let mut items = Vec::new();
+// Connect all systems with jump lanes. A minimum spanning tree over
+// distance guarantees no system is isolated; a few extra lanes to nearby
+// systems then add alternative routes.
+fn build_jump_lanes(systems: &mut HashMap<String, StarSystem>, extra_lane_probability: f64, rng: &mut StdRng) {
+    // Sorted so lane generation doesn't depend on HashMap ordering
+    let mut ids: Vec<String> = systems.keys().cloned().collect();
+    ids.sort();
+    if ids.len() < 2 {
+        return;
+    }
     
-    // Resources
-    let resources = [
-        "Iron", "Copper", "Silver", "Gold", "Titanium", "Water", "Hydrogen", "Oxygen"
-    ];
+    let positions: Vec<(f32, f32)> = ids.iter()
+        .map(|id| {
+            let system = &systems[id];
+            (system.x, system.y)
+        })
+        .collect();
+    let dist = |a: usize, b: usize| distance(positions[a].0, positions[a].1, positions[b].0, positions[b].1);
     
-    // Components
-    let components = [
-        "Power Cell", "Computer Chip", "Fusion Core", "Shield Generator", "Warp Drive"
-    ];
+    let mut lanes: Vec<(usize, usize)> = Vec::new();
     
-    // Products
-    let products = [
-        "Medical Supplies", "Luxury Goods", "Military Equipment", "Ship Parts"
-    ];
+    // Prim's algorithm for the spanning tree
+    let mut in_tree = vec![false; ids.len()];
+    let mut best: Vec<(f32, usize)> = vec![(f32::MAX, 0); ids.len()];
+    in_tree[0] = true;
+    for other in 1..ids.len() {
+        best[other] = (dist(0, other), 0);
+    }
     
-    // Add 3-5 random resources
-    let resource_count = rng.gen_range(3..6);
-    for _ in 0..resource_count {
-        let resource_idx = rng.gen_range(0..resources.len());
-        let name = resources[resource_idx].to_string();
-        
-        let base_value = 50 + rng.gen_range(0..100);
-        let quantity = rng.gen_range(10..100);
-        
-        // Determine resource type based on name
-        let resource_type = if name.contains("Water") || name.contains("Ice") {
-            ResourceType::Ice
-        } else if name.contains("Hydrogen") || name.contains("Oxygen") || name.contains("Gas") {
-            ResourceType::Gas
-        } else if name.contains("Titanium") || name.contains("Gold") {
-            ResourceType::Mineral
-        } else {
-            // Default to mineral for other common metals
-            ResourceType::Mineral
-        };
+    for _ in 1..ids.len() {
+        let next = (0..ids.len())
+            .filter(|&i| !in_tree[i])
+            .min_by(|&a, &b| best[a].0.partial_cmp(&best[b].0).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap();
         
-        let item = Item {
-            name,
-            value: base_value,
-            weight: 1,
-            item_type: ItemType::Resource(resource_type),
-        };
+        in_tree[next] = true;
+        lanes.push((best[next].1, next));
         
-        items.push((item, quantity));
+        for other in 0..ids.len() {
+            if !in_tree[other] && dist(next, other) < best[other].0 {
+                best[other] = (dist(next, other), next);
+            }
+        }
     }
     
-    // Add 2-3 random components
-    let component_count = rng.gen_range(2..4);
-    for _ in 0..component_count {
-        let component_idx = rng.gen_range(0..components.len());
-        let name = components[component_idx].to_string();
+    // Extra lanes to the nearest system not already linked
+    for from in 0..ids.len() {
+        if !rng.gen_bool(extra_lane_probability) {
+            continue;
+        }
         
-        let base_value = 200 + rng.gen_range(0..200);
-        let quantity = rng.gen_range(5..20);
+        let nearest_unlinked = (0..ids.len())
+            .filter(|&to| to != from)
+            .filter(|&to| !lanes.contains(&(from, to)) && !lanes.contains(&(to, from)))
+            .min_by(|&a, &b| dist(from, a).partial_cmp(&dist(from, b)).unwrap_or(std::cmp::Ordering::Equal));
         
-        let item = Item {
-            name,
-            value: base_value,
-            weight: 2,
-            item_type: ItemType::Component,
+        if let Some(to) = nearest_unlinked {
+            lanes.push((from, to));
+        }
+    }
+    
+    // Lanes run both ways
+    for (a, b) in lanes {
+        if let Some(system) = systems.get_mut(&ids[a]) {
+            system.connections.push(ids[b].clone());
+        }
+        if let Some(system) = systems.get_mut(&ids[b]) {
+            system.connections.push(ids[a].clone());
+        }
+    }
+    
+    for system in systems.values_mut() {
+        system.connections.sort();
+    }
+}
+
+fn generate_market_items(
+    definition: &MarketDefinition,
+    market_items: &mut HashMap<String, Vec<(Item, u32)>>,
+    system_id: &str, 
+    rng: &mut StdRng
+) {
+    let items = definition.generate_stock(system_id, rng);
+    market_items.insert(system_id.to_string(), items);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_large_universe_has_unique_system_ids() {
+        let config = UniverseConfig {
+            system_count: 200,
+            ..UniverseConfig::default()
         };
+        let universe = Universe::with_config(config).unwrap();
         
-        items.push((item, quantity));
+        // 200 generated systems plus Sol, none overwritten by an id collision
+        assert_eq!(universe.get_systems_count(), 201);
+        let ids: HashSet<String> = universe.get_all_system_ids().into_iter().collect();
+        assert_eq!(ids.len(), 201);
     }
     
-    // Add 1-2 random products
-    let product_count = rng.gen_range(1..3);
-    for _ in 0..product_count {
-        let product_idx = rng.gen_range(0..products.len());
-        let name = products[product_idx].to_string();
+    #[test]
+    fn test_jump_lanes_connect_every_system() {
+        let universe = Universe::new();
         
-        let base_value = 500 + rng.gen_range(0..500);
-        let quantity = rng.gen_range(2..10);
+        // Flood fill from Sol along lanes should reach every system
+        let mut reached: HashSet<String> = HashSet::new();
+        let mut frontier = vec!["sol".to_string()];
+        while let Some(id) = frontier.pop() {
+            if !reached.insert(id.clone()) {
+                continue;
+            }
+            for next in &universe.get_system(&id).unwrap().connections {
+                assert!(universe.are_connected(next, &id), "lanes must be symmetric");
+                frontier.push(next.clone());
+            }
+        }
         
-        let item = Item {
-            name,
-            value: base_value,
-            weight: 4,
-            item_type: ItemType::Product,
-        };
+        assert_eq!(reached.len(), universe.get_systems_count());
+    }
+    
+    #[test]
+    fn test_invalid_universe_config_rejected() {
+        let empty = UniverseConfig { system_count: 0, ..UniverseConfig::default() };
+        assert!(Universe::with_config(empty).is_err());
         
-        items.push((item, quantity));
+        let bad_probability = UniverseConfig { station_probability: 1.5, ..UniverseConfig::default() };
+        assert!(Universe::with_config(bad_probability).is_err());
     }
     
-    // Add fuel to all markets (since it's essential for travel)
-    let base_value = 120 + rng.gen_range(0..60);
-    let quantity = rng.gen_range(30..100);
+    #[test]
+    fn test_parallel_generation_matches_serial() {
+        let serial = UniverseConfig { system_count: 60, parallel_threshold: usize::MAX, ..UniverseConfig::default() };
+        let parallel = UniverseConfig { parallel_threshold: 0, ..serial.clone() };
+        
+        let serial = Universe::with_config(serial).unwrap();
+        let parallel = Universe::with_config(parallel).unwrap();
+        
+        for id in serial.get_all_system_ids() {
+            let a = serial.get_system(&id).unwrap();
+            let b = parallel.get_system(&id).unwrap();
+            assert_eq!((a.x, a.y, &a.connections), (b.x, b.y, &b.connections));
+            assert_eq!(a.stations.len(), b.stations.len());
+            assert_eq!(serial.market_items.get(&id), parallel.market_items.get(&id));
+        }
+    }
     
-    let fuel_item = Item {
-        name: "Standard Fuel".to_string(),
-        value: base_value,
-        weight: 1,
-        item_type: ItemType::Fuel,
-    };
+    #[test]
+    fn test_generation_stats_cover_phases() {
+        let universe = Universe::new();
+        let stats = universe.generation_stats();
+        
+        let phase_sum: Duration = stats.phases().iter().map(|(_, duration)| *duration).sum();
+        assert!(stats.total > Duration::ZERO);
+        assert!(phase_sum <= stats.total);
+        
+        // Stats describe this session's generation and are not saved
+        let json = serde_json::to_string(&universe).unwrap();
+        let loaded: Universe = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.generation_stats(), &GenerationStats::default());
+    }
     
-    items.push((fuel_item, quantity));
+    #[test]
+    fn test_start_system_follows_location_and_faction() {
+        let universe = Universe::new();
+        
+        let traders = universe.start_system(&StartLocation::Faction, &FactionType::Traders);
+        assert_eq!(traders.id, "sol");
+        let miners = universe.start_system(&StartLocation::Faction, &FactionType::Miners);
+        assert!(miners.stations.iter().any(|station| station.station_type == StationType::Mining));
+        
+        let random = universe.start_system(&StartLocation::Random, &FactionType::Traders);
+        assert_ne!(random.id, "sol");
+        assert_eq!(random.id, universe.start_system(&StartLocation::Random, &FactionType::Military).id);
+        
+        let unknown = StartLocation::parse("nowhere");
+        assert_eq!(universe.start_system(&unknown, &FactionType::Traders).id, "sol");
+    }
     
-    market_items.insert(system_id.to_string(), items);
+    #[test]
+    fn test_nearest_system_prefers_stations() {
+        let universe = Universe::new();
+        let sol = universe.get_system("sol").unwrap();
+        assert_eq!(universe.nearest_system(sol.x, sol.y).unwrap().id, "sol");
+        
+        // Far off the charts there's still somewhere to go, and it has a station
+        let nearest = universe.nearest_system(1.0e6, 1.0e6).unwrap();
+        assert!(!nearest.stations.is_empty());
+    }
 }
@@ -9,7 +9,7 @@ use uuid::Uuid;
 
 
 use crate::network::error::{NetworkError, NetworkResult};
-use crate::network::protocol::{Message, DEFAULT_SERVER_PORT, HEARTBEAT_INTERVAL};
+use crate::network::protocol::{Message, DEFAULT_SERVER_PORT, HEARTBEAT_INTERVAL, LeaderboardEntry, LeaderboardMetric};
 use crate::models::ship::Ship;
 use crate::models::universe::Universe;
 use crate::models::market::Market;
@@ -21,7 +21,7 @@ pub struct GameClient {
     username: String,
     server_addr: String,
     connected: bool,
-    universe: Option<Universe>,
+    universe: Option<Arc<Universe>>,
     player_ship: Option<Ship>,
     current_market: Option<Market>,
     tx: Option<mpsc::Sender<Message>>, 
@@ -255,6 +255,7 @@ impl GameClient {
         let nav_action = Message::NavigationAction {
             client_id: self.client_id,
             destination_system: destination,
+            action_id: None,
         };
         
         if let Some(tx) = &self.tx {
@@ -283,11 +284,55 @@ impl GameClient {
         }
     }
     
+    /// Fetch the top `limit` characters ranked by `metric`
+    pub async fn request_leaderboard(&self, metric: LeaderboardMetric, limit: u32) -> NetworkResult<Vec<LeaderboardEntry>> {
+        if !self.connected || self.tx.is_none() {
+            return Err(NetworkError::ClientError("Not connected to server".to_string()));
+        }
+        
+        let request = Message::RequestLeaderboard {
+            client_id: self.client_id,
+            metric,
+            limit,
+        };
+        
+        if let Some(tx) = &self.tx {
+            tx.send(request).await
+                .map_err(|_| NetworkError::ConnectionError("Failed to send leaderboard request".to_string()))?;
+        }
+        
+        match self.receive_message().await? {
+            Message::LeaderboardResponse { entries, .. } => Ok(entries),
+            _ => Err(NetworkError::ServerError("Unexpected response from server".to_string())),
+        }
+    }
+    
+    /// Send a chat line to everyone on the server. Replies arrive as
+    /// `Message::Chat`, or `Message::Error` if the server rejected it.
+    pub async fn send_chat(&self, text: String) -> NetworkResult<()> {
+        if !self.connected || self.tx.is_none() {
+            return Err(NetworkError::ClientError("Not connected to server".to_string()));
+        }
+        
+        let chat = Message::Chat {
+            client_id: self.client_id,
+            username: self.username.clone(),
+            text,
+        };
+        
+        if let Some(tx) = &self.tx {
+            tx.send(chat).await
+                .map_err(|_| NetworkError::ConnectionError("Failed to send chat message".to_string()))?;
+        }
+        
+        Ok(())
+    }
+    
     // Other action methods would follow a similar pattern
     
     /// Get the local universe data (if available)
     pub fn get_universe(&self) -> Option<&Universe> {
-        self.universe.as_ref()
+        self.universe.as_deref()
     }
     
     /// Get the player's ship data (if available)
@@ -295,6 +340,11 @@ impl GameClient {
         self.player_ship.as_ref()
     }
     
+    /// ID the server knows this client by
+    pub fn get_client_id(&self) -> Uuid {
+        self.client_id
+    }
+    
     /// Check if connected to server
     pub fn is_connected(&self) -> bool {
         self.connected
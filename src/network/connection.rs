@@ -0,0 +1,422 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use uuid::Uuid;
+
+use crate::network::error::{NetworkError, NetworkResult};
+use crate::network::protocol::{Message, HEARTBEAT_INTERVAL, CONNECTION_TIMEOUT};
+use crate::network::tls::{self, ClientTls, StreamReader, StreamWriter};
+
+/// Delay before the first reconnection attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest wait between reconnection attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Player actions held while offline; the oldest are dropped past this
+const OFFLINE_QUEUE_CAPACITY: usize = 50;
+
+/// State of the client's link to the server, for display
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionStatus {
+    Connected,
+    /// Lost the server; the next attempt is made at `retry_at`
+    Reconnecting {
+        attempt: u32,
+        retry_at: Instant,
+        queued: usize,
+    },
+    /// Gave up, or was never connected
+    Offline { reason: String },
+}
+
+impl ConnectionStatus {
+    pub fn describe(&self) -> String {
+        match self {
+            ConnectionStatus::Connected => "Online".to_string(),
+            ConnectionStatus::Reconnecting { attempt, retry_at, queued } => {
+                let wait = retry_at.saturating_duration_since(Instant::now()).as_secs();
+                let mut text = format!("Reconnecting (attempt {}, retry in {}s)", attempt, wait);
+                if *queued > 0 {
+                    text.push_str(&format!(" - {} action(s) queued", queued));
+                }
+                text
+            }
+            ConnectionStatus::Offline { reason } => format!("Offline: {}", reason),
+        }
+    }
+}
+
+/// Exponential backoff between reconnection attempts
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    next: Duration,
+    attempt: u32,
+}
+
+#[allow(dead_code)]
+impl Backoff {
+    pub fn new() -> Self {
+        Self { next: INITIAL_BACKOFF, attempt: 0 }
+    }
+
+    /// The wait before the next attempt; each call doubles the one after,
+    /// up to `MAX_BACKOFF`
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(MAX_BACKOFF);
+        self.attempt += 1;
+        delay
+    }
+
+    /// Attempts made since the last successful connection
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Start over after a successful connection
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Player actions made while the server can't be reached, replayed in order
+/// once it can. Only actions that were never written to the server are
+/// queued, so a replay can't apply one twice; the server validates each
+/// replayed action against its current state like any other.
+#[derive(Debug, Default)]
+pub struct OfflineQueue {
+    actions: VecDeque<Message>,
+}
+
+#[allow(dead_code)]
+impl OfflineQueue {
+    /// Whether `message` is a player action worth replaying. Heartbeats and
+    /// state requests are simply sent afresh after reconnecting.
+    pub fn is_replayable(message: &Message) -> bool {
+        matches!(
+            message,
+            Message::NavigationAction { .. }
+                | Message::MiningAction { .. }
+                | Message::MarketAction { .. }
+                | Message::Chat { .. }
+        )
+    }
+
+    /// Hold `message` for replay. Returns false if it isn't an action.
+    pub fn push(&mut self, message: Message) -> bool {
+        if !Self::is_replayable(&message) {
+            return false;
+        }
+        if self.actions.len() >= OFFLINE_QUEUE_CAPACITY {
+            self.actions.pop_front();
+        }
+        self.actions.push_back(message);
+        true
+    }
+
+    /// Put back an action whose replay failed, ahead of the rest
+    pub fn push_front(&mut self, message: Message) {
+        self.actions.push_front(message);
+    }
+
+    pub fn pop(&mut self) -> Option<Message> {
+        self.actions.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+/// An open, logged-in connection to the server
+pub struct Connection {
+    reader: StreamReader,
+    writer: StreamWriter,
+}
+
+/// Keeps a client connected to the server. Messages from the UI are written
+/// to the server and server messages handed back to the UI; when the
+/// connection drops it is reopened with exponential backoff, holding player
+/// actions until it is back.
+pub struct ClientLink {
+    server_addr: String,
+    client_id: Uuid,
+    username: String,
+    password: Option<String>,
+    backoff: Backoff,
+    queue: OfflineQueue,
+    status: watch::Sender<ConnectionStatus>,
+    /// Encrypts each connection when set
+    tls: Option<ClientTls>,
+}
+
+#[allow(dead_code)]
+impl ClientLink {
+    /// A link to `server_addr` ("host:port"), with the receiver the UI
+    /// watches for its status
+    pub fn new(
+        server_addr: String,
+        client_id: Uuid,
+        username: String,
+        password: Option<String>,
+    ) -> (Self, watch::Receiver<ConnectionStatus>) {
+        let (status, status_rx) = watch::channel(ConnectionStatus::Offline {
+            reason: "not connected yet".to_string(),
+        });
+        let link = Self {
+            server_addr,
+            client_id,
+            username,
+            password,
+            backoff: Backoff::new(),
+            queue: OfflineQueue::default(),
+            status,
+            tls: None,
+        };
+        (link, status_rx)
+    }
+
+    /// Connect over TLS from now on instead of plain TCP
+    pub fn use_tls(&mut self, tls: ClientTls) {
+        self.tls = Some(tls);
+    }
+
+    /// Open a connection and log in, returning it with the server's
+    /// `ConnectResponse`. A refused login is an `AuthError`.
+    pub async fn connect(&self) -> NetworkResult<(Connection, Message)> {
+        let stream = tokio::time::timeout(CONNECTION_TIMEOUT, TcpStream::connect(&self.server_addr)).await
+            .map_err(|_| NetworkError::Timeout)?
+            .map_err(|e| NetworkError::ConnectionError(format!("Failed to connect: {}", e)))?;
+        let (mut reader, mut writer) = match &self.tls {
+            Some(client_tls) => client_tls.connect(stream).await
+                .map_err(|e| NetworkError::ConnectionError(format!("TLS handshake failed: {}", e)))?,
+            None => tls::split(stream),
+        };
+
+        let connect = Message::Connect {
+            client_id: self.client_id,
+            username: self.username.clone(),
+            password: self.password.clone(),
+        };
+        write_message(&mut writer, &connect).await?;
+
+        let mut buffer = [0u8; 65536];
+        let n = tokio::time::timeout(CONNECTION_TIMEOUT, reader.read(&mut buffer)).await
+            .map_err(|_| NetworkError::Timeout)??;
+        if n == 0 {
+            return Err(NetworkError::ConnectionError("Server closed the connection".to_string()));
+        }
+        let response: Message = serde_json::from_slice(&buffer[..n])
+            .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+
+        match &response {
+            Message::ConnectResponse { success: true, .. } => Ok((Connection { reader, writer }, response)),
+            Message::ConnectResponse { message, .. } | Message::Error { message, .. } => {
+                Err(NetworkError::AuthError(message.clone()))
+            }
+            _ => Err(NetworkError::ConnectionError("Unexpected response from server".to_string())),
+        }
+    }
+
+    /// Relay messages over `connection` until `outgoing` closes, reconnecting
+    /// whenever the server is lost. Server messages, including the welcome
+    /// after each reconnection, are sent to `incoming`. Ends early if the
+    /// server turns the client away.
+    pub async fn run(
+        mut self,
+        connection: Connection,
+        mut outgoing: mpsc::Receiver<Message>,
+        incoming: mpsc::Sender<Message>,
+    ) {
+        let mut connection = Some(connection);
+        loop {
+            let open = match connection.take() {
+                Some(open) => open,
+                None => {
+                    match self.reconnect(&mut outgoing, &incoming).await {
+                        Ok(reopened) => connection = reopened,
+                        Err(reason) => {
+                            let _ = self.status.send(ConnectionStatus::Offline { reason });
+                            return;
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            self.backoff.reset();
+            let _ = self.status.send(ConnectionStatus::Connected);
+            match self.relay(open, &mut outgoing, &incoming).await {
+                Relay::Dropped => {},
+                Relay::Finished => return,
+                Relay::Refused(reason) => {
+                    let _ = self.status.send(ConnectionStatus::Offline { reason });
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Wait out the backoff, queueing actions sent meanwhile, then try to
+    /// connect once. `Err` means the server won't have this client back.
+    async fn reconnect(
+        &mut self,
+        outgoing: &mut mpsc::Receiver<Message>,
+        incoming: &mpsc::Sender<Message>,
+    ) -> Result<Option<Connection>, String> {
+        let delay = self.backoff.next_delay();
+        let retry_at = Instant::now() + delay;
+        let wait = tokio::time::sleep(delay);
+        tokio::pin!(wait);
+        loop {
+            let _ = self.status.send(ConnectionStatus::Reconnecting {
+                attempt: self.backoff.attempt(),
+                retry_at,
+                queued: self.queue.len(),
+            });
+            tokio::select! {
+                _ = &mut wait => break,
+                message = outgoing.recv() => match message {
+                    Some(message) => {
+                        self.queue.push(message);
+                    }
+                    None => return Err("client closed".to_string()),
+                },
+            }
+        }
+
+        match self.connect().await {
+            Ok((connection, welcome)) => {
+                let _ = incoming.send(welcome).await;
+                Ok(Some(connection))
+            }
+            Err(NetworkError::AuthError(reason)) => Err(reason),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Replay queued actions, then pass messages both ways until the
+    /// connection drops or the UI is done with it
+    async fn relay(
+        &mut self,
+        mut connection: Connection,
+        outgoing: &mut mpsc::Receiver<Message>,
+        incoming: &mpsc::Sender<Message>,
+    ) -> Relay {
+        while let Some(action) = self.queue.pop() {
+            if write_message(&mut connection.writer, &action).await.is_err() {
+                self.queue.push_front(action);
+                return Relay::Dropped;
+            }
+        }
+        // Catch up on whatever changed while the link was down
+        let _ = write_message(&mut connection.writer, &Message::RequestGameState { client_id: self.client_id }).await;
+
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let mut buffer = [0u8; 65536];
+        loop {
+            tokio::select! {
+                message = outgoing.recv() => match message {
+                    Some(message) => {
+                        if write_message(&mut connection.writer, &message).await.is_err() {
+                            self.queue.push(message);
+                            return Relay::Dropped;
+                        }
+                    }
+                    None => {
+                        let _ = write_message(&mut connection.writer, &Message::Disconnect { client_id: self.client_id }).await;
+                        return Relay::Finished;
+                    }
+                },
+                read = connection.reader.read(&mut buffer) => match read {
+                    Ok(0) | Err(_) => return Relay::Dropped,
+                    Ok(n) => {
+                        let message = match serde_json::from_slice::<Message>(&buffer[..n]) {
+                            Ok(message) => message,
+                            Err(e) => {
+                                eprintln!("Error deserializing message: {}", e);
+                                continue;
+                            }
+                        };
+                        let kicked = match &message {
+                            Message::Kicked { reason } => Some(format!("kicked: {}", reason)),
+                            _ => None,
+                        };
+                        if incoming.send(message).await.is_err() {
+                            return Relay::Finished;
+                        }
+                        if let Some(reason) = kicked {
+                            return Relay::Refused(reason);
+                        }
+                    }
+                },
+                _ = heartbeat.tick() => {
+                    let beat = Message::Heartbeat { client_id: self.client_id, timestamp: Instant::now().into() };
+                    if write_message(&mut connection.writer, &beat).await.is_err() {
+                        return Relay::Dropped;
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// How a relayed connection ended
+enum Relay {
+    /// Lost the server; try to get it back
+    Dropped,
+    /// The UI is done; nothing more to send
+    Finished,
+    /// The server won't have this client back
+    Refused(String),
+}
+
+async fn write_message(writer: &mut StreamWriter, message: &Message) -> NetworkResult<()> {
+    let bytes = serde_json::to_vec(message)
+        .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+    writer.write_all(&bytes).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap_and_resets() {
+        let mut backoff = Backoff::new();
+        assert_eq!(backoff.next_delay(), INITIAL_BACKOFF);
+        assert_eq!(backoff.next_delay(), INITIAL_BACKOFF * 2);
+        assert_eq!(backoff.next_delay(), INITIAL_BACKOFF * 4);
+        assert!((0..20).map(|_| backoff.next_delay()).all(|delay| delay <= MAX_BACKOFF));
+        assert_eq!(backoff.next_delay(), MAX_BACKOFF);
+
+        backoff.reset();
+        assert_eq!(backoff.attempt(), 0);
+        assert_eq!(backoff.next_delay(), INITIAL_BACKOFF);
+    }
+
+    #[test]
+    fn test_offline_queue_keeps_actions_only() {
+        let client_id = Uuid::new_v4();
+        let mut queue = OfflineQueue::default();
+
+        assert!(!queue.push(Message::RequestGameState { client_id }));
+        assert!(queue.push(Message::NavigationAction { client_id, destination_system: "sol".to_string(), action_id: None }));
+        assert_eq!(queue.len(), 1);
+        assert!(matches!(queue.pop(), Some(Message::NavigationAction { .. })));
+        assert!(queue.is_empty());
+    }
+}
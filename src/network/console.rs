@@ -0,0 +1,90 @@
+/// Commands an operator types at the server console
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    Help,
+    Status,
+    Clients,
+    /// Disconnect the client whose id starts with `client`
+    Kick { client: String, reason: String },
+    Save,
+    Broadcast(String),
+    Debug,
+    Stop,
+}
+
+/// Console commands and what they do, for `help`
+#[allow(dead_code)]
+pub const CONSOLE_HELP: &[(&str, &str)] = &[
+    ("status", "Show uptime, connections and economy figures"),
+    ("clients", "List connected clients"),
+    ("kick <id> [reason]", "Disconnect a client; the start of its id is enough"),
+    ("save", "Save the game, economy and accounts now"),
+    ("broadcast <message>", "Send an announcement to every client"),
+    ("debug", "Show debug information and perform diagnostics"),
+    ("stop", "Stop the server"),
+    ("help", "Show this help"),
+];
+
+/// Parse a console line. Blank lines give `None`. Command words are case
+/// insensitive; their arguments are kept as typed.
+#[allow(dead_code)]
+pub fn parse(line: &str) -> Result<Option<ConsoleCommand>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let (word, rest) = match line.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (line, ""),
+    };
+
+    let command = match word.to_lowercase().as_str() {
+        "help" => ConsoleCommand::Help,
+        "status" => ConsoleCommand::Status,
+        "clients" => ConsoleCommand::Clients,
+        "kick" => {
+            let (client, reason) = match rest.split_once(char::is_whitespace) {
+                Some((client, reason)) => (client, reason.trim()),
+                None => (rest, ""),
+            };
+            if client.is_empty() {
+                return Err("Usage: kick <id> [reason]".to_string());
+            }
+            let reason = if reason.is_empty() { "Kicked by the server operator" } else { reason };
+            ConsoleCommand::Kick { client: client.to_string(), reason: reason.to_string() }
+        }
+        "save" => ConsoleCommand::Save,
+        "broadcast" => {
+            if rest.is_empty() {
+                return Err("Usage: broadcast <message>".to_string());
+            }
+            ConsoleCommand::Broadcast(rest.to_string())
+        }
+        "debug" => ConsoleCommand::Debug,
+        "stop" => ConsoleCommand::Stop,
+        other => return Err(format!("Unknown command: {}", other)),
+    };
+    Ok(Some(command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_arguments_as_typed() {
+        assert_eq!(parse("  "), Ok(None));
+        assert_eq!(parse("STATUS"), Ok(Some(ConsoleCommand::Status)));
+        assert_eq!(
+            parse("broadcast Server Restarts Soon"),
+            Ok(Some(ConsoleCommand::Broadcast("Server Restarts Soon".to_string())))
+        );
+        assert_eq!(
+            parse("kick 3f2a  Spamming chat"),
+            Ok(Some(ConsoleCommand::Kick { client: "3f2a".to_string(), reason: "Spamming chat".to_string() }))
+        );
+        assert!(parse("kick").is_err());
+        assert!(parse("launch").is_err());
+    }
+}
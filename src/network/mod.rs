@@ -1,4 +1,9 @@
 pub mod protocol;
 pub mod client;
+pub mod connection;
+pub mod prediction;
 pub mod server;
-pub mod error;
\ No newline at end of file
+pub mod error;pub mod validation;
+pub mod rate_limit;
+pub mod tls;
+pub mod console;
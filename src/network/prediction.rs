@@ -0,0 +1,192 @@
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+use crate::game::Game;
+use crate::models::player::Player;
+use crate::models::ship::Ship;
+use crate::network::protocol::{ActionId, MarketActionType, Message, PlayerHoldings};
+use crate::systems::routine::RoutineStep;
+
+/// A step applied to the local game that the server hasn't answered yet
+struct PendingAction {
+    id: ActionId,
+    step: RoutineStep,
+    /// The player before the step, restored if the server refuses it
+    before: Player,
+}
+
+/// Client-side prediction. The local game applies the player's actions as
+/// soon as they are taken; each is then sent to the server under an id, and
+/// the server's `ActionResponse` either confirms it or has it rolled back.
+#[derive(Default)]
+pub struct Prediction {
+    next_id: ActionId,
+    pending: VecDeque<PendingAction>,
+}
+
+#[allow(dead_code)]
+impl Prediction {
+    /// Messages reporting `steps`, which the local game has already applied
+    /// to the player it had as `before`. Steps from one key press share that
+    /// snapshot, so refusing any of them undoes the whole press. Steps the
+    /// server has no message for are left to the local game.
+    pub fn predict(&mut self, client_id: Uuid, game: &Game, steps: Vec<RoutineStep>, before: &Player) -> Vec<Message> {
+        let mut messages = Vec::new();
+        for step in steps {
+            let id = self.next_id;
+            if let Some(message) = action_message(client_id, game, &step, id) {
+                self.next_id += 1;
+                self.pending.push_back(PendingAction { id, step, before: before.clone() });
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    /// Settle action `action_id` with the server's answer. A refusal rolls
+    /// the player back to before it, undoing any later action built on it.
+    /// Once nothing is left in flight, a ship, balances or cargo that differ
+    /// from the server's are replaced by its copy. Returns whether the local
+    /// game was changed.
+    pub fn reconcile(
+        &mut self,
+        game: &mut Game,
+        action_id: ActionId,
+        success: bool,
+        message: &str,
+        updated_ship: Option<Ship>,
+        updated_holdings: Option<PlayerHoldings>,
+    ) -> bool {
+        let index = match self.pending.iter().position(|pending| pending.id == action_id) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        if !success {
+            let mut undone: Vec<PendingAction> = self.pending.drain(index..).collect();
+            let abandon_jump = undone.iter().any(|pending| matches!(pending.step, RoutineStep::Travel(_)));
+            let later = undone.len() - 1;
+            let refused = undone.swap_remove(0);
+            let mut reason = format!("Server refused: {} ({})", refused.step.describe(), message);
+            if later > 0 {
+                reason.push_str(&format!("; {} later action(s) undone", later));
+            }
+            game.roll_back_player(refused.before, abandon_jump, reason);
+            return true;
+        }
+
+        // Answers come back in the order actions were sent, so anything older
+        // still waiting was lost with a dropped connection
+        self.pending.drain(..=index);
+        if !self.pending.is_empty() {
+            return false;
+        }
+        let mut changed = false;
+        if let Some(ship) = updated_ship.filter(|ship| ship_drifted(&game.player.ship, ship)) {
+            game.adopt_server_ship(ship);
+            changed = true;
+        }
+        if let Some(holdings) = updated_holdings.filter(|holdings| holdings_drifted(&game.player, holdings)) {
+            game.adopt_server_holdings(holdings);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Actions sent but not yet answered
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Whether the local ship disagrees with the server's on what actions change
+fn ship_drifted(local: &Ship, server: &Ship) -> bool {
+    local.current_fuel != server.current_fuel || local.hull != server.hull || local.shield != server.shield
+}
+
+/// Whether the local balances or cargo disagree with the server's. Trades are
+/// priced the same way on both sides, but price moves between the client's
+/// quote and the server's fill still show up here.
+fn holdings_drifted(local: &Player, server: &PlayerHoldings) -> bool {
+    let server_currencies: HashMap<_, _> = server.currencies.iter().cloned().collect();
+    local.credits != server.credits
+        || local.currencies != server_currencies
+        || local.inventory.items != server.cargo.items
+}
+
+/// The server message for `step`, if the server handles that kind of step
+fn action_message(client_id: Uuid, game: &Game, step: &RoutineStep, action_id: ActionId) -> Option<Message> {
+    let action_id = Some(action_id);
+    match step {
+        RoutineStep::Travel(destination_system) => Some(Message::NavigationAction {
+            client_id,
+            destination_system: destination_system.clone(),
+            action_id,
+        }),
+        RoutineStep::Buy { item, quantity } => Some(Message::MarketAction {
+            client_id,
+            action_type: MarketActionType::Buy,
+            item_name: item.clone(),
+            quantity: *quantity,
+            action_id,
+        }),
+        RoutineStep::Sell { item, quantity } => Some(Message::MarketAction {
+            client_id,
+            action_type: MarketActionType::Sell,
+            item_name: item.clone(),
+            quantity: *quantity,
+            action_id,
+        }),
+        RoutineStep::Mine { resource, attempts } => {
+            let fields = game.mining_system.get_resources_for_system(&game.player.current_system.id);
+            let (name, _) = fields.first()?.resources.get(*resource)?;
+            Some(Message::MiningAction {
+                client_id,
+                resource: name.clone(),
+                quantity: *attempts,
+                action_id,
+            })
+        }
+        RoutineStep::Refuel => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refusal_rolls_back_and_confirmation_settles() {
+        let mut game = Game::new_with_seed(7).unwrap();
+        let mut prediction = Prediction::default();
+        let client_id = Uuid::new_v4();
+
+        // A purchase the local game has already paid for
+        let before = game.player.clone();
+        let buy = RoutineStep::Buy { item: "Fuel".to_string(), quantity: 1 };
+        let messages = prediction.predict(client_id, &game, vec![buy.clone(), RoutineStep::Refuel], &before);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(prediction.pending(), 1);
+        game.player.credits -= 100;
+
+        assert!(prediction.reconcile(&mut game, 0, false, "Cannot afford it", None, None));
+        assert_eq!(game.player.credits, before.credits);
+        assert_eq!(prediction.pending(), 0);
+
+        // A confirmed action whose ship matches needs no correction
+        prediction.predict(client_id, &game, vec![buy], &before);
+        let ship = game.player.ship.clone();
+        let holdings = PlayerHoldings::of(&game.player);
+        assert!(!prediction.reconcile(&mut game, 1, true, "Purchased", Some(ship), Some(holdings)));
+        assert_eq!(prediction.pending(), 0);
+
+        // The server filled at a different price and has other cargo
+        prediction.predict(client_id, &game, vec![RoutineStep::Buy { item: "Fuel".to_string(), quantity: 1 }], &before);
+        let mut server = game.player.clone();
+        server.credits -= 7;
+        server.inventory.items.clear();
+        assert!(prediction.reconcile(&mut game, 2, true, "Purchased", None, Some(PlayerHoldings::of(&server))));
+        assert_eq!(game.player.credits, server.credits);
+        assert!(game.player.inventory.items.is_empty());
+    }
+}
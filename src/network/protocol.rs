@@ -1,10 +1,16 @@
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
+use std::sync::Arc;
 use std::time::Duration;
 use crate::utils::serde::SerializableInstant;
+use crate::models::currency::Currency;
+use crate::models::item::Inventory;
+use crate::models::player::Player;
 use crate::models::ship::Ship;
-use crate::models::universe::Universe;
-use crate::models::account::UserAccount;
+use crate::models::universe::{Universe, UniverseConfig};
+use crate::models::account::{AccountRole, UserAccount};
+use crate::network::rate_limit::RateLimitConfig;
+use crate::network::tls::TlsConfig;
 
 /// Market type just for network protocol
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,9 +30,34 @@ pub struct MarketItem {
 // Constants
 pub const DEFAULT_SERVER_PORT: u16 = 7890;
 pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+pub const MAX_CHAT_LENGTH: usize = 200;
+pub const CHAT_COOLDOWN: Duration = Duration::from_secs(1);
 #[allow(dead_code)]
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Client-chosen number tying an action to the server's `ActionResponse`
+pub type ActionId = u64;
+
+/// The player's balances and cargo as the server has them after an action,
+/// so a client can correct its own copy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerHoldings {
+    pub credits: u32,
+    /// Scrip balances; scrip can't key a JSON map
+    pub currencies: Vec<(Currency, u32)>,
+    pub cargo: Inventory,
+}
+
+impl PlayerHoldings {
+    pub fn of(player: &Player) -> Self {
+        PlayerHoldings {
+            credits: player.credits,
+            currencies: player.currencies.iter().map(|(currency, amount)| (currency.clone(), *amount)).collect(),
+            cargo: player.inventory.clone(),
+        }
+    }
+}
+
 /// Protocol messages between client and server
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Message {
@@ -36,10 +67,12 @@ pub enum Message {
         username: String,
         password: Option<String>,
     },
+    /// The server sends the universe from its cached encoding (see
+    /// `UniverseResponse`), so building a response doesn't serialize it again
     ConnectResponse {
         success: bool,
         message: String,
-        universe: Option<Universe>,
+        universe: Option<Arc<Universe>>,
         player_ship: Option<Ship>,
     },
     Disconnect {
@@ -81,6 +114,18 @@ pub enum Message {
         success: bool,
         message: String,
     },
+    RequestPasswordReset {
+        username: String,
+    },
+    CompletePasswordReset {
+        username: String,
+        token: String,
+        new_password: String,
+    },
+    PasswordResetResponse {
+        success: bool,
+        message: String,
+    },
     DeleteAccount {
         client_id: Uuid,
         username: String,
@@ -121,7 +166,7 @@ pub enum Message {
     SelectCharacterResponse {
         success: bool,
         message: String,
-        universe: Option<Universe>,
+        universe: Option<Arc<Universe>>,
         player_ship: Option<Ship>,
     },
     
@@ -130,33 +175,84 @@ pub enum Message {
         client_id: Uuid,
     },
     GameStateUpdate {
-        universe: Universe,
+        universe: Arc<Universe>,
         player_ship: Ship,
     },
     
-    // Player action messages
+    // Player action messages. A client that applies actions before the
+    // server confirms them numbers each one so it can match the response.
     NavigationAction {
         client_id: Uuid,
         destination_system: String,
+        #[serde(default)]
+        action_id: Option<ActionId>,
     },
     MiningAction {
         client_id: Uuid,
         resource: String,
         quantity: u32,
+        #[serde(default)]
+        action_id: Option<ActionId>,
     },
     MarketAction {
         client_id: Uuid,
         action_type: MarketActionType,
         item_name: String,
         quantity: u32,
+        #[serde(default)]
+        action_id: Option<ActionId>,
     },
     
-    // Action responses
+    // Action responses, echoing the action's id
     ActionResponse {
         success: bool,
         message: String,
         updated_ship: Option<Ship>,
         updated_market: Option<Market>,
+        #[serde(default)]
+        action_id: Option<ActionId>,
+        #[serde(default)]
+        updated_holdings: Option<PlayerHoldings>,
+    },
+    
+    // Chat between connected players. The server fills in `username` from
+    // the sender's connection and blanks `client_id` before rebroadcasting.
+    Chat {
+        client_id: Uuid,
+        username: String,
+        text: String,
+    },
+    
+    // Leaderboard
+    RequestLeaderboard {
+        client_id: Uuid,
+        metric: LeaderboardMetric,
+        limit: u32,
+    },
+    LeaderboardResponse {
+        metric: LeaderboardMetric,
+        entries: Vec<LeaderboardEntry>,
+    },
+    
+    // Server administration. Only accounts with a high enough role may use these.
+    AdminCommand {
+        client_id: Uuid,
+        command: AdminCommand,
+    },
+    AdminCommandResponse {
+        success: bool,
+        message: String,
+    },
+    Announcement {
+        message: String,
+    },
+    Kicked {
+        reason: String,
+    },
+    
+    // Sent to every client just before the server stops
+    ServerShutdown {
+        message: String,
     },
     
     // Error messages
@@ -173,22 +269,168 @@ pub enum MarketActionType {
     Sell,
 }
 
-/// Game configuration that can be changed on the server
+/// Operations reserved for moderators and admins
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AdminCommand {
+    KickClient { target: Uuid, reason: String },
+    Announce { message: String },
+    ResetMarket { system_id: String },
+    AdjustCredits { character_id: String, amount: i64 },
+}
+
+impl AdminCommand {
+    /// Lowest account role allowed to run this command
+    pub fn required_role(&self) -> AccountRole {
+        match self {
+            AdminCommand::KickClient { .. } | AdminCommand::Announce { .. } => AccountRole::Moderator,
+            AdminCommand::ResetMarket { .. } | AdminCommand::AdjustCredits { .. } => AccountRole::Admin,
+        }
+    }
+}
+
+/// What characters are ranked by on the leaderboard
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    NetWorth,
+    Reputation, // Sum of standing with every faction
+}
+
+/// One ranked character. Ranks start at 1.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    pub character_name: String,
+    pub score: i64,
+}
+
+/// Environment variable naming the server config file
+pub const CONFIG_PATH_ENV: &str = "SPACE_TRADER_CONFIG";
+
+/// Game configuration that can be changed on the server. Keys missing from a
+/// config file take their default values.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct GameConfig {
+    /// Port the server listens on
+    pub port: u16,
+    /// Password clients must give to connect, if any
+    pub password: Option<String>,
+    /// Seconds between automatic saves of the game state
+    pub autosave_interval_secs: u64,
+    /// Milliseconds between simulation (economy and world) updates
+    pub economy_tick_ms: u64,
+    /// Game seconds a market keeps being fully simulated after a player was
+    /// last in or next to its system; unset simulates every market always
+    pub economy_dormant_after_secs: Option<u64>,
     pub time_scale: f32,
     pub starting_credits: u32,
     pub universe_seed: u64,
     pub market_volatility: f32,
+    /// Universe generation parameters (the seed comes from `universe_seed`)
+    pub universe_generation: UniverseConfig,
+    /// Per-client message rate and per-IP connection limits
+    pub rate_limit: RateLimitConfig,
+    /// Certificate to encrypt connections with; plain TCP when unset
+    pub tls: Option<TlsConfig>,
 }
 
 impl Default for GameConfig {
     fn default() -> Self {
         Self {
+            port: DEFAULT_SERVER_PORT,
+            password: None,
+            autosave_interval_secs: 60,
+            economy_tick_ms: 100,
+            economy_dormant_after_secs: Some(24 * 3600),
             time_scale: 1.0,
             starting_credits: 1000,
             universe_seed: 42,
             market_volatility: 0.2,
+            universe_generation: UniverseConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            tls: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl GameConfig {
+    /// Full universe config for this server, using the configured seed
+    pub fn universe_config(&self) -> UniverseConfig {
+        UniverseConfig {
+            seed: self.universe_seed,
+            ..self.universe_generation.clone()
+        }
+    }
+    
+    /// Read and validate a JSON config file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {}", path, e))?;
+        let config: GameConfig = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse config file {}: {}", path, e))?;
+        config.validate()?;
+        Ok(config)
+    }
+    
+    /// Config file named by a `--config <path>` argument, or failing that the
+    /// `SPACE_TRADER_CONFIG` environment variable
+    pub fn path_from_env_or_args() -> Option<String> {
+        let args: Vec<String> = std::env::args().collect();
+        args.iter()
+            .position(|arg| arg == "--config")
+            .and_then(|i| args.get(i + 1).cloned())
+            .or_else(|| std::env::var(CONFIG_PATH_ENV).ok())
+    }
+    
+    /// Check every setting is within a usable range
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("port must be between 1 and 65535".to_string());
+        }
+        if self.autosave_interval_secs < 5 {
+            return Err("autosave_interval_secs must be at least 5".to_string());
+        }
+        if !(10..=60_000).contains(&self.economy_tick_ms) {
+            return Err("economy_tick_ms must be between 10 and 60000".to_string());
+        }
+        if self.economy_dormant_after_secs == Some(0) {
+            return Err("economy_dormant_after_secs must be above 0, or unset to simulate every market".to_string());
+        }
+        if self.time_scale <= 0.0 || self.time_scale > 100.0 {
+            return Err("time_scale must be above 0 and at most 100".to_string());
         }
+        if !(0.0..=1.0).contains(&self.market_volatility) {
+            return Err("market_volatility must be between 0 and 1".to_string());
+        }
+        if self.rate_limit.messages_per_second <= 0.0 || self.rate_limit.burst == 0 {
+            return Err("rate_limit must allow at least one message".to_string());
+        }
+        if self.rate_limit.max_connections_per_ip == 0 {
+            return Err("rate_limit.max_connections_per_ip must be at least 1".to_string());
+        }
+        if let Some(tls) = &self.tls {
+            if tls.cert_path.is_empty() || tls.key_path.is_empty() {
+                return Err("tls needs both cert_path and key_path".to_string());
+            }
+        }
+        self.universe_config().validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let config: GameConfig = serde_json::from_str(r#"{ "port": 9000, "starting_credits": 5000 }"#).unwrap();
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.starting_credits, 5000);
+        assert_eq!(config.autosave_interval_secs, GameConfig::default().autosave_interval_secs);
+        assert!(config.validate().is_ok());
+
+        let config = GameConfig { economy_tick_ms: 0, ..GameConfig::default() };
+        assert!(config.validate().is_err());
     }
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Instant;
+use serde::{Serialize, Deserialize};
+
+/// Limits on how hard a single client can push the server
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    /// Sustained messages per second allowed from one client
+    pub messages_per_second: f32,
+    /// Messages a client can send in a burst before being throttled
+    pub burst: u32,
+    /// Simultaneous connections allowed from one IP address
+    pub max_connections_per_ip: usize,
+    /// Simultaneous connections allowed in all
+    #[serde(default = "default_max_connections")]
+    pub max_connections: usize,
+    /// Messages that can wait to be written to one client. A client that
+    /// lets its queue fill up is disconnected.
+    #[serde(default = "default_client_queue_len")]
+    pub client_queue_len: usize,
+}
+
+fn default_max_connections() -> usize {
+    256
+}
+
+fn default_client_queue_len() -> usize {
+    100
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            messages_per_second: 10.0,
+            burst: 20,
+            max_connections_per_ip: 4,
+            max_connections: default_max_connections(),
+            client_queue_len: default_client_queue_len(),
+        }
+    }
+}
+
+/// Token bucket: each message takes a token, tokens refill at a steady rate
+/// up to the bucket's capacity
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f32,
+    tokens: f32,
+    refill_per_second: f32,
+    last_refill: Instant,
+}
+
+#[allow(dead_code)]
+impl TokenBucket {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        let capacity = config.burst.max(1) as f32;
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_second: config.messages_per_second.max(0.0),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token if one is available
+    pub fn try_take(&mut self) -> bool {
+        self.try_take_at(Instant::now())
+    }
+
+    pub fn try_take_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f32();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Why a connection was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionRefusal {
+    /// The server already has its maximum number of connections
+    ServerFull,
+    /// The address already has its maximum number of connections
+    TooManyFromAddress,
+}
+
+impl ConnectionRefusal {
+    /// Error code sent to the refused client
+    pub fn code(&self) -> u32 {
+        match self {
+            ConnectionRefusal::ServerFull => 503,
+            ConnectionRefusal::TooManyFromAddress => 429,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ConnectionRefusal::ServerFull => "The server is full, try again later".to_string(),
+            ConnectionRefusal::TooManyFromAddress => "Too many connections from your address".to_string(),
+        }
+    }
+}
+
+/// Counts open connections, in all and per IP address
+#[derive(Debug, Default)]
+pub struct ConnectionLimiter {
+    max_per_ip: usize,
+    max_total: usize,
+    open: HashMap<IpAddr, usize>,
+    total: usize,
+}
+
+#[allow(dead_code)]
+impl ConnectionLimiter {
+    pub fn new(max_per_ip: usize, max_total: usize) -> Self {
+        Self {
+            max_per_ip,
+            max_total,
+            open: HashMap::new(),
+            total: 0,
+        }
+    }
+
+    /// Reserve a connection slot for `ip`, or refuse if the server or the
+    /// address already has the maximum
+    pub fn try_acquire(&mut self, ip: IpAddr) -> Result<(), ConnectionRefusal> {
+        if self.total >= self.max_total {
+            return Err(ConnectionRefusal::ServerFull);
+        }
+        let count = self.open.entry(ip).or_insert(0);
+        if *count >= self.max_per_ip {
+            return Err(ConnectionRefusal::TooManyFromAddress);
+        }
+        *count += 1;
+        self.total += 1;
+        Ok(())
+    }
+
+    pub fn release(&mut self, ip: IpAddr) {
+        if let Some(count) = self.open.get_mut(&ip) {
+            *count = count.saturating_sub(1);
+            self.total = self.total.saturating_sub(1);
+            if *count == 0 {
+                self.open.remove(&ip);
+            }
+        }
+    }
+
+    pub fn total_connections(&self) -> usize {
+        self.total
+    }
+
+    pub fn connections_from(&self, ip: IpAddr) -> usize {
+        self.open.get(&ip).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_throttles_bursts_and_refills() {
+        let config = RateLimitConfig { messages_per_second: 2.0, burst: 3, ..RateLimitConfig::default() };
+        let mut bucket = TokenBucket::new(&config);
+        let start = Instant::now();
+
+        assert!((0..3).all(|_| bucket.try_take_at(start)));
+        assert!(!bucket.try_take_at(start));
+        assert!(bucket.try_take_at(start + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_connection_limit_per_ip() {
+        let mut limiter = ConnectionLimiter::new(2, 10);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert!(limiter.try_acquire(ip).is_ok());
+        assert_eq!(limiter.try_acquire(ip), Err(ConnectionRefusal::TooManyFromAddress));
+        limiter.release(ip);
+        assert!(limiter.try_acquire(ip).is_ok());
+    }
+
+    #[test]
+    fn test_connection_limit_in_all() {
+        let mut limiter = ConnectionLimiter::new(2, 3);
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(limiter.try_acquire(a).is_ok());
+        assert!(limiter.try_acquire(a).is_ok());
+        assert!(limiter.try_acquire(b).is_ok());
+        assert_eq!(limiter.try_acquire(b), Err(ConnectionRefusal::ServerFull));
+        limiter.release(a);
+        assert!(limiter.try_acquire(b).is_ok());
+        assert_eq!(limiter.total_connections(), 3);
+    }
+}
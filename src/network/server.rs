@@ -1,19 +1,93 @@
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
+use serde::Serialize;
 use serde_json;
+use serde_json::value::RawValue;
 use std::net::SocketAddr;
 use bcrypt::{hash, verify, DEFAULT_COST};
+use tokio_rustls::TlsAcceptor;
 
 use crate::network::error::{NetworkError, NetworkResult};
-use crate::network::protocol::{Message, MarketActionType, DEFAULT_SERVER_PORT, HEARTBEAT_INTERVAL, GameConfig};
+use crate::network::protocol::{Message, ActionId, MarketActionType, HEARTBEAT_INTERVAL, GameConfig, MAX_CHAT_LENGTH, CHAT_COOLDOWN, LeaderboardEntry, LeaderboardMetric, AdminCommand, PlayerHoldings};
 use crate::game::Game;
+use crate::models::universe::Universe;
+use crate::models::ship::Ship;
 use crate::utils::save_load;
-use crate::models::account::{AccountManager, AccountError, UserAccount};
+use crate::models::account::{AccountManager, AccountError, AccountRole, UserAccount};
+use crate::models::faction::{self, FactionType};
+use crate::models::player::{Player, TransactionCategory};
+use crate::utils::clock::GameClock;
+use crate::network::validation;
+use crate::network::rate_limit::{ConnectionLimiter, RateLimitConfig, TokenBucket};
+use crate::network::tls;
+use crate::debug::{self, integrity, LogLevel};
+use crate::systems::economy::EconomySystem;
+use crate::systems::encounter::EncounterOutcome;
+
+/// Largest leaderboard a client can ask for
+const MAX_LEADERBOARD_SIZE: u32 = 100;
+
+/// Characters created on this server, keyed by character ID
+type CharacterStore = Arc<Mutex<HashMap<String, Player>>>;
+
+/// The universe as last sent to clients, shared between connections
+type SharedUniverse = Arc<Mutex<UniverseSnapshot>>;
+
+/// The game's universe encoded as JSON, which responses share until the
+/// universe changes instead of each response serializing the whole universe
+#[derive(Default)]
+struct UniverseSnapshot {
+    revision: u64,
+    encoded: Option<Arc<RawValue>>,
+}
+
+impl UniverseSnapshot {
+    /// The shared encoding of `universe`, serialized afresh if it has
+    /// changed since the last one
+    fn share(&mut self, universe: &Universe) -> NetworkResult<Arc<RawValue>> {
+        match &self.encoded {
+            Some(shared) if self.revision == universe.revision() => Ok(shared.clone()),
+            _ => {
+                let shared: Arc<RawValue> = serde_json::value::to_raw_value(universe)
+                    .map_err(|e| NetworkError::SerializationError(e.to_string()))?
+                    .into();
+                self.revision = universe.revision();
+                self.encoded = Some(shared.clone());
+                Ok(shared)
+            }
+        }
+    }
+}
+
+/// The `Message` variants that carry the universe, with the snapshot's
+/// encoding in place of the universe. They serialize to the same JSON as
+/// the `Message` they mirror, so clients read them as that.
+#[derive(Serialize)]
+enum UniverseResponse {
+    ConnectResponse {
+        success: bool,
+        message: String,
+        universe: Option<Arc<RawValue>>,
+        player_ship: Option<Ship>,
+    },
+    SelectCharacterResponse {
+        success: bool,
+        message: String,
+        universe: Option<Arc<RawValue>>,
+        player_ship: Option<Ship>,
+    },
+    GameStateUpdate {
+        universe: Arc<RawValue>,
+        player_ship: Ship,
+    },
+}
 
 /// Represents a client connection to the server
 #[allow(dead_code)]
@@ -22,68 +96,207 @@ struct ClientConnection {
     username: String,
     addr: SocketAddr,
     last_heartbeat: Instant,
-    sender: mpsc::Sender<Vec<u8>>,
+    last_chat: Option<Instant>,
+    rate_limit: TokenBucket,
+    account: Option<String>, // Username of the account logged in on this connection
+    sender: ClientSender,
+}
+
+/// Sending half of a client's outgoing queue. The queue is bounded and
+/// sends never wait on it: a client that reads so slowly that its queue
+/// fills is marked as overflowed and disconnected, rather than stalling
+/// the server or buffering without limit.
+#[derive(Clone)]
+struct ClientSender {
+    queue: mpsc::Sender<Vec<u8>>,
+    overflowed: Arc<AtomicBool>,
+}
+
+impl ClientSender {
+    fn new(queue: mpsc::Sender<Vec<u8>>) -> Self {
+        Self {
+            queue,
+            overflowed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Queue `data` for the client, failing if the queue is full or closed
+    async fn send(&self, data: Vec<u8>) -> Result<(), TrySendError<Vec<u8>>> {
+        let result = self.queue.try_send(data);
+        if let Err(TrySendError::Full(_)) = result {
+            self.overflowed.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn has_overflowed(&self) -> bool {
+        self.overflowed.load(Ordering::Relaxed)
+    }
 }
 
+/// Queued on a client's sender to close its connection once everything sent
+/// before it has been written
+const CLOSE_CONNECTION: Vec<u8> = Vec::new();
+
 /// Game server that manages connections and game state
 #[allow(dead_code)]
 pub struct GameServer {
     game: Arc<Mutex<Game>>,
     clients: Arc<Mutex<HashMap<Uuid, ClientConnection>>>,
     password: Option<String>,
-    #[allow(dead_code)]
     config: GameConfig,
     accounts: Arc<Mutex<AccountManager>>,
+    characters: CharacterStore,
+    connection_limiter: Arc<Mutex<ConnectionLimiter>>,
+    /// Authoritative market state, saved to its own file next to the game
+    economy: Arc<Mutex<EconomySystem>>,
+    universe_snapshot: SharedUniverse,
+    started_at: Instant,
+}
+
+/// What the server console reports about a running server
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ServerStatus {
+    pub uptime: Duration,
+    pub connected_clients: usize,
+    pub game_time: String,
+    /// Economy simulation steps run so far
+    pub economy_steps: u64,
+    pub active_listings: usize,
+    pub open_bids: usize,
+    pub contracts: usize,
+}
+
+#[allow(dead_code)]
+impl ServerStatus {
+    /// One line per figure, for printing
+    pub fn lines(&self) -> Vec<String> {
+        let secs = self.uptime.as_secs();
+        vec![
+            format!("Uptime: {}h {:02}m {:02}s", secs / 3600, secs / 60 % 60, secs % 60),
+            format!("Connected clients: {}", self.connected_clients),
+            format!("Game time: {}", self.game_time),
+            format!("Economy steps: {}", self.economy_steps),
+            format!(
+                "Player market: {} listings, {} bids, {} contracts",
+                self.active_listings, self.open_bids, self.contracts
+            ),
+        ]
+    }
+}
+
+/// A connected client as listed on the server console
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ClientSummary {
+    pub id: Uuid,
+    pub username: String,
+    pub addr: SocketAddr,
+    /// Account logged in on the connection, if any
+    pub account: Option<String>,
+    /// Time since the client was last heard from
+    pub idle: Duration,
 }
 
 #[allow(dead_code)]
 impl GameServer {
-    /// Create a new game server with optional password protection and existing game state
-    pub async fn new(password: Option<String>, game_state: Arc<Mutex<Game>>) -> Self {
-        // Load configuration or use defaults
-        let config = GameConfig::default();
-        
+    /// Create a game server for an existing game state, using the port,
+    /// password and other settings from `config`
+    pub async fn new(config: GameConfig, game_state: Arc<Mutex<Game>>) -> Self {
         // Load account manager or create new
         let accounts = AccountManager::load();
         println!("Loaded account manager");
         
+        // Load the saved economy, falling back to fresh markets
+        let mut economy = {
+            let game = game_state.lock().await;
+            save_load::load_economy(&game.universe).unwrap_or_else(|e| {
+                eprintln!("Error loading economy, starting fresh markets: {}", e);
+                EconomySystem::from_universe(&game.universe)
+            })
+        };
+        economy.dormant_after = config.economy_dormant_after_secs;
+        println!("Loaded economy with {} markets", economy.system_markets.len());
+        
         Self {
             game: game_state,
             clients: Arc::new(Mutex::new(HashMap::new())),
-            password: password.map(|p| {
+            password: config.password.clone().map(|p| {
                 // Hash the password for secure storage
                 hash(p, DEFAULT_COST).expect("Failed to hash password")
             }),
-            config,
             accounts: Arc::new(Mutex::new(accounts)),
+            characters: Arc::new(Mutex::new(HashMap::new())),
+            connection_limiter: Arc::new(Mutex::new(ConnectionLimiter::new(
+                config.rate_limit.max_connections_per_ip,
+                config.rate_limit.max_connections,
+            ))),
+            economy: Arc::new(Mutex::new(economy)),
+            universe_snapshot: Arc::new(Mutex::new(UniverseSnapshot::default())),
+            started_at: Instant::now(),
+            config,
         }
     }
     
-    /// Start the server and listen for connections
-    pub async fn start(&self, port: Option<u16>) -> NetworkResult<()> {
-        let port = port.unwrap_or(DEFAULT_SERVER_PORT);
+    /// Start the server and listen for connections until `shutdown` fires (or
+    /// its sender is dropped), then shut down cleanly
+    pub async fn start(&self, port: Option<u16>, mut shutdown: oneshot::Receiver<()>) -> NetworkResult<()> {
+        let port = port.unwrap_or(self.config.port);
         let addr = format!("0.0.0.0:{}", port);
         let listener = TcpListener::bind(&addr).await
             .map_err(|e| NetworkError::IoError(e))?;
         
-        println!("Server started on {}", addr);
+        // Encrypt connections when the config names a certificate
+        let tls = match &self.config.tls {
+            Some(tls_config) => Some(tls::server_acceptor(tls_config).map_err(NetworkError::ServerError)?),
+            None => None,
+        };
+        
+        println!("Server started on {}{}", addr, if tls.is_some() { " (TLS)" } else { "" });
         
         // Auto-save game state periodically
         let game_clone = self.game.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        let economy_clone = self.economy.clone();
+        let autosave_interval = std::time::Duration::from_secs(self.config.autosave_interval_secs);
+        let autosave_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(autosave_interval);
             loop {
                 interval.tick().await;
                 let game = game_clone.lock().await;
                 if let Err(e) = save_load::save_game(&*game) {
                     eprintln!("Error saving game state: {}", e);
                 }
+                drop(game);
+                if let Err(e) = save_load::save_economy(&*economy_clone.lock().await) {
+                    eprintln!("Error saving economy: {}", e);
+                }
+            }
+        });
+        
+        // Run the economy on game time. The player's system is recorded on
+        // every tick, so a dormant market is caught up as soon as they arrive
+        // and markets nobody is near can go dormant.
+        let game_clone = self.game.clone();
+        let economy_clone = self.economy.clone();
+        let economy_tick = Duration::from_millis(self.config.economy_tick_ms);
+        let economy_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(economy_tick);
+            loop {
+                interval.tick().await;
+                let (system_id, clock) = {
+                    let game = game_clone.lock().await;
+                    (game.player.current_system.id.clone(), game.time_system.clock())
+                };
+                let mut economy = economy_clone.lock().await;
+                economy.mark_active(&system_id, &clock);
+                economy.update(&clock);
             }
         });
         
         // Clean up disconnected clients
         let clients_clone = self.clients.clone();
-        tokio::spawn(async move {
+        let cleanup_task = tokio::spawn(async move {
             let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
             loop {
                 interval.tick().await;
@@ -103,39 +316,195 @@ impl GameServer {
         });
         
         // Accept incoming connections
-        while let Ok((stream, addr)) = listener.accept().await {
+        loop {
+            let (stream, addr) = tokio::select! {
+                _ = &mut shutdown => break,
+                accepted = listener.accept() => match accepted {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        eprintln!("Error accepting connection: {}", e);
+                        continue;
+                    }
+                },
+            };
+            
+            // Refuse connections past the server's limit, or from hosts that
+            // already hold too many
+            if let Err(refusal) = self.connection_limiter.lock().await.try_acquire(addr.ip()) {
+                debug::log_internal(LogLevel::Warning, "network", &format!(
+                    "Refused connection from {}: {}", addr, refusal.message()
+                ));
+                // A fresh socket's send buffer takes the short error without
+                // waiting, so the accept loop is never held up by it
+                let refused = Message::Error { code: refusal.code(), message: refusal.message() };
+                if let Ok(bytes) = serde_json::to_vec(&refused) {
+                    let _ = stream.try_write(&bytes);
+                }
+                continue;
+            }
+            
             println!("New connection from: {}", addr);
             let game = self.game.clone();
             let clients = self.clients.clone();
             let password = self.password.clone();
             let accounts = self.accounts.clone();
+            let characters = self.characters.clone();
+            let connection_limiter = self.connection_limiter.clone();
+            let universe_snapshot = self.universe_snapshot.clone();
+            let rate_limit = self.config.rate_limit;
+            let tls = tls.clone();
             
             tokio::spawn(async move {
-                if let Err(e) = Self::handle_connection(stream, addr, game, clients, accounts, password).await {
+                if let Err(e) = Self::handle_connection(stream, tls, addr, game, clients, accounts, characters, universe_snapshot, rate_limit, password).await {
                     eprintln!("Connection error: {}", e);
                 }
+                connection_limiter.lock().await.release(addr.ip());
             });
         }
         
+        autosave_task.abort();
+        economy_task.abort();
+        cleanup_task.abort();
+        self.shutdown().await
+    }
+    
+    /// Tell every client the server is going down, then write the game and
+    /// accounts to disk
+    async fn shutdown(&self) -> NetworkResult<()> {
+        println!("Server shutting down");
+        
+        if let Err(e) = self.broadcast(Message::ServerShutdown {
+            message: "The server is shutting down".to_string(),
+        }).await {
+            eprintln!("Error notifying clients of shutdown: {}", e);
+        }
+        
+        if let Err(e) = self.save_all().await {
+            eprintln!("{}", e);
+        }
+        
+        self.clients.lock().await.clear();
         Ok(())
     }
     
+    /// Write the game, economy and accounts to disk. Each is saved even if
+    /// an earlier one fails; the errors are reported together.
+    pub async fn save_all(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        
+        let game = self.game.lock().await;
+        if let Err(e) = save_load::save_game(&*game) {
+            errors.push(format!("Error saving game state: {}", e));
+        }
+        drop(game);
+        
+        if let Err(e) = save_load::save_economy(&*self.economy.lock().await) {
+            errors.push(format!("Error saving economy: {}", e));
+        }
+        
+        if let Err(e) = self.accounts.lock().await.save() {
+            errors.push(format!("Error saving accounts: {}", e));
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+    
+    /// Uptime, connections and economy figures for the server console
+    pub async fn status(&self) -> ServerStatus {
+        let connected_clients = self.clients.lock().await.len();
+        let game_time = self.game.lock().await.time_system.get_formatted_time();
+        let economy = self.economy.lock().await;
+        ServerStatus {
+            uptime: self.started_at.elapsed(),
+            connected_clients,
+            game_time,
+            economy_steps: economy.simulation_step,
+            active_listings: economy.player_market.listings.len(),
+            open_bids: economy.player_market.bids.len(),
+            contracts: economy.player_market.contracts.len(),
+        }
+    }
+    
+    /// Connected clients, ordered by username
+    pub async fn client_summaries(&self) -> Vec<ClientSummary> {
+        let now = Instant::now();
+        let mut summaries: Vec<ClientSummary> = self.clients.lock().await.values()
+            .map(|client| ClientSummary {
+                id: client.id,
+                username: client.username.clone(),
+                addr: client.addr,
+                account: client.account.clone(),
+                idle: now.duration_since(client.last_heartbeat),
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.username.cmp(&b.username).then(a.id.cmp(&b.id)));
+        summaries
+    }
+    
+    /// The connected client whose id starts with `prefix`, so the console
+    /// doesn't need the whole id typed out
+    pub async fn find_client(&self, prefix: &str) -> Result<Uuid, String> {
+        let prefix = prefix.to_lowercase();
+        let matches: Vec<Uuid> = self.clients.lock().await.keys()
+            .filter(|id| id.to_string().starts_with(&prefix))
+            .copied()
+            .collect();
+        match matches.as_slice() {
+            [id] => Ok(*id),
+            [] => Err(format!("No connected client matches {}", prefix)),
+            _ => Err(format!("{} matches {} clients; type more of the id", prefix, matches.len())),
+        }
+    }
+    
+    /// Run an admin command from the server console, which needs no account
+    pub async fn run_console_command(&self, command: AdminCommand) -> Result<String, String> {
+        Self::run_admin_command(command, self.game.clone(), self.clients.clone(), self.characters.clone()).await
+    }
+    
     /// Handle a client connection
     async fn handle_connection(
         stream: TcpStream, 
+        tls: Option<TlsAcceptor>,
         addr: SocketAddr,
         game: Arc<Mutex<Game>>,
         clients: Arc<Mutex<HashMap<Uuid, ClientConnection>>>,
         accounts: Arc<Mutex<AccountManager>>,
+        characters: CharacterStore,
+        universe_snapshot: SharedUniverse,
+        rate_limit: RateLimitConfig,
         server_password: Option<String>
     ) -> NetworkResult<()> {
-        let (mut reader, mut writer) = stream.into_split();
-        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(100);
+        // The handshake runs here rather than in the accept loop, so a slow
+        // client can't hold up everyone else's connections
+        let (mut reader, mut writer) = match tls {
+            Some(acceptor) => {
+                let stream = acceptor.accept(stream).await
+                    .map_err(|e| NetworkError::ConnectionError(format!("TLS handshake failed: {}", e)))?;
+                tls::split(stream)
+            }
+            None => tls::split(stream),
+        };
+        let (queue, mut rx) = mpsc::channel::<Vec<u8>>(rate_limit.client_queue_len.max(1));
+        let tx = ClientSender::new(queue);
         
-        // Create a task to forward messages to the client
+        // Create a task to forward messages to the client. A client whose
+        // queue overflowed has its backlog dropped and the connection closed.
+        let writer_tx = tx.clone();
         tokio::spawn(async move {
             while let Some(data) = rx.recv().await {
-                if let Err(e) = writer.write_all(&data).await {
+                if data == CLOSE_CONNECTION || writer_tx.has_overflowed() {
+                    break;
+                }
+                // TLS buffers what it encrypts, so push each message out
+                let written = match writer.write_all(&data).await {
+                    Ok(()) => writer.flush().await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = written {
                     eprintln!("Error writing to client: {}", e);
                     break;
                 }
@@ -188,12 +557,15 @@ impl GameServer {
                     username: username.clone(),
                     addr,
                     last_heartbeat: Instant::now(),
+                    last_chat: None,
+                    rate_limit: TokenBucket::new(&rate_limit),
+                    account: None,
                     sender: tx.clone(),
                 };
                 
                 // Get game state to send to the client
                 let game_lock = game.lock().await;
-                let universe = game_lock.universe.clone();
+                let universe = universe_snapshot.lock().await.share(&game_lock.universe)?;
                 
                 // Create a new ship for the player or get existing one
                 let player_ship = game_lock.player.ship.clone();
@@ -202,7 +574,7 @@ impl GameServer {
                 clients.lock().await.insert(client_id, client);
                 
                 // Send success response
-                let response = Message::ConnectResponse {
+                let response = UniverseResponse::ConnectResponse {
                     success: true,
                     message: format!("Welcome to ASTR, {}!", username),
                     universe: Some(universe),
@@ -224,7 +596,18 @@ impl GameServer {
                         break; // Connection closed
                     }
                     
-                    if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone()).await {
+                    // Kicked clients are dropped from the client list
+                    if !clients.lock().await.contains_key(&client_id) {
+                        break;
+                    }
+                    if tx.has_overflowed() {
+                        debug::log_internal(LogLevel::Warning, "network", &format!(
+                            "Disconnected client {}: it fell too far behind reading its messages", client_id
+                        ));
+                        break;
+                    }
+                    
+                    if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone(), characters.clone(), universe_snapshot.clone()).await {
                         eprintln!("Error processing message: {}", e);
                         
                         // Send error to client
@@ -257,6 +640,9 @@ impl GameServer {
                     username: "registration".to_string(),
                     addr,
                     last_heartbeat: Instant::now(),
+                    last_chat: None,
+                    rate_limit: TokenBucket::new(&rate_limit),
+                    account: None,
                     sender: tx.clone(),
                 };
                 
@@ -264,7 +650,7 @@ impl GameServer {
                 clients.lock().await.insert(client_id, client);
                 
                 // Process the registration message
-                if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone()).await {
+                if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone(), characters.clone(), universe_snapshot.clone()).await {
                     eprintln!("Error processing registration: {}", e);
                     
                     // Send error to client
@@ -285,7 +671,18 @@ impl GameServer {
                         break; // Connection closed
                     }
                     
-                    if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone()).await {
+                    // Kicked clients are dropped from the client list
+                    if !clients.lock().await.contains_key(&client_id) {
+                        break;
+                    }
+                    if tx.has_overflowed() {
+                        debug::log_internal(LogLevel::Warning, "network", &format!(
+                            "Disconnected client {}: it fell too far behind reading its messages", client_id
+                        ));
+                        break;
+                    }
+                    
+                    if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone(), characters.clone(), universe_snapshot.clone()).await {
                         eprintln!("Error processing message: {}", e);
                         
                         // Send error to client
@@ -305,8 +702,10 @@ impl GameServer {
                 clients.lock().await.remove(&client_id);
             }
             
-            // Allow login as first message
-            Message::LoginAccount { username, password } => {
+            // Allow login, or recovering a forgotten password, as first message
+            Message::LoginAccount { username, .. }
+            | Message::RequestPasswordReset { username }
+            | Message::CompletePasswordReset { username, .. } => {
                 println!("Login attempt from {}: {}", addr, username);
                 
                 // Create a temporary client ID for this login
@@ -318,6 +717,9 @@ impl GameServer {
                     username: "login".to_string(),
                     addr,
                     last_heartbeat: Instant::now(),
+                    last_chat: None,
+                    rate_limit: TokenBucket::new(&rate_limit),
+                    account: None,
                     sender: tx.clone(),
                 };
                 
@@ -325,7 +727,7 @@ impl GameServer {
                 clients.lock().await.insert(client_id, client);
                 
                 // Process the login message
-                if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone()).await {
+                if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone(), characters.clone(), universe_snapshot.clone()).await {
                     eprintln!("Error processing login: {}", e);
                     
                     // Send error to client
@@ -346,7 +748,18 @@ impl GameServer {
                         break; // Connection closed
                     }
                     
-                    if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone()).await {
+                    // Kicked clients are dropped from the client list
+                    if !clients.lock().await.contains_key(&client_id) {
+                        break;
+                    }
+                    if tx.has_overflowed() {
+                        debug::log_internal(LogLevel::Warning, "network", &format!(
+                            "Disconnected client {}: it fell too far behind reading its messages", client_id
+                        ));
+                        break;
+                    }
+                    
+                    if let Err(e) = Self::process_message(&buffer[..n], client_id, game.clone(), clients.clone(), accounts.clone(), characters.clone(), universe_snapshot.clone()).await {
                         eprintln!("Error processing message: {}", e);
                         
                         // Send error to client
@@ -367,7 +780,7 @@ impl GameServer {
             }
             
             _ => {
-                return Err(NetworkError::ConnectionError("Expected Connect, RegisterAccount, or LoginAccount message".to_string()));
+                return Err(NetworkError::ConnectionError("Expected Connect, RegisterAccount, LoginAccount, or password reset message".to_string()));
             }
         }
         
@@ -380,28 +793,38 @@ impl GameServer {
         client_id: Uuid,
         game: Arc<Mutex<Game>>,
         clients: Arc<Mutex<HashMap<Uuid, ClientConnection>>>,
-        accounts: Arc<Mutex<AccountManager>>
+        accounts: Arc<Mutex<AccountManager>>,
+        characters: CharacterStore,
+        universe_snapshot: SharedUniverse,
     ) -> NetworkResult<()> {
-        let message: Message = serde_json::from_slice(data)
-            .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
-        
-        // Update client's last heartbeat time
+        // Update client's last heartbeat time, and drop the message unread if
+        // the client is over its rate limit
         if let Some(client) = clients.lock().await.get_mut(&client_id) {
             client.last_heartbeat = Instant::now();
+            if !client.rate_limit.try_take() {
+                debug::log_internal(LogLevel::Warning, "network", &format!(
+                    "Rate limit exceeded by {} ({}, {}); message dropped",
+                    client.username, client_id, client.addr
+                ));
+                return Ok(());
+            }
         } else {
             return Err(NetworkError::ClientError("Client not found".to_string()));
         }
         
+        let message: Message = serde_json::from_slice(data)
+            .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+        
         match message {
             Message::Heartbeat { .. } => {
                 // Already updated the heartbeat time above
                 Ok(())
             }
             
-            Message::RequestGameState { client_id } => {
+            Message::RequestGameState { client_id: _ } => {
                 let game_state = game.lock().await;
-                let response = Message::GameStateUpdate {
-                    universe: game_state.universe.clone(),
+                let response = UniverseResponse::GameStateUpdate {
+                    universe: universe_snapshot.lock().await.share(&game_state.universe)?,
                     player_ship: game_state.player.ship.clone(),
                 };
                 
@@ -416,47 +839,31 @@ impl GameServer {
                 Ok(())
             }
             
-            Message::NavigationAction { client_id, destination_system } => {
+            Message::NavigationAction { client_id: _, destination_system, action_id } => {
                 let mut game_state = game.lock().await;
+                let Game { navigation_system, player, universe, time_system, .. } = &mut *game_state;
                 
-                // Process navigation in the game logic
-                let success;
-                let message;
-                
-                // First, let's check if the system exists
-                let destination_system_exists = game_state.universe.get_system(&destination_system).is_some();
+                // Travel has to be possible from the server's copy of the player
+                let destination = match validation::validate_travel(navigation_system, player, universe, &destination_system) {
+                    Ok(destination) => destination,
+                    Err(message) => return Self::reject_player_action(&clients, client_id, action_id, message).await,
+                };
                 
-                if destination_system_exists {
-                    // Get the destination again to avoid borrowing issues
-                    let destination = game_state.universe.get_system(&destination_system).unwrap().clone();
-                    
-                    // Check if we can travel there
-                    let can_travel = game_state.navigation_system.can_travel_to(&game_state.player, &destination);
-                    
-                    if can_travel {
-                        // To avoid multiple mutable borrows, let's implement travel directly
-                        // In a complete implementation, navigation_system would be restructured
-                        // to avoid the double borrow issue
-                        
-                        // Instead, just update the player's current system
-                        // We need to clone the entire destination StarSystem
-                        game_state.player.current_system = destination.clone();
-                        success = true;
-                        message = format!("Traveling to {}", destination_system);
-                    } else {
-                        success = false;
-                        message = "Cannot travel to that system - too far away".to_string();
-                    }
-                } else {
-                    success = false;
-                    message = format!("Star system '{}' not found", destination_system);
+                // Start the jump properly so fuel is spent and travel time applies
+                let name = destination.name.clone();
+                let outcome = navigation_system.travel_to(player, destination, &time_system.clock());
+                let mut message = format!("Traveling to {}", name);
+                if outcome != EncounterOutcome::SafePassage {
+                    message.push_str(&format!(". {}", outcome.describe()));
                 }
                 
                 let response = Message::ActionResponse {
-                    success,
+                    success: true,
                     message,
                     updated_ship: Some(game_state.player.ship.clone()),
                     updated_market: None,
+                    action_id,
+                    updated_holdings: Some(PlayerHoldings::of(&game_state.player)),
                 };
                 
                 let response_bytes = serde_json::to_vec(&response)
@@ -468,50 +875,29 @@ impl GameServer {
                 }
                 
                 // Save game state after significant action
-                if success {
-                    if let Err(e) = save_load::save_game(&*game_state) {
-                        eprintln!("Error saving game state: {}", e);
-                    }
+                if let Err(e) = save_load::save_game(&*game_state) {
+                    eprintln!("Error saving game state: {}", e);
                 }
                 
                 Ok(())
             }
             
-            Message::MiningAction { client_id, resource, quantity } => {
+            Message::MiningAction { client_id: _, resource, quantity, action_id } => {
                 let game_state = game.lock().await;
                 
-                // Process mining in the game logic
-                let success;
-                let message;
-                
-                // Get the current system ID (skipping for now to avoid warning)
-                let _current_system_id = game_state.player.current_system.id.clone();
-                
-                // We'll extract the values we need to avoid borrow conflicts
-                let available_space = game_state.player.ship.get_cargo_space_available();
-                
-                if quantity > available_space {
-                    success = false;
-                    message = format!("Not enough cargo space. Available: {}", available_space);
-                } else {
-                    // Instead of directly using mining_system, we'll do a simpler implementation
-                    // This avoids the borrow checker error by not borrowing twice
-                    
-                    // For a real implementation, you'd need to restructure mining_system
-                    // to avoid multiple borrows of game_state
-                    success = true;
-                    message = format!("Successfully mined {} units of {}", quantity, resource);
-                    
-                    // Simulate mining by directly modifying the player
-                    // In a real implementation, this would be handled properly by mining_system
-                    // with the appropriate restructuring to avoid borrow issues
+                if let Err(message) = validation::validate_mining(&game_state.player, &resource, quantity) {
+                    return Self::reject_player_action(&clients, client_id, action_id, message).await;
                 }
                 
+                // Mining itself is still simulated client-side; the server only
+                // confirms the request is possible
                 let response = Message::ActionResponse {
-                    success,
-                    message,
+                    success: true,
+                    message: format!("Successfully mined {} units of {}", quantity, resource),
                     updated_ship: Some(game_state.player.ship.clone()),
                     updated_market: None,
+                    action_id,
+                    updated_holdings: None, // Mined cargo is still only added client-side
                 };
                 
                 let response_bytes = serde_json::to_vec(&response)
@@ -523,46 +909,32 @@ impl GameServer {
                 }
                 
                 // Save game state after significant action
-                if success {
-                    if let Err(e) = save_load::save_game(&*game_state) {
-                        eprintln!("Error saving game state: {}", e);
-                    }
+                if let Err(e) = save_load::save_game(&*game_state) {
+                    eprintln!("Error saving game state: {}", e);
                 }
                 
                 Ok(())
             }
             
-            Message::MarketAction { client_id, action_type, item_name, quantity } => {
-                let game_state = game.lock().await;
+            Message::MarketAction { client_id: _, action_type, item_name, quantity, action_id } => {
+                let mut game_state = game.lock().await;
+                let clock = game_state.time_system.clock();
                 
-                // Process market action in the game logic
-                let success;
-                let message;
-                let updated_market = None;
-                
-                // For simplicity in fixing borrow issues, we'll use a direct approach
-                match action_type {
-                    MarketActionType::Buy => {
-                        // Simplify to avoid borrow checking issues
-                        // In a real implementation, you'd need to restructure trading_system
-                        // to avoid multiple borrows
-                        success = true;
-                        message = format!("Purchased {} units of {}", quantity, item_name);
-                    },
-                    MarketActionType::Sell => {
-                        // Simplify to avoid borrow checking issues
-                        // In a real implementation, you'd need to restructure trading_system
-                        // to avoid multiple borrows
-                        success = true;
-                        message = format!("Sold {} units of {}", quantity, item_name);
-                    }
-                }
+                // Check against the server's copy of the player, then apply
+                let result = Self::execute_market_action(&mut game_state, &action_type, &item_name, quantity, &clock);
+                
+                let message = match result {
+                    Ok(message) => message,
+                    Err(message) => return Self::reject_player_action(&clients, client_id, action_id, message).await,
+                };
                 
                 let response = Message::ActionResponse {
-                    success,
+                    success: true,
                     message,
                     updated_ship: Some(game_state.player.ship.clone()),
-                    updated_market,
+                    updated_market: None,
+                    action_id,
+                    updated_holdings: Some(PlayerHoldings::of(&game_state.player)),
                 };
                 
                 let response_bytes = serde_json::to_vec(&response)
@@ -574,10 +946,8 @@ impl GameServer {
                 }
                 
                 // Save game state after significant action
-                if success {
-                    if let Err(e) = save_load::save_game(&*game_state) {
-                        eprintln!("Error saving game state: {}", e);
-                    }
+                if let Err(e) = save_load::save_game(&*game_state) {
+                    eprintln!("Error saving game state: {}", e);
                 }
                 
                 Ok(())
@@ -653,6 +1023,10 @@ impl GameServer {
                                 .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
                         }
                         
+                        if let Some(client) = clients.lock().await.get_mut(&client_id) {
+                            client.account = Some(username.clone());
+                        }
+                        
                         println!("User logged in: {}", username);
                         Ok(())
                     },
@@ -679,7 +1053,7 @@ impl GameServer {
                 }
             }
             
-            Message::ChangePassword { client_id, username, current_password, new_password } => {
+            Message::ChangePassword { client_id: _, username, current_password, new_password } => {
                 let mut accounts_lock = accounts.lock().await;
                 
                 // Try to change password
@@ -723,7 +1097,63 @@ impl GameServer {
                 }
             }
             
-            Message::DeleteAccount { client_id, username, password } => {
+            Message::RequestPasswordReset { username } => {
+                // Answer the same way whether or not the account exists, so
+                // the request can't be used to probe for usernames
+                if let Err(e) = accounts.lock().await.create_reset_token(&username) {
+                    println!("Password reset requested for {}: {}", username, e);
+                }
+                
+                let response = Message::PasswordResetResponse {
+                    success: true,
+                    message: "If that account exists, a reset token has been sent.".to_string(),
+                };
+                
+                let response_bytes = serde_json::to_vec(&response)
+                    .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+                
+                if let Some(client) = clients.lock().await.get(&client_id) {
+                    client.sender.send(response_bytes).await
+                        .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+                }
+                
+                Ok(())
+            }
+            
+            Message::CompletePasswordReset { username, token, new_password } => {
+                let result = accounts.lock().await.consume_reset_token(&username, &token, &new_password);
+                
+                let response = match &result {
+                    Ok(()) => Message::PasswordResetResponse {
+                        success: true,
+                        message: "Password reset successfully!".to_string(),
+                    },
+                    Err(AccountError::ResetTokenExpired) => Message::PasswordResetResponse {
+                        success: false,
+                        message: "Reset token has expired, please request a new one".to_string(),
+                    },
+                    Err(_) => Message::PasswordResetResponse {
+                        success: false,
+                        message: "Invalid username or reset token".to_string(),
+                    },
+                };
+                
+                let response_bytes = serde_json::to_vec(&response)
+                    .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+                
+                if let Some(client) = clients.lock().await.get(&client_id) {
+                    client.sender.send(response_bytes).await
+                        .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+                }
+                
+                match result {
+                    Ok(()) => println!("Password reset for user: {}", username),
+                    Err(e) => println!("Password reset failed for {}: {}", username, e),
+                }
+                Ok(())
+            }
+            
+            Message::DeleteAccount { client_id: _, username, password } => {
                 let mut accounts_lock = accounts.lock().await;
                 
                 // Try to delete account
@@ -767,7 +1197,7 @@ impl GameServer {
                 }
             }
             
-            Message::CreateCharacter { client_id, account_username, character_name, faction_type, storyline_id } => {
+            Message::CreateCharacter { client_id: _, account_username, character_name, faction_type, storyline_id } => {
                 // This would create a new character for the user
                 // For now, we'll just create a placeholder character ID
                 let character_id = Uuid::new_v4().to_string();
@@ -793,10 +1223,28 @@ impl GameServer {
                     return Ok(());
                 }
                 
-                // In a complete implementation, we would:
-                // 1. Create a new Player object with the character info
-                // 2. Set up initial ship, inventory, etc.
-                // 3. Add the character to the game state
+                drop(accounts_lock);
+                
+                // Keep the character's player state so it can be ranked
+                let faction = match faction_type {
+                    1 => FactionType::Miners,
+                    2 => FactionType::Military,
+                    3 => FactionType::Scientists,
+                    _ => FactionType::Traders,
+                };
+                let storylines = faction::get_storylines_for_faction(&faction);
+                let storyline = storylines.iter()
+                    .find(|storyline| storyline.id == storyline_id)
+                    .or_else(|| storylines.first())
+                    .cloned()
+                    .unwrap_or_else(|| faction::Storyline::new(&storyline_id, faction.clone(), "Freelancer", "", 5));
+                let mut player = Player::with_character(&character_name, faction, storyline);
+                player.id = character_id.clone();
+                {
+                    let mut characters_lock = characters.lock().await;
+                    characters_lock.insert(character_id.clone(), player);
+                    integrity::report("character creation", &integrity::validate_character_ids(characters_lock.values()));
+                }
 
                 let response = Message::CreateCharacterResponse {
                     success: true,
@@ -816,7 +1264,7 @@ impl GameServer {
                 Ok(())
             }
             
-            Message::ListCharacters { client_id, account_username } => {
+            Message::ListCharacters { client_id: _, account_username } => {
                 // Get the account
                 let accounts_lock = accounts.lock().await;
                 let account = match accounts_lock.get_account_by_username(&account_username) {
@@ -865,7 +1313,7 @@ impl GameServer {
                 Ok(())
             }
             
-            Message::SelectCharacter { client_id, account_username, character_id } => {
+            Message::SelectCharacter { client_id: _, account_username, character_id } => {
                 // Get the account to verify the character belongs to it
                 let accounts_lock = accounts.lock().await;
                 let account = match accounts_lock.get_account_by_username(&account_username) {
@@ -917,10 +1365,10 @@ impl GameServer {
                 // For now, we'll just return the current game state
                 
                 let game_state = game.lock().await;
-                let universe = game_state.universe.clone();
+                let universe = universe_snapshot.lock().await.share(&game_state.universe)?;
                 let player_ship = game_state.player.ship.clone();
                 
-                let response = Message::SelectCharacterResponse {
+                let response = UniverseResponse::SelectCharacterResponse {
                     success: true,
                     message: format!("Character selected successfully!"),
                     universe: Some(universe),
@@ -939,7 +1387,120 @@ impl GameServer {
                 Ok(())
             }
             
-            Message::Disconnect { client_id } => {
+            Message::Chat { text, .. } => {
+                // The sender is the connection the message arrived on; the
+                // client_id in the message is ignored
+                let text = text.trim().to_string();
+                let mut clients_lock = clients.lock().await;
+                let sender = match clients_lock.get_mut(&client_id) {
+                    Some(client) => client,
+                    None => return Err(NetworkError::ClientError("Client not found".to_string())),
+                };
+                
+                // Reject empty, oversized and too frequent messages back to the sender only
+                let rejection = if text.is_empty() {
+                    Some((400, "Chat message is empty".to_string()))
+                } else if text.chars().count() > MAX_CHAT_LENGTH {
+                    Some((400, format!("Chat messages are limited to {} characters", MAX_CHAT_LENGTH)))
+                } else if sender.last_chat.map_or(false, |last| last.elapsed() < CHAT_COOLDOWN) {
+                    Some((429, "You're sending messages too quickly".to_string()))
+                } else {
+                    None
+                };
+                
+                if let Some((code, message)) = rejection {
+                    let error_bytes = serde_json::to_vec(&Message::Error { code, message })
+                        .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+                    sender.sender.send(error_bytes).await
+                        .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+                    return Ok(());
+                }
+                
+                sender.last_chat = Some(Instant::now());
+                
+                // Use the name the server knows rather than whatever the client
+                // claimed, and keep the sender's connection id to the server
+                let chat = Message::Chat { client_id: Uuid::nil(), username: sender.username.clone(), text };
+                let chat_bytes = serde_json::to_vec(&chat)
+                    .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+                
+                for client in clients_lock.values() {
+                    if client.sender.send(chat_bytes.clone()).await.is_err() {
+                        eprintln!("Failed to deliver chat to client {}", client.username);
+                    }
+                }
+                
+                Ok(())
+            }
+            
+            Message::RequestLeaderboard { client_id: _, metric, limit } => {
+                let entries = {
+                    let game_state = game.lock().await;
+                    let characters_lock = characters.lock().await;
+                    let economy = EconomySystem::from_universe(&game_state.universe);
+                    
+                    let scores = characters_lock.values()
+                        .map(|player| {
+                            let score = match metric {
+                                LeaderboardMetric::NetWorth => player.net_worth(&economy).total() as i64,
+                                LeaderboardMetric::Reputation => player.reputation.values().map(|v| *v as i64).sum(),
+                            };
+                            (player.character.name.clone(), score)
+                        })
+                        .collect();
+                    rank_leaderboard(scores, limit.min(MAX_LEADERBOARD_SIZE) as usize)
+                };
+                
+                let response = Message::LeaderboardResponse { metric, entries };
+                let response_bytes = serde_json::to_vec(&response)
+                    .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+                
+                if let Some(client) = clients.lock().await.get(&client_id) {
+                    client.sender.send(response_bytes).await
+                        .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+                }
+                
+                Ok(())
+            }
+            
+            Message::AdminCommand { command, .. } => {
+                // Authorize against the account logged in on the connection the
+                // message arrived on; the client_id in the message is ignored
+                let account = clients.lock().await.get(&client_id).and_then(|client| client.account.clone());
+                let role = match &account {
+                    Some(username) => accounts.lock().await.get_role(username),
+                    None => None,
+                };
+                let required = command.required_role();
+                if !role.map_or(false, |role| role.allows(required)) {
+                    debug::log_internal(LogLevel::Warning, "network", &format!(
+                        "Refused admin command from client {} ({:?}): requires {:?}",
+                        client_id, account, required
+                    ));
+                    return Self::reject_action(&clients, client_id, format!("This command requires the {:?} role", required)).await;
+                }
+                
+                let result = Self::run_admin_command(command, game, clients.clone(), characters).await;
+                if let Ok(message) = &result {
+                    println!("Admin {}: {}", account.unwrap_or_default(), message);
+                }
+                
+                let response = match result {
+                    Ok(message) => Message::AdminCommandResponse { success: true, message },
+                    Err(message) => Message::AdminCommandResponse { success: false, message },
+                };
+                let response_bytes = serde_json::to_vec(&response)
+                    .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+                
+                if let Some(client) = clients.lock().await.get(&client_id) {
+                    client.sender.send(response_bytes).await
+                        .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+                }
+                
+                Ok(())
+            }
+            
+            Message::Disconnect { client_id: _ } => {
                 // Client is disconnecting gracefully
                 clients.lock().await.remove(&client_id);
                 println!("Client {} disconnected gracefully", client_id);
@@ -952,6 +1513,151 @@ impl GameServer {
         }
     }
     
+    /// Carry out an already authorized admin command, describing what was done
+    async fn run_admin_command(
+        command: AdminCommand,
+        game: Arc<Mutex<Game>>,
+        clients: Arc<Mutex<HashMap<Uuid, ClientConnection>>>,
+        characters: CharacterStore,
+    ) -> Result<String, String> {
+        match command {
+            AdminCommand::KickClient { target, reason } => {
+                let client = clients.lock().await.remove(&target)
+                    .ok_or_else(|| format!("No connected client {}", target))?;
+                
+                if let Ok(kicked_bytes) = serde_json::to_vec(&Message::Kicked { reason: reason.clone() }) {
+                    let _ = client.sender.send(kicked_bytes).await;
+                }
+                let _ = client.sender.send(CLOSE_CONNECTION).await;
+                Ok(format!("Kicked {} ({})", client.username, reason))
+            }
+            
+            AdminCommand::Announce { message } => {
+                let announcement_bytes = serde_json::to_vec(&Message::Announcement { message: message.clone() })
+                    .map_err(|e| e.to_string())?;
+                
+                for client in clients.lock().await.values() {
+                    if client.sender.send(announcement_bytes.clone()).await.is_err() {
+                        eprintln!("Failed to deliver announcement to client {}", client.username);
+                    }
+                }
+                Ok(format!("Announced: {}", message))
+            }
+            
+            AdminCommand::ResetMarket { system_id } => {
+                game.lock().await.universe.reset_market(&system_id)?;
+                Ok(format!("Reset the market in {}", system_id))
+            }
+            
+            AdminCommand::AdjustCredits { character_id, amount } => {
+                let clock = game.lock().await.time_system.clock();
+                let mut characters_lock = characters.lock().await;
+                let player = characters_lock.get_mut(&character_id)
+                    .ok_or_else(|| format!("No character {}", character_id))?;
+                player.apply_transaction(amount, TransactionCategory::Adjustment, "Adjusted by an administrator", &clock)?;
+                Ok(format!("Adjusted {}'s credits by {} to {}", player.character.name, amount, player.credits))
+            }
+        }
+    }
+    
+    /// Give an account a role, e.g. to set up the first admin
+    pub async fn grant_role(&self, username: &str, role: AccountRole) -> Result<(), AccountError> {
+        self.accounts.lock().await.set_role(username, role)
+    }
+    
+    /// Tell a client its action was refused by server-side validation
+    async fn reject_action(
+        clients: &Arc<Mutex<HashMap<Uuid, ClientConnection>>>,
+        client_id: Uuid,
+        message: String,
+    ) -> NetworkResult<()> {
+        let error_bytes = serde_json::to_vec(&Message::Error { code: 403, message })
+            .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+        
+        if let Some(client) = clients.lock().await.get(&client_id) {
+            client.sender.send(error_bytes).await
+                .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Trade at the market in the player's system once validation passes:
+    /// priced by the market's own quotes in its currency, with the market's
+    /// stock, the player's cargo and their balance all changed together
+    fn execute_market_action(
+        game_state: &mut Game,
+        action_type: &MarketActionType,
+        item_name: &str,
+        quantity: u32,
+        clock: &GameClock,
+    ) -> Result<String, String> {
+        let Game { player, universe, .. } = game_state;
+        let mut market = universe.get_market(&player.current_system.id)
+            .ok_or_else(|| "There is no market in this system".to_string())?;
+        let currency = market.currency.clone();
+        
+        let message = match action_type {
+            MarketActionType::Buy => {
+                validation::validate_purchase(player, &market, item_name, quantity)?;
+                let (item, quantity, total) = market.buy_item(item_name, quantity, clock)
+                    .ok_or_else(|| format!("{} is no longer in stock", item_name))?;
+                if !player.inventory.add_item(item.clone(), quantity) {
+                    return Err(format!("Not enough cargo space for {} {}", quantity, item_name));
+                }
+                let description = format!("Bought {} {}", quantity, item.name);
+                if let Err(e) = player.adjust_balance(&currency, -(total as i64), TransactionCategory::Trade, &description, clock) {
+                    player.inventory.discard(&item, quantity);
+                    return Err(e);
+                }
+                format!("Purchased {} units of {} for {}", quantity, item_name, currency.format(total))
+            },
+            MarketActionType::Sell => {
+                let (item, _) = validation::validate_sale(player, &market, item_name, quantity)?;
+                if player.inventory.discard(&item, quantity) < quantity {
+                    return Err(format!("You don't have {} {}", quantity, item_name));
+                }
+                let revenue = market.sell_item(item.clone(), quantity, clock);
+                let description = format!("Sold {} {}", quantity, item.name);
+                if let Err(e) = player.adjust_balance(&currency, revenue as i64, TransactionCategory::Trade, &description, clock) {
+                    *player.inventory.items.entry(item).or_insert(0) += quantity;
+                    return Err(e);
+                }
+                format!("Sold {} units of {} for {}", quantity, item_name, currency.format(revenue))
+            },
+        };
+        
+        universe.update_market(market);
+        Ok(message)
+    }
+    
+    /// Turn down a player action with a failed `ActionResponse`, so a client
+    /// that already applied it locally knows to undo it
+    async fn reject_player_action(
+        clients: &Arc<Mutex<HashMap<Uuid, ClientConnection>>>,
+        client_id: Uuid,
+        action_id: Option<ActionId>,
+        message: String,
+    ) -> NetworkResult<()> {
+        let response = Message::ActionResponse {
+            success: false,
+            message,
+            updated_ship: None,
+            updated_market: None,
+            action_id,
+            updated_holdings: None,
+        };
+        let response_bytes = serde_json::to_vec(&response)
+            .map_err(|e| NetworkError::SerializationError(e.to_string()))?;
+        
+        if let Some(client) = clients.lock().await.get(&client_id) {
+            client.sender.send(response_bytes).await
+                .map_err(|_| NetworkError::ConnectionError("Failed to send response".to_string()))?;
+        }
+        
+        Ok(())
+    }
+    
     /// Broadcast a message to all connected clients
     pub async fn broadcast(&self, message: Message) -> NetworkResult<()> {
         let message_bytes = serde_json::to_vec(&message)
@@ -970,6 +1676,145 @@ impl GameServer {
     }
 }
 
+/// Top `limit` characters by score, highest first. Equal scores are ordered
+/// by name so every client sees the same ranking.
+pub fn rank_leaderboard(mut scores: Vec<(String, i64)>, limit: usize) -> Vec<LeaderboardEntry> {
+    scores.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scores.into_iter()
+        .take(limit)
+        .enumerate()
+        .map(|(i, (character_name, score))| LeaderboardEntry {
+            rank: i as u32 + 1,
+            character_name,
+            score,
+        })
+        .collect()
+}
+
 // Define helper constants outside impl block
 #[allow(dead_code)]
-const CONNECTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
\ No newline at end of file
+const CONNECTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaderboard_breaks_ties_by_name() {
+        let scores = vec![
+            ("Vega".to_string(), 500),
+            ("Ash".to_string(), 900),
+            ("Orin".to_string(), 500),
+            ("Bex".to_string(), 100),
+        ];
+
+        let entries = rank_leaderboard(scores, 3);
+        let names: Vec<&str> = entries.iter().map(|e| e.character_name.as_str()).collect();
+
+        assert_eq!(names, vec!["Ash", "Orin", "Vega"]);
+        assert_eq!(entries[2].rank, 3);
+    }
+
+    #[test]
+    fn test_universe_snapshot_is_shared_until_the_universe_changes() {
+        let mut universe = Universe::new();
+        let mut snapshot = UniverseSnapshot::default();
+
+        let first = snapshot.share(&universe).unwrap();
+        assert!(Arc::ptr_eq(&first, &snapshot.share(&universe).unwrap()));
+
+        let market_system = universe.get_all_system_ids().into_iter()
+            .find(|id| universe.get_market(id).is_some())
+            .unwrap();
+        universe.reset_market(&market_system).unwrap();
+        assert!(!Arc::ptr_eq(&first, &snapshot.share(&universe).unwrap()));
+
+        // Clients read the shared encoding as the message it mirrors
+        let response = UniverseResponse::GameStateUpdate {
+            universe: snapshot.share(&universe).unwrap(),
+            player_ship: Game::with_start_menu().player.ship,
+        };
+        let message: Message = serde_json::from_slice(&serde_json::to_vec(&response).unwrap()).unwrap();
+        match message {
+            Message::GameStateUpdate { universe: received, .. } => {
+                assert_eq!(received.get_all_system_ids().len(), universe.get_all_system_ids().len());
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_admin_command_with_spoofed_client_id_is_refused() {
+        let accounts_path = std::env::temp_dir().join(format!("accounts-{}.json", Uuid::new_v4()));
+        let mut accounts = AccountManager::with_path(accounts_path.clone());
+        accounts.register_account("boss", "password", None).unwrap();
+        accounts.set_role("boss", AccountRole::Admin).unwrap();
+        
+        let connect = |account: Option<&str>| {
+            let (tx, rx) = mpsc::channel(8);
+            let client = ClientConnection {
+                id: Uuid::new_v4(),
+                username: account.unwrap_or("guest").to_string(),
+                addr: "127.0.0.1:4000".parse().unwrap(),
+                last_heartbeat: Instant::now(),
+                last_chat: None,
+                rate_limit: TokenBucket::new(&RateLimitConfig::default()),
+                account: account.map(str::to_string),
+                sender: ClientSender::new(tx),
+            };
+            (client, rx)
+        };
+        let (admin, _admin_rx) = connect(Some("boss"));
+        let (attacker, mut attacker_rx) = connect(None);
+        let (admin_id, attacker_id) = (admin.id, attacker.id);
+        let clients = Arc::new(Mutex::new(HashMap::from([(admin_id, admin), (attacker_id, attacker)])));
+        
+        // The attacker copies the admin's id into the message
+        let message = Message::AdminCommand {
+            client_id: admin_id,
+            command: AdminCommand::KickClient { target: admin_id, reason: "spoofed".to_string() },
+        };
+        GameServer::process_message(
+            &serde_json::to_vec(&message).unwrap(),
+            attacker_id,
+            Arc::new(Mutex::new(Game::with_start_menu())),
+            clients.clone(),
+            Arc::new(Mutex::new(accounts)),
+            Arc::new(Mutex::new(HashMap::new())),
+            Arc::new(Mutex::new(UniverseSnapshot::default())),
+        ).await.unwrap();
+        
+        assert!(clients.lock().await.contains_key(&admin_id));
+        let reply: Message = serde_json::from_slice(&attacker_rx.recv().await.unwrap()).unwrap();
+        assert!(matches!(reply, Message::Error { code: 403, .. }));
+        let _ = std::fs::remove_file(&accounts_path);
+        let _ = std::fs::remove_file(accounts_path.with_extension("json.bak"));
+    }
+    
+    #[test]
+    fn test_market_action_trades_at_the_market_quote_and_moves_stock() {
+        let mut game = Game::with_start_menu();
+        let clock = GameClock::default();
+        game.player.is_docked = true;
+        let system_id = game.player.current_system.id.clone();
+        let market = game.universe.get_market(&system_id).unwrap();
+        let (name, stocked) = market.items.iter()
+            .find(|(_, item)| item.quantity >= 2)
+            .map(|(name, item)| (name.clone(), item.quantity))
+            .unwrap();
+        let quote = market.quote_buy(&name, 2).unwrap();
+        let balance = game.player.balance(&market.currency);
+        
+        GameServer::execute_market_action(&mut game, &MarketActionType::Buy, &name, 2, &clock).unwrap();
+        assert_eq!(game.player.balance(&market.currency), balance - quote.total);
+        assert_eq!(game.player.inventory.get_item_quantity(&name), 2);
+        let market = game.universe.get_market(&system_id).unwrap();
+        assert_eq!(market.items[&name].quantity, stocked - 2);
+        
+        let held = game.player.inventory.items.keys().find(|item| item.name == name).unwrap().clone();
+        let revenue = market.quote_sell(&held, 1).total;
+        GameServer::execute_market_action(&mut game, &MarketActionType::Sell, &name, 1, &clock).unwrap();
+        assert_eq!(game.player.balance(&market.currency), balance - quote.total + revenue);
+        assert_eq!(game.universe.get_market(&system_id).unwrap().items[&name].quantity, stocked - 1);
+        assert!(GameServer::execute_market_action(&mut game, &MarketActionType::Sell, &name, 2, &clock).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+/// Certificate and key a server encrypts connections with. Without one the
+/// server speaks plain TCP, which is fine for local play.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM file holding the certificate chain, server certificate first
+    pub cert_path: String,
+    /// PEM file holding the certificate's private key
+    pub key_path: String,
+}
+
+/// Read half of a connection, encrypted or not
+pub type StreamReader = Box<dyn AsyncRead + Unpin + Send>;
+/// Write half of a connection, encrypted or not
+pub type StreamWriter = Box<dyn AsyncWrite + Unpin + Send>;
+
+/// Split a plain or TLS stream into halves that can be used from separate tasks
+pub fn split<S>(stream: S) -> (StreamReader, StreamWriter)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (reader, writer) = tokio::io::split(stream);
+    (Box::new(reader), Box::new(writer))
+}
+
+/// Acceptor performing the server side of the handshake with `config`'s
+/// certificate
+pub fn server_acceptor(config: &TlsConfig) -> Result<TlsAcceptor, String> {
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_key(&config.key_path)?;
+    let server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {}", e))?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Client side of TLS: which certificates to trust and the name the
+/// server's certificate must carry
+#[derive(Clone)]
+pub struct ClientTls {
+    connector: TlsConnector,
+    server_name: ServerName,
+}
+
+#[allow(dead_code)]
+impl ClientTls {
+    /// Trust the certificates in the PEM file at `trusted_cert_path`, usually
+    /// the CA that issued the server's certificate or the server's own
+    /// self-signed one, for connections to `host`. A self-signed server
+    /// certificate must not be marked as a CA, or rustls rejects it.
+    pub fn new(trusted_cert_path: &str, host: &str) -> Result<Self, String> {
+        let mut roots = RootCertStore::empty();
+        for cert in load_certs(trusted_cert_path)? {
+            roots.add(&cert)
+                .map_err(|e| format!("Cannot trust certificate in {}: {}", trusted_cert_path, e))?;
+        }
+        let client_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let server_name = ServerName::try_from(host)
+            .map_err(|_| format!("'{}' is not a valid server name for TLS", host))?;
+        Ok(Self {
+            connector: TlsConnector::from(Arc::new(client_config)),
+            server_name,
+        })
+    }
+
+    /// Run the handshake over a freshly connected `stream`
+    pub async fn connect(&self, stream: TcpStream) -> io::Result<(StreamReader, StreamWriter)> {
+        let stream = self.connector.connect(self.server_name.clone(), stream).await?;
+        Ok(split(stream))
+    }
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open certificate file {}: {}", path, e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .map_err(|e| format!("Failed to read certificate file {}: {}", path, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// The first private key in the file, in any format rustls accepts
+fn load_key(path: &str) -> Result<PrivateKey, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open key file {}: {}", path, e))?;
+    let mut reader = BufReader::new(file);
+    loop {
+        match rustls_pemfile::read_one(&mut reader) {
+            Ok(Some(rustls_pemfile::Item::PKCS8Key(key)))
+            | Ok(Some(rustls_pemfile::Item::RSAKey(key)))
+            | Ok(Some(rustls_pemfile::Item::ECKey(key))) => return Ok(PrivateKey(key)),
+            Ok(Some(_)) => continue,
+            Ok(None) => return Err(format!("No private key found in {}", path)),
+            Err(e) => return Err(format!("Failed to read key file {}: {}", path, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_certificate_is_reported() {
+        let config = TlsConfig {
+            cert_path: "no/such/cert.pem".to_string(),
+            key_path: "no/such/key.pem".to_string(),
+        };
+        let error = server_acceptor(&config).err().unwrap();
+        assert!(error.contains("no/such/cert.pem"));
+        assert!(ClientTls::new("no/such/cert.pem", "localhost").is_err());
+    }
+}
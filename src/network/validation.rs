@@ -0,0 +1,158 @@
+// Authoritative checks for actions requested by network clients. The server
+// runs these against its own copy of the player before applying anything, so
+// a client can't travel out of range, sell cargo it doesn't have or buy
+// without the credits.
+
+use crate::models::item::Item;
+use crate::models::market::{Market, TradeQuote};
+use crate::models::player::Player;
+use crate::models::universe::{StarSystem, Universe};
+use crate::systems::navigation::NavigationSystem;
+
+pub fn validate_travel(
+    navigation: &NavigationSystem,
+    player: &Player,
+    universe: &Universe,
+    destination_id: &str,
+) -> Result<StarSystem, String> {
+    let destination = universe.get_system(destination_id)
+        .ok_or_else(|| format!("Star system '{}' not found", destination_id))?;
+
+    if destination.id == player.current_system.id {
+        return Err(format!("Already in {}", destination.name));
+    }
+    if navigation.is_traveling() {
+        return Err("Already traveling".to_string());
+    }
+    if player.is_docked {
+        return Err("Undock before traveling".to_string());
+    }
+    if !navigation.can_travel_to(player, destination) {
+        return Err(format!("{} is out of range or you lack the fuel to get there", destination.name));
+    }
+
+    Ok(destination.clone())
+}
+
+// The item bought and what `quantity` units cost at `market`'s ask, in
+// the market's currency. `market` is the one in the player's current system.
+pub fn validate_purchase(
+    player: &Player,
+    market: &Market,
+    item_name: &str,
+    quantity: u32,
+) -> Result<(Item, TradeQuote), String> {
+    if quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+    if !player.is_docked {
+        return Err("You must be docked at a station to trade".to_string());
+    }
+
+    let market_item = market.items.get(item_name)
+        .ok_or_else(|| format!("{} is not sold here", item_name))?;
+    if market_item.quantity < quantity {
+        return Err(format!("Only {} {} in stock", market_item.quantity, item_name));
+    }
+    quantity.checked_mul(market_item.buy_price())
+        .ok_or_else(|| "Order is too large".to_string())?;
+
+    let quote = market.quote_buy(item_name, quantity)
+        .ok_or_else(|| format!("{} is not sold here", item_name))?;
+    if player.balance(&market.currency) < quote.total {
+        return Err(format!("Cannot afford {} {} (need {})", quantity, item_name, market.currency.format(quote.total)));
+    }
+    if player.inventory.remaining_capacity() < market_item.item.weight.saturating_mul(quantity) {
+        return Err(format!("Not enough cargo space for {} {}", quantity, item_name));
+    }
+
+    Ok((market_item.item.clone(), quote))
+}
+
+// The held stack and what `quantity` units of it fetch at `market`'s bid.
+// `item_name` names the stack as the market stocks it, rarity included.
+pub fn validate_sale(player: &Player, market: &Market, item_name: &str, quantity: u32) -> Result<(Item, TradeQuote), String> {
+    if quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+    if !player.is_docked {
+        return Err("You must be docked at a station to trade".to_string());
+    }
+
+    let held = player.inventory.items.iter()
+        .find(|(item, _)| Market::stock_key(item) == item_name)
+        .map(|(item, held)| (item.clone(), *held));
+    match held {
+        Some((item, held)) if held >= quantity => {
+            let quote = market.quote_sell(&item, quantity);
+            Ok((item, quote))
+        },
+        held => Err(format!("You only have {} {}", held.map_or(0, |(_, held)| held), item_name)),
+    }
+}
+
+pub fn validate_mining(player: &Player, resource: &str, quantity: u32) -> Result<(), String> {
+    if quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+
+    let available = player.current_system.resource_fields.iter()
+        .any(|field| field.resources.iter().any(|(name, _)| name == resource));
+    if !available {
+        return Err(format!("No {} to mine in {}", resource, player.current_system.name));
+    }
+    if player.inventory.remaining_capacity() < quantity {
+        return Err(format!("Not enough cargo space. Available: {}", player.inventory.remaining_capacity()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::ItemType;
+
+    #[test]
+    fn test_cannot_sell_cargo_not_held() {
+        let market = Market::new("test");
+        let mut player = Player::new("Test");
+        player.is_docked = true;
+        let ore = Item::new("Ore", 100, 1, ItemType::Product);
+        player.inventory.add_item(ore.clone(), 2);
+
+        assert!(validate_sale(&player, &market, "Ore", 3).is_err());
+        assert!(validate_sale(&player, &market, "Gold", 1).is_err());
+        assert_eq!(validate_sale(&player, &market, "Ore", 2), Ok((ore.clone(), market.quote_sell(&ore, 2))));
+    }
+
+    #[test]
+    fn test_purchase_is_priced_at_the_ask_in_the_market_currency() {
+        let universe = Universe::new();
+        let mut player = Player::new("Test");
+        player.is_docked = true;
+        let market = universe.get_market(&player.current_system.id).unwrap();
+        let (name, market_item) = market.items.iter().next().unwrap();
+
+        let (item, quote) = validate_purchase(&player, &market, name, 1).unwrap();
+        assert_eq!(item, market_item.item);
+        assert_eq!(Some(quote), market.quote_buy(name, 1));
+        assert!(quote.unit_price >= market_item.buy_price());
+        assert!(validate_purchase(&player, &market, name, market_item.quantity + 1).is_err());
+
+        player.credits = 0;
+        player.currencies.clear();
+        assert!(validate_purchase(&player, &market, name, 1).is_err());
+    }
+
+    #[test]
+    fn test_cannot_teleport_to_current_or_unknown_system() {
+        let universe = Universe::new();
+        let navigation = NavigationSystem::new();
+        let mut player = Player::new("Test");
+        player.current_system = universe.get_system("sol").unwrap().clone();
+
+        assert!(validate_travel(&navigation, &player, &universe, "sol").is_err());
+        assert!(validate_travel(&navigation, &player, &universe, "nowhere").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::models::player::{Player, TransactionCategory};
+use crate::models::faction::FactionType;
+use crate::models::crew::CrewRole;
+use crate::utils::clock::GameClock;
+
+// An NPC ship the player can fight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpcShip {
+    pub name: String,
+    pub attack: u32,
+    pub defense: u32,
+    pub hull: u32,
+    pub bounty: u32,
+    // Faction that rewards the player for defeating this ship, if any
+    pub wanted_by: Option<FactionType>,
+}
+
+#[allow(dead_code)]
+impl NpcShip {
+    // A pirate raider scaled by the danger (0-5) of the system it hunts in
+    pub fn pirate<R: Rng + ?Sized>(danger: u8, rng: &mut R) -> Self {
+        let danger = danger as u32;
+        let attack = rng.gen_range(5..=15) + danger * 2;
+
+        NpcShip {
+            name: "Pirate Raider".to_string(),
+            attack,
+            defense: 2 + danger,
+            hull: 40 + danger * 15,
+            bounty: attack * 20,
+            wanted_by: Some(FactionType::Military),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CombatAction {
+    Fight,
+    Flee,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CombatOutcome {
+    Victory,
+    Defeat,
+    Fled,
+    Stalemate, // Neither side won before the fight broke off
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombatResult {
+    pub outcome: CombatOutcome,
+    pub rounds: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub bounty: u32,
+    pub experience_gained: u32,
+    pub reputation_change: Option<(FactionType, i32)>,
+}
+
+#[allow(dead_code)]
+impl CombatResult {
+    pub fn summary(&self) -> String {
+        let result = match self.outcome {
+            CombatOutcome::Victory => format!("Victory after {} rounds, {} credit bounty", self.rounds, self.bounty),
+            CombatOutcome::Defeat => format!("Defeated after {} rounds", self.rounds),
+            CombatOutcome::Fled => format!("Escaped after {} rounds", self.rounds),
+            CombatOutcome::Stalemate => format!("Broke off after {} rounds", self.rounds),
+        };
+        format!("{} (dealt {}, took {} damage)", result, self.damage_dealt, self.damage_taken)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombatSystem {
+    max_rounds: u32,
+}
+
+#[allow(dead_code)]
+impl CombatSystem {
+    pub fn new() -> Self {
+        CombatSystem {
+            max_rounds: 10,
+        }
+    }
+
+    pub fn with_max_rounds(max_rounds: u32) -> Self {
+        CombatSystem {
+            max_rounds: max_rounds.max(1),
+        }
+    }
+
+    // Chance for an attack to land, from the attacker's attack against the
+    // defender's defense plus a flat accuracy bonus
+    fn hit_chance(attack: u32, defense: u32, accuracy_bonus: f32) -> f32 {
+        let edge = attack as f32 - defense as f32;
+        (0.6 + edge * 0.02 + accuracy_bonus).max(0.1).min(0.95)
+    }
+
+    fn roll_damage<R: Rng + ?Sized>(attack: u32, defense: u32, rng: &mut R) -> u32 {
+        let base = attack.saturating_sub(defense / 2).max(1);
+        base + rng.gen_range(0..=base / 2)
+    }
+
+    // Fight `npc` round by round. With `CombatAction::Flee` the player tries
+    // to break away each round instead of shooting, taking fire while they do.
+    pub fn resolve<R: Rng + ?Sized>(
+        &self,
+        player: &mut Player,
+        npc: &NpcShip,
+        action: CombatAction,
+        rng: &mut R,
+        clock: &GameClock,
+    ) -> CombatResult {
+        let combat_level = player.skills.get_combat_level();
+        let attack = player.ship.attack_rating();
+        let defense = player.ship.defense_rating();
+        let accuracy = combat_level as f32 * 0.03;
+        let flee_chance = (player.ship.speed as f32 / 200.0
+            + player.ship.crew_bonus(&CrewRole::Navigator)).min(0.9);
+        let starting_hull = player.ship.hull;
+
+        let mut npc_hull = npc.hull;
+        let mut damage_dealt = 0;
+        let mut rounds = 0;
+        let mut outcome = CombatOutcome::Stalemate;
+
+        while rounds < self.max_rounds {
+            rounds += 1;
+
+            match action {
+                CombatAction::Flee => {
+                    if rng.gen::<f32>() < flee_chance {
+                        outcome = CombatOutcome::Fled;
+                        break;
+                    }
+                },
+                CombatAction::Fight => {
+                    if rng.gen::<f32>() < Self::hit_chance(attack, npc.defense, accuracy) {
+                        let damage = Self::roll_damage(attack, npc.defense, rng).min(npc_hull);
+                        npc_hull -= damage;
+                        damage_dealt += damage;
+                    }
+                    if npc_hull == 0 {
+                        outcome = CombatOutcome::Victory;
+                        break;
+                    }
+                },
+            }
+
+            // The NPC returns fire
+            if rng.gen::<f32>() < Self::hit_chance(npc.attack, defense, 0.0) {
+                let damage = Self::roll_damage(npc.attack, defense, rng);
+                if player.ship.take_damage(damage) {
+                    outcome = CombatOutcome::Defeat;
+                    break;
+                }
+            }
+        }
+
+        let damage_taken = starting_hull - player.ship.hull;
+        let (bounty, experience_gained, reputation_change) = match outcome {
+            CombatOutcome::Victory => (
+                npc.bounty,
+                20 + rounds * 5,
+                npc.wanted_by.clone().map(|faction| (faction, 5)),
+            ),
+            CombatOutcome::Defeat => (0, 5, None),
+            CombatOutcome::Fled => (0, 2, None),
+            CombatOutcome::Stalemate => (0, rounds * 2, None),
+        };
+
+        let _ = player.apply_transaction(bounty as i64, TransactionCategory::Bounty, &format!("Bounty for {}", npc.name), clock);
+        player.skills.gain_combat_experience(experience_gained);
+        if let Some((faction, delta)) = &reputation_change {
+            player.adjust_reputation(faction, *delta);
+        }
+
+        CombatResult {
+            outcome,
+            rounds,
+            damage_dealt,
+            damage_taken,
+            bounty,
+            experience_gained,
+            reputation_change,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::models::ship::{ModuleType, ShipModule};
+
+    fn weak_pirate() -> NpcShip {
+        NpcShip {
+            name: "Test Pirate".to_string(),
+            attack: 2,
+            defense: 0,
+            hull: 20,
+            bounty: 300,
+            wanted_by: Some(FactionType::Military),
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_fight() {
+        let combat = CombatSystem::new();
+        let npc = NpcShip::pirate(3, &mut StdRng::seed_from_u64(1));
+
+        let mut first = Player::new("A");
+        let mut second = Player::new("B");
+        let a = combat.resolve(&mut first, &npc, CombatAction::Fight, &mut StdRng::seed_from_u64(42), &GameClock::default());
+        let b = combat.resolve(&mut second, &npc, CombatAction::Fight, &mut StdRng::seed_from_u64(42), &GameClock::default());
+
+        assert_eq!(a.outcome, b.outcome);
+        assert_eq!(a.rounds, b.rounds);
+        assert_eq!(a.damage_dealt, b.damage_dealt);
+        assert_eq!(first.ship.hull, second.ship.hull);
+    }
+
+    #[test]
+    fn test_victory_pays_bounty_and_rewards() {
+        let mut player = Player::new("Test");
+        player.ship.install_module(ShipModule::new("Railgun", ModuleType::Weapon, 20)).unwrap();
+        let credits = player.credits;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let result = CombatSystem::new().resolve(&mut player, &weak_pirate(), CombatAction::Fight, &mut rng, &GameClock::default());
+
+        assert_eq!(result.outcome, CombatOutcome::Victory);
+        assert_eq!(player.credits, credits + 300);
+        assert_eq!(player.get_reputation(&FactionType::Military), 5);
+        assert!(player.skills.get_skill(&crate::models::skills::SkillCategory::Combat).unwrap().points > 0);
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use rand::Rng;
 use uuid::Uuid;
 
-use crate::models::item::{Inventory, Item, ItemType, ResourceType};
+use crate::models::item::{Inventory, Item, ItemAttributes, ItemType, Rarity, ResourceType};
+use crate::models::ship::ModuleType;
 
 /// Represents a recipe/blueprint for crafting an item
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -31,6 +33,60 @@ pub enum CraftingQuality {
     Masterwork,
 }
 
+impl CraftingQuality {
+    pub fn to_string(&self) -> String {
+        match self {
+            CraftingQuality::Poor => "Poor".to_string(),
+            CraftingQuality::Standard => "Standard".to_string(),
+            CraftingQuality::Fine => "Fine".to_string(),
+            CraftingQuality::Superior => "Superior".to_string(),
+            CraftingQuality::Exceptional => "Exceptional".to_string(),
+            CraftingQuality::Masterwork => "Masterwork".to_string(),
+        }
+    }
+    
+    /// Rarity grade an item crafted at this quality is sold as
+    pub fn rarity(&self) -> Rarity {
+        match self {
+            CraftingQuality::Poor | CraftingQuality::Standard => Rarity::Common,
+            CraftingQuality::Fine => Rarity::Uncommon,
+            CraftingQuality::Superior => Rarity::Rare,
+            CraftingQuality::Exceptional => Rarity::Epic,
+            CraftingQuality::Masterwork => Rarity::Legendary,
+        }
+    }
+    
+    /// Scale applied to a crafted item's module rating, durability and charges
+    pub fn attribute_multiplier(&self) -> f32 {
+        match self {
+            CraftingQuality::Poor => 0.8,
+            CraftingQuality::Standard => 1.0,
+            CraftingQuality::Fine => 1.1,
+            CraftingQuality::Superior => 1.25,
+            CraftingQuality::Exceptional => 1.5,
+            CraftingQuality::Masterwork => 2.0,
+        }
+    }
+    
+    /// Turn a recipe's standard output into one made at this quality.
+    /// Better work is a rarer grade of the item, which also sets its value;
+    /// poor work keeps the common grade but is worth less.
+    pub fn apply(&self, item: Item) -> Item {
+        let mut item = item.with_rarity(self.rarity());
+        if *self == CraftingQuality::Poor {
+            item.value = (item.value as f32 * 0.7) as u32;
+        }
+        
+        let scale = |amount: u32| ((amount as f32 * self.attribute_multiplier()).round() as u32).max(1);
+        if let Some(module) = item.attributes.module.as_mut() {
+            module.rating = scale(module.rating);
+        }
+        item.attributes.durability = item.attributes.durability.map(scale);
+        item.attributes.charges = item.attributes.charges.map(scale);
+        item
+    }
+}
+
 /// Tracks a crafting job in progress
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CraftingJob {
@@ -42,6 +98,26 @@ pub struct CraftingJob {
     pub quality_bonus: f32,
     pub is_completed: bool,
     pub input_items: Vec<(String, u32)>, // Materials committed
+    /// Rolled when the job is started; older jobs roll on collection
+    #[serde(default)]
+    pub quality: Option<CraftingQuality>,
+}
+
+/// Identifies a blueprint across saves. Derived from the blueprint's name,
+/// see `blueprint_id`.
+pub type BlueprintId = String;
+
+/// Cost of one attempt at the Blueprint Research service
+pub const RESEARCH_COST: u32 = 2000;
+/// Game hours one research attempt takes
+pub const RESEARCH_HOURS: u64 = 8;
+
+/// The stable id for a blueprint called `name`, e.g. "standard-fuel"
+pub fn blueprint_id(name: &str) -> BlueprintId {
+    name.split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
 /// Blueprint for crafting items
@@ -89,6 +165,24 @@ impl CraftingSystem {
         }
     }
     
+    /// Recipes that take `item_name` as an input, sorted by name
+    pub fn recipes_using(&self, item_name: &str) -> Vec<&CraftingRecipe> {
+        let mut recipes: Vec<&CraftingRecipe> = self.recipes.values()
+            .filter(|recipe| recipe.input_items.iter().any(|(name, _)| name == item_name))
+            .collect();
+        recipes.sort_by(|a, b| a.name.cmp(&b.name));
+        recipes
+    }
+    
+    /// Recipes whose output is `item_name`, sorted by name
+    pub fn recipes_producing(&self, item_name: &str) -> Vec<&CraftingRecipe> {
+        let mut recipes: Vec<&CraftingRecipe> = self.recipes.values()
+            .filter(|recipe| recipe.output_item.name == item_name)
+            .collect();
+        recipes.sort_by(|a, b| a.name.cmp(&b.name));
+        recipes
+    }
+    
     /// Register a new crafting recipe
     pub fn register_recipe(&mut self, recipe: CraftingRecipe) -> String {
         let id = recipe.id.clone();
@@ -244,6 +338,7 @@ impl CraftingSystem {
             quality_bonus,
             is_completed: false,
             input_items: recipe.input_items.clone(),
+            quality: None,
         };
         
         self.active_jobs.insert(job_id.clone(), job);
@@ -268,8 +363,21 @@ impl CraftingSystem {
         Some(is_complete)
     }
     
-    /// Craft an item from a blueprint
-    pub fn craft_item(&mut self, player: &mut crate::models::player::Player, blueprint_idx: usize) -> Result<String, String> {
+    /// Craft an item from a blueprint, starting the job at `current_time`
+    /// in game seconds. Returns the job id and the quality the job will
+    /// turn out at.
+    pub fn craft_item(&mut self, player: &mut crate::models::player::Player, blueprint_idx: usize, current_time: u64) -> Result<(String, CraftingQuality), String> {
+        self.craft_item_with_rng(player, blueprint_idx, current_time, &mut rand::thread_rng())
+    }
+    
+    /// `craft_item` with the quality roll drawn from `rng`
+    pub fn craft_item_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        player: &mut crate::models::player::Player,
+        blueprint_idx: usize,
+        current_time: u64,
+        rng: &mut R,
+    ) -> Result<(String, CraftingQuality), String> {
         if blueprint_idx >= self.blueprints.len() {
             return Err("Invalid blueprint index".to_string());
         }
@@ -282,7 +390,7 @@ impl CraftingSystem {
             return Err("You haven't discovered this blueprint yet".to_string());
         }
         
-        if !blueprint_clone.learned {
+        if !player.knows_blueprint(&blueprint_clone.id) {
             return Err("You haven't learned how to craft this item yet".to_string());
         }
         
@@ -293,10 +401,9 @@ impl CraftingSystem {
             None => return Err("Recipe not found for this blueprint".to_string()),
         };
         
-        // Check if player knows the recipe
-        if !self.player_knows_recipe(&player.id, &recipe_id) {
-            return Err("You don't know this recipe".to_string());
-        }
+        // The player's blueprints are what they know; keep the recipe
+        // book used by crafting jobs in step with them
+        self.learn_recipe(&player.id, &recipe_id);
         
         // Check if player has required materials
         for (item_name, required_qty) in &recipe_clone.input_items {
@@ -334,12 +441,6 @@ impl CraftingSystem {
         // Start crafting job - determine appropriate facility types
         let available_facilities = vec!["Basic Crafting".to_string()]; // Placeholder - should come from player location
         
-        // Get current time
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(std::time::Duration::from_secs(0))
-            .as_secs();
-            
         // Convert skill set to map for compatibility
         let skills_map = convert_skillset_to_map(&player.skills);
         
@@ -358,8 +459,17 @@ impl CraftingSystem {
             player.inventory.remove_item(item_name, *required_qty);
         }
         
-        // Return the job ID
-        Ok(result.0)
+        // Roll the quality now, with the crafter's Engineering skill on top
+        // of any margin over the recipe's requirements
+        let engineering_bonus = player.skills
+            .get_skill(&crate::models::skills::SkillCategory::Engineering)
+            .map_or(0.0, |skill| skill.get_quality_bonus());
+        let job = self.active_jobs.get_mut(&result.0).expect("job was just started");
+        job.quality_bonus += engineering_bonus;
+        let quality = Self::roll_quality(job.quality_bonus, rng);
+        job.quality = Some(quality);
+        
+        Ok((result.0, quality))
     }
     
     /// Collect a completed crafting job
@@ -395,23 +505,11 @@ impl CraftingSystem {
         // Remove the job
         self.active_jobs.remove(job_id);
         
-        // Calculate job quality
-        let quality = self.calculate_job_quality(&job, recipe);
+        // Use the quality rolled at the start, if any
+        let quality = job.quality.unwrap_or_else(|| self.calculate_job_quality(&job, recipe));
         
         // Apply quality bonuses to the item
-        let mut output_item = recipe.output_item.clone();
-        
-        // Adjust item value based on quality
-        let quality_multiplier = match quality {
-            CraftingQuality::Poor => 0.7,
-            CraftingQuality::Standard => 1.0,
-            CraftingQuality::Fine => 1.2,
-            CraftingQuality::Superior => 1.5,
-            CraftingQuality::Exceptional => 2.0,
-            CraftingQuality::Masterwork => 3.0,
-        };
-        
-        output_item.value = (output_item.value as f32 * quality_multiplier) as u32;
+        let output_item = quality.apply(recipe.output_item.clone());
         
         // Return the crafted item and quantity
         Ok((output_item, recipe.output_quantity, quality))
@@ -419,6 +517,11 @@ impl CraftingSystem {
     
     /// Calculate the quality of a crafting job
     fn calculate_job_quality(&self, job: &CraftingJob, _recipe: &CraftingRecipe) -> CraftingQuality {
+        Self::roll_quality(job.quality_bonus, &mut rand::thread_rng())
+    }
+    
+    /// Roll a quality level, shifted towards better results by `quality_bonus`
+    fn roll_quality<R: Rng + ?Sized>(quality_bonus: f32, rng: &mut R) -> CraftingQuality {
         // Base chance for quality levels
         let base_chances = [
             (CraftingQuality::Poor, 0.1),
@@ -433,17 +536,17 @@ impl CraftingSystem {
         let mut adjusted_chances = base_chances.clone();
         
         // Apply skill quality bonus - reduces chance of poor, increases chance of better results
-        if job.quality_bonus > 0.0 {
+        if quality_bonus > 0.0 {
             // Reduce poor chance
-            adjusted_chances[0].1 = (adjusted_chances[0].1 - job.quality_bonus * 0.1).max(0.01);
+            adjusted_chances[0].1 = (adjusted_chances[0].1 - quality_bonus * 0.1).max(0.01);
             
             // Reduce standard chance if bonus is high enough
-            if job.quality_bonus > 0.2 {
-                adjusted_chances[1].1 = (adjusted_chances[1].1 - (job.quality_bonus - 0.2) * 0.1).max(0.2);
+            if quality_bonus > 0.2 {
+                adjusted_chances[1].1 = (adjusted_chances[1].1 - (quality_bonus - 0.2) * 0.1).max(0.2);
             }
             
             // Redistribute to higher qualities
-            let reduced = 0.1 + (job.quality_bonus - 0.2).max(0.0) * 0.1;
+            let reduced = 0.1 + (quality_bonus - 0.2).max(0.0) * 0.1;
             let to_distribute = reduced / 4.0; // Distribute to Fine, Superior, Exceptional, Masterwork
             
             adjusted_chances[2].1 += to_distribute;
@@ -453,7 +556,7 @@ impl CraftingSystem {
         }
         
         // Roll for quality
-        let roll = rand::random::<f32>();
+        let roll = rng.gen::<f32>();
         let mut cumulative = 0.0;
         
         for (quality, chance) in adjusted_chances.iter() {
@@ -545,7 +648,7 @@ impl CraftingSystem {
             2000, 
             10, 
             ItemType::ShipModule
-        );
+        ).with_attributes(ItemAttributes::module(ModuleType::Shield, 10).with_durability(100));
         
         let recipe = Self::create_recipe(
             "Basic Shield Generator", 
@@ -581,6 +684,50 @@ impl CraftingSystem {
         completed_jobs
     }
     
+    /// Finish any of the player's jobs that are done by `current_time` and
+    /// move the products into their cargo hold. Jobs whose output doesn't fit
+    /// stay completed until there is room. Returns a line per delivered job.
+    pub fn deliver_completed_jobs(&mut self, player: &mut crate::models::player::Player, current_time: u64) -> Vec<String> {
+        let mut finished: Vec<(String, u64)> = self.active_jobs.iter()
+            .filter(|(_, job)| job.player_id == player.id && (job.is_completed || current_time >= job.end_time))
+            .map(|(job_id, job)| (job_id.clone(), job.end_time))
+            .collect();
+        finished.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+        
+        let mut delivered = Vec::new();
+        for (job_id, _) in finished {
+            if let Some(job) = self.active_jobs.get_mut(&job_id) {
+                job.is_completed = true;
+            }
+            
+            let fits = self.active_jobs.get(&job_id)
+                .and_then(|job| self.recipes.get(&job.recipe_id))
+                .map_or(false, |recipe| {
+                    let weight = recipe.output_item.weight * recipe.output_quantity;
+                    player.inventory.used_capacity() + weight <= player.inventory.capacity
+                });
+            if !fits {
+                continue;
+            }
+            
+            if let Ok((item, quantity, quality)) = self.collect_completed_job(&job_id, &player.id, current_time) {
+                let name = item.display_name();
+                player.inventory.add_item(item, quantity);
+                player.stats.items_crafted += quantity as u64;
+                delivered.push(format!("{} {} ({} quality)", quantity, name, quality.to_string()));
+            }
+        }
+        
+        delivered
+    }
+    
+    /// Whether any of the player's jobs has finished by `current_time`
+    /// and is still waiting to be delivered
+    pub fn has_finished_jobs(&self, player_id: &str, current_time: u64) -> bool {
+        self.active_jobs.values()
+            .any(|job| job.player_id == player_id && (job.is_completed || current_time >= job.end_time))
+    }
+    
     /// Get available recipes for a player that match item type and skill requirements
     pub fn find_available_recipes(
         &self,
@@ -669,7 +816,7 @@ impl CraftingSystem {
         }
         
         let blueprint = Blueprint {
-            id: Uuid::new_v4().to_string(),
+            id: blueprint_id(name),
             name: name.to_string(),
             description: description.to_string(),
             recipe_id: recipe_id.to_string(),
@@ -685,6 +832,19 @@ impl CraftingSystem {
         Ok(id)
     }
     
+    /// Inputs of the blueprint at `blueprint_idx` as (item, needed, have),
+    /// counting what's in `inventory`. Empty if there's no such blueprint.
+    pub fn blueprint_requirements(&self, blueprint_idx: usize, inventory: &Inventory) -> Vec<(String, u32, u32)> {
+        self.blueprints.get(blueprint_idx)
+            .and_then(|blueprint| self.recipes.get(&blueprint.recipe_id))
+            .map(|recipe| {
+                recipe.input_items.iter()
+                    .map(|(name, needed)| (name.clone(), *needed, inventory.get_item_quantity(name)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    
     /// Get all available blueprints
     pub fn get_available_blueprints(&self) -> Vec<&Blueprint> {
         self.blueprints.iter().filter(|bp| bp.discovered).collect()
@@ -741,6 +901,62 @@ impl CraftingSystem {
         self.learn_recipe(player_id, &recipe_id)
     }
     
+    /// Chance that one research attempt turns up a new blueprint. Each
+    /// Research skill level adds 5%, and the Scientific Academy's training
+    /// adds 15% more.
+    pub fn research_chance(player: &crate::models::player::Player) -> f32 {
+        let research_level = player.skills
+            .get_skill(&crate::models::skills::SkillCategory::Research)
+            .map_or(0, |skill| skill.level);
+        let faction_bonus = match player.character.faction {
+            crate::models::faction::FactionType::Scientists => 0.15,
+            _ => 0.0,
+        };
+        (0.2 + research_level as f32 * 0.05 + faction_bonus).min(0.9)
+    }
+    
+    /// Discovered blueprints the player doesn't know yet
+    pub fn unknown_blueprints(&self, player: &crate::models::player::Player) -> Vec<&Blueprint> {
+        self.blueprints.iter()
+            .filter(|bp| bp.discovered && !player.knows_blueprint(&bp.id))
+            .collect()
+    }
+    
+    /// Make one research attempt. On success the player learns one of the
+    /// blueprints they don't know, commoner ones being likelier, and its
+    /// name is returned. A failed attempt returns `Ok(None)`.
+    pub fn research_blueprint<R: Rng + ?Sized>(
+        &self,
+        player: &mut crate::models::player::Player,
+        rng: &mut R,
+    ) -> Result<Option<String>, String> {
+        let candidates = self.unknown_blueprints(player);
+        if candidates.is_empty() {
+            return Err("There are no blueprints left for you to research".to_string());
+        }
+        
+        if rng.gen::<f32>() >= Self::research_chance(player) {
+            return Ok(None);
+        }
+        
+        let weight = |bp: &Blueprint| match bp.rarity {
+            BlueprintRarity::Common => 6,
+            BlueprintRarity::Uncommon => 4,
+            BlueprintRarity::Rare => 3,
+            BlueprintRarity::VeryRare => 2,
+            BlueprintRarity::Exceptional | BlueprintRarity::Legendary => 1,
+        };
+        let mut roll = rng.gen_range(0..candidates.iter().map(|bp| weight(bp)).sum::<u32>());
+        for blueprint in candidates {
+            if roll < weight(blueprint) {
+                player.learn_blueprint(&blueprint.id);
+                return Ok(Some(blueprint.name.clone()));
+            }
+            roll -= weight(blueprint);
+        }
+        unreachable!("roll is below the total weight")
+    }
+    
     /// Initialize test blueprints
     pub fn setup_basic_blueprints(&mut self) {
         // First, make sure we have recipes
@@ -748,8 +964,9 @@ impl CraftingSystem {
             self.setup_basic_recipes();
         }
         
-        // Collect recipe data first to avoid borrowing conflicts
-        let recipe_data: Vec<(String, String, String, u32)> = self.recipes
+        // Collect recipe data first to avoid borrowing conflicts. Sorted so
+        // the blueprint list keeps the same order from game to game.
+        let mut recipe_data: Vec<(String, String, String, u32)> = self.recipes
             .iter()
             .map(|(id, recipe)| (
                 id.clone(),
@@ -758,6 +975,7 @@ impl CraftingSystem {
                 recipe.difficulty
             ))
             .collect();
+        recipe_data.sort_by(|a, b| a.1.cmp(&b.1));
         
         // Now add blueprints using the collected data
         for (recipe_id, name, description, difficulty) in recipe_data {
@@ -878,4 +1096,76 @@ fn convert_skillset_to_map(skills: &crate::models::skills::SkillSet) -> HashMap<
     }
     
     skill_map
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::ItemType;
+    use crate::models::player::Player;
+
+    #[test]
+    fn test_jobs_deliver_once_game_time_reaches_end() {
+        let mut crafting = CraftingSystem::new();
+        let mut player = Player::new("Test");
+        let recipe = CraftingSystem::create_recipe(
+            "Plating", "", Vec::new(), Item::new("Hull Plate", 100, 1, ItemType::Component), 2, 600,
+        );
+        let recipe_id = crafting.register_recipe(recipe);
+        crafting.learn_recipe(&player.id, &recipe_id);
+        crafting.start_crafting_job(&player.id, &recipe_id, 1000, &HashMap::new(), &[]).unwrap();
+
+        assert!(crafting.deliver_completed_jobs(&mut player, 1599).is_empty());
+        assert_eq!(crafting.deliver_completed_jobs(&mut player, 1600).len(), 1);
+        assert_eq!(player.inventory.get_item_quantity("Hull Plate"), 2);
+        assert!(crafting.active_jobs.is_empty());
+    }
+
+    #[test]
+    fn test_only_known_blueprints_craft_and_research_learns_new_ones() {
+        use crate::models::faction::FactionType;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut crafting = CraftingSystem::new();
+        crafting.setup_basic_blueprints();
+        let mut player = Player::new("Test");
+        player.character.faction = FactionType::Scientists;
+        player.learn_blueprint(&blueprint_id("Standard Fuel"));
+        player.skills.get_skill_mut(&crate::models::skills::SkillCategory::Engineering).unwrap().level = 1;
+        player.inventory.add_item(Item::new("Hydrogen", 10, 1, ItemType::Fuel), 10);
+        player.inventory.add_item(Item::new("Oxygen", 10, 1, ItemType::Fuel), 10);
+
+        let fuel = crafting.blueprints.iter().position(|bp| bp.id == "standard-fuel").unwrap();
+        let alloy = crafting.blueprints.iter().position(|bp| bp.id == "basic-mineral-alloy").unwrap();
+        assert!(crafting.craft_item(&mut player, alloy, 0).is_err());
+        crafting.craft_item(&mut player, fuel, 0).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(7);
+        while player.known_blueprints.len() < crafting.blueprints.len() {
+            crafting.research_blueprint(&mut player, &mut rng).unwrap();
+        }
+        assert!(crafting.research_blueprint(&mut player, &mut rng).is_err());
+        assert!(player.knows_blueprint("basic-mineral-alloy"));
+    }
+
+    #[test]
+    fn test_quality_sets_rarity_and_scales_attributes() {
+        let generator = Item::new("Shield Generator", 2000, 10, ItemType::ShipModule)
+            .with_attributes(ItemAttributes::module(ModuleType::Shield, 10).with_durability(100));
+
+        let standard = CraftingQuality::Standard.apply(generator.clone());
+        assert_eq!(standard.rarity, Rarity::Common);
+        assert_eq!(standard.value, 2000);
+
+        let superior = CraftingQuality::Superior.apply(generator.clone());
+        assert_eq!(superior.rarity, Rarity::Rare);
+        assert!(superior.value > standard.value);
+        assert_eq!(superior.attributes.module.as_ref().unwrap().rating, 13);
+        assert_eq!(superior.attributes.durability, Some(125));
+
+        let poor = CraftingQuality::Poor.apply(generator);
+        assert_eq!(poor.rarity, Rarity::Common);
+        assert!(poor.value < standard.value);
+        assert_eq!(poor.attributes.module.unwrap().rating, 8);
+    }
+}
@@ -1,12 +1,252 @@
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use uuid::Uuid;
 
 use crate::models::item::{Item, ItemType, ResourceType};
-use crate::models::market::{Market, MarketItem, MarketType, EconomicEvent, TradeOrder, OrderStatus, OrderType};
+use crate::models::market::{self, Market, MarketItem, MarketType, EconomicEvent, TradeOrder, OrderKind, OrderStatus, OrderType};
+use crate::models::universe::{StarSystem, Universe};
+use crate::models::market_definition::MarketDefinition;
+use crate::models::news::{MarketNews, NEWS_HISTORY};
+use crate::models::player::Player;
 use crate::models::player_market::{PlayerMarket, PlayerMarketListing, MarketBid, MarketContract};
+use crate::systems::time::TimeSystem;
+use crate::systems::trading::{validate_order_terms, default_max_orders_per_player};
+use crate::utils::clock::GameClock;
+use crate::utils::serde::pair_key_map_serde;
+
+/// Share of an export good's stock shipped along the production chain each update
+#[allow(dead_code)]
+const CHAIN_EXPORT_SHARE: f32 = 0.1;
+/// Resource units an industrial market uses up per component made
+#[allow(dead_code)]
+const RESOURCES_PER_COMPONENT: u32 = 2;
+/// Most components of each kind an industrial market makes per update
+#[allow(dead_code)]
+const MANUFACTURE_BATCH: u32 = 10;
+/// Updates between the batched catch-ups of dormant markets
+#[allow(dead_code)]
+const DORMANT_BATCH_STEPS: u64 = 24;
+/// Most a price can move in one update, either way
+#[allow(dead_code)]
+const MAX_PRICE_CHANGE_PER_STEP: f32 = 0.2;
+
+/// NPC market maker that keeps every system market's core commodities
+/// tradeable. It tops each up toward a target level on every update, and
+/// sells whatever a buyer wants past the market's stock at its own, wider
+/// spread, so players can always buy, at a worse price.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MarketMaker {
+    /// Units of each core commodity it keeps a market stocked toward
+    pub target_stock: u32,
+    /// Share of the shortfall it makes up each update
+    pub restock_rate: f32,
+    /// Spread around the mid price it sells at once the market's stock is
+    /// gone; never better than the market's own ask
+    pub spread: f32,
+}
+
+impl Default for MarketMaker {
+    fn default() -> Self {
+        MarketMaker {
+            target_stock: 100,
+            restock_rate: 0.25,
+            spread: 0.3,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl MarketMaker {
+    /// Goods it makes a market in: raw resources and fuel
+    pub fn trades(item_type: &ItemType) -> bool {
+        market::is_core_commodity(item_type)
+    }
+    
+    /// Top `item` up toward the target stock, always by at least a unit
+    /// while it is short
+    pub fn restock(&self, item: &mut MarketItem) {
+        if !Self::trades(&item.item.item_type) || item.quantity >= self.target_stock {
+            return;
+        }
+        let shortfall = self.target_stock - item.quantity;
+        let delivery = (shortfall as f32 * self.restock_rate.clamp(0.0, 1.0)).ceil() as u32;
+        item.quantity += delivery.clamp(1, shortfall);
+    }
+    
+    /// Restock `item` as `steps` updates in a row would. Each one delivers
+    /// at least a unit, so this stops within `target_stock` steps.
+    pub fn restock_steps(&self, item: &mut MarketItem, steps: u64) {
+        for _ in 0..steps.min(self.target_stock as u64) {
+            if item.quantity >= self.target_stock {
+                break;
+            }
+            self.restock(item);
+        }
+    }
+    
+    /// Stand behind `market`: restocking happens in the market updates,
+    /// and from now on buyers can take more than the market holds
+    pub fn serve(&self, market: &mut Market) {
+        market.maker_spread = Some(self.spread);
+    }
+}
+
+/// Goods a market type sends out along the production chain
+#[allow(dead_code)]
+fn exports(market_type: &MarketType, item_type: &ItemType) -> bool {
+    match market_type {
+        MarketType::Mining => matches!(item_type, ItemType::Resource(_)),
+        MarketType::Industrial => *item_type == ItemType::Component,
+        _ => false,
+    }
+}
+
+/// Goods a market type takes in from the production chain
+#[allow(dead_code)]
+fn imports(market_type: &MarketType, item_type: &ItemType) -> bool {
+    match market_type {
+        MarketType::Mining => *item_type == ItemType::Component,
+        MarketType::Industrial => matches!(item_type, ItemType::Resource(_)),
+        _ => false,
+    }
+}
+
+/// Supply level implied by an item's stock against its production rate
+#[allow(dead_code)]
+fn supply_level_for(item: &MarketItem) -> f32 {
+    if item.quantity == 0 {
+        0.1 // Critical shortage
+    } else if item.quantity < item.production_rate * 2 {
+        0.5 // Low supply
+    } else if item.quantity > item.production_rate * 10 {
+        1.5 // Surplus
+    } else {
+        1.0 // Normal supply
+    }
+}
+
+/// Turn resources in an industrial market's stock into each component it
+/// trades, drawing on whichever resource it holds most of
+#[allow(dead_code)]
+fn manufacture_components(market: &mut Market) {
+    let mut components: Vec<String> = market.items.iter()
+        .filter(|(_, item)| item.item.item_type == ItemType::Component)
+        .map(|(name, _)| name.clone())
+        .collect();
+    components.sort();
+    
+    for component in components {
+        let resource = market.items.iter()
+            .filter(|(_, item)| matches!(item.item.item_type, ItemType::Resource(_)))
+            .max_by(|a, b| a.1.quantity.cmp(&b.1.quantity).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, item)| (name.clone(), item.quantity));
+        let (resource, stock) = match resource {
+            Some(resource) => resource,
+            None => return,
+        };
+        
+        let made = (stock / RESOURCES_PER_COMPONENT).min(MANUFACTURE_BATCH);
+        if made == 0 {
+            return;
+        }
+        if let Some(item) = market.items.get_mut(&resource) {
+            item.quantity -= made * RESOURCES_PER_COMPONENT;
+            item.supply_level = supply_level_for(item);
+        }
+        if let Some(item) = market.items.get_mut(&component) {
+            item.quantity += made;
+            item.supply_level = supply_level_for(item);
+        }
+    }
+}
+
+/// One-line summary of what a system trades in, e.g.
+/// "Mining Facility - cheap ore, buys components". A market that was given
+/// a type of its own keeps it; a generic trading market takes the type most
+/// of the system's stations run.
+pub fn system_specialization(system: &StarSystem, market: Option<&Market>) -> String {
+    // Most common station type; ties go to the station listed first
+    let station_type = system.stations.iter().rev()
+        .map(|station| &station.station_type)
+        .max_by_key(|station_type| system.stations.iter().filter(|other| other.station_type == **station_type).count());
+
+    match (station_type, market) {
+        (None, None) => "no facilities".to_string(),
+        (Some(station_type), None) => format!("{} - no market", station_type.to_string()),
+        (None, Some(market)) => market.market_type.specialty().to_string(),
+        (Some(station_type), Some(market)) => {
+            let market_type = match market.market_type {
+                MarketType::Trading => station_type.market_type(),
+                ref specialized => specialized.clone(),
+            };
+            format!("{} - {}", station_type.to_string(), market_type.specialty())
+        },
+    }
+}
+
+/// Quote a CSV field if it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Price movement of one item in one market over a simulation run
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceChange {
+    pub system_id: String,
+    pub item_name: String,
+    pub old_price: u32,
+    pub new_price: u32,
+}
+
+#[allow(dead_code)]
+impl PriceChange {
+    /// Relative change in percent (positive = price rose)
+    pub fn percent_change(&self) -> f32 {
+        if self.old_price == 0 {
+            return 0.0;
+        }
+        (self.new_price as f32 - self.old_price as f32) / self.old_price as f32 * 100.0
+    }
+}
+
+/// What changed while running the economy forward with `EconomySystem::tick`
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct TickSummary {
+    pub ticks: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub price_changes: Vec<PriceChange>, // Only items whose price moved
+    pub executed_orders: Vec<TradeOrder>, // Filled and settled with their owners
+}
+
+#[allow(dead_code)]
+impl TickSummary {
+    pub fn price_change(&self, system_id: &str, item_name: &str) -> Option<&PriceChange> {
+        self.price_changes.iter()
+            .find(|change| change.system_id == system_id && change.item_name == item_name)
+    }
+}
+
+/// Starting RNG for a deserialized economy, replaced by `restore_rng`
+fn unseeded_rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
 
 /// System responsible for global economic simulation and market dynamics
+#[derive(Serialize, Deserialize)]
 pub struct EconomySystem {
     // Maps system_id to system market
     pub system_markets: HashMap<String, Market>,
@@ -23,24 +263,59 @@ pub struct EconomySystem {
     // Random events and their probabilities
     pub random_events: Vec<(EconomicEvent, f32)>,
     
+    /// Events that have broken, oldest first. A market's local events last
+    /// as long as the report that brought them.
+    #[serde(default)]
+    pub news: Vec<MarketNews>,
+    
     // Tax policies and tariffs
     pub base_tax_rate: f32,
     pub faction_tax_rates: HashMap<String, f32>,
+    #[serde(with = "pair_key_map_serde")]
     pub trade_route_tariffs: HashMap<(String, String), f32>, // Tariffs between systems
     
+    /// Keeps core commodities in stock in every market
+    #[serde(default)]
+    pub market_maker: MarketMaker,
+    
+    /// Jump lanes between systems; production chains only ship along these.
+    /// When empty every market is treated as reachable from every other.
+    #[serde(default)]
+    pub connections: HashMap<String, Vec<String>>,
+    
+    /// When set, only markets in or next to a system a player has been in
+    /// within this many game seconds are fully simulated each update. The
+    /// rest lie dormant, drifting in cheap batches, and are caught up as soon
+    /// as they become active again. Unset, every market is simulated.
+    #[serde(default)]
+    pub dormant_after: Option<u64>,
+    /// Game time a player was last seen in each system
+    #[serde(default)]
+    pub last_active: HashMap<String, u64>,
+    
+    /// Most active trade orders one player may have open across every
+    /// market. Every open order is checked on each market update.
+    #[serde(default = "default_max_orders_per_player")]
+    pub max_orders_per_player: usize,
+    
     // Economy cycle tracking
     pub last_update: u64,
     pub update_interval: u64, // How often to update the economy (in seconds)
     pub simulation_step: u64,  // Economy simulation step counter
+    
+    // Seeded RNG so the economy evolves identically for the same seed
+    rng_seed: u64,
+    #[serde(skip, default = "unseeded_rng")]
+    rng: StdRng,
 }
 
 impl EconomySystem {
     pub fn new() -> Self {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
-            
+        Self::with_seed(12345)  // Same fixed seed as the default universe
+    }
+    
+    /// Create an economy whose random fluctuations are driven by the given seed
+    pub fn with_seed(seed: u64) -> Self {
         EconomySystem {
             system_markets: HashMap::new(),
             player_market: PlayerMarket::new(),
@@ -56,12 +331,94 @@ impl EconomySystem {
                 (EconomicEvent::LocalConflict, 0.03),
                 (EconomicEvent::LocalPeace, 0.03),
             ],
+            news: Vec::new(),
             base_tax_rate: 0.05, // 5% base tax
             faction_tax_rates: HashMap::new(),
             trade_route_tariffs: HashMap::new(),
-            last_update: current_time,
+            market_maker: MarketMaker::default(),
+            connections: HashMap::new(),
+            dormant_after: None,
+            last_active: HashMap::new(),
+            max_orders_per_player: default_max_orders_per_player(),
+            last_update: 0, // Start of game time
             update_interval: 3600, // Update economy every hour
             simulation_step: 0,
+            rng_seed: seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+    
+    /// Seed the economy RNG was created with
+    pub fn get_seed(&self) -> u64 {
+        self.rng_seed
+    }
+    
+    /// Reseed the RNG after loading. The generator's internal state isn't
+    /// saved, so it continues from the seed and the current simulation step
+    /// rather than where it left off.
+    pub fn restore_rng(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.rng_seed ^ self.simulation_step.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    }
+    
+    /// Economy seeded with a copy of every market in the universe
+    pub fn from_universe(universe: &Universe) -> Self {
+        let mut economy = Self::with_seed(universe.get_seed());
+        economy.news = universe.market_news().to_vec();
+        for system_id in universe.get_all_system_ids() {
+            if let Some(system) = universe.get_system(&system_id) {
+                economy.connections.insert(system_id.clone(), system.connections.clone());
+            }
+            if let Some(market) = universe.get_market(&system_id) {
+                economy.system_markets.insert(system_id, market);
+            }
+        }
+        economy
+    }
+    
+    /// Write every item in every market to `path` as CSV, one row per
+    /// (system, item), for analysis outside the game
+    pub fn export_market_csv(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "system,item,current_price,base_price,supply,demand,quantity,trend_percent")?;
+        
+        for system_id in self.sorted_system_ids() {
+            let market = &self.system_markets[&system_id];
+            let mut item_names: Vec<&String> = market.items.keys().collect();
+            item_names.sort();
+            
+            for item_name in item_names {
+                let item = &market.items[item_name];
+                let trend = market.get_price_trend(item_name)
+                    .map(|(percent, _)| format!("{:.2}", percent))
+                    .unwrap_or_default();
+                writeln!(
+                    writer,
+                    "{},{},{},{},{:.2},{:.2},{},{}",
+                    csv_field(&system_id),
+                    csv_field(item_name),
+                    item.current_price,
+                    item.base_price,
+                    item.supply_level,
+                    item.demand_level,
+                    item.quantity,
+                    trend,
+                )?;
+            }
+        }
+        
+        writer.flush()
+    }
+    
+    /// Stock every market with the fixed items the definition sets for its
+    /// system and market type, replacing any existing entries for them
+    pub fn apply_market_definition(&mut self, definition: &MarketDefinition, clock: &GameClock) {
+        for system_id in self.sorted_system_ids() {
+            if let Some(market) = self.system_markets.get_mut(&system_id) {
+                let market_type = market.market_type.clone();
+                for stock in definition.fixed_stock_for(&system_id, &market_type) {
+                    market.add_item(stock.to_item(), stock.quantity, stock.base_price, stock.volatility, clock);
+                }
+            }
         }
     }
     
@@ -85,10 +442,65 @@ impl EconomySystem {
         ].iter().cloned().collect();
     }
     
-    /// Update the entire economy
-    pub fn update(&mut self, current_time: u64) {
+    /// Run the economy forward headlessly, one update interval per tick,
+    /// advancing the given time system as it goes. No UI or networking needed.
+    /// After each update the trade orders of `players` are settled against
+    /// them through `Market::process_orders`.
+    pub fn tick(&mut self, time_system: &mut TimeSystem, ticks: u64, players: &mut [Player]) -> TickSummary {
+        let start_prices = self.price_table();
+        let mut summary = TickSummary {
+            ticks,
+            start_time: time_system.game_time_seconds(),
+            ..TickSummary::default()
+        };
+        
+        for _ in 0..ticks {
+            time_system.advance_game_seconds(self.update_interval);
+            let clock = time_system.clock();
+            self.update(&clock);
+            
+            for system_id in self.sorted_system_ids() {
+                if let Some(market) = self.system_markets.get_mut(&system_id) {
+                    for player in players.iter_mut() {
+                        summary.executed_orders.extend(market.process_orders(player, &clock));
+                    }
+                }
+            }
+        }
+        
+        summary.end_time = time_system.game_time_seconds();
+        
+        for ((system_id, item_name), new_price) in self.price_table() {
+            let old_price = start_prices.get(&(system_id.clone(), item_name.clone()))
+                .copied()
+                .unwrap_or(0);
+            if old_price != new_price {
+                summary.price_changes.push(PriceChange { system_id, item_name, old_price, new_price });
+            }
+        }
+        summary.price_changes.sort_by(|a, b| (&a.system_id, &a.item_name).cmp(&(&b.system_id, &b.item_name)));
+        
+        summary
+    }
+    
+    /// Current price of every item in every market
+    fn price_table(&self) -> HashMap<(String, String), u32> {
+        let mut prices = HashMap::new();
+        for (system_id, market) in &self.system_markets {
+            for (item_name, item) in &market.items {
+                prices.insert((system_id.clone(), item_name.clone()), item.current_price);
+            }
+        }
+        prices
+    }
+    
+    /// Update the entire economy. Player trade orders are left for
+    /// `Market::process_orders` to settle against their owners.
+    pub fn update(&mut self, clock: &GameClock) {
+        let current_time = clock.now();
+        
         // Only update at specified intervals
-        if current_time - self.last_update < self.update_interval {
+        if current_time.saturating_sub(self.last_update) < self.update_interval {
             return;
         }
         
@@ -100,53 +512,154 @@ impl EconomySystem {
         
         // 2. Update each system market
         // Store system IDs first to avoid borrowing issues
-        let system_ids: Vec<String> = self.system_markets.keys().cloned().collect();
+        let system_ids = self.sorted_system_ids();
+        let interval = self.update_interval;
+        let dormant_batch = self.simulation_step % DORMANT_BATCH_STEPS == 0;
         
         for system_id in system_ids {
+            let active = self.is_active(&system_id, current_time);
             if let Some(market) = self.system_markets.get_mut(&system_id) {
+                if !active {
+                    if dormant_batch {
+                        Self::catch_up_market(market, current_time, interval, &self.market_maker);
+                    }
+                    continue;
+                }
+                // A market that just woke up first makes up the updates it
+                // slept through, leaving this one to the full simulation
+                if self.dormant_after.is_some() {
+                    Self::catch_up_market(market, current_time.saturating_sub(interval), interval, &self.market_maker);
+                }
+                
                 // Use a separate helper function that doesn't require &mut self
-                Self::update_market_helper(market, current_time);
+                Self::update_market_helper(market, current_time, &mut self.rng, &self.market_maker);
             }
         }
         
-        // 3. Apply random events
-        self.apply_random_events();
+        // 3. Apply random events and end those that have run their course
+        self.apply_random_events(current_time);
+        self.expire_events(current_time);
         
         // 4. Simulate inter-system trade
         self.simulate_trade_flows();
         
-        // 5. Process player market expirations
-        self.player_market.process_expirations();
+        // 5. Process player market expirations. Closed auctions settle into
+        // the winners' and sellers' market accounts as they close
+        self.player_market.process_expirations(clock);
         
         // 6. Adjust resource scarcity
         self.adjust_resource_scarcity();
     }
     
+    /// Whether `system_id`'s market is fully simulated: always without a
+    /// dormancy window, otherwise only while a player is in or next to the
+    /// system or has been within the window
+    pub fn is_active(&self, system_id: &str, current_time: u64) -> bool {
+        let window = match self.dormant_after {
+            Some(window) => window,
+            None => return true,
+        };
+        let recent = |id: &str| self.last_active.get(id)
+            .map_or(false, |&seen| current_time.saturating_sub(seen) <= window);
+        recent(system_id)
+            || self.connections.get(system_id).map_or(false, |links| links.iter().any(|link| recent(link)))
+    }
+    
+    /// Record a player in `system_id`, first bringing its market up to date
+    /// if it had gone dormant, so the player never sees stale prices
+    pub fn mark_active(&mut self, system_id: &str, clock: &GameClock) {
+        let current_time = clock.now();
+        let interval = self.update_interval;
+        let was_dormant = !self.is_active(system_id, current_time);
+        self.last_active.insert(system_id.to_string(), current_time);
+        if was_dormant {
+            if let Some(market) = self.system_markets.get_mut(system_id) {
+                Self::catch_up_market(market, current_time, interval, &self.market_maker);
+            }
+        }
+    }
+    
+    /// Move a market forward over the updates it missed up to `current_time`
+    /// in one aggregate step: the production, consumption, inflation and
+    /// bounded price movement a full update would apply, and the market
+    /// maker's restocking, without the random swings or events
+    fn catch_up_market(market: &mut Market, current_time: u64, interval: u64, market_maker: &MarketMaker) {
+        market_maker.serve(market);
+        let steps = current_time.saturating_sub(market.last_update) / interval.max(1);
+        if steps == 0 {
+            return;
+        }
+        // Past a few hundred steps the bounds are effectively open anyway
+        let exponent = steps.min(1000) as i32;
+        let inflation = (1.0 + 0.02f32 / 52.0).powi(exponent);
+        let max_down = (1.0 - MAX_PRICE_CHANGE_PER_STEP).powi(exponent);
+        let max_up = (1.0 + MAX_PRICE_CHANGE_PER_STEP).powi(exponent);
+        
+        for item in market.items.values_mut() {
+            let produced = item.production_rate as u64 * steps;
+            let consumed = item.consumption_rate as u64 * steps;
+            item.quantity = (item.quantity as u64 + produced).saturating_sub(consumed).min(u32::MAX as u64) as u32;
+            market_maker.restock_steps(item, steps);
+            item.supply_level = supply_level_for(item);
+            item.base_price = (item.base_price as f32 * inflation) as u32;
+            
+            let target = item.base_price as f32 * (2.0 - item.supply_level) * item.demand_level;
+            let current = item.current_price as f32;
+            item.current_price = target.max(current * max_down).min(current * max_up) as u32;
+            
+            item.price_history.push(crate::models::market::PriceHistory {
+                timestamp: current_time,
+                price: item.current_price,
+            });
+            if item.price_history.len() > 10 {
+                item.price_history.remove(0);
+            }
+        }
+        market.track_trailing_stops();
+        market.last_update = current_time;
+    }
+    
+    /// System IDs in a stable order, so RNG draws don't depend on HashMap ordering
+    fn sorted_system_ids(&self) -> Vec<String> {
+        let mut system_ids: Vec<String> = self.system_markets.keys().cloned().collect();
+        system_ids.sort();
+        system_ids
+    }
+    
     /// Update global economic factors
     fn update_global_factors(&mut self) {
         // Simulate small fluctuations in global trade index
-        let fluctuation = (rand::random::<f32>() - 0.5) * 0.1;
+        let fluctuation = (self.rng.gen::<f32>() - 0.5) * 0.1;
         self.global_trade_index = (self.global_trade_index + fluctuation).max(0.5).min(1.5);
         
         // Adjust inflation rate occasionally
         if self.simulation_step % 10 == 0 {
-            let inflation_change = (rand::random::<f32>() - 0.5) * 0.01;
+            let inflation_change = (self.rng.gen::<f32>() - 0.5) * 0.01;
             self.global_inflation_rate = (self.global_inflation_rate + inflation_change).max(0.0).min(0.1);
         }
     }
     
     /// Update a specific market
     fn update_market(&mut self, market: &mut Market, current_time: u64) {
-        Self::update_market_helper(market, current_time);
+        Self::update_market_helper(market, current_time, &mut self.rng, &self.market_maker)
     }
     
     /// Helper method to update a market without requiring &mut self
-    fn update_market_helper(market: &mut Market, current_time: u64) {
+    fn update_market_helper(market: &mut Market, current_time: u64, rng: &mut StdRng, market_maker: &MarketMaker) {
         // Set last update time
         market.last_update = current_time;
+        market_maker.serve(market);
+        
+        // Update each item's price and quantity, in a stable order for reproducibility
+        let mut item_names: Vec<String> = market.items.keys().cloned().collect();
+        item_names.sort();
         
-        // Update each item's price and quantity
-        for item_entry in market.items.values_mut() {
+        for item_name in &item_names {
+            let item_entry = match market.items.get_mut(item_name) {
+                Some(item_entry) => item_entry,
+                None => continue,
+            };
+            
             // Simulate production
             item_entry.quantity += item_entry.production_rate;
             
@@ -154,6 +667,10 @@ impl EconomySystem {
             let consumption = item_entry.consumption_rate.min(item_entry.quantity);
             item_entry.quantity -= consumption;
             
+            // The market maker tops up what's running short; the restocked
+            // supply also steadies the price below
+            market_maker.restock(item_entry);
+            
             // Apply a standard inflation factor (moved from self.global_inflation_rate)
             let inflation_factor = 1.0 + 0.02 / 52.0; // 2% annual inflation, weekly adjustment
             item_entry.base_price = ((item_entry.base_price as f32) * inflation_factor) as u32;
@@ -206,7 +723,7 @@ impl EconomySystem {
             let demand_factor = item_entry.demand_level;
             
             // Add some random fluctuation based on volatility
-            let random_factor = 1.0 + ((rand::random::<f32>() - 0.5) * item_entry.price_volatility);
+            let random_factor = 1.0 + ((rng.gen::<f32>() - 0.5) * item_entry.price_volatility);
             
             // Calculate new price with all factors
             let new_price = (item_entry.base_price as f32 * 
@@ -215,7 +732,7 @@ impl EconomySystem {
                          random_factor) as u32;
             
             // Ensure price doesn't change too drastically
-            let max_change_pct = 0.2; // Max 20% change from current price
+            let max_change_pct = MAX_PRICE_CHANGE_PER_STEP;
             let current = item_entry.current_price as f32;
             let max_up = current * (1.0 + max_change_pct);
             let max_down = current * (1.0 - max_change_pct);
@@ -236,62 +753,16 @@ impl EconomySystem {
             }
         }
         
-        // Process any trade orders
-        let mut completed_order_indices = Vec::new();
-        
-        for (i, order) in market.trade_orders.iter_mut().enumerate() {
-            if order.status != OrderStatus::Active {
-                continue;
-            }
-            
-            // Check if the order's conditions are met
-            if let Some(item) = market.items.get(&order.item_name) {
-                let current_price = item.current_price;
-                
-                let condition_met = match order.order_type {
-                    OrderType::Buy => {
-                        // For buy orders, trigger when price falls below target
-                        if order.price_condition == "below" {
-                            current_price <= order.target_price
-                        } else {
-                            false
-                        }
-                    },
-                    OrderType::Sell => {
-                        // For sell orders, trigger when price rises above target
-                        if order.price_condition == "above" {
-                            current_price >= order.target_price
-                        } else {
-                            false
-                        }
-                    }
-                };
-                
-                if condition_met {
-                    // Mark the order for execution
-                    completed_order_indices.push(i);
-                    
-                    // Set status to completed
-                    order.status = OrderStatus::Completed;
-                    order.executed_at = Some(current_time);
-                }
-            }
-        }
-        
-        // Remove completed orders (in reverse order to maintain indices)
-        for index in completed_order_indices.into_iter().rev() {
-            market.trade_orders.remove(index);
-        }
-        
-        // Randomly apply expiration to local events
-        market.local_events.retain(|_| rand::random::<f32>() < 0.8); // 20% chance to expire each event
+        // Orders wait for their owners to settle them, but trailing stops
+        // follow the new prices in the meantime
+        market.track_trailing_stops();
     }
     
-    /// Apply random economic events
-    fn apply_random_events(&mut self) {
+    /// Apply random economic events, reporting each one in the news
+    fn apply_random_events(&mut self, current_time: u64) {
         // Global events
         for (event, probability) in &self.random_events {
-            if rand::random::<f32>() < *probability {
+            if self.rng.gen::<f32>() < *probability {
                 // Apply global event to all markets
                 match event {
                     EconomicEvent::TariffIncrease => {
@@ -322,23 +793,29 @@ impl EconomySystem {
                             market.local_events.push(event.clone());
                         }
                     },
-                    _ => {} // Handle other events at local level
+                    _ => continue, // Handle other events at local level
                 }
+                let report = MarketNews::new(None, event.clone(), current_time);
+                Self::report(&mut self.news, report);
             }
         }
         
         // Local events - apply to random markets
-        let system_ids: Vec<String> = self.system_markets.keys().cloned().collect();
+        let system_ids = self.sorted_system_ids();
         
         if !system_ids.is_empty() {
-            let idx = rand::random::<usize>() % system_ids.len();
+            let idx = self.rng.gen_range(0..system_ids.len());
             let system_id = &system_ids[idx];
             
             if let Some(market) = self.system_markets.get_mut(system_id) {
-                let local_event = match rand::random::<u32>() % 4 {
+                let mut item_names: Vec<String> = market.items.keys().cloned().collect();
+                item_names.sort();
+                let random_item = item_names.choose(&mut self.rng).cloned();
+                
+                let local_event = match self.rng.gen_range(0..4) {
                     0 => {
                         // Create shortage of random item
-                        if let Some(item_name) = market.items.keys().next().cloned() {
+                        if let Some(item_name) = random_item {
                             EconomicEvent::Shortage(item_name)
                         } else {
                             return;
@@ -346,7 +823,7 @@ impl EconomySystem {
                     },
                     1 => {
                         // Create surplus of random item
-                        if let Some(item_name) = market.items.keys().next().cloned() {
+                        if let Some(item_name) = random_item {
                             EconomicEvent::Surplus(item_name)
                         } else {
                             return;
@@ -356,19 +833,112 @@ impl EconomySystem {
                     _ => EconomicEvent::LocalPeace,
                 };
                 
-                market.local_events.push(local_event);
+                market.local_events.push(local_event.clone());
+                Self::report(&mut self.news, MarketNews::new(Some(system_id.clone()), local_event, current_time));
+            }
+        }
+    }
+    
+    /// Add a report to the news, dropping the oldest past the history limit
+    fn report(news: &mut Vec<MarketNews>, report: MarketNews) {
+        news.push(report);
+        if news.len() > NEWS_HISTORY {
+            news.remove(0);
+        }
+    }
+    
+    /// Take ended events out of the markets they were felt in. Ended
+    /// reports stay in the news as history.
+    fn expire_events(&mut self, current_time: u64) {
+        for (system_id, market) in self.system_markets.iter_mut() {
+            let running: Vec<&EconomicEvent> = self.news.iter()
+                .filter(|report| report.is_active(current_time) && report.affects(system_id))
+                .map(|report| &report.event)
+                .collect();
+            market.local_events.retain(|event| running.contains(&event));
+        }
+    }
+    
+    /// Whether goods can ship directly between two systems
+    fn are_connected(&self, a: &str, b: &str) -> bool {
+        self.connections.is_empty()
+            || self.connections.get(a).map_or(false, |links| links.iter().any(|id| id == b))
+    }
+    
+    /// Ship goods along the production chain and turn imported resources
+    /// into components. Each exporter sends a share of its export stock,
+    /// split evenly between the connected markets that import it.
+    fn simulate_production_chains(&mut self) {
+        let system_ids = self.sorted_system_ids();
+        // (from, to, item, units), worked out before any stock moves
+        let mut shipments: Vec<(String, String, MarketItem, u32)> = Vec::new();
+        
+        for source_id in &system_ids {
+            let source = &self.system_markets[source_id];
+            let mut item_names: Vec<&String> = source.items.keys().collect();
+            item_names.sort();
+            
+            for item_name in item_names {
+                let item = &source.items[item_name];
+                if !exports(&source.market_type, &item.item.item_type) {
+                    continue;
+                }
+                
+                let importers: Vec<&String> = system_ids.iter()
+                    .filter(|id| *id != source_id && self.are_connected(source_id, id))
+                    .filter(|id| imports(&self.system_markets[*id].market_type, &item.item.item_type))
+                    .collect();
+                if importers.is_empty() {
+                    continue;
+                }
+                
+                let per_importer = (item.quantity as f32 * CHAIN_EXPORT_SHARE) as u32 / importers.len() as u32;
+                if per_importer == 0 {
+                    continue;
+                }
+                for importer in importers {
+                    shipments.push((source_id.clone(), importer.clone(), item.clone(), per_importer));
+                }
+            }
+        }
+        
+        for (source_id, dest_id, item, amount) in &shipments {
+            if let Some(source_item) = self.system_markets.get_mut(source_id).and_then(|m| m.items.get_mut(&Market::stock_key(&item.item))) {
+                source_item.quantity = source_item.quantity.saturating_sub(*amount);
+                source_item.supply_level = supply_level_for(source_item);
+            }
+            if let Some(dest) = self.system_markets.get_mut(dest_id) {
+                let dest_item = dest.items.entry(Market::stock_key(&item.item)).or_insert_with(|| {
+                    let mut new_item = item.clone();
+                    new_item.quantity = 0;
+                    new_item
+                });
+                dest_item.quantity += amount;
+                dest_item.supply_level = supply_level_for(dest_item);
+            }
+        }
+        
+        for system_id in &system_ids {
+            if let Some(market) = self.system_markets.get_mut(system_id) {
+                if market.market_type == MarketType::Industrial {
+                    manufacture_components(market);
+                }
             }
         }
     }
     
     /// Simulate trade flows between systems
     fn simulate_trade_flows(&mut self) {
+        // Goods move along production chains first, then surplus drifts
+        // towards wherever it is scarcer
+        self.simulate_production_chains();
+        
         // This would simulate ship cargo flows and item distribution
         // between connected star systems
         
         // For a basic implementation, we'll just move a percentage of goods
         // from high-supply to low-supply markets
-        let system_ids: Vec<String> = self.system_markets.keys().cloned().collect();
+        let system_ids = self.sorted_system_ids();
         
         // Skip if we have fewer than 2 markets
         if system_ids.len() < 2 {
@@ -378,20 +948,17 @@ impl EconomySystem {
         // Create a list of all items across all markets
         let mut all_items = Vec::new();
         
-        for (system_id, market) in &self.system_markets {
-            for item_name in market.items.keys() {
-                all_items.push((system_id.clone(), item_name.clone()));
+        for system_id in &system_ids {
+            if let Some(market) = self.system_markets.get(system_id) {
+                for item_name in market.items.keys() {
+                    all_items.push((system_id.clone(), item_name.clone()));
+                }
             }
         }
         
         // Shuffle to randomize which items are traded
-        all_items.sort_by(|_, _| {
-            if rand::random::<bool>() {
-                std::cmp::Ordering::Less
-            } else {
-                std::cmp::Ordering::Greater
-            }
-        });
+        all_items.sort();
+        all_items.shuffle(&mut self.rng);
         
         // Process a limited number of trades
         let trades_to_process = all_items.len().min(5); // Process up to 5 trades per update
@@ -416,11 +983,16 @@ impl EconomySystem {
                 continue;
             };
             
-            for (system_id, market) in &self.system_markets {
+            for system_id in &system_ids {
                 if system_id == source_system_id {
                     continue;
                 }
                 
+                let market = match self.system_markets.get(system_id) {
+                    Some(market) => market,
+                    None => continue,
+                };
+                
                 if let Some(item) = market.items.get(item_name) {
                     if item.supply_level < source_supply {
                         potential_destinations.push(system_id.clone());
@@ -433,7 +1005,7 @@ impl EconomySystem {
             
             // If we have potential destinations, pick one and trade
             if !potential_destinations.is_empty() {
-                let dest_idx = rand::random::<usize>() % potential_destinations.len();
+                let dest_idx = self.rng.gen_range(0..potential_destinations.len());
                 let dest_system_id = &potential_destinations[dest_idx];
                 
                 // Transfer goods from source to destination
@@ -505,10 +1077,23 @@ impl EconomySystem {
     /// Adjust resource scarcity levels
     fn adjust_resource_scarcity(&mut self) {
         // Over time, resources become more or less scarce
-        for (_, scarcity) in self.resource_scarcity.iter_mut() {
-            // Small random adjustment
-            *scarcity += (rand::random::<f32>() - 0.5) * 0.1;
-            *scarcity = scarcity.max(0.5).min(2.0);
+        // ResourceType has no ordering, so use a fixed list to keep draws stable
+        let resource_types = [
+            ResourceType::Mineral,
+            ResourceType::Gas,
+            ResourceType::Ice,
+            ResourceType::Lunar,
+            ResourceType::Stellar,
+            ResourceType::Exotic,
+            ResourceType::Refined,
+        ];
+        
+        for resource_type in resource_types.iter() {
+            if let Some(scarcity) = self.resource_scarcity.get_mut(resource_type) {
+                // Small random adjustment
+                *scarcity += (self.rng.gen::<f32>() - 0.5) * 0.1;
+                *scarcity = scarcity.max(0.5).min(2.0);
+            }
         }
     }
     
@@ -527,15 +1112,10 @@ impl EconomySystem {
         negotiable: bool,
         description: &str,
         tags: Vec<String>,
+        clock: &GameClock,
     ) -> String {
         // Convert expiration hours to timestamp if provided
-        let expiration = expiration_hours.map(|hours| {
-            let current_time = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or(Duration::from_secs(0))
-                .as_secs();
-            current_time + (hours * 3600) // Convert hours to seconds
-        });
+        let expiration = expiration_hours.map(|hours| clock.hours_from_now(hours));
         
         // Create listing
         self.player_market.create_listing(
@@ -552,6 +1132,7 @@ impl EconomySystem {
             negotiable,
             description,
             tags,
+            clock,
         )
     }
     
@@ -600,6 +1181,37 @@ impl EconomySystem {
         results
     }
     
+    /// Active trade orders `player_id` has open in any market
+    pub fn open_order_count(&self, player_id: &str) -> usize {
+        self.system_markets.values()
+            .map(|market| market.get_player_orders(player_id).len())
+            .sum()
+    }
+    
+    /// Check a new order's terms, that its market exists and that the
+    /// player has room for another open order
+    fn validate_new_order(
+        &self,
+        player_id: &str,
+        system_id: &str,
+        quantity: u32,
+        target_price: u32,
+        expires_at: Option<u64>,
+        clock: &GameClock,
+    ) -> Result<(), String> {
+        validate_order_terms(quantity, OrderKind::Limit, target_price, expires_at, clock)?;
+        if !self.system_markets.contains_key(system_id) {
+            return Err("Cannot find market for that system".to_string());
+        }
+        if self.open_order_count(player_id) >= self.max_orders_per_player {
+            return Err(format!(
+                "You already have {} open orders, the most allowed; cancel one first",
+                self.max_orders_per_player
+            ));
+        }
+        Ok(())
+    }
+    
     /// Create a buy order for automatic purchases
     pub fn create_buy_order(
         &mut self,
@@ -609,17 +1221,14 @@ impl EconomySystem {
         quantity: u32,
         target_price: u32,
         expires_hours: Option<u64>,
-    ) -> String {
+        clock: &GameClock,
+    ) -> Result<String, String> {
         let order_id = Uuid::new_v4().to_string();
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         // Calculate expiration timestamp if provided
-        let expires_at = expires_hours.map(|hours| {
-            current_time + (hours * 3600) // Convert hours to seconds
-        });
+        let expires_at = expires_hours.map(|hours| clock.hours_from_now(hours));
+        self.validate_new_order(player_id, system_id, quantity, target_price, expires_at, clock)?;
         
         let order = TradeOrder {
             id: Uuid::parse_str(&order_id).unwrap_or_else(|_| Uuid::new_v4()),
@@ -634,6 +1243,9 @@ impl EconomySystem {
             created_at: current_time,
             expires_at,
             executed_at: None,
+            executed_price: None,
+            kind: OrderKind::Limit,
+            peak_price: None,
             notes: format!("Buy {} of {} when price falls below {}", 
                          quantity, item_name, target_price),
         };
@@ -643,7 +1255,7 @@ impl EconomySystem {
             market.trade_orders.push(order);
         }
         
-        order_id
+        Ok(order_id)
     }
     
     /// Create a sell order for automatic sales
@@ -655,17 +1267,14 @@ impl EconomySystem {
         quantity: u32,
         target_price: u32,
         expires_hours: Option<u64>,
-    ) -> String {
+        clock: &GameClock,
+    ) -> Result<String, String> {
         let order_id = Uuid::new_v4().to_string();
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_secs();
+        let current_time = clock.now();
             
         // Calculate expiration timestamp if provided
-        let expires_at = expires_hours.map(|hours| {
-            current_time + (hours * 3600) // Convert hours to seconds
-        });
+        let expires_at = expires_hours.map(|hours| clock.hours_from_now(hours));
+        self.validate_new_order(player_id, system_id, quantity, target_price, expires_at, clock)?;
         
         let order = TradeOrder {
             id: Uuid::parse_str(&order_id).unwrap_or_else(|_| Uuid::new_v4()),
@@ -680,6 +1289,9 @@ impl EconomySystem {
             created_at: current_time,
             expires_at,
             executed_at: None,
+            executed_price: None,
+            kind: OrderKind::Limit,
+            peak_price: None,
             notes: format!("Sell {} of {} when price rises above {}", 
                          quantity, item_name, target_price),
         };
@@ -689,7 +1301,7 @@ impl EconomySystem {
             market.trade_orders.push(order);
         }
         
-        order_id
+        Ok(order_id)
     }
     
     /// Get market trends for multiple items
@@ -741,19 +1353,25 @@ impl EconomySystem {
         listing_id: &str,
         bidder_id: &str,
         bidder_name: &str,
+        bidder_faction: Option<&str>,
+        bidder_reputation: i32,
         bid_amount: u32,
         quantity: u32,
         message: &str,
         expires_in_hours: Option<u64>,
+        clock: &GameClock,
     ) -> Result<String, String> {
         self.player_market.place_bid(
             listing_id,
             bidder_id,
             bidder_name,
+            bidder_faction,
+            bidder_reputation,
             bid_amount,
             quantity,
             message,
             expires_in_hours,
+            clock,
         )
     }
     
@@ -791,4 +1409,246 @@ impl EconomySystem {
         
         market_ratings
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn seeded_economy(seed: u64) -> EconomySystem {
+        let mut economy = EconomySystem::with_seed(seed);
+        let clock = GameClock::default();
+        
+        for system_id in ["sol", "alpha_centauri", "sirius"] {
+            economy.initialize_system_market(system_id, MarketType::Trading);
+            let market = economy.system_markets.get_mut(system_id).unwrap();
+            market.add_item(Item::new("Iron Ore", 10, 1, ItemType::Resource(ResourceType::Mineral)), 100, 10, 0.3, &clock);
+            market.add_item(Item::new("Fuel Cell", 25, 1, ItemType::Fuel), 50, 25, 0.5, &clock);
+        }
+        economy.set_resource_scarcity();
+        economy
+    }
+    
+    fn price_snapshot(economy: &EconomySystem) -> Vec<(String, String, u32, u32)> {
+        let mut prices = Vec::new();
+        for (system_id, market) in &economy.system_markets {
+            for (item_name, item) in &market.items {
+                prices.push((system_id.clone(), item_name.clone(), item.current_price, item.quantity));
+            }
+        }
+        prices.sort();
+        prices
+    }
+    
+    #[test]
+    fn test_same_seed_reproduces_economy() {
+        let mut first = seeded_economy(42);
+        let mut second = seeded_economy(42);
+        
+        // Run a day's worth of hourly updates on both
+        for hour in 1..=24 {
+            let clock = GameClock::at(hour * 3600);
+            first.update(&clock);
+            second.update(&clock);
+        }
+        
+        assert_eq!(price_snapshot(&first), price_snapshot(&second));
+        assert_eq!(first.global_trade_index, second.global_trade_index);
+        assert_eq!(first.get_seed(), 42);
+    }
+    
+    #[test]
+    fn test_tick_reports_changes() {
+        let mut economy = seeded_economy(7);
+        let mut time_system = TimeSystem::new();
+        
+        // A standing buy order well above market price fills against its owner
+        let order_id = economy.system_markets.get_mut("sol").unwrap()
+            .create_buy_order("player", "Iron Ore", 5, 1000, None, "test", &GameClock::default())
+            .unwrap();
+        let mut player = Player::new("Trader");
+        player.id = "player".to_string();
+        let credits = player.credits;
+        
+        let summary = economy.tick(&mut time_system, 10, std::slice::from_mut(&mut player));
+        
+        assert_eq!(summary.ticks, 10);
+        assert_eq!(summary.end_time - summary.start_time, 10 * economy.update_interval);
+        assert_eq!(summary.executed_orders.len(), 1);
+        assert_eq!(summary.executed_orders[0].id, order_id);
+        let order = economy.system_markets["sol"].trade_orders.iter().find(|order| order.id == order_id).unwrap();
+        assert_eq!(order.status, OrderStatus::Completed);
+        assert_eq!(player.inventory.get_item_quantity("Iron Ore"), 5);
+        assert!(player.credits < credits);
+        for change in &summary.price_changes {
+            assert_ne!(change.old_price, change.new_price);
+        }
+    }
+    
+    #[test]
+    fn test_industrial_market_builds_components_from_connected_supplier() {
+        let clock = GameClock::default();
+        let mut economy = EconomySystem::with_seed(1);
+        economy.initialize_system_market("mine", MarketType::Mining);
+        economy.initialize_system_market("forge", MarketType::Industrial);
+        economy.initialize_system_market("far_forge", MarketType::Industrial);
+        
+        economy.system_markets.get_mut("mine").unwrap()
+            .add_item(Item::new("Iron Ore", 10, 1, ItemType::Resource(ResourceType::Mineral)), 500, 10, 0.1, &clock);
+        for forge in ["forge", "far_forge"] {
+            economy.system_markets.get_mut(forge).unwrap()
+                .add_item(Item::new("Circuit Board", 50, 1, ItemType::Component), 10, 50, 0.1, &clock);
+        }
+        economy.connections = [
+            ("mine", vec!["forge"]),
+            ("forge", vec!["mine"]),
+            ("far_forge", vec![]),
+        ].into_iter()
+            .map(|(id, links)| (id.to_string(), links.into_iter().map(String::from).collect()))
+            .collect();
+        
+        let components = |economy: &EconomySystem, system_id: &str| {
+            economy.system_markets[system_id].items["Circuit Board"].quantity
+        };
+        let (forge_before, far_before) = (components(&economy, "forge"), components(&economy, "far_forge"));
+        
+        for _ in 0..5 {
+            economy.simulate_production_chains();
+        }
+        
+        assert!(components(&economy, "forge") > forge_before);
+        assert_eq!(components(&economy, "far_forge"), far_before);
+        assert!(economy.system_markets["mine"].items["Iron Ore"].quantity < 500);
+    }
+    
+    #[test]
+    fn test_market_csv_export_escapes_names() {
+        let mut economy = seeded_economy(1);
+        let clock = GameClock::default();
+        economy.system_markets.get_mut("sol").unwrap()
+            .add_item(Item::new("Ore, \"Rich\"", 40, 1, ItemType::Product), 5, 40, 0.2, &clock);
+        
+        let path = std::env::temp_dir().join(format!("market_export_test_{}.csv", std::process::id()));
+        economy.export_market_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "system,item,current_price,base_price,supply,demand,quantity,trend_percent");
+        assert_eq!(lines.len(), 1 + 3 * 2 + 1);
+        assert!(lines.iter().any(|line| line.starts_with("sol,\"Ore, \"\"Rich\"\"\",")));
+    }
+    
+    #[test]
+    fn test_system_specialization_joins_stations_and_market() {
+        let universe = Universe::new();
+        let mut system = universe.get_system("sol").unwrap().clone();
+        let market = universe.get_market("sol").unwrap();
+        assert_eq!(market.market_type, MarketType::Trading);
+        
+        system.stations.truncate(1);
+        system.stations[0].station_type = crate::models::universe::StationType::Mining;
+        assert_eq!(system_specialization(&system, Some(&market)), "Mining Facility - cheap ore, buys components");
+        assert_eq!(system_specialization(&system, None), "Mining Facility - no market");
+        
+        system.stations.clear();
+        assert_eq!(system_specialization(&system, None), "no facilities");
+    }
+    
+    #[test]
+    fn test_dormant_markets_batch_and_catch_up_on_arrival() {
+        let mut economy = seeded_economy(3);
+        economy.dormant_after = Some(6 * 3600);
+        economy.connections = [
+            ("sol", vec!["alpha_centauri"]),
+            ("alpha_centauri", vec!["sol"]),
+            ("sirius", vec![]),
+        ].into_iter()
+            .map(|(id, links)| (id.to_string(), links.into_iter().map(String::from).collect()))
+            .collect();
+        
+        // A player stays in Sol throughout
+        for hour in 1..=10 {
+            let clock = GameClock::at(hour * 3600);
+            economy.mark_active("sol", &clock);
+            economy.update(&clock);
+        }
+        
+        // Sol and its neighbour are simulated every update; Sirius waits
+        let last_update = |economy: &EconomySystem, id: &str| economy.system_markets[id].last_update;
+        assert_eq!(last_update(&economy, "sol"), 10 * 3600);
+        assert_eq!(last_update(&economy, "alpha_centauri"), 10 * 3600);
+        assert_eq!(last_update(&economy, "sirius"), 0);
+        assert!(!economy.is_active("sirius", 10 * 3600));
+        
+        // Arriving catches the market up to the present in one step
+        economy.mark_active("sirius", &GameClock::at(10 * 3600));
+        assert_eq!(last_update(&economy, "sirius"), 10 * 3600);
+        let history = &economy.system_markets["sirius"].items["Iron Ore"].price_history;
+        assert_eq!(history.last().unwrap().timestamp, 10 * 3600);
+        assert!(economy.is_active("sirius", 10 * 3600));
+    }
+    
+    #[test]
+    fn test_market_maker_keeps_core_commodities_in_stock() {
+        let clock = GameClock::default();
+        let mut economy = EconomySystem::with_seed(5);
+        economy.initialize_system_market("sol", MarketType::Trading);
+        let market = economy.system_markets.get_mut("sol").unwrap();
+        market.add_item(Item::new("Iron Ore", 10, 1, ItemType::Resource(ResourceType::Mineral)), 1, 10, 0.1, &clock);
+        market.add_item(Item::new("Gadget", 100, 1, ItemType::Product), 1, 100, 0.1, &clock);
+        for item in market.items.values_mut() {
+            item.quantity = 0;
+            item.production_rate = 0;
+        }
+        
+        economy.update(&GameClock::at(economy.update_interval));
+        
+        // Ore is back on the shelf; the maker doesn't deal in products
+        let market = &economy.system_markets["sol"];
+        let target = economy.market_maker.target_stock;
+        assert!(market.items["Iron Ore"].quantity > 0 && market.items["Iron Ore"].quantity < target);
+        assert_eq!(market.items["Gadget"].quantity, 0);
+        
+        // Past the stock the maker still sells ore, at a worse price, but
+        // nobody sells gadgets
+        let stocked = market.items["Iron Ore"].quantity;
+        let shelf = market.quote_buy("Iron Ore", stocked).unwrap();
+        let beyond = market.quote_buy("Iron Ore", stocked * 2).unwrap();
+        assert!(beyond.unit_price > shelf.unit_price);
+        let market = economy.system_markets.get_mut("sol").unwrap();
+        assert!(market.buy_item("Iron Ore", stocked * 2, &clock).is_some());
+        assert_eq!(market.items["Iron Ore"].quantity, 0);
+        assert!(market.buy_item("Gadget", 1, &clock).is_none());
+        
+        // A dormant market woken up has been restocked for the time it slept
+        economy.dormant_after = Some(3600);
+        economy.mark_active("sol", &GameClock::at(10 * economy.update_interval));
+        let quantity = economy.system_markets["sol"].items["Iron Ore"].quantity;
+        assert!(quantity > stocked && quantity <= target);
+    }
+    
+    #[test]
+    fn test_open_orders_are_capped_per_player() {
+        let mut economy = seeded_economy(7);
+        let clock = GameClock::default();
+        economy.max_orders_per_player = 3;
+        
+        assert!(economy.create_buy_order("player", "sol", "Iron Ore", 0, 5, None, &clock).is_err());
+        assert!(economy.create_buy_order("player", "nowhere", "Iron Ore", 1, 5, None, &clock).is_err());
+        economy.create_buy_order("player", "sol", "Iron Ore", 1, 5, None, &clock).unwrap();
+        economy.create_buy_order("player", "sirius", "Iron Ore", 1, 5, Some(2), &clock).unwrap();
+        let order_id = economy.create_sell_order("player", "alpha_centauri", "Fuel Cell", 1, 500, None, &clock).unwrap();
+        assert_eq!(economy.open_order_count("player"), 3);
+        
+        let error = economy.create_buy_order("player", "sol", "Iron Ore", 1, 5, None, &clock).unwrap_err();
+        assert!(error.contains("open orders"));
+        assert!(economy.create_sell_order("player", "sol", "Fuel Cell", 1, 500, None, &clock).is_err());
+        assert!(economy.create_buy_order("someone_else", "sol", "Iron Ore", 1, 5, None, &clock).is_ok());
+        
+        // Closing an order frees a slot
+        let order = economy.system_markets.get_mut("alpha_centauri").unwrap().trade_orders.iter_mut()
+            .find(|order| order.id.to_string() == order_id).unwrap();
+        order.status = OrderStatus::Cancelled;
+        assert!(economy.create_buy_order("player", "sol", "Iron Ore", 1, 5, None, &clock).is_ok());
+    }
+}
@@ -0,0 +1,156 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::models::player::{Player, TransactionCategory};
+use crate::models::universe::{StarSystem, StationType};
+use crate::systems::combat::{CombatSystem, CombatAction, CombatOutcome, NpcShip};
+use crate::utils::clock::GameClock;
+
+// Encounter chance never goes above this, however rich the cargo
+const MAX_ENCOUNTER_CHANCE: f32 = 0.9;
+
+// What happened on the way to a destination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EncounterOutcome {
+    SafePassage,
+    Robbed { credits: u32, cargo: Vec<(String, u32)> },
+    Escaped,
+    CombatWon { bounty: u32 },
+}
+
+#[allow(dead_code)]
+impl EncounterOutcome {
+    pub fn describe(&self) -> String {
+        match self {
+            EncounterOutcome::SafePassage => "Safe passage".to_string(),
+            EncounterOutcome::Robbed { credits, cargo } => {
+                let mut losses = Vec::new();
+                if *credits > 0 {
+                    losses.push(format!("{} credits", credits));
+                }
+                for (name, quantity) in cargo {
+                    losses.push(format!("{} {}", quantity, name));
+                }
+                format!("Pirates boarded your ship and took {}", losses.join(", "))
+            },
+            EncounterOutcome::Escaped => "Pirates gave chase but you outran them".to_string(),
+            EncounterOutcome::CombatWon { bounty } => {
+                format!("You fought off a pirate ambush and collected a {} credit bounty", bounty)
+            },
+        }
+    }
+}
+
+// Systems with a military presence are patrolled well enough that pirates stay away
+pub fn is_patrolled(system: &StarSystem) -> bool {
+    system.stations.iter().any(|station| station.station_type == StationType::Military)
+}
+
+// Danger of a system (0-5), taken from its most dangerous resource field
+pub fn system_danger(system: &StarSystem) -> u8 {
+    system.resource_fields.iter()
+        .map(|field| field.field_type.danger_level())
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn cargo_value(player: &Player) -> u32 {
+    player.inventory.items.iter()
+        .map(|(item, quantity)| item.value * quantity)
+        .sum()
+}
+
+// Chance of being intercepted on the way to `destination`. Dangerous space
+// and valuable cargo both attract pirates.
+pub fn encounter_chance(base_chance: f32, player: &Player, destination: &StarSystem) -> f32 {
+    if is_patrolled(destination) {
+        return 0.0;
+    }
+
+    let danger_factor = 1.0 + system_danger(destination) as f32 * 0.25;
+    let cargo_factor = 1.0 + cargo_value(player) as f32 / 10_000.0;
+    (base_chance * danger_factor * cargo_factor).min(MAX_ENCOUNTER_CHANCE)
+}
+
+// Roll for an encounter and fight it out with the combat system
+pub fn resolve_encounter<R: Rng + ?Sized>(
+    player: &mut Player,
+    destination: &StarSystem,
+    base_chance: f32,
+    rng: &mut R,
+    clock: &GameClock,
+) -> EncounterOutcome {
+    let chance = encounter_chance(base_chance, player, destination);
+    if rng.gen::<f32>() >= chance {
+        return EncounterOutcome::SafePassage;
+    }
+
+    // Outgunned pilots try to run rather than fight
+    let pirate = NpcShip::pirate(system_danger(destination), rng);
+    let action = if player.ship.attack_rating() >= pirate.attack {
+        CombatAction::Fight
+    } else {
+        CombatAction::Flee
+    };
+
+    let result = CombatSystem::new().resolve(player, &pirate, action, rng, clock);
+    match result.outcome {
+        CombatOutcome::Victory => return EncounterOutcome::CombatWon { bounty: result.bounty },
+        CombatOutcome::Fled => return EncounterOutcome::Escaped,
+        CombatOutcome::Defeat | CombatOutcome::Stalemate => {},
+    }
+
+    rob_player(player, clock)
+}
+
+// Pirates take half of the most valuable cargo stack, or a fifth of the
+// player's credits if the hold is empty
+fn rob_player(player: &mut Player, clock: &GameClock) -> EncounterOutcome {
+    let most_valuable = player.inventory.items.iter()
+        .max_by_key(|(item, quantity)| item.value * **quantity)
+        .map(|(item, quantity)| (item.name.clone(), *quantity));
+
+    if let Some((name, quantity)) = most_valuable {
+        let taken = (quantity + 1) / 2;
+        player.inventory.remove_item(&name, taken);
+        return EncounterOutcome::Robbed { credits: 0, cargo: vec![(name, taken)] };
+    }
+
+    let credits = player.credits / 5;
+    let _ = player.apply_transaction(-(credits as i64), TransactionCategory::Piracy, "Robbed by pirates", clock);
+    EncounterOutcome::Robbed { credits, cargo: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+    use crate::models::item::{Item, ItemType};
+
+    #[test]
+    fn test_forced_robbery_takes_cargo() {
+        let mut player = Player::new("Test");
+        player.ship.weapon_power = 0;
+        player.ship.shield = 0;
+        player.ship.speed = 0;
+        player.inventory.add_item(Item::new("Gold", 400, 1, ItemType::Product), 10);
+
+        let destination = player.current_system.clone();
+
+        // An all-zero RNG always triggers the encounter and loses every roll
+        let mut rng = StepRng::new(0, 0);
+        let outcome = resolve_encounter(&mut player, &destination, 0.5, &mut rng, &GameClock::default());
+
+        assert_eq!(outcome, EncounterOutcome::Robbed { credits: 0, cargo: vec![("Gold".to_string(), 5)] });
+        assert_eq!(player.inventory.get_item_quantity("Gold"), 5);
+    }
+
+    #[test]
+    fn test_no_encounters_when_chance_is_zero() {
+        let mut player = Player::new("Test");
+        let destination = player.current_system.clone();
+        let mut rng = StepRng::new(0, 0);
+
+        assert_eq!(resolve_encounter(&mut player, &destination, 0.0, &mut rng, &GameClock::default()), EncounterOutcome::SafePassage);
+    }
+}
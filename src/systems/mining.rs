@@ -4,7 +4,8 @@ use serde::{Serialize, Deserialize};
 use crate::utils::serde::option_instant_serde;
 
 use crate::models::player::Player;
-use crate::models::item::{Item, ItemType, ResourceType};
+use crate::models::crew::CrewRole;
+use crate::models::item::{Item, ItemAttributes, ItemType, Rarity, ResourceType};
 use crate::models::universe::{Universe, ResourceFieldType, ResourceField};
 use crate::systems::time::TimeSystem;
 
@@ -103,6 +104,7 @@ impl MiningSystem {
                     
                     // Add mining experience to player skills
                     player.skills.gain_mining_experience(yield_amount);
+                    player.record_mined(yield_amount);
                 }
             }
         }
@@ -111,13 +113,21 @@ impl MiningSystem {
     // Helper function to add resources to the appropriate specialized cargo bay
     fn add_resource_to_player(&self, player: &mut Player, resource_name: &str, 
                            resource_type: &ResourceType, amount: u32) {
-        // Create the resource item
+        // Create the resource item. Exotic fields can turn up a rarer haul.
+        let field_type = match resource_type {
+            ResourceType::Exotic => ResourceFieldType::BlackHoleAccretion,
+            _ => ResourceFieldType::AsteroidField,
+        };
+        let rarity = Self::roll_rarity(&field_type, &mut rand::thread_rng());
         let item = Item {
             name: resource_name.to_string(),
             value: 50 + (amount / 2), // Base value plus bonus for larger amounts
             weight: 1,
             item_type: ItemType::Resource(resource_type.clone()),
-        };
+            rarity: Rarity::Common,
+            unique_id: None,
+            attributes: ItemAttributes::default(),
+        }.with_rarity(rarity);
         
         // TODO: Store resources in specialized cargo bays
         // For now, just add to regular inventory
@@ -157,9 +167,10 @@ impl MiningSystem {
         };
         
         // Calculate yield based on ship mining power and player skill
-        let base_yield = player.ship.mining_power;
+        let base_yield = player.ship.mining_rating();
         let skill_multiplier = 1.0 + (player.skills.get_mining_level() as f32 * 0.1);
-        let yield_per_cycle = (base_yield as f32 * skill_multiplier) as u32;
+        let crew_multiplier = 1.0 + player.ship.crew_bonus(&CrewRole::Miner);
+        let yield_per_cycle = (base_yield as f32 * skill_multiplier * crew_multiplier) as u32;
         
         // Choose a resource from the field
         if field.resources.is_empty() {
@@ -234,6 +245,11 @@ impl MiningSystem {
 
     // Legacy methods for backward compatibility
     pub fn mine_resource(&mut self, player: &mut Player, resource_index: usize) -> Option<String> {
+        self.mine_resource_with_rng(player, resource_index, &mut rand::thread_rng())
+    }
+
+    // Mine one unit, drawing the success, rarity and damage rolls from `rng`
+    pub fn mine_resource_with_rng<R: Rng + ?Sized>(&mut self, player: &mut Player, resource_index: usize, rng: &mut R) -> Option<String> {
         // Get available resource fields in the current system
         let fields = self.get_resources_for_system(&player.current_system.id);
         
@@ -256,8 +272,11 @@ impl MiningSystem {
         }
         
         // Calculate mining success chance based on ship's mining power and resource abundance
-        let success_chance = (player.ship.mining_power as f32 * *abundance as f32 / 100.0) as u32;
-        let success = rand::thread_rng().gen_range(0..100) < success_chance;
+        // Crew miners improve the odds
+        let crew_multiplier = 1.0 + player.ship.crew_bonus(&CrewRole::Miner);
+        let success_chance = (player.ship.mining_rating() as f32 * *abundance as f32 / 100.0 * crew_multiplier) as u32;
+        let success = rng.gen_range(0..100) < success_chance;
+        let mut mined_name = resource_name.clone();
         
         if success {
             // Determine resource type based on field type
@@ -270,23 +289,82 @@ impl MiningSystem {
                 ResourceFieldType::BlackHoleAccretion => ResourceType::Exotic,
             };
             
-            // Create the resource item
+            // Create the resource item at whatever grade the field gave up
+            let rarity = Self::roll_rarity(&field.field_type, rng);
             let item = Item {
                 name: resource_name.clone(),
                 value: 50 + (*abundance / 2),
                 weight: 1,
                 item_type: ItemType::Resource(resource_type),
-            };
+                rarity: Rarity::Common,
+                unique_id: None,
+                attributes: ItemAttributes::default(),
+            }.with_rarity(rarity);
+            mined_name = item.display_name();
             
             // Add to inventory
             player.inventory.add_item(item, 1);
             
             // Add a small amount of mining experience
             player.skills.gain_mining_experience(1);
-            
-            Some(format!("Successfully mined 1 {}", resource_name))
+            player.record_mined(1);
+        }
+        
+        let mut result = if success {
+            format!("Successfully mined 1 {}", mined_name)
         } else {
-            Some(format!("Failed to mine {}", resource_name))
+            format!("Failed to mine {}", resource_name)
+        };
+        
+        // Every attempt risks damage, more so in dangerous fields
+        if let Some(damage_report) = Self::roll_field_damage(player, &field.field_type, rng) {
+            result.push_str(". ");
+            result.push_str(&damage_report);
+        }
+        
+        Some(result)
+    }
+    
+    // Roll the grade of a mined unit. Most finds are common; black hole
+    // accretion disks are the only place legendary material turns up.
+    pub fn roll_rarity<R: Rng + ?Sized>(field_type: &ResourceFieldType, rng: &mut R) -> Rarity {
+        // Cumulative weights out of 1000 for Uncommon, Rare, Epic, Legendary
+        let weights: [u32; 4] = match field_type {
+            ResourceFieldType::BlackHoleAccretion => [250, 200, 120, 30],
+            ResourceFieldType::StarCorona => [200, 80, 20, 0],
+            _ => [150, 45, 5, 0],
+        };
+        
+        let mut roll = rng.gen_range(0..1000);
+        for (rarity, weight) in [Rarity::Legendary, Rarity::Epic, Rarity::Rare, Rarity::Uncommon].iter()
+            .zip(weights.iter().rev()) {
+            if roll < *weight {
+                return *rarity;
+            }
+            roll -= weight;
+        }
+        Rarity::Common
+    }
+    
+    // Roll for hull damage from mining in a field. The chance and size of a
+    // hit both scale with the field's danger level.
+    fn roll_field_damage<R: Rng + ?Sized>(player: &mut Player, field_type: &ResourceFieldType, rng: &mut R) -> Option<String> {
+        let danger = field_type.danger_level() as u32;
+        
+        // 4% chance per danger level to take a hit
+        if rng.gen_range(0..100) >= danger * 4 {
+            return None;
+        }
+        
+        let damage = rng.gen_range(1..=danger * 8);
+        if player.ship.take_damage(damage) {
+            Some(format!("{} tore your hull apart - ship destroyed!", field_type.to_string()))
+        } else {
+            Some(format!(
+                "Took {} damage from debris. Hull integrity {:.0}%",
+                damage,
+                player.ship.hull_integrity()
+            ))
         }
     }
 
@@ -294,3 +372,42 @@ impl MiningSystem {
         self.selected_resource = Some(resource_name);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_legendary_finds_only_in_black_holes() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let asteroid_legendary = (0..5000)
+            .filter(|_| MiningSystem::roll_rarity(&ResourceFieldType::AsteroidField, &mut rng) == Rarity::Legendary)
+            .count();
+        let black_hole_legendary = (0..5000)
+            .filter(|_| MiningSystem::roll_rarity(&ResourceFieldType::BlackHoleAccretion, &mut rng) == Rarity::Legendary)
+            .count();
+
+        assert_eq!(asteroid_legendary, 0);
+        assert!(black_hole_legendary > 0);
+    }
+
+    #[test]
+    fn test_field_damage_is_reproducible_from_the_seed() {
+        let mut first = Player::new("First");
+        let mut second = Player::new("Second");
+        let mut first_rng = StdRng::seed_from_u64(11);
+        let mut second_rng = StdRng::seed_from_u64(11);
+
+        let mut hits = 0;
+        for _ in 0..50 {
+            let a = MiningSystem::roll_field_damage(&mut first, &ResourceFieldType::StarCorona, &mut first_rng);
+            let b = MiningSystem::roll_field_damage(&mut second, &ResourceFieldType::StarCorona, &mut second_rng);
+            hits += a.is_some() as u32;
+            assert_eq!(a, b);
+        }
+        assert!(hits > 0);
+        assert_eq!((first.ship.hull, first.ship.shield), (second.ship.hull, second.ship.shield));
+    }
+}
@@ -3,4 +3,9 @@ pub mod trading;
 pub mod crafting;
 pub mod economy;
 pub mod navigation;
-pub mod time;
\ No newline at end of file
+pub mod time;
+pub mod encounter;
+pub mod combat;
+pub mod tutorial;
+pub mod routine;
+pub mod scheduler;
@@ -1,16 +1,37 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use std::time::Duration;
 use serde::{Serialize, Deserialize};
 
 use crate::models::player::Player;
-use crate::models::universe::StarSystem;
+use crate::models::market::Market;
+use crate::models::crew::CrewRole;
+use crate::models::universe::{StarSystem, CelestialBodyType};
 use crate::models::universe::Universe;
 use crate::systems::time::TimeSystem;
+use crate::utils::clock::GameClock;
+use crate::systems::encounter::{self, EncounterOutcome};
+
+// Jump gates link to any other gated system within this range (light years)
+const JUMP_GATE_RANGE: f32 = 12.0;
+// Flat fuel cost of a gate-to-gate jump
+const JUMP_GATE_FUEL_COST: u32 = 1;
+
+fn default_piracy_chance() -> f32 {
+    0.1
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct NavigationSystem {
     travel_in_progress: bool,
     destination: Option<StarSystem>,
     travel_time_remaining: Duration,
+    // Remaining hops (system ids) of a multi-jump course, after the current destination
+    #[serde(default)]
+    course: Vec<String>,
+    // Base chance of a pirate encounter per jump, before danger and cargo modifiers
+    #[serde(default = "default_piracy_chance")]
+    piracy_chance: f32,
 }
 
 #[allow(dead_code)]
@@ -20,6 +41,8 @@ impl NavigationSystem {
             travel_in_progress: false,
             destination: None,
             travel_time_remaining: Duration::from_secs(0),
+            course: Vec::new(),
+            piracy_chance: default_piracy_chance(),
         }
     }
 
@@ -48,6 +71,16 @@ impl NavigationSystem {
             return false;
         }
         
+        // Gate-linked systems can always be reached for the flat gate cost
+        if self.is_gate_link(&player.current_system, destination) {
+            return player.ship.current_fuel >= JUMP_GATE_FUEL_COST;
+        }
+        
+        // Otherwise there must be a jump lane to the destination
+        if !self.has_lane(&player.current_system, destination) {
+            return false;
+        }
+        
         // Check if destination is within jump range
         let distance = self.calculate_distance(&player.current_system, destination);
         if !self.is_in_range(player, distance) {
@@ -55,9 +88,245 @@ impl NavigationSystem {
         }
         
         // Check if player has enough fuel
-        let fuel_required = self.calculate_fuel_required(distance);
+        let fuel_required = self.calculate_fuel_required_for(player, distance);
         player.ship.current_fuel >= fuel_required
     }
+
+    // Lanes are symmetric, so checking either end is enough. The destination
+    // is checked as well because the player's copy of their system may predate lanes.
+    pub fn has_lane(&self, from: &StarSystem, to: &StarSystem) -> bool {
+        from.connections.contains(&to.id) || to.connections.contains(&from.id)
+    }
+
+    // Whether a jump is possible at all, ignoring fuel and range
+    pub fn is_linked(&self, from: &StarSystem, to: &StarSystem) -> bool {
+        self.has_lane(from, to) || self.is_gate_link(from, to)
+    }
+
+    pub fn has_jump_gate(&self, system: &StarSystem) -> bool {
+        system.celestial_bodies.iter().any(|body| body.body_type == CelestialBodyType::JumpGate)
+    }
+
+    // Two systems are gate-linked if both have a jump gate and are within gate range
+    pub fn is_gate_link(&self, from: &StarSystem, to: &StarSystem) -> bool {
+        self.has_jump_gate(from)
+            && self.has_jump_gate(to)
+            && self.calculate_distance(from, to) <= JUMP_GATE_RANGE
+    }
+
+    // Fuel cost of a single hop, or None if the hop isn't possible with the given jump range
+    fn hop_fuel_cost(&self, from: &StarSystem, to: &StarSystem, jump_range: f32) -> Option<u32> {
+        if self.is_gate_link(from, to) {
+            return Some(JUMP_GATE_FUEL_COST);
+        }
+        
+        let distance = self.calculate_distance(from, to);
+        if self.has_lane(from, to) && distance <= jump_range {
+            Some(self.calculate_fuel_required(distance))
+        } else {
+            None
+        }
+    }
+
+    // Find the cheapest multi-jump route (by fuel) from one system to another.
+    // Each hop must be within `jump_range` unless it goes through a jump gate,
+    // and the whole route must fit in `fuel`. Returns the system ids along the
+    // route, including both ends, or None if the destination is unreachable.
+    pub fn plan_route(&self, universe: &Universe, from: &str, to: &str, fuel: u32, jump_range: f32) -> Option<Vec<String>> {
+        if universe.get_system(from).is_none() || universe.get_system(to).is_none() {
+            return None;
+        }
+        if from == to {
+            return Some(vec![from.to_string()]);
+        }
+        
+        let systems = universe.get_all_systems();
+        let mut best_cost: HashMap<String, u32> = HashMap::new();
+        let mut came_from: HashMap<String, String> = HashMap::new();
+        let mut frontier = BinaryHeap::new();
+        
+        best_cost.insert(from.to_string(), 0);
+        frontier.push(Reverse((0u32, from.to_string())));
+        
+        while let Some(Reverse((cost, system_id))) = frontier.pop() {
+            if system_id == to {
+                break;
+            }
+            if cost > *best_cost.get(&system_id).unwrap_or(&u32::MAX) {
+                continue; // Stale entry
+            }
+            
+            let current = match universe.get_system(&system_id) {
+                Some(system) => system,
+                None => continue,
+            };
+            
+            for next in &systems {
+                if next.id == system_id {
+                    continue;
+                }
+                
+                let hop_cost = match self.hop_fuel_cost(current, next, jump_range) {
+                    Some(hop_cost) => hop_cost,
+                    None => continue,
+                };
+                
+                let next_cost = cost + hop_cost;
+                if next_cost > fuel {
+                    continue;
+                }
+                
+                if next_cost < *best_cost.get(&next.id).unwrap_or(&u32::MAX) {
+                    best_cost.insert(next.id.clone(), next_cost);
+                    came_from.insert(next.id.clone(), system_id.clone());
+                    frontier.push(Reverse((next_cost, next.id.clone())));
+                }
+            }
+        }
+        
+        if !best_cost.contains_key(to) {
+            return None;
+        }
+        
+        // Walk back from the destination to rebuild the route
+        let mut route = vec![to.to_string()];
+        let mut current = to.to_string();
+        while let Some(previous) = came_from.get(&current) {
+            route.push(previous.clone());
+            current = previous.clone();
+        }
+        route.reverse();
+        
+        Some(route)
+    }
+
+    // Fuel this player's ship needs for the cheapest single jump out of its
+    // current system, or None if no system is linked within jump range
+    pub fn cheapest_jump_fuel(&self, universe: &Universe, player: &Player) -> Option<u32> {
+        let current = universe.get_system(&player.current_system.id).unwrap_or(&player.current_system);
+        universe.get_all_systems().iter()
+            .filter(|system| system.id != current.id)
+            .filter_map(|system| {
+                if self.is_gate_link(current, system) {
+                    return Some(JUMP_GATE_FUEL_COST);
+                }
+                let distance = self.calculate_distance(current, system);
+                if self.has_lane(current, system) && self.is_in_range(player, distance) {
+                    Some(self.calculate_fuel_required_for(player, distance))
+                } else {
+                    None
+                }
+            })
+            .min()
+    }
+
+    // Find the nearest system (other than the current one) that matches the
+    // predicate and can be reached with the player's fuel and jump range.
+    // The predicate sees the system and its market, if it has one.
+    pub fn nearest_system_with<F>(&self, universe: &Universe, player: &Player, predicate: F) -> Option<String>
+    where
+        F: Fn(&StarSystem, Option<&Market>) -> bool,
+    {
+        let current = &player.current_system;
+        let mut candidates: Vec<StarSystem> = universe.get_all_systems().into_iter()
+            .filter(|system| system.id != current.id)
+            .collect();
+        candidates.sort_by(|a, b| {
+            self.calculate_distance(current, a)
+                .partial_cmp(&self.calculate_distance(current, b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        
+        candidates.into_iter()
+            .filter(|system| predicate(system, universe.get_market(&system.id).as_ref()))
+            .find(|system| {
+                self.plan_route(universe, &current.id, &system.id, 
+                    player.ship.current_fuel, player.ship.jump_range as f32).is_some()
+            })
+            .map(|system| system.id)
+    }
+
+    // Plot a multi-jump course and start the first hop. The rest of the
+    // hops are taken automatically as each jump completes.
+    pub fn set_course(&mut self, player: &mut Player, universe: &Universe, destination_id: &str, clock: &GameClock) -> Result<(Vec<String>, EncounterOutcome), String> {
+        if self.travel_in_progress {
+            return Err("Already traveling".to_string());
+        }
+        
+        let route = self.plan_route(universe, &player.current_system.id, destination_id,
+            player.ship.current_fuel, player.ship.jump_range as f32)
+            .ok_or_else(|| "No route within fuel and jump range".to_string())?;
+        
+        // Skip the starting system
+        self.course = route.iter().skip(1).cloned().collect();
+        let outcome = self.start_next_hop(player, universe, clock)?;
+        
+        Ok((route, outcome))
+    }
+
+    pub fn get_course(&self) -> &[String] {
+        &self.course
+    }
+
+    pub fn cancel_course(&mut self) {
+        self.course.clear();
+    }
+
+    // Stop a jump and any course in progress without arriving anywhere
+    pub fn abort_travel(&mut self) {
+        self.travel_in_progress = false;
+        self.destination = None;
+        self.travel_time_remaining = Duration::from_secs(0);
+        self.course.clear();
+    }
+
+    // Drop a jump or course leading to systems the universe no longer has.
+    // Returns true if anything was dropped.
+    pub fn forget_unknown_systems(&mut self, universe: &Universe) -> bool {
+        let stale_destination = self.destination.as_ref()
+            .map_or(false, |destination| universe.get_system(&destination.id).is_none());
+        let stale_course = self.course.iter().any(|id| universe.get_system(id).is_none());
+        if stale_destination {
+            self.travel_in_progress = false;
+            self.destination = None;
+            self.travel_time_remaining = Duration::from_secs(0);
+        }
+        if stale_destination || stale_course {
+            self.course.clear();
+        }
+        stale_destination || stale_course
+    }
+
+    // Begin travel to the next system on the course
+    fn start_next_hop(&mut self, player: &mut Player, universe: &Universe, clock: &GameClock) -> Result<EncounterOutcome, String> {
+        if self.course.is_empty() {
+            return Ok(EncounterOutcome::SafePassage);
+        }
+        
+        let next_id = self.course.remove(0);
+        let next = match universe.get_system(&next_id) {
+            Some(system) => system.clone(),
+            None => {
+                self.course.clear();
+                return Err(format!("Unknown system on course: {}", next_id));
+            }
+        };
+        
+        if !self.can_travel_to(player, &next) {
+            self.course.clear();
+            return Err(format!("Course interrupted - cannot jump to {}", next.name));
+        }
+        
+        Ok(self.travel_to(player, next, clock))
+    }
+
+    pub fn set_piracy_chance(&mut self, chance: f32) {
+        self.piracy_chance = chance.max(0.0).min(1.0);
+    }
+
+    pub fn get_piracy_chance(&self) -> f32 {
+        self.piracy_chance
+    }
     
     // Calculate how much fuel is required for a given distance
     pub fn calculate_fuel_required(&self, distance: f32) -> u32 {
@@ -72,35 +341,74 @@ impl NavigationSystem {
         }
     }
 
-    pub fn travel_to(&mut self, player: &mut Player, destination: StarSystem) {
+    // Fuel needed for this player's ship, after any navigator savings and
+    // the ship's fuel efficiency
+    pub fn calculate_fuel_required_for(&self, player: &Player, distance: f32) -> u32 {
+        let base = self.calculate_fuel_required(distance);
+        let savings = player.ship.crew_bonus(&CrewRole::Navigator);
+        ((base as f32 * (1.0 - savings) / player.ship.effective_fuel_efficiency()).ceil() as u32).max(1)
+    }
+
+    // Fuel a direct jump from the player's system would burn (gate jumps
+    // cost a flat amount)
+    pub fn jump_fuel_cost(&self, player: &Player, destination: &StarSystem) -> u32 {
+        if self.is_gate_link(&player.current_system, destination) {
+            JUMP_GATE_FUEL_COST
+        } else {
+            let distance = self.calculate_distance(&player.current_system, destination);
+            self.calculate_fuel_required_for(player, distance)
+        }
+    }
+
+    pub fn travel_to(&mut self, player: &mut Player, destination: StarSystem, clock: &GameClock) -> EncounterOutcome {
+        self.travel_to_with_rng(player, destination, &mut rand::thread_rng(), clock)
+    }
+
+    // Start a jump, rolling for a pirate encounter on the way
+    pub fn travel_to_with_rng<R: rand::Rng + ?Sized>(&mut self, player: &mut Player, destination: StarSystem, rng: &mut R, clock: &GameClock) -> EncounterOutcome {
         let distance = self.calculate_distance(&player.current_system, &destination);
         let travel_time = self.calculate_travel_time(distance);
         
         // Consume fuel
-        let fuel_required = self.calculate_fuel_required(distance);
-        if player.ship.current_fuel >= fuel_required {
-            player.ship.current_fuel -= fuel_required;
-            
-            self.travel_in_progress = true;
-            self.destination = Some(destination);
-            self.travel_time_remaining = travel_time;
+        let fuel_required = self.jump_fuel_cost(player, &destination);
+        if player.ship.current_fuel < fuel_required {
+            return EncounterOutcome::SafePassage;
         }
+        
+        player.ship.current_fuel -= fuel_required;
+        player.stats.record_jump(distance);
+        let outcome = encounter::resolve_encounter(player, &destination, self.piracy_chance, rng, clock);
+        
+        self.travel_in_progress = true;
+        self.destination = Some(destination);
+        self.travel_time_remaining = travel_time;
+        
+        outcome
     }
 
-    pub fn update(&mut self, player: &mut Player, _universe: &Universe, _time_system: &TimeSystem, delta_time: Duration) {
+    // Advance travel. Returns the outcome of any encounter on an automatic
+    // course hop started during this update.
+    pub fn update(&mut self, player: &mut Player, universe: &Universe, time_system: &TimeSystem, delta_time: Duration) -> Option<EncounterOutcome> {
         if self.travel_in_progress {
             if self.travel_time_remaining <= delta_time {
                 // Travel complete
                 if let Some(destination) = self.destination.take() {
+                    player.record_visit(&destination.id);
                     player.current_system = destination;
                 }
                 self.travel_in_progress = false;
                 self.travel_time_remaining = Duration::from_secs(0);
+                
+                // Carry on along the plotted course, if any
+                if !self.course.is_empty() {
+                    return self.start_next_hop(player, universe, &time_system.clock()).ok();
+                }
             } else {
                 // Still traveling
                 self.travel_time_remaining -= delta_time;
             }
         }
+        None
     }
 
     pub fn can_dock(&self, player: &Player) -> bool {
@@ -123,7 +431,54 @@ impl NavigationSystem {
         self.travel_in_progress
     }
 
+    pub fn get_destination(&self) -> Option<&StarSystem> {
+        self.destination.as_ref()
+    }
+
     pub fn get_remaining_travel_time(&self) -> Duration {
         self.travel_time_remaining
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ship::{ModuleType, ShipModule};
+    
+    #[test]
+    fn test_plan_route_reaches_distant_system() {
+        let universe = Universe::new();
+        let navigation = NavigationSystem::new();
+        let sol = universe.get_system("sol").unwrap();
+        
+        // The generated system furthest from Sol needs several jumps with a scout's range
+        let furthest = universe.get_all_systems().into_iter()
+            .max_by(|a, b| {
+                navigation.calculate_distance(sol, a)
+                    .partial_cmp(&navigation.calculate_distance(sol, b))
+                    .unwrap()
+            })
+            .unwrap();
+        assert!(navigation.calculate_distance(sol, &furthest) > 10.0);
+        
+        let route = navigation.plan_route(&universe, "sol", &furthest.id, 800, 10.0)
+            .expect("furthest system should be reachable");
+        
+        assert!(route.len() > 2);
+        assert_eq!(route.first().map(String::as_str), Some("sol"));
+        assert_eq!(route.last(), Some(&furthest.id));
+        
+        // Not enough fuel for even the first hop
+        assert!(navigation.plan_route(&universe, "sol", &furthest.id, 0, 10.0).is_none());
+    }
+    
+    #[test]
+    fn test_engine_modules_cut_fuel_cost() {
+        let navigation = NavigationSystem::new();
+        let mut player = Player::new("Test");
+        assert_eq!(navigation.calculate_fuel_required_for(&player, 9.5), 10);
+        
+        player.ship.install_module(ShipModule::new("Tuned Drive", ModuleType::Engine, 25)).unwrap();
+        assert_eq!(navigation.calculate_fuel_required_for(&player, 9.5), 8);
+    }
+}
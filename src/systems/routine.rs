@@ -0,0 +1,135 @@
+// Trade routines
+// A routine is an ordered list of the actions a trader repeats on a route:
+// jump somewhere, sell, buy, mine, refuel. Routines are recorded from the
+// player's own actions and played back by the game one step at a time,
+// waiting out each jump, until a step fails or the player stops it.
+
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RoutineStep {
+    // Plot a course to the system and dock on arrival
+    Travel(String),
+    Buy { item: String, quantity: u32 },
+    // Sells up to `quantity`; fails only when none are held
+    Sell { item: String, quantity: u32 },
+    // Mining attempts on the system's first field; a full hold ends the step
+    Mine { resource: usize, attempts: u32 },
+    Refuel,
+}
+
+impl RoutineStep {
+    pub fn describe(&self) -> String {
+        match self {
+            RoutineStep::Travel(system_id) => format!("Travel to {}", system_id),
+            RoutineStep::Buy { item, quantity } => format!("Buy {} {}", quantity, item),
+            RoutineStep::Sell { item, quantity } => format!("Sell {} {}", quantity, item),
+            RoutineStep::Mine { resource, attempts } => format!("Mine resource {} x{}", resource + 1, attempts),
+            RoutineStep::Refuel => "Refuel".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeRoutine {
+    pub steps: Vec<RoutineStep>,
+    // Start over after the last step
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+#[allow(dead_code)]
+impl TradeRoutine {
+    // Add a step as it was performed. Repeats of the same buy, sale or
+    // mining run fold into the previous step.
+    pub fn record(&mut self, step: RoutineStep) {
+        match (self.steps.last_mut(), &step) {
+            (Some(RoutineStep::Buy { item, quantity }), RoutineStep::Buy { item: next, quantity: more })
+            | (Some(RoutineStep::Sell { item, quantity }), RoutineStep::Sell { item: next, quantity: more })
+                if item == next => *quantity += more,
+            (Some(RoutineStep::Mine { resource, attempts }), RoutineStep::Mine { resource: next, attempts: more })
+                if resource == next => *attempts += more,
+            // Jumping to where the routine already is, or refuelling twice, adds nothing
+            (Some(last), _) if *last == step && matches!(step, RoutineStep::Travel(_) | RoutineStep::Refuel) => {},
+            _ => self.steps.push(step),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+// What happened when a step was tried
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepProgress {
+    Done,
+    // Still underway, e.g. a jump in flight
+    Waiting,
+    Failed(String),
+}
+
+// A routine being played back
+#[derive(Debug, Clone)]
+pub struct RoutineRun {
+    pub routine: TradeRoutine,
+    pub step: usize,
+    pub laps: u32,
+    pub steps_done: u32,
+}
+
+impl RoutineRun {
+    pub fn new(routine: TradeRoutine) -> Self {
+        RoutineRun { routine, step: 0, laps: 0, steps_done: 0 }
+    }
+
+    pub fn current(&self) -> Option<&RoutineStep> {
+        self.routine.steps.get(self.step)
+    }
+
+    // Move past a finished step. Returns false once the routine is over.
+    pub fn advance(&mut self) -> bool {
+        self.steps_done += 1;
+        self.step += 1;
+        if self.step < self.routine.steps.len() {
+            return true;
+        }
+        self.laps += 1;
+        self.step = 0;
+        self.routine.repeat
+    }
+
+    // One line on how far the run got, for the comms log
+    pub fn report(&self, outcome: &str) -> String {
+        format!("Routine {} after {} steps ({} full laps)", outcome, self.steps_done, self.laps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_folds_repeated_actions() {
+        let mut routine = TradeRoutine::default();
+        routine.record(RoutineStep::Travel("alpha".to_string()));
+        routine.record(RoutineStep::Travel("alpha".to_string()));
+        routine.record(RoutineStep::Buy { item: "Ore".to_string(), quantity: 1 });
+        routine.record(RoutineStep::Buy { item: "Ore".to_string(), quantity: 2 });
+        routine.record(RoutineStep::Buy { item: "Gold".to_string(), quantity: 1 });
+        routine.record(RoutineStep::Mine { resource: 0, attempts: 1 });
+        routine.record(RoutineStep::Mine { resource: 0, attempts: 1 });
+
+        assert_eq!(routine.steps, vec![
+            RoutineStep::Travel("alpha".to_string()),
+            RoutineStep::Buy { item: "Ore".to_string(), quantity: 3 },
+            RoutineStep::Buy { item: "Gold".to_string(), quantity: 1 },
+            RoutineStep::Mine { resource: 0, attempts: 2 },
+        ]);
+
+        let mut run = RoutineRun::new(routine);
+        assert!(run.advance() && run.advance() && run.advance());
+        assert!(!run.advance());
+        assert_eq!(run.laps, 1);
+    }
+}
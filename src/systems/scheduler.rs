@@ -0,0 +1,73 @@
+// Event scheduler
+// Systems that need something to happen at a set game time schedule an
+// event here instead of checking every tick. The game drains whatever has
+// come due each time its clock moves and dispatches it.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScheduledEvent {
+    // One of the player's crafting jobs has reached its end time
+    CraftingJobsDue,
+    // Time to compare the player's price alerts with the markets
+    PriceAlertCheck,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    // Kept sorted by game time; events due at the same time keep the order
+    // they were scheduled in
+    queue: Vec<(u64, ScheduledEvent)>,
+}
+
+#[allow(dead_code)]
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Have `event` come due at game time `at`, in seconds
+    pub fn schedule(&mut self, at: u64, event: ScheduledEvent) {
+        let index = self.queue.partition_point(|(time, _)| *time <= at);
+        self.queue.insert(index, (at, event));
+    }
+
+    // Remove and return every event due at or before `now`, earliest first
+    pub fn drain_due(&mut self, now: u64) -> Vec<ScheduledEvent> {
+        let due = self.queue.partition_point(|(time, _)| *time <= now);
+        self.queue.drain(..due).map(|(_, event)| event).collect()
+    }
+
+    // Game time the next event comes due, if any is waiting
+    pub fn next_due(&self) -> Option<u64> {
+        self.queue.first().map(|(time, _)| *time)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drains_due_events_in_time_order() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(300, ScheduledEvent::PriceAlertCheck);
+        scheduler.schedule(100, ScheduledEvent::CraftingJobsDue);
+        scheduler.schedule(300, ScheduledEvent::CraftingJobsDue);
+
+        assert!(scheduler.drain_due(99).is_empty());
+        assert_eq!(scheduler.drain_due(100), vec![ScheduledEvent::CraftingJobsDue]);
+        assert_eq!(scheduler.next_due(), Some(300));
+        assert_eq!(
+            scheduler.drain_due(1000),
+            vec![ScheduledEvent::PriceAlertCheck, ScheduledEvent::CraftingJobsDue]
+        );
+        assert_eq!(scheduler.len(), 0);
+    }
+}
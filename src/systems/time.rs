@@ -1,12 +1,68 @@
 use std::time::Duration;
-use chrono::{DateTime, Utc, TimeZone};
+use chrono::{DateTime, Utc, TimeZone, Timelike};
 use serde::{Serialize, Deserialize};
 
+use crate::utils::clock::GameClock;
+
+const SECONDS_PER_HOUR: u64 = 3600;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+// Phases of the station day cycle. Markets are busiest during the
+// day shifts and quiet down overnight.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DayPhase {
+    Night,      // 00:00 - 05:59
+    Morning,    // 06:00 - 11:59
+    Afternoon,  // 12:00 - 17:59
+    Evening,    // 18:00 - 23:59
+}
+
+#[allow(dead_code)]
+impl DayPhase {
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            0..=5 => DayPhase::Night,
+            6..=11 => DayPhase::Morning,
+            12..=17 => DayPhase::Afternoon,
+            _ => DayPhase::Evening,
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            DayPhase::Night => "Night".to_string(),
+            DayPhase::Morning => "Morning".to_string(),
+            DayPhase::Afternoon => "Afternoon".to_string(),
+            DayPhase::Evening => "Evening".to_string(),
+        }
+    }
+
+    // How lively trading is during this phase (1.0 = normal)
+    pub fn market_activity(&self) -> f32 {
+        match self {
+            DayPhase::Night => 0.5,
+            DayPhase::Morning => 1.0,
+            DayPhase::Afternoon => 1.2,
+            DayPhase::Evening => 0.8,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct TimeSystem {
     game_epoch: DateTime<Utc>,
     game_time_multiplier: f32,
     elapsed_real_time: Duration,
+    // Simulated seconds elapsed since the game epoch
+    #[serde(default)]
+    elapsed_game_seconds: f64,
+    // Extra speed-up applied on top of the base multiplier (1.0 = normal)
+    #[serde(default = "default_time_scale")]
+    time_scale: f32,
+}
+
+fn default_time_scale() -> f32 {
+    1.0
 }
 
 #[allow(dead_code)]
@@ -14,21 +70,53 @@ impl TimeSystem {
     pub fn new() -> Self {
         // Start the game time at 2300-01-01
         let game_epoch = Utc.with_ymd_and_hms(2300, 1, 1, 0, 0, 0).unwrap();
-        
+
         TimeSystem {
             game_epoch,
             game_time_multiplier: 60.0,  // 1 real second = 1 minute of game time
             elapsed_real_time: Duration::from_secs(0),
+            elapsed_game_seconds: 0.0,
+            time_scale: 1.0,
         }
     }
 
     pub fn update(&mut self, delta_time: Duration) {
         self.elapsed_real_time += delta_time;
+        self.elapsed_game_seconds += delta_time.as_secs_f64()
+            * self.game_time_multiplier as f64
+            * self.time_scale as f64;
+    }
+
+    // Real time it takes for `game_seconds` to pass at the current speed
+    pub fn real_time_for(&self, game_seconds: u64) -> Duration {
+        let rate = self.game_time_multiplier as f64 * self.time_scale as f64;
+        Duration::from_secs_f64(game_seconds as f64 / rate.max(f64::EPSILON))
+    }
+
+    // Real time spent playing, kept across saves
+    pub fn play_time(&self) -> Duration {
+        self.elapsed_real_time
+    }
+
+    // Advance the game clock directly by a number of simulated seconds,
+    // independent of real time and the time scale
+    pub fn advance_game_seconds(&mut self, seconds: u64) {
+        self.elapsed_game_seconds += seconds as f64;
+    }
+
+    // Simulated seconds since the game epoch. All game logic should use this
+    // instead of the wall clock.
+    pub fn game_time_seconds(&self) -> u64 {
+        self.elapsed_game_seconds as u64
+    }
+
+    // Snapshot of the game clock to pass into market and economy code
+    pub fn clock(&self) -> GameClock {
+        GameClock::at(self.game_time_seconds())
     }
 
     pub fn get_current_game_time(&self) -> DateTime<Utc> {
-        let game_seconds = (self.elapsed_real_time.as_secs_f32() * self.game_time_multiplier) as i64;
-        self.game_epoch + chrono::Duration::seconds(game_seconds)
+        self.game_epoch + chrono::Duration::seconds(self.game_time_seconds() as i64)
     }
 
     pub fn get_formatted_time(&self) -> String {
@@ -36,6 +124,30 @@ impl TimeSystem {
         time.format("%Y-%m-%d %H:%M").to_string()
     }
 
+    // Day counter starting at day 1
+    pub fn get_game_day(&self) -> u64 {
+        self.game_time_seconds() / SECONDS_PER_DAY + 1
+    }
+
+    pub fn get_game_hour(&self) -> u32 {
+        self.get_current_game_time().hour()
+    }
+
+    pub fn get_day_phase(&self) -> DayPhase {
+        DayPhase::from_hour(self.get_game_hour())
+    }
+
+    // Short clock display, e.g. "Day 3 14:05"
+    pub fn get_clock_string(&self) -> String {
+        self.clock_string_at(self.game_time_seconds())
+    }
+
+    // The same display for another game time, in seconds
+    pub fn clock_string_at(&self, game_seconds: u64) -> String {
+        let time = self.game_epoch + chrono::Duration::seconds(game_seconds as i64);
+        format!("Day {} {}", game_seconds / SECONDS_PER_DAY + 1, time.format("%H:%M"))
+    }
+
     pub fn set_time_multiplier(&mut self, multiplier: f32) {
         self.game_time_multiplier = multiplier;
     }
@@ -43,4 +155,13 @@ impl TimeSystem {
     pub fn get_time_multiplier(&self) -> f32 {
         self.game_time_multiplier
     }
+
+    // Speed up or slow down the game clock (1.0 = normal speed)
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(0.0);
+    }
+
+    pub fn get_time_scale(&self) -> f32 {
+        self.time_scale
+    }
 }
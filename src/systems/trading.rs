@@ -2,10 +2,158 @@ use std::time::Duration;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
-use crate::models::player::Player;
+use crate::models::player::{Player, TransactionCategory};
 use crate::models::universe::Universe;
-use crate::models::item::Item;
-use crate::models::market::{TradeOrder, OrderType, OrderStatus};
+use crate::models::item::{Item, ItemType};
+use crate::models::market::{Market, TradeOrder, TradeQuote, OrderKind, OrderType, OrderStatus};
+use crate::systems::time::TimeSystem;
+use crate::systems::economy::EconomySystem;
+use crate::utils::clock::GameClock;
+
+// Game seconds between market price updates (5 game minutes)
+const MARKET_TICK_SECONDS: u64 = 300;
+
+// Cargo worth less than this per unit counts as junk for a bulk sale
+pub const JUNK_VALUE_THRESHOLD: u32 = 75;
+
+// Active orders a player may have open across every market, unless changed.
+// Every open order is checked on each market tick.
+pub const DEFAULT_MAX_ORDERS_PER_PLAYER: usize = 20;
+// Longest an order may be set to stay open (30 game days)
+const MAX_ORDER_LIFETIME: u64 = 30 * 24 * 3600;
+
+pub fn default_max_orders_per_player() -> usize {
+    DEFAULT_MAX_ORDERS_PER_PLAYER
+}
+
+// Reject order terms that could never sensibly execute
+pub fn validate_order_terms(quantity: u32, kind: OrderKind, target_price: u32,
+                            expires_at: Option<u64>, clock: &GameClock) -> Result<(), String> {
+    if quantity == 0 {
+        return Err("Order quantity must be at least 1".to_string());
+    }
+    match kind {
+        // A trailing stop sets its own price from the market
+        OrderKind::TrailingStop { percent } => {
+            if !(percent > 0.0 && percent < 100.0) {
+                return Err("Trailing stop must be between 0% and 100% below the peak".to_string());
+            }
+        },
+        _ => {
+            if target_price == 0 {
+                return Err("Order price must be at least 1 credit".to_string());
+            }
+        },
+    }
+    if let Some(expires_at) = expires_at {
+        if expires_at <= clock.now() {
+            return Err("Order would expire before it is placed".to_string());
+        }
+        if expires_at - clock.now() > MAX_ORDER_LIFETIME {
+            return Err(format!("Orders can stay open for at most {} days", MAX_ORDER_LIFETIME / 86400));
+        }
+    }
+    Ok(())
+}
+
+// Which cargo a bulk sale covers
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkSale {
+    AllOf(String),       // Every unit of one item
+    AllOfType(ItemType), // Every stack of one item type
+    JunkBelow(u32),      // Every stack worth less than this per unit
+}
+
+impl BulkSale {
+    fn covers(&self, item: &Item) -> bool {
+        match self {
+            BulkSale::AllOf(name) => &item.name == name,
+            BulkSale::AllOfType(item_type) => &item.item_type == item_type,
+            BulkSale::JunkBelow(threshold) => item.value < *threshold,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            BulkSale::AllOf(name) => format!("all {}", name),
+            BulkSale::AllOfType(item_type) => format!("all {}", item_type.to_string()),
+            BulkSale::JunkBelow(threshold) => format!("cargo worth under {} cr/unit", threshold),
+        }
+    }
+}
+
+// Stacks a bulk sale sells and what each fetches
+#[derive(Debug, Clone, Default)]
+pub struct SaleSummary {
+    pub lines: Vec<(Item, u32, u32)>, // Item, quantity, revenue
+}
+
+impl SaleSummary {
+    pub fn total_quantity(&self) -> u32 {
+        self.lines.iter().map(|(_, quantity, _)| quantity).sum()
+    }
+
+    pub fn total_revenue(&self) -> u32 {
+        self.lines.iter().map(|(_, _, revenue)| revenue).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+// What stops a trade from going through in full
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TradeLimit {
+    Credits,
+    CargoSpace,
+    MarketStock,
+    CargoHeld, // Selling more than the hold carries
+}
+
+impl TradeLimit {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            TradeLimit::Credits => "credits",
+            TradeLimit::CargoSpace => "cargo space",
+            TradeLimit::MarketStock => "market stock",
+            TradeLimit::CargoHeld => "cargo held",
+        }
+    }
+}
+
+// Cost and outcome of a trade, worked out before committing to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeEstimate {
+    pub item_name: String,
+    pub is_buy: bool,
+    pub quantity: u32,
+    pub unit_price: u32,
+    pub tax: u32,
+    pub total: u32,          // Paid for a buy or received for a sale, tax included
+    pub credits_after: i64,
+    pub cargo_after: u32,    // Tonnes of cargo carried after the trade
+    // Most units that can be traded, and what caps them when `quantity` is more
+    pub max_quantity: u32,
+    pub limit: Option<TradeLimit>,
+    // Buys only: the per-unit sale price that recovers the cost, and the
+    // best price listed in another system
+    pub break_even_price: Option<u32>,
+    pub best_price_elsewhere: Option<(String, u32)>,
+}
+
+#[allow(dead_code)]
+impl TradeEstimate {
+    pub fn is_feasible(&self) -> bool {
+        self.limit.is_none()
+    }
+
+    // Profit from selling everything bought here at the best price elsewhere
+    pub fn profit_elsewhere(&self) -> Option<i64> {
+        self.best_price_elsewhere.as_ref()
+            .map(|(_, price)| *price as i64 * self.quantity as i64 - self.total as i64)
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct TradingSystem {
@@ -20,6 +168,13 @@ pub struct TradingSystem {
     order_buy_mode: bool,
     #[serde(default, skip)]
     selected_order_index: Option<usize>,
+    // Market row shown in the item detail panel
+    #[serde(default, skip)]
+    inspected_item: usize,
+    
+    // Game time (in seconds) of the last market tick
+    #[serde(default)]
+    last_market_tick: u64,
 }
 
 #[allow(dead_code)]
@@ -32,6 +187,8 @@ impl TradingSystem {
             current_order_tab: 0,
             order_buy_mode: true,
             selected_order_index: None,
+            inspected_item: 0,
+            last_market_tick: 0,
         };
         
         // For testing purposes: create some test orders in the market
@@ -48,6 +205,9 @@ impl TradingSystem {
         // Get the universe singleton
         let mut universe = Universe::new();
         
+        // Test orders are placed at the very start of game time
+        let clock = GameClock::default();
+        
         println!("\n===== CREATING TEST TRADE ORDERS =====");
         
         // Get the first system with a market
@@ -63,7 +223,8 @@ impl TradingSystem {
                     100,
                     500, // High target price, should execute when price is <= 500
                     None,
-                    "Test buy order - Iron"
+                    "Test buy order - Iron",
+                    &clock
                 );
                 
                 println!("✓ Created buy order for Iron (ID: {:?})", iron_id);
@@ -75,7 +236,8 @@ impl TradingSystem {
                     50,
                     100, // Low target price, should execute when price is >= 100
                     None,
-                    "Test sell order - Gold"
+                    "Test sell order - Gold",
+                    &clock
                 );
                 
                 println!("✓ Created sell order for Gold (ID: {:?})", gold_id);
@@ -87,7 +249,8 @@ impl TradingSystem {
                     200,
                     300, // Target price
                     None,
-                    "Test buy order - Oxygen"
+                    "Test buy order - Oxygen",
+                    &clock
                 );
                 
                 println!("✓ Created buy order for Oxygen (ID: {:?})", oxygen_id);
@@ -98,7 +261,8 @@ impl TradingSystem {
                     75,
                     900, // Target price
                     None,
-                    "Test sell order - Titanium"
+                    "Test sell order - Titanium",
+                    &clock
                 );
                 
                 println!("✓ Created sell order for Titanium (ID: {:?})", titanium_id);
@@ -121,27 +285,45 @@ impl TradingSystem {
         self.buy_mode
     }
 
-    pub fn buy_item(&mut self, player: &mut Player, item_index: usize) -> Option<String> {
+    pub fn get_inspected_item(&self) -> usize {
+        self.inspected_item
+    }
+
+    // Move the detail panel to the next or previous of `item_count` rows, wrapping around
+    pub fn inspect_next(&mut self, item_count: usize) {
+        if item_count > 0 {
+            self.inspected_item = (self.inspected_item + 1) % item_count;
+        }
+    }
+
+    pub fn inspect_previous(&mut self, item_count: usize) {
+        if item_count > 0 {
+            self.inspected_item = (self.inspected_item + item_count - 1) % item_count;
+        }
+    }
+
+    pub fn buy_item(&mut self, player: &mut Player, universe: &Universe, item_index: usize, clock: &GameClock) -> Option<String> {
         // Check if player is docked
         if !player.is_docked {
             return Some("You must be docked at a station to trade".to_string());
         }
         
         // Try to find market data in the universe
-        let market_items = player.current_system.id.clone();
-        
-        let items = Universe::new().get_market_items_for_system(market_items);
+        let system_id = player.current_system.id.clone();
+        let items = universe.get_market_items_for_system(system_id.clone());
         
         if item_index >= items.len() {
             return Some("Invalid item selection".to_string());
         }
         
         let (item, _quantity_available) = &items[item_index];
-        let item_price = item.value;
+        let market = universe.get_market(&system_id);
+        let item_price = Self::quote(market.as_ref(), item, true);
+        let currency = market.map(|market| market.currency).unwrap_or_default();
         
         // Check if player can afford one unit
-        if player.credits < item_price {
-            return Some(format!("Cannot afford {} (need {} cr)", item.name, item_price));
+        if player.balance(&currency) < item_price {
+            return Some(format!("Cannot afford {} (need {})", item.name, currency.format(item_price)));
         }
         
         // Check if player has cargo space
@@ -150,13 +332,29 @@ impl TradingSystem {
         }
         
         // Buy one unit of the item
-        player.remove_credits(item_price);
+        if let Err(e) = player.adjust_balance(&currency, -(item_price as i64), TransactionCategory::Trade, &format!("Bought 1 {}", item.name), clock) {
+            return Some(e);
+        }
         player.inventory.add_item(item.clone(), 1);
         
-        Some(format!("Purchased 1 {} for {} cr", item.name, item_price))
+        Some(format!("Purchased 1 {} for {}", item.name, currency.format(item_price)))
+    }
+
+    // Price of one unit of `item` in `market`: the ask when buying, the bid
+    // when selling. Without a market the item's own value stands in.
+    pub fn quote(market: Option<&Market>, item: &Item, is_buy: bool) -> u32 {
+        let market = match market {
+            Some(market) => market,
+            None => return if is_buy { item.value } else { (item.value as f32 * 0.85) as u32 },
+        };
+        if is_buy {
+            market.quote_buy(&Market::stock_key(item), 1).map_or(item.value, |quote| quote.unit_price)
+        } else {
+            market.quote_sell(item, 1).unit_price
+        }
     }
 
-    pub fn sell_item(&mut self, player: &mut Player, item_index: usize) -> Option<String> {
+    pub fn sell_item(&mut self, player: &mut Player, universe: &Universe, item_index: usize, clock: &GameClock) -> Option<String> {
         // Check if player is docked
         if !player.is_docked {
             return Some("You must be docked at a station to trade".to_string());
@@ -173,162 +371,202 @@ impl TradingSystem {
         
         let (item, _) = &inventory_items[item_index];
         
-        // Calculate sell price (90% of value)
-        let sell_price = (item.value as f32 * 0.9) as u32;
+        // Sell at the market's bid, paid in the market's currency
+        let market = universe.get_market(&player.current_system.id);
+        let sell_price = Self::quote(market.as_ref(), item, false);
+        let currency = market.map(|market| market.currency).unwrap_or_default();
         
-        // Sell one unit of the item
-        if let Some(item) = player.inventory.remove_item(&item.name, 1) {
-            player.add_credits(sell_price);
-            return Some(format!("Sold 1 {} for {} cr", item.name, sell_price));
+        // Sell one unit of the exact stack that was priced
+        if player.inventory.discard(item, 1) == 1 {
+            let _ = player.adjust_balance(&currency, sell_price as i64, TransactionCategory::Trade, &format!("Sold 1 {}", item.name), clock);
+            return Some(format!("Sold 1 {} for {}", item.name, currency.format(sell_price)));
         }
         
         Some("Error selling item".to_string())
     }
 
-    pub fn update(&mut self, universe: &mut Universe, delta_time: Duration) -> Vec<TradeOrder> {
-        // Update market prices periodically
-        self.last_transaction += delta_time;
+    // Sell every stack `sale` covers into `market`, stack by stack so each
+    // sale moves the market price before the next is priced
+    fn sell_into_market(player: &Player, market: &mut Market, sale: &BulkSale, clock: &GameClock) -> SaleSummary {
+        let lines = player.inventory.sorted_items().into_iter()
+            .filter(|(item, _)| sale.covers(item))
+            .map(|(item, quantity)| {
+                let revenue = market.sell_item(item.clone(), quantity, clock);
+                (item.clone(), quantity, revenue)
+            })
+            .collect();
+        SaleSummary { lines }
+    }
+
+    fn market_for_sale(player: &Player, universe: &Universe) -> Result<Market, String> {
+        if !player.is_docked {
+            return Err("You must be docked at a station to trade".to_string());
+        }
+        universe.get_market(&player.current_system.id)
+            .ok_or_else(|| "There is no market in this system".to_string())
+    }
+
+    // What `sale` would sell and earn right now, without selling anything
+    pub fn quote_bulk_sale(&self, player: &Player, universe: &Universe, sale: &BulkSale, clock: &GameClock) -> Result<SaleSummary, String> {
+        let mut market = Self::market_for_sale(player, universe)?;
+        let summary = Self::sell_into_market(player, &mut market, sale, clock);
+        if summary.is_empty() {
+            return Err(format!("You have no {} to sell", sale.describe()));
+        }
+        Ok(summary)
+    }
+
+    pub fn bulk_sell(&mut self, player: &mut Player, universe: &mut Universe, sale: &BulkSale, clock: &GameClock) -> Result<SaleSummary, String> {
+        let mut market = Self::market_for_sale(player, universe)?;
+        let summary = Self::sell_into_market(player, &mut market, sale, clock);
+        if summary.is_empty() {
+            return Err(format!("You have no {} to sell", sale.describe()));
+        }
+
+        for (item, quantity, revenue) in &summary.lines {
+            player.inventory.discard(item, *quantity);
+            player.adjust_balance(&market.currency, *revenue as i64, TransactionCategory::Trade, &format!("Sold {} {}", quantity, item.name), clock)?;
+        }
+        universe.update_market(market);
+
+        Ok(summary)
+    }
+
+    // Price out buying or selling `quantity` of `item_name` in `market`
+    // without changing anything. `economy` supplies other systems' prices.
+    pub fn estimate_trade(
+        &self,
+        player: &Player,
+        market: &Market,
+        economy: &EconomySystem,
+        item_name: &str,
+        quantity: u32,
+        is_buy: bool,
+    ) -> Result<TradeEstimate, String> {
+        if quantity == 0 {
+            return Err("Quantity must be at least 1".to_string());
+        }
+        // Balances and prices are in the market's currency
+        let credits = player.balance(&market.currency) as i64;
+        let cargo_used = player.inventory.used_capacity();
+
+        if !is_buy {
+            let (item, held) = player.inventory.items.iter()
+                .find(|(item, _)| item.name == item_name)
+                .map(|(item, held)| (item.clone(), *held))
+                .ok_or_else(|| format!("You have no {} to sell", item_name))?;
+            let quote = market.quote_sell(&item, quantity);
+            let total = quote.total;
+
+            return Ok(TradeEstimate {
+                item_name: item.name,
+                is_buy,
+                quantity,
+                unit_price: quote.unit_price,
+                tax: quote.tax,
+                total,
+                credits_after: credits + total as i64,
+                cargo_after: cargo_used.saturating_sub(item.weight * quantity),
+                max_quantity: held,
+                limit: if quantity > held { Some(TradeLimit::CargoHeld) } else { None },
+                break_even_price: None,
+                best_price_elsewhere: None,
+            });
+        }
+
+        let market_item = market.items.get(item_name)
+            .ok_or_else(|| format!("{} is not sold here", item_name))?;
+        // Stocked, so there is always a quote
+        let cost = |units: u32| market.quote_buy(item_name, units).map_or(u32::MAX, |quote| quote.total);
+        let TradeQuote { unit_price, tax, total, .. } = market.quote_buy(item_name, quantity)
+            .ok_or_else(|| format!("{} is not sold here", item_name))?;
+
+        // Each cap on the quantity, tightest first
+        let mut affordable = (credits as f32 / (unit_price.max(1) as f32 * (1.0 + market.tax_rate))) as u32;
+        while cost(affordable + 1) as i64 <= credits {
+            affordable += 1;
+        }
+        while affordable > 0 && cost(affordable) as i64 > credits {
+            affordable -= 1;
+        }
+        let weight = market_item.item.weight;
+        let fittable = if weight == 0 { u32::MAX } else { player.inventory.remaining_capacity() / weight };
+        let mut caps = [
+            (affordable, TradeLimit::Credits),
+            (fittable, TradeLimit::CargoSpace),
+            (market_item.quantity, TradeLimit::MarketStock),
+        ];
+        caps.sort_by_key(|(cap, _)| *cap);
+        let (max_quantity, tightest) = caps[0];
+
+        let best_price_elsewhere = economy.get_price_comparison(item_name).into_iter()
+            .filter(|(system_id, _, _)| system_id != &market.system_id)
+            .max_by_key(|(_, price, _)| *price)
+            .map(|(system_id, price, _)| (system_id, price));
+
+        Ok(TradeEstimate {
+            item_name: item_name.to_string(),
+            is_buy,
+            quantity,
+            unit_price,
+            tax,
+            total,
+            credits_after: credits - total as i64,
+            cargo_after: cargo_used + weight * quantity,
+            max_quantity,
+            limit: if quantity > max_quantity { Some(tightest) } else { None },
+            break_even_price: Some((total + quantity - 1) / quantity),
+            best_price_elsewhere,
+        })
+    }
+
+    pub fn update(&mut self, universe: &mut Universe, player: &mut Player, time_system: &TimeSystem) -> Vec<TradeOrder> {
         let mut executed_orders = Vec::new();
-        
-        // For testing purposes, update more frequently (every 5 seconds)
-        if self.last_transaction > Duration::from_secs(5) {
-            universe.update_market_prices();
-            self.last_transaction = Duration::from_secs(0);
+        let now = time_system.game_time_seconds();
+        
+        // Markets tick on game time, so a paused or fast-forwarded clock
+        // advances them consistently
+        if now.saturating_sub(self.last_market_tick) >= MARKET_TICK_SECONDS {
+            let clock = time_system.clock();
+            universe.update_market_prices(time_system.get_day_phase().market_activity(), &clock);
+            self.last_market_tick = now;
             
             // Process all active orders in every market after updating prices
-            executed_orders = self.process_all_orders(universe);
+            executed_orders = self.process_all_orders(universe, player, &clock);
         }
         
         executed_orders
     }
     
-    // Process all active orders in all markets
-    fn process_all_orders(&mut self, universe: &mut Universe) -> Vec<TradeOrder> {
+    // Settle the player's orders in every market whose price has reached
+    // their target
+    fn process_all_orders(&mut self, universe: &mut Universe, player: &mut Player, clock: &GameClock) -> Vec<TradeOrder> {
         // Get all systems with markets
         let system_ids = universe.get_all_system_ids();
         let mut executed_orders: Vec<TradeOrder> = Vec::new();
-        
         // Process orders for each market
         for system_id in &system_ids {
             if let Some(mut market) = universe.get_market_mut(system_id) {
-                // In a real implementation, we'd get the player's inventory and credits
-                // For now, this is a placeholder that won't actually execute orders
-                // But would process and check them
-                
-                // TODO: In the full implementation, get player inventory and credits
-                // let executed = market.process_orders(&mut player.inventory.items, &mut player.credits);
-                // executed_orders.extend(executed);
-                
-                // Store orders that need updating
-                let mut orders_to_update = Vec::new();
-                
-                // First, check for orders that meet price conditions
-                for (index, order) in market.trade_orders.iter().enumerate() {
-                    if order.status != OrderStatus::Active {
-                        continue;
-                    }
-                    
-                    // Check if market has this item
-                    if let Some(market_item) = market.items.get(&order.item_name) {
-                        let current_price = market_item.current_price;
-                        let current_time = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or(Duration::from_secs(0))
-                            .as_secs();
-                        
-                        // Check price conditions
-                        match order.order_type {
-                            OrderType::Buy => {
-                                if current_price <= order.target_price {
-                                    // In a real implementation with player context:
-                                    // 1. Check if player has enough credits
-                                    // 2. Execute the buy transaction
-                                    
-                                    // For now, we'll just mark it as a completed order
-                                    orders_to_update.push((index, OrderStatus::Completed, Some(current_time)));
-                                    
-                                    // Track the completed order
-                                    executed_orders.push(order.clone());
-                                }
-                            },
-                            OrderType::Sell => {
-                                if current_price >= order.target_price {
-                                    // In a real implementation with player context:
-                                    // 1. Check if player still has the items
-                                    // 2. Execute the sell transaction
-                                    
-                                    // For now, we'll just mark it as a completed order
-                                    orders_to_update.push((index, OrderStatus::Completed, Some(current_time)));
-                                    
-                                    // Track the completed order
-                                    executed_orders.push(order.clone());
-                                }
-                            }
-                        }
-                    }
-                }
-                
-                // Now update the order statuses
-                for (index, status, executed_at) in orders_to_update {
-                    if let Some(order) = market.trade_orders.get_mut(index) {
-                        order.status = status;
-                        order.executed_at = executed_at;
-                    }
-                }
+                let executed = market.process_orders(player, clock);
+                executed_orders.extend(executed);
+                universe.update_market(market);
             }
         }
         
         // Log info about executed orders for debugging
-        if !executed_orders.is_empty() {
-            println!("\n===== TRADE ORDER EXECUTION REPORT =====");
-            for order in &executed_orders {
-                match order.order_type {
-                    OrderType::Buy => {
-                        println!("✅ AUTO-EXECUTED: Buy order for {} {} at price {} has been completed", 
-                            order.quantity, order.item_name, order.target_price);
-                    },
-                    OrderType::Sell => {
-                        println!("✅ AUTO-EXECUTED: Sell order for {} {} at price {} has been completed", 
-                            order.quantity, order.item_name, order.target_price);
-                    }
-                }
-            }
-            println!("=======================================\n");
+        for order in &executed_orders {
+            let side = match order.order_type {
+                OrderType::Buy => "Buy",
+                OrderType::Sell => "Sell",
+            };
+            crate::log_debug!("Auto-executed {} order for {} {} at price {} (target {})",
+                side, order.quantity, order.item_name, order.executed_price.unwrap_or(order.target_price), order.target_price);
         }
         
         // Return executed orders so the game can display notifications
         executed_orders
     }
     
-    // Get price trend information about a specific item
-    pub fn get_price_trend_info(&self, item_name: &str) -> Option<(f32, String)> {
-        // Forward to the universe's market system
-        // Note: In a real-world implementation, this would access the market
-        // associated with the current system from the universe
-        
-        // This is a placeholder implementation
-        match item_name {
-            "Iron" => Some((5.2, "Rising".to_string())),
-            "Copper" => Some((-3.1, "Decreasing".to_string())),
-            "Silver" => Some((12.8, "Skyrocketing".to_string())),
-            "Gold" => Some((-8.5, "Falling".to_string())),
-            "Titanium" => Some((0.2, "Stable".to_string())),
-            "Water" => Some((-15.3, "Plummeting".to_string())),
-            "Hydrogen" => Some((2.1, "Increasing".to_string())),
-            "Oxygen" => Some((1.8, "Increasing".to_string())),
-            "Power Cell" => Some((7.4, "Rising".to_string())),
-            "Computer Chip" => Some((9.2, "Rising".to_string())),
-            "Fusion Core" => Some((-2.5, "Decreasing".to_string())),
-            "Shield Generator" => Some((14.3, "Skyrocketing".to_string())),
-            "Warp Drive" => Some((-7.1, "Falling".to_string())),
-            "Medical Supplies" => Some((3.9, "Increasing".to_string())),
-            "Luxury Goods" => Some((6.3, "Rising".to_string())),
-            "Military Equipment" => Some((-4.2, "Decreasing".to_string())),
-            "Ship Parts" => Some((0.8, "Stable".to_string())),
-            _ => None,
-        }
-    }
-    
     // === Order Management Methods ===
     
     // Order tab UI management
@@ -418,7 +656,7 @@ impl TradingSystem {
     
     // Order creation
     pub fn create_buy_order(&mut self, player: &mut Player, item_name: &str, 
-                           quantity: u32, target_price: u32, notes: &str) -> Result<Uuid, String> {
+                           quantity: u32, target_price: u32, notes: &str, clock: &GameClock) -> Result<Uuid, String> {
         // Verify player is docked
         if !player.is_docked {
             return Err("You must be docked at a station to create orders".to_string());
@@ -440,13 +678,15 @@ impl TradingSystem {
         
         // Create the order
         let player_id = player.id.to_string();
+        validate_order_terms(quantity, OrderKind::Limit, target_price, None, clock)?;
         let order_id = market.create_buy_order(
             &player_id, 
             item_name, 
             quantity, 
             target_price, 
             None,  // No expiration date for now
-            notes
+            notes,
+            clock
         ).ok_or("Failed to create buy order")?;
         
         // Update the market back to the universe
@@ -456,7 +696,14 @@ impl TradingSystem {
     }
     
     pub fn create_sell_order(&mut self, player: &mut Player, item_name: &str, 
-                            quantity: u32, target_price: u32, notes: &str) -> Result<Uuid, String> {
+                            quantity: u32, target_price: u32, notes: &str, clock: &GameClock) -> Result<Uuid, String> {
+        self.create_sell_order_of_kind(player, item_name, quantity, OrderKind::Limit, target_price, notes, clock)
+    }
+    
+    // Sell order that executes the way `kind` says: a plain limit, a stop-loss,
+    // a take-profit or a trailing stop
+    pub fn create_sell_order_of_kind(&mut self, player: &mut Player, item_name: &str, quantity: u32,
+                            kind: OrderKind, target_price: u32, notes: &str, clock: &GameClock) -> Result<Uuid, String> {
         // Verify player is docked
         if !player.is_docked {
             return Err("You must be docked at a station to create orders".to_string());
@@ -481,13 +728,16 @@ impl TradingSystem {
             
         // Create the order
         let player_id = player.id.to_string();
-        let order_id = market.create_sell_order(
+        validate_order_terms(quantity, kind, target_price, None, clock)?;
+        let order_id = market.create_sell_order_of_kind(
             &player_id, 
             item_name, 
             quantity, 
+            kind,
             target_price, 
             None,  // No expiration date for now
-            notes
+            notes,
+            clock
         ).ok_or("Failed to create sell order")?;
         
         // Update the market back to the universe
@@ -574,3 +824,93 @@ impl TradingSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::ResourceType;
+
+    #[test]
+    fn test_junk_sale_sells_only_cheap_cargo() {
+        let mut universe = Universe::new();
+        let mut trading = TradingSystem::new();
+        let clock = GameClock::default();
+
+        let mut player = Player::new("Test");
+        player.is_docked = true;
+        assert!(player.inventory.add_item(Item::new("Slag", 10, 1, ItemType::Resource(ResourceType::Mineral)), 5));
+        assert!(player.inventory.add_item(Item::new("Gold", 500, 1, ItemType::Resource(ResourceType::Mineral)), 2));
+        let credits = player.credits;
+
+        let sale = BulkSale::JunkBelow(JUNK_VALUE_THRESHOLD);
+        let quote = trading.quote_bulk_sale(&player, &universe, &sale, &clock).unwrap();
+        assert_eq!(quote.total_quantity(), 5);
+
+        let summary = trading.bulk_sell(&mut player, &mut universe, &sale, &clock).unwrap();
+        assert_eq!(summary.total_revenue(), quote.total_revenue());
+        assert_eq!(player.credits, credits + summary.total_revenue());
+        assert_eq!(player.inventory.get_item_quantity("Slag"), 0);
+        assert_eq!(player.inventory.get_item_quantity("Gold"), 2);
+
+        assert!(trading.bulk_sell(&mut player, &mut universe, &sale, &clock).is_err());
+    }
+
+    #[test]
+    fn test_selling_takes_the_stack_that_was_priced() {
+        use crate::models::item::Rarity;
+
+        let universe = Universe::new();
+        let mut trading = TradingSystem::new();
+        let clock = GameClock::default();
+
+        let mut player = Player::new("Test");
+        player.is_docked = true;
+        let ore = Item::new("Ore", 100, 1, ItemType::Resource(ResourceType::Mineral));
+        let rare = ore.clone().with_rarity(Rarity::Rare);
+        assert!(player.inventory.add_item(ore.clone(), 2));
+        assert!(player.inventory.add_item(rare.clone(), 2));
+        let credits = player.credits;
+        let index = player.inventory.items.keys().position(|item| *item == rare).unwrap();
+        let price = TradingSystem::quote(universe.get_market(&player.current_system.id).as_ref(), &rare, false);
+
+        trading.sell_item(&mut player, &universe, index, &clock).unwrap();
+        assert_eq!(player.inventory.items[&rare], 1);
+        assert_eq!(player.inventory.items[&ore], 2);
+        assert_eq!(player.credits, credits + price);
+    }
+
+    #[test]
+    fn test_estimate_reports_what_caps_a_buy() {
+        let universe = Universe::new();
+        let economy = EconomySystem::from_universe(&universe);
+        let trading = TradingSystem::new();
+        let market = universe.get_market("sol").unwrap();
+        let (name, market_item) = market.items.iter().next().unwrap();
+
+        let mut player = Player::new("Test");
+        player.credits = market_item.buy_price() * 3;
+
+        let one = trading.estimate_trade(&player, &market, &economy, name, 1, true).unwrap();
+        assert!(one.is_feasible());
+        assert_eq!(one.credits_after, player.credits as i64 - one.total as i64);
+        assert!(one.break_even_price.unwrap() >= one.unit_price);
+
+        let many = trading.estimate_trade(&player, &market, &economy, name, 1000, true).unwrap();
+        assert!(!many.is_feasible());
+        assert!(many.max_quantity < 3);
+        assert!(trading.estimate_trade(&player, &market, &economy, name, 1, false).is_err());
+    }
+
+    #[test]
+    fn test_new_order_terms_are_validated() {
+        let clock = GameClock::at(1000);
+
+        assert!(validate_order_terms(0, OrderKind::Limit, 100, None, &clock).is_err());
+        assert!(validate_order_terms(5, OrderKind::StopLoss, 0, None, &clock).is_err());
+        assert!(validate_order_terms(5, OrderKind::TrailingStop { percent: 0.0 }, 0, None, &clock).is_err());
+        assert!(validate_order_terms(5, OrderKind::TrailingStop { percent: 10.0 }, 0, None, &clock).is_ok());
+        assert!(validate_order_terms(5, OrderKind::Limit, 100, Some(1000), &clock).is_err());
+        assert!(validate_order_terms(5, OrderKind::Limit, 100, Some(1000 + MAX_ORDER_LIFETIME + 1), &clock).is_err());
+        assert!(validate_order_terms(5, OrderKind::Limit, 100, Some(1000 + 3600), &clock).is_ok());
+    }
+}
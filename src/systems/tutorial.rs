@@ -0,0 +1,124 @@
+// Guided start for new players
+// Walks a new pilot through their first jump, docking, purchase and mining
+// run. Each step is ticked off by watching the player's state, so the
+// tutorial never needs hooks in the screens it points at.
+
+use serde::{Serialize, Deserialize};
+
+use crate::models::player::{Player, TransactionCategory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TutorialStep {
+    #[default]
+    Travel,
+    Dock,
+    Buy,
+    Mine,
+    Done,
+}
+
+impl TutorialStep {
+    pub fn next(self) -> Self {
+        match self {
+            TutorialStep::Travel => TutorialStep::Dock,
+            TutorialStep::Dock => TutorialStep::Buy,
+            TutorialStep::Buy => TutorialStep::Mine,
+            TutorialStep::Mine | TutorialStep::Done => TutorialStep::Done,
+        }
+    }
+
+    // What finishing the step taught, for the comms log
+    pub fn completed_message(&self) -> &'static str {
+        match self {
+            TutorialStep::Travel => "First jump made",
+            TutorialStep::Dock => "Docked for the first time",
+            TutorialStep::Buy => "First purchase made",
+            TutorialStep::Mine => "First ore mined",
+            TutorialStep::Done => "Tutorial complete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TutorialState {
+    pub enabled: bool,
+    pub step: TutorialStep,
+    // System the current step started in, to notice the first jump
+    #[serde(default)]
+    pub start_system: String,
+    // Units mined when the mining step started
+    #[serde(default)]
+    pub mined_at_start: u32,
+}
+
+#[allow(dead_code)]
+impl TutorialState {
+    // A tutorial that starts from the first step, as offered to new characters
+    pub fn new() -> Self {
+        TutorialState { enabled: true, ..Default::default() }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.step != TutorialStep::Done
+    }
+
+    // Turn the tutorial off for good
+    pub fn skip(&mut self) {
+        self.enabled = false;
+    }
+
+    // Move past every step the player has now done. Returns the steps
+    // completed, in order.
+    pub fn observe(&mut self, player: &Player) -> Vec<TutorialStep> {
+        let mut completed = Vec::new();
+        while self.is_active() {
+            if self.start_system.is_empty() {
+                self.start_system = player.current_system.id.clone();
+            }
+            let done = match self.step {
+                TutorialStep::Travel => player.current_system.id != self.start_system,
+                TutorialStep::Dock => player.is_docked,
+                TutorialStep::Buy => player.ledger.last().map_or(false, |entry| {
+                    entry.category == TransactionCategory::Trade && entry.amount < 0
+                }),
+                TutorialStep::Mine => player.units_mined > self.mined_at_start,
+                TutorialStep::Done => false,
+            };
+            if !done {
+                break;
+            }
+            completed.push(self.step);
+            self.step = self.step.next();
+            if self.step == TutorialStep::Mine {
+                self.mined_at_start = player.units_mined;
+            }
+        }
+        completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::universe::StarSystem;
+
+    #[test]
+    fn test_steps_advance_with_player_state() {
+        let mut player = Player::new("Test");
+        let mut tutorial = TutorialState::new();
+        assert!(tutorial.observe(&player).is_empty());
+
+        player.current_system = StarSystem { id: "alpha".to_string(), ..player.current_system.clone() };
+        player.is_docked = true;
+        assert_eq!(tutorial.observe(&player), vec![TutorialStep::Travel, TutorialStep::Dock]);
+
+        player.apply_transaction(-100, TransactionCategory::Trade, "Bought 1 Ore", &crate::utils::clock::GameClock::default()).unwrap();
+        player.units_mined = 5;
+        assert_eq!(tutorial.observe(&player), vec![TutorialStep::Buy]);
+        assert_eq!(tutorial.mined_at_start, 5);
+
+        player.record_mined(1);
+        assert_eq!(tutorial.observe(&player), vec![TutorialStep::Mine]);
+        assert!(!tutorial.is_active());
+    }
+}
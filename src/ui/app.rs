@@ -1,19 +1,26 @@
 use std::io;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::{Mutex, mpsc, watch};
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{self, Event, KeyCode, KeyEvent},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use tui::{
     backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
     Terminal,
 };
+use uuid::Uuid;
 
 use crate::game::{Game, GameScreen};
-use crate::network::protocol::Message;
+use crate::network::connection::ConnectionStatus;
+use crate::network::prediction::Prediction;
+use crate::network::protocol::{Message, MAX_CHAT_LENGTH};
+use crate::ui::widgets::chat_pane::draw_chat_pane;
+use crate::ui::widgets::status_bar::draw_connection_status;
 use crate::ui::screens::{
     main_menu::draw_main_menu,
     navigation::draw_navigation_screen,
@@ -33,8 +40,21 @@ pub struct App {
     tx_network: mpsc::Sender<Message>,
     rx_ui: mpsc::Receiver<Message>,
     network_messages: Vec<String>, // Store recent messages from server
+    client_id: Uuid,
+    username: String,
+    chat_log: VecDeque<String>,
+    // Line being typed while in chat input mode
+    chat_input: Option<String>,
+    // Link state published by the network task; None when playing offline
+    connection_status: Option<watch::Receiver<ConnectionStatus>>,
+    // Actions applied locally and awaiting the server; None when offline
+    prediction: Option<Prediction>,
 }
 
+// Chat lines kept for the comms pane
+const CHAT_LOG_CAPACITY: usize = 50;
+const CHAT_PANE_HEIGHT: u16 = 6;
+
 #[allow(dead_code)]
 impl App {
     pub fn new(
@@ -47,8 +67,31 @@ impl App {
             tx_network,
             rx_ui,
             network_messages: Vec::new(),
+            client_id: Uuid::nil(),
+            username: String::new(),
+            chat_log: VecDeque::new(),
+            chat_input: None,
+            connection_status: None,
+            prediction: None,
         }
     }
+    
+    // Who outgoing chat is sent as, once connected
+    pub fn set_identity(&mut self, client_id: Uuid, username: String) {
+        self.client_id = client_id;
+        self.username = username;
+    }
+
+    // Show the server link's state above the comms pane
+    pub fn set_connection_status(&mut self, status: watch::Receiver<ConnectionStatus>) {
+        self.connection_status = Some(status);
+    }
+
+    // Apply the player's actions at once and send them to the server to
+    // confirm, rather than playing purely locally
+    pub fn predict_actions(&mut self) {
+        self.prediction = Some(Prediction::default());
+    }
 
     pub fn run(&mut self) -> Result<(), io::Error> {
         // Terminal initialization
@@ -67,39 +110,67 @@ impl App {
             self.check_network_messages();
             
             // Render the UI
+            let chat_log = &self.chat_log;
+            let chat_input = self.chat_input.as_deref();
+            let connection_status = self.connection_status.as_ref().map(|status| status.borrow().clone());
             terminal.draw(|f| {
                 // Get a read lock on the game state
                 let game = self.game.blocking_lock();
                 
+                // Screens above, then the server link line and the comms pane
+                let status_height = if connection_status.is_some() { 1 } else { 0 };
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(10),
+                        Constraint::Length(status_height),
+                        Constraint::Length(CHAT_PANE_HEIGHT),
+                    ])
+                    .split(f.size());
+                if let Some(status) = &connection_status {
+                    draw_connection_status(f, status, chunks[1]);
+                }
+                draw_chat_pane(f, chat_log, chat_input, chunks[2]);
+                let area = chunks[0];
+                
                 // Draw the appropriate screen based on game state
                 match game.current_screen {
-                    GameScreen::MainMenu => draw_main_menu(f, &game, f.size()),
+                    GameScreen::StartMenu => {
+                        use crate::ui::screens::start_menu::draw_start_menu;
+                        draw_start_menu(f, &game, area);
+                    },
+                    GameScreen::MainMenu => draw_main_menu(f, &game, area),
                     GameScreen::CharacterCreation => {
                         // Import and call the appropriate function
                         use crate::ui::screens::character_creation::draw_character_creation;
-                        draw_character_creation(f, &game, f.size());
+                        draw_character_creation(f, &game, area);
                     },
-                    GameScreen::Navigation => draw_navigation_screen(f, &game, f.size()),
-                    GameScreen::Market => draw_market_screen(f, &game, f.size()),
-                    GameScreen::Ship => draw_ship_screen(f, &game, f.size()),
-                    GameScreen::Mining => draw_mining_screen(f, &game, f.size()),
-                    GameScreen::Crafting => draw_crafting_screen(f, &game, f.size()),
-                    GameScreen::Inventory => draw_inventory(f, &game, f.size()),
-                    GameScreen::Character => draw_character_info(f, &game, f.size()),
+                    GameScreen::Navigation => draw_navigation_screen(f, &game, area),
+                    GameScreen::Market => draw_market_screen(f, &game, area),
+                    GameScreen::Ship => draw_ship_screen(f, &game, area),
+                    GameScreen::Mining => draw_mining_screen(f, &game, area),
+                    GameScreen::Crafting => draw_crafting_screen(f, &game, area),
+                    GameScreen::Inventory => draw_inventory(f, &game, area),
+                    GameScreen::Character => draw_character_info(f, &game, area),
                     GameScreen::Orders => {
                         // Import and call the appropriate function
                         use crate::ui::screens::orders::draw_orders_screen;
-                        draw_orders_screen(f, &game, f.size());
+                        draw_orders_screen(f, &game, area);
                     },
                     GameScreen::StationServices => {
                         // Import and call the appropriate function
                         use crate::ui::screens::station_services::draw_station_services_screen;
-                        draw_station_services_screen(f, &game, f.size());
+                        draw_station_services_screen(f, &game, area);
                     },
-                    GameScreen::Help => draw_help(f, &game, f.size()),
+                    GameScreen::News => {
+                        // Import and call the appropriate function
+                        use crate::ui::screens::news::draw_news_screen;
+                        draw_news_screen(f, &game, area);
+                    },
+                    GameScreen::Help => draw_help(f, &game, area),
                     GameScreen::Quit => {
                         // Draw quit confirmation
-                        draw_main_menu(f, &game, f.size()); // For now, just show the main menu as background
+                        draw_main_menu(f, &game, area); // For now, just show the main menu as background
                     }
                 }
                 
@@ -114,9 +185,20 @@ impl App {
                 
             if crossterm::event::poll(timeout)? {
                 if let Event::Key(key) = event::read()? {
+                    if self.chat_input.is_some() {
+                        self.handle_chat_input(key);
+                        continue;
+                    }
+                    
                     // Get a write lock on the game state
                     let mut game = self.game.blocking_lock();
                     
+                    // '/' opens the chat line, except where the player is typing a name
+                    if key.code == KeyCode::Char('/') && game.current_screen != GameScreen::CharacterCreation {
+                        self.chat_input = Some(String::new());
+                        continue;
+                    }
+                    
                     // Check if game is over
                     if key.code == KeyCode::Char('q') && game.current_screen != GameScreen::Quit {
                         if game.confirm_quit() {
@@ -125,15 +207,21 @@ impl App {
                     } else if key.code == KeyCode::Esc {
                         game.cancel_action();
                     } else {
-                        // Handle the key event in the game
+                        // Handle the key event in the game, keeping the player as
+                        // they were in case the server refuses what they did
+                        let before = self.prediction.as_ref().map(|_| {
+                            game.report_actions();
+                            game.player.clone()
+                        });
                         game.handle_input(key);
                         
-                        // Send network messages based on game actions
-                        // (This would be implemented based on the specific action)
-                        // For example, if navigating to a new system:
-                        if game.current_screen == GameScreen::Navigation {
-                            // This is just an example - real implementation would check if navigation was requested
-                            // self.send_navigation_request(&game.destination_system);
+                        // The local game has already applied the player's actions;
+                        // send them to the server to confirm
+                        if let (Some(prediction), Some(before)) = (self.prediction.as_mut(), before) {
+                            let steps = game.take_reported_actions();
+                            for message in prediction.predict(self.client_id, &game, steps, &before) {
+                                self.send_to_server(message);
+                            }
                         }
                         
                         // Check if game is over after handling input
@@ -167,7 +255,11 @@ impl App {
         // Poll for network messages without blocking
         if let Ok(msg) = self.rx_ui.try_recv() {
             match msg {
-                Message::ActionResponse { success, message, .. } => {
+                Message::ActionResponse { success, message, updated_ship, action_id, updated_holdings, .. } => {
+                    if let (Some(prediction), Some(action_id)) = (self.prediction.as_mut(), action_id) {
+                        let mut game = self.game.blocking_lock();
+                        prediction.reconcile(&mut game, action_id, success, &message, updated_ship, updated_holdings);
+                    }
                     self.network_messages.push(format!("{}: {}", 
                         if success { "Success" } else { "Error" },
                         message
@@ -178,6 +270,17 @@ impl App {
                         self.network_messages.remove(0);
                     }
                 },
+                Message::ServerShutdown { message } => {
+                    // Keep a local copy of progress before the connection drops
+                    if let Err(e) = self.game.blocking_lock().save_state() {
+                        eprintln!("Failed to save game after server shutdown: {}", e);
+                    }
+                    self.network_messages.push(format!("Server: {}", message));
+                    self.push_chat_line(format!("*** {} ***", message));
+                },
+                Message::Chat { username, text, .. } => {
+                    self.push_chat_line(format!("{}: {}", username, text));
+                },
                 Message::Error { code, message } => {
                     self.network_messages.push(format!("Error {}: {}", code, message));
                     
@@ -193,6 +296,56 @@ impl App {
         }
     }
     
+    fn handle_chat_input(&mut self, key: KeyEvent) {
+        let input = match self.chat_input.as_mut() {
+            Some(input) => input,
+            None => return,
+        };
+        
+        match key.code {
+            KeyCode::Char(c) if input.chars().count() < MAX_CHAT_LENGTH => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            },
+            KeyCode::Enter => {
+                let text = input.trim().to_string();
+                self.chat_input = None;
+                if !text.is_empty() {
+                    self.send_chat(text);
+                }
+            },
+            KeyCode::Esc => self.chat_input = None,
+            _ => {}
+        }
+    }
+    
+    fn push_chat_line(&mut self, line: String) {
+        self.chat_log.push_back(line);
+        while self.chat_log.len() > CHAT_LOG_CAPACITY {
+            self.chat_log.pop_front();
+        }
+    }
+    
+    // Chat is echoed back by the server, so nothing is added to the log here
+    fn send_chat(&self, text: String) {
+        let message = Message::Chat {
+            client_id: self.client_id,
+            username: self.username.clone(),
+            text,
+        };
+        
+        self.send_to_server(message);
+    }
+    
+    fn send_to_server(&self, message: Message) {
+        let tx = self.tx_network.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tx.send(message).await {
+                eprintln!("Failed to send message to server: {}", e);
+            }
+        });
+    }
+    
     // Method to send a navigation request to the server
     #[allow(dead_code)]
     fn send_navigation_request(&self, destination: &str) {
@@ -204,6 +357,7 @@ impl App {
         let message = Message::NavigationAction {
             client_id,
             destination_system: destination.to_string(),
+            action_id: None,
         };
         
         // Send the message (this is a non-blocking operation)
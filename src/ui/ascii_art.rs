@@ -147,3 +147,51 @@ pub fn get_engine_particles(current_time: &Instant) -> Vec<(u16, u16, char)> {
     
     particles
 }
+
+// Small side-on ship used in the jump animation; the exhaust flickers by frame
+pub fn get_travel_ship(frame_num: u64) -> [String; 3] {
+    let exhaust = ["~", "≈", "-"][(frame_num % 3) as usize];
+    [
+        "  __".to_string(),
+        format!("{}{}|__>", exhaust, exhaust),
+        "  ‾‾".to_string(),
+    ]
+}
+
+// One frame of the jump animation: star streaks rushing past with the ship
+// placed `progress` (0.0-1.0) of the way across
+pub fn get_travel_frame(width: u16, height: u16, frame_num: u64, progress: f32) -> Vec<String> {
+    let width = width as usize;
+    let height = height as usize;
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let mut rows: Vec<Vec<char>> = vec![vec![' '; width]; height];
+
+    // Streaks scroll left a little further every frame
+    let streaks = ['-', '─', '·', '='];
+    for y in 0..height {
+        for i in 0..3u64 {
+            let seed = (y as u64 * 31 + i * 17) % 97;
+            let x = ((seed * 7 + frame_num * (2 + i)) % width.max(1) as u64) as usize;
+            rows[y][width - 1 - x] = streaks[((seed + i) % streaks.len() as u64) as usize];
+        }
+    }
+
+    // Overlay the ship in the middle rows
+    let ship = get_travel_ship(frame_num);
+    let ship_width = ship.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let x = ((width.saturating_sub(ship_width)) as f32 * progress.max(0.0).min(1.0)) as usize;
+    let top = (height / 2).saturating_sub(1);
+    for (dy, line) in ship.iter().enumerate() {
+        if let Some(row) = rows.get_mut(top + dy) {
+            for (dx, ch) in line.chars().enumerate() {
+                if ch != ' ' && x + dx < width {
+                    row[x + dx] = ch;
+                }
+            }
+        }
+    }
+
+    rows.into_iter().map(|row| row.into_iter().collect()).collect()
+}
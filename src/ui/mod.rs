@@ -10,73 +10,186 @@ use tui::{
     Frame,
 };
 
-use crate::game::{Game, GameScreen};
+use crossterm::event::KeyCode;
+use crate::game::{Game, GameScreen, MessageKind};
 use screens::{
     navigation::draw_navigation_screen,
-    market::draw_market_screen,
+    market::{draw_market_screen, click_regions as market_click_regions},
     ship::draw_ship_screen,
     mining::draw_mining_screen,
     crafting::draw_crafting_screen,
     inventory::draw_inventory,
     help::draw_help,
-    main_menu::draw_main_menu,
+    main_menu::{draw_main_menu, menu_item_rects},
     character_creation::draw_character_creation,
     character_info::draw_character_info,
     orders::draw_orders_screen,
     station_services::draw_station_services_screen,
+    start_menu::draw_start_menu,
+    news::draw_news_screen,
 };
 use widgets::status_bar::draw_status_bar;
+use widgets::confirm_dialog::draw_confirm_dialog;
+use widgets::travel_animation::draw_travel_animation;
+use widgets::item_detail::draw_item_detail;
+use widgets::tutorial_hint::draw_tutorial_hint;
+
+// Smallest terminal the screens can be drawn in without clipping
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 20;
+// Below this height the message log is cut down to a single line
+const COMPACT_HEIGHT: u16 = 30;
+const MAX_MESSAGE_LINES: usize = 3;
+// Marks level-up and storyline messages in the comms log
+const MILESTONE_GLYPH: &str = "♪";
 
 pub fn draw<B: Backend>(f: &mut Frame<B>, game: &Game) {
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        draw_too_small(f, size);
+        return;
+    }
+
     // For main menu and character creation, use the full screen
     match game.current_screen {
+        GameScreen::StartMenu => {
+            draw_start_menu(f, game, size);
+            return;
+        },
         GameScreen::MainMenu => {
-            draw_main_menu(f, game, f.size());
+            draw_main_menu(f, game, size);
+            if let Some(hint) = game.tutorial_hint() {
+                draw_tutorial_hint(f, &hint, size);
+            }
             return;
         },
         GameScreen::CharacterCreation => {
-            draw_character_creation(f, game, f.size());
+            draw_character_creation(f, game, size);
             return;
         },
         _ => {}
     }
 
     // For other screens, create the standard layout
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1),  // Status bar
-            Constraint::Min(10),    // Main content
-            Constraint::Length(3),  // Message area
-        ])
-        .split(f.size());
+    let chunks = main_chunks(game, size);
 
     // Draw the status bar
     draw_status_bar(f, game, chunks[0]);
 
-    // Draw the main content based on the current screen
+    // Draw the main content based on the current screen; a jump in
+    // progress takes over the content area while its animation plays
+    if game.travel_animation.is_some() {
+        draw_travel_animation(f, game, chunks[1]);
+    } else {
+        draw_screen(f, game, chunks[1]);
+    }
+
+    // Draw message area
+    draw_message_area(f, game, chunks[2]);
+
+    if let Some(item_name) = &game.item_detail {
+        draw_item_detail(f, game, item_name, chunks[1]);
+    } else if let Some(hint) = game.tutorial_hint() {
+        draw_tutorial_hint(f, &hint, chunks[1]);
+    }
+
+    // Confirmation prompts sit on top of everything else
+    if let Some(dialog) = &game.confirm_dialog {
+        draw_confirm_dialog(f, dialog, size);
+    }
+}
+
+fn draw_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     match game.current_screen {
+        GameScreen::StartMenu => {}, // Already handled
         GameScreen::MainMenu => {}, // Already handled
         GameScreen::CharacterCreation => {}, // Already handled
-        GameScreen::Navigation => draw_navigation_screen(f, game, chunks[1]),
-        GameScreen::Market => draw_market_screen(f, game, chunks[1]),
-        GameScreen::Ship => draw_ship_screen(f, game, chunks[1]),
-        GameScreen::Mining => draw_mining_screen(f, game, chunks[1]),
-        GameScreen::Crafting => draw_crafting_screen(f, game, chunks[1]),
-        GameScreen::Inventory => draw_inventory(f, game, chunks[1]),
-        GameScreen::Character => draw_character_info(f, game, chunks[1]),
-        GameScreen::Orders => draw_orders_screen(f, game, chunks[1]),
-        GameScreen::StationServices => draw_station_services_screen(f, game, chunks[1]),
-        GameScreen::Help => draw_help(f, game, chunks[1]),
-        GameScreen::Quit => draw_quit_screen(f, game, chunks[1]),
+        GameScreen::Navigation => draw_navigation_screen(f, game, area),
+        GameScreen::Market => draw_market_screen(f, game, area),
+        GameScreen::Ship => draw_ship_screen(f, game, area),
+        GameScreen::Mining => draw_mining_screen(f, game, area),
+        GameScreen::Crafting => draw_crafting_screen(f, game, area),
+        GameScreen::Inventory => draw_inventory(f, game, area),
+        GameScreen::Character => draw_character_info(f, game, area),
+        GameScreen::Orders => draw_orders_screen(f, game, area),
+        GameScreen::StationServices => draw_station_services_screen(f, game, area),
+        GameScreen::News => draw_news_screen(f, game, area),
+        GameScreen::Help => draw_help(f, game, area),
+        GameScreen::Quit => draw_quit_screen(f, game, area),
     }
+}
 
-    // Draw message area
-    draw_message_area(f, game, chunks[2]);
+fn main_chunks(game: &Game, size: Rect) -> Vec<Rect> {
+    // The message log grows with the number of messages, up to a cap that
+    // shrinks on short terminals
+    let max_lines = if size.height < COMPACT_HEIGHT { 1 } else { MAX_MESSAGE_LINES };
+    let message_lines = game.message_log.len().max(1).min(max_lines) as u16;
+    // A pinned milestone takes a line of its own
+    let message_lines = message_lines + if game.milestones.is_empty() { 0 } else { 1 };
+
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),                  // Status bar
+            Constraint::Min(10),                    // Main content
+            Constraint::Length(message_lines + 2),  // Message log plus border
+        ])
+        .split(size)
+}
+
+// Screen regions that respond to mouse clicks, each standing in for a key press
+pub struct LayoutRects {
+    regions: Vec<(Rect, KeyCode)>,
+}
+
+impl LayoutRects {
+    pub fn key_at(&self, column: u16, row: u16) -> Option<KeyCode> {
+        self.regions.iter()
+            .find(|(rect, _)| {
+                column >= rect.x && column < rect.right() && row >= rect.y && row < rect.bottom()
+            })
+            .map(|(_, key)| *key)
+    }
+}
+
+// Clickable regions for the screen as `draw` would lay it out at `size`
+pub fn layout_rects(game: &Game, size: Rect) -> LayoutRects {
+    let regions = if size.width < MIN_WIDTH || size.height < MIN_HEIGHT || game.confirm_dialog.is_some() || game.item_detail.is_some() {
+        Vec::new()
+    } else {
+        match game.current_screen {
+            GameScreen::MainMenu => menu_item_rects(size),
+            GameScreen::Market => market_click_regions(game, main_chunks(game, size)[1]),
+            _ => Vec::new(),
+        }
+    };
+
+    LayoutRects { regions }
 }
 
 // Old screen functions removed and replaced with new module imports
 
+fn draw_too_small<B: Backend>(f: &mut Frame<B>, area: Rect) {
+    use tui::widgets::{Paragraph, Wrap};
+    use tui::layout::Alignment;
+    use tui::text::{Span, Spans};
+    use tui::style::Style;
+    use crate::ui::colors;
+
+    let text = vec![
+        Spans::from(Span::styled("Terminal too small", Style::default().fg(colors::WARNING))),
+        Spans::from(Span::styled(
+            format!("Need {}x{}, have {}x{}", MIN_WIDTH, MIN_HEIGHT, area.width, area.height),
+            Style::default().fg(colors::DIM),
+        )),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, area);
+}
+
 fn draw_quit_screen<B: Backend>(f: &mut Frame<B>, _game: &Game, area: Rect) {
     use tui::widgets::{Block, Borders, Paragraph};
     use tui::text::{Span, Spans};
@@ -117,15 +230,35 @@ fn draw_message_area<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(colors::DIM));
 
-    let text = if let Some(message) = &game.message {
-        vec![
+    // The oldest undismissed milestone stays pinned on the first line
+    let mut text: Vec<Spans> = Vec::new();
+    if let Some(milestone) = game.milestones.front() {
+        let mut spans = vec![
+            Span::styled(format!("{} {}", MILESTONE_GLYPH, milestone), Style::default().fg(colors::ENERGY)),
+            Span::styled("  [Ctrl+X] Dismiss", Style::default().fg(colors::DIM)),
+        ];
+        if game.milestones.len() > 1 {
+            spans.push(Span::styled(format!(" ({} more)", game.milestones.len() - 1), Style::default().fg(colors::DIM)));
+        }
+        text.push(Spans::from(spans));
+    }
+
+    // Show as many of the latest messages as fit inside the border
+    let visible = (area.height.saturating_sub(2) as usize).saturating_sub(text.len());
+    text.extend(game.recent_messages(visible)
+        .map(|(_, message, kind)| {
+            let color = match kind {
+                MessageKind::Info => colors::INFO,
+                MessageKind::Success => colors::SUCCESS,
+                MessageKind::Warning => colors::WARNING,
+                MessageKind::Error => colors::DANGER,
+                MessageKind::Milestone => colors::ENERGY,
+            };
+            let glyph = if *kind == MessageKind::Milestone { format!("{} ", MILESTONE_GLYPH) } else { String::new() };
             Spans::from(vec![
-                Span::styled(message, Style::default().fg(colors::INFO)),
-            ]),
-        ]
-    } else {
-        vec![Spans::from("")]
-    };
+                Span::styled(format!("{}{}", glyph, message), Style::default().fg(color)),
+            ])
+        }));
 
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, area);
@@ -252,6 +252,14 @@ pub fn draw_character_creation<B: Backend>(f: &mut Frame<B>, game: &Game, area:
                         Style::default().fg(colors::SECONDARY)
                     ),
                 ]),
+                Spans::from(Span::raw("")),
+                Spans::from(vec![
+                    Span::styled("Tutorial: ", Style::default().fg(colors::INFO)),
+                    Span::styled(
+                        if game.tutorial.enabled { "On - hints guide your first steps" } else { "Off" },
+                        Style::default().fg(colors::PRIMARY)
+                    ),
+                ]),
             ];
             
             let summary = Paragraph::new(summary_text)
@@ -265,7 +273,7 @@ pub fn draw_character_creation<B: Backend>(f: &mut Frame<B>, game: &Game, area:
             
             // Instructions
             let instructions = Paragraph::new(
-                "Press Y to confirm and start game\nPress N to start over\nBackspace to return to storyline selection"
+                "Press Y to confirm and start game\nPress T to turn the tutorial on or off\nPress N to start over\nBackspace to return to storyline selection"
             )
             .style(Style::default().fg(colors::INFO))
             .alignment(Alignment::Center);
@@ -1,12 +1,15 @@
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, List, ListItem, Paragraph, Tabs, Wrap},
     Frame,
 };
 use crate::game::Game;
+use crate::models::faction::FactionType;
+use crate::models::stats::{ACHIEVEMENTS, ALL_STATS, Stat};
+use crate::systems::economy::EconomySystem;
 use crate::ui::colors;
 
 // Character info screen enum to track which tab is active
@@ -16,6 +19,7 @@ pub enum CharacterInfoTab {
     Reputation,
     Assets,
     Background,
+    Stats,
 }
 
 #[allow(dead_code)]
@@ -26,6 +30,7 @@ impl CharacterInfoTab {
             CharacterInfoTab::Reputation => 1,
             CharacterInfoTab::Assets => 2,
             CharacterInfoTab::Background => 3,
+            CharacterInfoTab::Stats => 4,
         }
     }
 
@@ -35,6 +40,7 @@ impl CharacterInfoTab {
             1 => CharacterInfoTab::Reputation,
             2 => CharacterInfoTab::Assets,
             3 => CharacterInfoTab::Background,
+            4 => CharacterInfoTab::Stats,
             _ => CharacterInfoTab::Skills,
         }
     }
@@ -51,7 +57,7 @@ pub fn draw_character_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect
     f.render_widget(block, area);
 
     // Create tabs for different character info sections
-    let tab_titles = vec!["Skills", "Reputation", "Assets", "Background"];
+    let tab_titles = vec!["Skills", "Reputation", "Assets", "Background", "Stats"];
     let tabs = Tabs::new(
         tab_titles.iter().map(|t| {
             Spans::from(vec![Span::styled(*t, Style::default().fg(colors::PRIMARY))])
@@ -81,6 +87,7 @@ pub fn draw_character_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect
         CharacterInfoTab::Reputation => draw_reputation_tab(f, game, chunks[1]),
         CharacterInfoTab::Assets => draw_assets_tab(f, game, chunks[1]),
         CharacterInfoTab::Background => draw_background_tab(f, game, chunks[1]),
+        CharacterInfoTab::Stats => draw_stats_tab(f, game, chunks[1]),
     }
     
     // Draw instructions at the bottom
@@ -89,7 +96,7 @@ pub fn draw_character_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect
             Span::raw("Press ["),
             Span::styled("1", Style::default().fg(colors::PRIMARY)),
             Span::raw("-"),
-            Span::styled("4", Style::default().fg(colors::PRIMARY)),
+            Span::styled("5", Style::default().fg(colors::PRIMARY)),
             Span::raw("] to switch tabs, ["),
             Span::styled("M", Style::default().fg(colors::PRIMARY)),
             Span::raw("] to return to main menu")
@@ -150,7 +157,7 @@ fn draw_skills_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     f.render_widget(skills_list, inner_area);
 }
 
-fn draw_reputation_tab<B: Backend>(f: &mut Frame<B>, _game: &Game, area: Rect) {
+fn draw_reputation_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let block = Block::default()
         .title(Span::styled(" REPUTATION ", Style::default().fg(colors::INFO)))
         .borders(Borders::ALL)
@@ -159,8 +166,14 @@ fn draw_reputation_tab<B: Backend>(f: &mut Frame<B>, _game: &Game, area: Rect) {
     let inner_area = block.inner(area);
     f.render_widget(block.clone(), area);
     
-    // In a full implementation, we would display faction reputation here
-    let placeholder = Paragraph::new(vec![
+    let factions = [
+        FactionType::Traders,
+        FactionType::Miners,
+        FactionType::Military,
+        FactionType::Scientists,
+    ];
+    
+    let mut text = vec![
         Spans::from(vec![
             Span::styled(
                 "Faction Relations:", 
@@ -168,32 +181,46 @@ fn draw_reputation_tab<B: Backend>(f: &mut Frame<B>, _game: &Game, area: Rect) {
             ),
         ]),
         Spans::from(""),
-        Spans::from(vec![
-            Span::raw("United Trade Federation: "),
-            Span::styled("Neutral", Style::default().fg(colors::INFO)),
-        ]),
-        Spans::from(vec![
-            Span::raw("Mining Consortium: "),
-            Span::styled("Friendly", Style::default().fg(colors::SUCCESS)),
-        ]),
-        Spans::from(vec![
-            Span::raw("Galactic Security Force: "),
-            Span::styled("Neutral", Style::default().fg(colors::INFO)),
-        ]),
-        Spans::from(vec![
-            Span::raw("Scientific Academy: "),
-            Span::styled("Neutral", Style::default().fg(colors::INFO)),
-        ]),
-    ])
-    .block(Block::default())
-    .wrap(Wrap { trim: true });
+    ];
+    
+    for faction in factions.iter() {
+        let value = game.player.get_reputation(faction);
+        let (standing, color) = reputation_standing(value);
+        text.push(Spans::from(vec![
+            Span::raw(format!("{}: ", faction.to_string())),
+            Span::styled(format!("{} ({:+})", standing, value), Style::default().fg(color)),
+        ]));
+    }
+    
+    let paragraph = Paragraph::new(text)
+        .block(Block::default())
+        .wrap(Wrap { trim: true });
     
-    f.render_widget(placeholder, inner_area);
+    f.render_widget(paragraph, inner_area);
+}
+
+// Label and color for a reputation value in -100..=100
+fn reputation_standing(value: i32) -> (&'static str, Color) {
+    match value {
+        v if v >= 50 => ("Allied", colors::PRIMARY),
+        v if v >= 10 => ("Friendly", colors::SUCCESS),
+        v if v > -10 => ("Neutral", colors::INFO),
+        v if v > -50 => ("Unfriendly", colors::WARNING),
+        _ => ("Hostile", colors::DANGER),
+    }
 }
 
 fn draw_assets_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let player = &game.player;
     
+    // Holdings on the left, recent transactions on the right
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(area);
+    let area = columns[0];
+    draw_ledger(f, game, columns[1]);
+    
     let block = Block::default()
         .title(Span::styled(" ASSETS ", Style::default().fg(colors::INFO)))
         .borders(Borders::ALL)
@@ -202,7 +229,15 @@ fn draw_assets_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let inner_area = block.inner(area);
     f.render_widget(block.clone(), area);
     
-    // Display player's assets (credits, ships, property)
+    let worth = player.net_worth(&EconomySystem::from_universe(&game.universe));
+    let value_line = |label: &str, value: u64| {
+        Spans::from(vec![
+            Span::raw(format!("{:<10}", label)),
+            Span::styled(format!("{:>10} cr", value), Style::default().fg(colors::SUCCESS)),
+        ])
+    };
+    
+    // Display player's assets (credits, cargo, listings, ship)
     let assets_info = Paragraph::new(vec![
         Spans::from(vec![
             Span::styled(
@@ -210,11 +245,15 @@ fn draw_assets_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::PRIMARY).add_modifier(Modifier::BOLD)
             ),
         ]),
+        value_line("Credits", worth.credits),
+        value_line("Cargo", worth.cargo),
+        value_line("Listings", worth.listings),
+        value_line("Escrow", worth.escrow),
         Spans::from(vec![
-            Span::raw("Credits: "),
+            Span::raw(format!("{:<10}", "Orders")),
             Span::styled(
-                format!("{}", player.credits), 
-                Style::default().fg(colors::SUCCESS)
+                format!("{:>10} cr committed", worth.order_commitments), 
+                Style::default().fg(colors::DIM)
             ),
         ]),
         Spans::from(""),
@@ -238,13 +277,6 @@ fn draw_assets_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::INFO)
             ),
         ]),
-        Spans::from(vec![
-            Span::raw("Ship Value: "),
-            Span::styled(
-                "50,000 credits (estimated)", 
-                Style::default().fg(colors::SUCCESS)
-            ),
-        ]),
         Spans::from(""),
         Spans::from(vec![
             Span::styled(
@@ -252,7 +284,7 @@ fn draw_assets_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::PRIMARY).add_modifier(Modifier::BOLD)
             ),
             Span::styled(
-                format!(" {} credits", player.credits + 50000), 
+                format!(" {} credits", worth.total()), 
                 Style::default().fg(colors::SUCCESS).add_modifier(Modifier::BOLD)
             ),
         ]),
@@ -263,6 +295,71 @@ fn draw_assets_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     f.render_widget(assets_info, inner_area);
 }
 
+// Latest credit changes, newest at the top
+fn draw_ledger<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let block = Block::default()
+        .title(Span::styled(" LEDGER ", Style::default().fg(colors::INFO)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::DIM));
+    
+    let visible = block.inner(area).height as usize;
+    let entries: Vec<ListItem> = game.player.recent_transactions(visible).map(|entry| {
+        let color = if entry.amount >= 0 { colors::SUCCESS } else { colors::DANGER };
+        ListItem::new(Spans::from(vec![
+            Span::styled(format!("{:>+8} ", entry.amount), Style::default().fg(color)),
+            Span::styled(format!("{:<8} ", entry.category.to_string()), Style::default().fg(colors::DIM)),
+            Span::raw(entry.description.clone()),
+        ]))
+    }).collect();
+    
+    if entries.is_empty() {
+        let paragraph = Paragraph::new(Span::styled("No transactions yet", Style::default().fg(colors::DIM)))
+            .block(block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+    
+    f.render_widget(List::new(entries).block(block), area);
+}
+
+// Lifetime totals, then every achievement with earned ones lit up
+fn draw_stats_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let stats = &game.player.stats;
+    let block = Block::default()
+        .title(Span::styled(" LIFETIME STATS ", Style::default().fg(colors::INFO)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::DIM));
+
+    let mut lines: Vec<Spans> = ALL_STATS.iter()
+        .map(|stat| {
+            let value = match stat {
+                Stat::DistanceTraveled => format!("{:.1}", stats.distance_traveled),
+                _ => stats.get(*stat).to_string(),
+            };
+            Spans::from(vec![
+                Span::raw(format!("{:<24}", stat.label())),
+                Span::styled(value, Style::default().fg(colors::HIGHLIGHT)),
+            ])
+        })
+        .collect();
+
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled(
+        format!("Achievements ({}/{})", stats.achievements.len(), ACHIEVEMENTS.len()),
+        Style::default().fg(colors::PRIMARY).add_modifier(Modifier::BOLD),
+    )));
+    for achievement in ACHIEVEMENTS {
+        let (marker, style) = if stats.has_achievement(achievement.id) {
+            ("★", Style::default().fg(colors::SUCCESS))
+        } else {
+            ("☆", Style::default().fg(colors::DIM))
+        };
+        lines.push(Spans::from(Span::styled(format!("{} {}", marker, achievement.describe()), style)));
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), area);
+}
+
 fn draw_background_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let player = &game.player;
     
@@ -291,7 +388,34 @@ fn draw_background_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
         "You are a space trader seeking fortune among the stars.".to_string()
     };
     
-    let background_info = Paragraph::new(vec![
+    // Current stage and what it takes to finish it
+    let mut progress_lines = Vec::new();
+    if let Some(storyline) = &player.character.active_storyline {
+        progress_lines.push(Spans::from(vec![
+            Span::raw("Progress: "),
+            Span::styled(
+                format!("stage {}/{} ({:.0}%)", storyline.progress.min(storyline.total_steps), storyline.total_steps, storyline.get_progress_percentage()),
+                Style::default().fg(colors::INFO)
+            ),
+        ]));
+        match storyline.current_objective() {
+            Some(objective) => {
+                let (current, target) = player.objective_progress(&objective.goal);
+                progress_lines.push(Spans::from(vec![
+                    Span::raw("Objective: "),
+                    Span::styled(objective.goal.describe(), Style::default().fg(colors::HIGHLIGHT)),
+                    Span::styled(format!(" ({}/{})", current.min(target), target), Style::default().fg(colors::DIM)),
+                    Span::styled(format!("  reward {} cr", objective.reward_credits), Style::default().fg(colors::SUCCESS)),
+                ]));
+            },
+            None if storyline.completed => {
+                progress_lines.push(Spans::from(Span::styled("Storyline complete", Style::default().fg(colors::SUCCESS))));
+            },
+            None => {},
+        }
+    }
+    
+    let mut lines = vec![
         Spans::from(vec![
             Span::styled(
                 format!("Commander: {}", player.character.name), 
@@ -312,6 +436,9 @@ fn draw_background_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::INFO)
             ),
         ]),
+    ];
+    lines.extend(progress_lines);
+    lines.extend(vec![
         Spans::from(""),
         Spans::from(vec![
             Span::styled(
@@ -323,9 +450,11 @@ fn draw_background_tab<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
         Spans::from(vec![
             Span::raw(background),
         ]),
-    ])
-    .block(Block::default())
-    .wrap(Wrap { trim: true });
+    ]);
+    
+    let background_info = Paragraph::new(lines)
+        .block(Block::default())
+        .wrap(Wrap { trim: true });
     
     f.render_widget(background_info, inner_area);
 }
\ No newline at end of file
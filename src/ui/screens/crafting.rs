@@ -3,7 +3,7 @@ use tui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Style},
     text::{Span, Spans},
-    widgets::{Paragraph, Table, Row},
+    widgets::{Cell, Paragraph, Table, Row},
     Frame,
 };
 
@@ -84,10 +84,10 @@ fn draw_no_blueprints_message<B: Backend>(f: &mut Frame<B>, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
-fn draw_blueprints<B: Backend>(f: &mut Frame<B>, _game: &Game, blueprints: Vec<&crate::systems::crafting::Blueprint>, area: Rect) {
+fn draw_blueprints<B: Backend>(f: &mut Frame<B>, game: &Game, blueprints: Vec<&crate::systems::crafting::Blueprint>, area: Rect) {
     let block = style_utils::create_primary_block("AVAILABLE SCHEMATICS");
 
-    let header = Row::new(vec!["#", "Blueprint", "Rarity"]).style(Style::default().fg(colors::INFO));
+    let header = Row::new(vec!["#", "Blueprint", "Rarity", "Known", "Materials (have/need)"]).style(Style::default().fg(colors::INFO));
     
     let rows: Vec<Row> = blueprints.iter().enumerate().map(|(i, blueprint)| {
         // Format rarity with appropriate color
@@ -100,17 +100,36 @@ fn draw_blueprints<B: Backend>(f: &mut Frame<B>, _game: &Game, blueprints: Vec<&
             crate::systems::crafting::BlueprintRarity::Legendary => "Legendary",
         };
         
+        // One line per input, green when the hold has enough of it
+        let requirements = game.crafting_system.blueprint_requirements(i, &game.player.inventory);
+        let materials: Vec<Spans> = requirements.iter().map(|(name, needed, have)| {
+            let color = if have >= needed { colors::PRIMARY } else { colors::DANGER };
+            Spans::from(vec![
+                Span::styled(format!("{}/{}", have, needed), Style::default().fg(color)),
+                Span::raw(format!(" {}", name)),
+            ])
+        }).collect();
+        let height = materials.len().max(1) as u16;
+        
+        // Blueprints still to be researched are listed but can't be crafted
+        let known = game.player.knows_blueprint(&blueprint.id);
         Row::new(vec![
-            format!("{}", i + 1),
-            blueprint.name.clone(),
-            rarity_str.to_string(),
+            Cell::from(format!("{}", i + 1)),
+            Cell::from(blueprint.name.clone()),
+            Cell::from(rarity_str),
+            Cell::from(if known { "Yes" } else { "No" }),
+            Cell::from(materials),
         ])
+        .height(height)
+        .style(if known { Style::default() } else { Style::default().fg(colors::DIM) })
     }).collect();
 
     let widths = [
         Constraint::Length(3),
-        Constraint::Percentage(70),
-        Constraint::Percentage(20),
+        Constraint::Percentage(30),
+        Constraint::Percentage(15),
+        Constraint::Percentage(10),
+        Constraint::Percentage(40),
     ];
 
     let table = Table::new(rows)
@@ -126,7 +145,7 @@ fn draw_ingredients<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
 
     let selected_blueprint = game.crafting_system.get_selected_blueprint_index();
     
-    let text = if let Some(_) = selected_blueprint {
+    let text = if let Some(blueprint_idx) = selected_blueprint {
         let selected_blueprint = game.crafting_system.get_selected_blueprint();
         
         if let Some(blueprint) = selected_blueprint {
@@ -134,16 +153,15 @@ fn draw_ingredients<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
             let recipe_id = &blueprint.recipe_id;
             let mut spans = Vec::new();
             
-            if let Some(recipe) = game.crafting_system.recipes.get(recipe_id) {
+            if game.crafting_system.recipes.contains_key(recipe_id) {
                 spans.push(Spans::from(vec![
                     Span::styled(format!("Blueprint: {}", blueprint.name), Style::default().fg(colors::PRIMARY)),
                 ]));
                 spans.push(Spans::from("Requires:"));
                 
                 // Display ingredients from the recipe
-                for (ingredient_name, amount) in &recipe.input_items {
-                    let owned = game.player.inventory.get_item_quantity(ingredient_name);
-                    let style = if owned >= *amount {
+                for (ingredient_name, amount, owned) in game.crafting_system.blueprint_requirements(blueprint_idx, &game.player.inventory) {
+                    let style = if owned >= amount {
                         Style::default().fg(colors::PRIMARY)
                     } else {
                         Style::default().fg(colors::DANGER)
@@ -3,30 +3,103 @@ use tui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Style},
     text::{Span, Spans},
-    widgets::{Paragraph},
+    widgets::{Paragraph, Row, Table},
     Frame,
 };
 use crate::game::Game;
 use crate::ui::colors;
 use crate::ui::screens::style_utils;
 
-pub fn draw_inventory<B: Backend>(f: &mut Frame<B>, _game: &Game, area: Rect) {
-    let block = style_utils::create_primary_block("CARGO MANIFEST");
-    
-    f.render_widget(block, area);
-    
-    // Placeholder for inventory screen - will be implemented later
-    let inner = Layout::default()
+pub fn draw_inventory<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .margin(2)
-        .constraints([Constraint::Min(1)])
-        .split(area)[0];
-    
-    let text = Spans::from(vec![
-        Span::styled("Cargo manifest interface available in future update", 
-                     Style::default().fg(colors::INFO))
-    ]);
-    
-    let paragraph = Paragraph::new(text);
-    f.render_widget(paragraph, inner);
-}
\ No newline at end of file
+        .constraints([
+            Constraint::Min(5),     // Cargo list
+            Constraint::Length(3),  // Capacity and controls
+        ])
+        .split(area);
+
+    draw_cargo_list(f, game, chunks[0]);
+    draw_cargo_footer(f, game, chunks[1]);
+}
+
+fn draw_cargo_list<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let title = format!("CARGO MANIFEST - SORT: {}", game.inventory_sort.label().to_uppercase());
+    let block = style_utils::create_primary_block(&title);
+    let items = game.inventory_view();
+
+    if items.is_empty() {
+        let message = if game.player.inventory.items.is_empty() {
+            "Your cargo hold is empty".to_string()
+        } else {
+            format!("No cargo matches \"{}\"", game.inventory_filter)
+        };
+        let text = Spans::from(vec![
+            Span::styled(message, Style::default().fg(colors::DIM))
+        ]);
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let header = Row::new(vec!["Item", "Type", "Quantity", "Weight", "Value"])
+        .style(Style::default().fg(colors::INFO));
+
+    let rows: Vec<Row> = items.iter().enumerate().map(|(i, (item, quantity))| {
+        let row = Row::new(vec![
+            item.display_name(),
+            item.item_type.to_string(),
+            format!("{}", quantity),
+            format!("{} t", item.weight * quantity),
+            format!("{} cr", item.value * quantity),
+        ]);
+
+        if i == game.inventory_selected {
+            row.style(Style::default().fg(colors::HIGHLIGHT))
+        } else {
+            row.style(Style::default().fg(colors::NORMAL))
+        }
+    }).collect();
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(block)
+        .widths(&[
+            Constraint::Percentage(35),
+            Constraint::Percentage(20),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+        ]);
+
+    f.render_widget(table, area);
+}
+
+fn draw_cargo_footer<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let block = style_utils::create_info_block("HOLD");
+    let inventory = &game.player.inventory;
+
+    let mut spans = vec![
+        Span::styled(
+            format!("{}/{} t used", inventory.used_capacity(), inventory.capacity),
+            Style::default().fg(colors::NORMAL),
+        ),
+    ];
+
+    if let Some(input) = &game.jettison_input {
+        let (name, held) = game.selected_inventory_stack()
+            .map_or((String::new(), 0), |(item, held)| (item.name, held));
+        spans.push(Span::styled(format!("   Jettison how many {}? {}_", name, input), Style::default().fg(colors::WARNING)));
+        spans.push(Span::styled(format!("   (blank = all {})  [Enter] Confirm  [Esc] Cancel", held), Style::default().fg(colors::DIM)));
+    } else if game.inventory_filter_editing {
+        spans.push(Span::styled(format!("   Filter: {}_", game.inventory_filter), Style::default().fg(colors::HIGHLIGHT)));
+        spans.push(Span::styled("   [Enter] Done  [Esc] Clear", Style::default().fg(colors::DIM)));
+    } else {
+        if !game.inventory_filter.is_empty() {
+            spans.push(Span::styled(format!("   Filter: {}", game.inventory_filter), Style::default().fg(colors::INFO)));
+        }
+        spans.push(Span::styled("   [↑/↓] Select  [Enter] Examine  [S] Sort  [/] Filter  [F] Fit  [J] Jettison  [M] Menu", Style::default().fg(colors::DIM)));
+    }
+    let text = Spans::from(spans);
+
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
@@ -6,6 +6,7 @@ use tui::{
     widgets::{Block, Borders, Paragraph, BorderType, Wrap},
     Frame,
 };
+use crossterm::event::KeyCode;
 use crate::game::{Game, GameScreen};
 use crate::ui::{colors, ascii_art};
 
@@ -39,14 +40,7 @@ pub fn draw_main_menu<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     f.render_widget(star_canvas, area);
     
     // Split the screen into sections: title, menu, status
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(8),  // Title
-            Constraint::Min(12),    // Menu
-            Constraint::Length(4),  // Status
-        ])
-        .split(area);
+    let chunks = menu_chunks(area);
     
     // Draw title with ASCII art
     let title_art = ascii_art::get_title_art();
@@ -80,25 +74,16 @@ pub fn draw_main_menu<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
         .border_type(BorderType::Double)
         .border_style(Style::default().fg(colors::SECONDARY));
     
-    // Menu content area
-    let menu_area = menu_block.inner(chunks[1]);
     f.render_widget(menu_block, chunks[1]);
     
     // Create menu items with animation
     let menu_items = get_animated_menu_items(game);
     
-    // Layout for menu items
-    let menu_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Length(1); menu_items.len() + 4])
-        .margin(2)
-        .split(menu_area);
-    
     // Render each menu item
-    for (i, item) in menu_items.iter().enumerate() {
+    for (item, (row, _)) in menu_items.iter().zip(menu_item_rects(area)) {
         let menu_text = Paragraph::new(item.clone())
             .alignment(Alignment::Center);
-        f.render_widget(menu_text, menu_layout[i + 2]);
+        f.render_widget(menu_text, row);
     }
     
     // Draw status bar at the bottom
@@ -126,25 +111,53 @@ pub fn draw_main_menu<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     f.render_widget(status_text, chunks[2]);
 }
 
+// Menu options with their hotkeys
+const MENU_OPTIONS: [(&str, &str, char, GameScreen); 11] = [
+    ("[N]", "Navigation", 'n', GameScreen::Navigation),
+    ("[M]", "Market", 'm', GameScreen::Market),
+    ("[S]", "Ship", 's', GameScreen::Ship),
+    ("[R]", "Mining", 'r', GameScreen::Mining),
+    ("[C]", "Crafting", 'c', GameScreen::Crafting),
+    ("[I]", "Inventory", 'i', GameScreen::Inventory),
+    ("[P]", "Character Profile", 'p', GameScreen::Character),
+    ("[W]", "Market News", 'w', GameScreen::News),
+    ("[H]", "Help", 'h', GameScreen::Help),
+    ("[G]", "New Game", 'g', GameScreen::CharacterCreation),
+    ("[Q]", "Quit", 'q', GameScreen::Quit),
+];
+
+fn menu_chunks(area: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8),  // Title
+            Constraint::Min(12),    // Menu
+            Constraint::Length(4),  // Status
+        ])
+        .split(area)
+}
+
+// Screen row of each menu option paired with its hotkey, for drawing and
+// for mouse clicks
+pub fn menu_item_rects(area: Rect) -> Vec<(Rect, KeyCode)> {
+    let menu_area = Block::default().borders(Borders::ALL).inner(menu_chunks(area)[1]);
+    let menu_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); MENU_OPTIONS.len() + 4])
+        .margin(2)
+        .split(menu_area);
+    
+    MENU_OPTIONS.iter().enumerate()
+        .map(|(i, (_, _, key, _))| (menu_layout[i + 2], KeyCode::Char(*key)))
+        .collect()
+}
+
 fn get_animated_menu_items(game: &Game) -> Vec<Spans> {
     let elapsed_ms = game.last_update.elapsed().as_millis();
     let mut menu_items = Vec::new();
     
-    // Define menu options with their hotkeys
-    let options = vec![
-        ("[N]", "Navigation", GameScreen::Navigation),
-        ("[M]", "Market", GameScreen::Market),
-        ("[S]", "Ship", GameScreen::Ship),
-        ("[R]", "Mining", GameScreen::Mining),
-        ("[C]", "Crafting", GameScreen::Crafting),
-        ("[I]", "Inventory", GameScreen::Inventory),
-        ("[P]", "Character Profile", GameScreen::Character),
-        ("[H]", "Help", GameScreen::Help),
-        ("[Q]", "Quit", GameScreen::Quit),
-    ];
-    
     // Animation logic - different items animate at different times
-    for (i, (key, text, screen)) in options.iter().enumerate() {
+    for (i, (key, text, _, screen)) in MENU_OPTIONS.iter().enumerate() {
         // Calculate animation phase for this item (offset by index)
         let phase = (elapsed_ms + (i as u128 * MENU_ANIMATION_SPEED)) % (MENU_ANIMATION_SPEED * 4);
         
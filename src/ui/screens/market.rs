@@ -3,13 +3,17 @@ use tui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Style},
     text::{Span, Spans},
-    widgets::{Paragraph, Table, Row},
+    widgets::{Cell, Paragraph, Table, Row},
     Frame,
 };
 
+use crossterm::event::KeyCode;
 use crate::game::Game;
+use crate::models::market::PriceDelta;
+use crate::systems::trading::{TradingSystem, TradeEstimate, JUNK_VALUE_THRESHOLD};
 use crate::ui::colors;
 use crate::ui::screens::style_utils;
+use crate::ui::widgets::sparkline::sparkline;
 
 pub fn draw_market_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     // Check if player is docked at a station
@@ -19,15 +23,7 @@ pub fn draw_market_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
     }
 
     // Split the screen into sections
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Mode selection
-            Constraint::Min(10),    // Market items
-            Constraint::Length(3),  // Player info
-            Constraint::Length(3),  // Comms
-        ])
-        .split(area);
+    let chunks = market_chunks(area);
 
     // Draw mode selection
     draw_market_mode(f, game, chunks[0]);
@@ -35,11 +31,63 @@ pub fn draw_market_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
     // Draw market items
     draw_market_items(f, game, chunks[1]);
 
+    // Draw price history of the inspected item
+    draw_item_detail(f, game, chunks[2]);
+
     // Draw player info
-    draw_player_market_info(f, game, chunks[2]);
+    draw_player_market_info(f, game, chunks[3]);
     
     // Draw comms with market info
-    draw_comms(f, game, chunks[3]);
+    draw_comms(f, game, chunks[4]);
+}
+
+fn market_chunks(area: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Mode selection
+            Constraint::Min(10),    // Market items
+            Constraint::Length(5),  // Item detail and trade estimate
+            Constraint::Length(3),  // Player info
+            Constraint::Length(3),  // Comms
+        ])
+        .split(area)
+}
+
+// Clickable parts of the market screen mapped to the keys they stand for:
+// the mode buttons and the first nine item rows
+pub fn click_regions(game: &Game, area: Rect) -> Vec<(Rect, KeyCode)> {
+    if !game.navigation_system.is_docked(&game.player) {
+        return Vec::new();
+    }
+    
+    let chunks = market_chunks(area);
+    let mut regions = Vec::new();
+    
+    // "[B] Buy Items    [S] Sell Items    [M] Main Menu" inside the border
+    let mode_row = chunks[0].y + 1;
+    for (offset, width, key) in [(0, 13, 'b'), (17, 14, 's'), (35, 13, 'm')] {
+        let x = chunks[0].x + 1 + offset;
+        if x + width <= chunks[0].right() {
+            regions.push((Rect::new(x, mode_row, width, 1), KeyCode::Char(key)));
+        }
+    }
+    
+    let item_count = if game.trading_system.is_buy_mode() {
+        game.universe.get_market_items_for_system(game.player.current_system.id.clone()).len()
+    } else {
+        game.player.inventory.items.len()
+    };
+    
+    // Rows start below the top border and the header
+    let items_area = chunks[1];
+    let visible_rows = items_area.height.saturating_sub(3) as usize;
+    for i in 0..item_count.min(visible_rows).min(9) {
+        let row = Rect::new(items_area.x + 1, items_area.y + 2 + i as u16, items_area.width.saturating_sub(2), 1);
+        regions.push((row, KeyCode::Char(std::char::from_digit(i as u32 + 1, 10).unwrap())));
+    }
+    
+    regions
 }
 
 fn draw_not_docked_message<B: Backend>(f: &mut Frame<B>, area: Rect) {
@@ -66,7 +114,7 @@ fn draw_market_mode<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
 
     let is_buy_mode = game.trading_system.is_buy_mode();
 
-    let text = vec![
+    let mut text = vec![
         Spans::from(vec![
             Span::raw("["),
             Span::styled("B", Style::default().fg(if is_buy_mode { colors::PRIMARY } else { colors::WARNING })),
@@ -78,10 +126,38 @@ fn draw_market_mode<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
         ]),
     ];
 
+    if let Some(change) = largest_change_since_last_visit(game) {
+        let color = if change.is_gone() || change.price_change_percent().unwrap_or(0.0) < 0.0 {
+            colors::DANGER
+        } else {
+            colors::SUCCESS
+        };
+        text[0].0.push(Span::raw("    |  "));
+        text[0].0.push(Span::styled(format!("{} since last visit", change.summary()), Style::default().fg(color)));
+    }
+
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, area);
 }
 
+// The most notable change in this market since the player last undocked here
+fn largest_change_since_last_visit(game: &Game) -> Option<PriceDelta> {
+    let system_id = &game.player.current_system.id;
+    let snapshot = game.player.get_market_snapshot(system_id)?;
+    let market = game.universe.get_market(system_id)?;
+
+    market.diff_against(snapshot)
+        .into_iter()
+        .max_by(|a, b| {
+            // New or vanished items outrank any price move
+            let weight = |delta: &PriceDelta| match delta.price_change_percent() {
+                Some(pct) => pct.abs(),
+                None => f32::MAX,
+            };
+            weight(a).partial_cmp(&weight(b)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
 fn draw_market_items<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let title = if game.trading_system.is_buy_mode() {
         "AVAILABLE MERCHANDISE"
@@ -93,7 +169,7 @@ fn draw_market_items<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
 
     // Enhanced header with trend information
     let header = if game.trading_system.is_buy_mode() {
-        Row::new(vec!["#", "Item", "Quantity", "Price", "Trend"]).style(Style::default().fg(colors::INFO))
+        Row::new(vec!["#", "Item", "Quantity", "Price", "Trend", "History"]).style(Style::default().fg(colors::INFO))
     } else {
         Row::new(vec!["#", "Item", "Quantity", "Sell Price"]).style(Style::default().fg(colors::INFO))
     };
@@ -126,59 +202,82 @@ fn draw_market_items<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
         let paragraph = Paragraph::new(text).block(block);
         f.render_widget(paragraph, area);
     } else {
+        let market = game.universe.get_market(&game.player.current_system.id);
         let rows: Vec<Row> = items.iter().enumerate().map(|(i, (item, quantity))| {
-            let price = if game.trading_system.is_buy_mode() {
-                item.value
-            } else {
-                // Sell price is slightly lower than buy price
-                (item.value as f32 * 0.9) as u32
-            };
+            // Buying pays the market's ask, selling gets its bid
+            let price = TradingSystem::quote(market.as_ref(), item, game.trading_system.is_buy_mode());
             
             if game.trading_system.is_buy_mode() {
-                // Get price trend info from market if available
-                let trend_info = game.trading_system.get_price_trend_info(&item.name);
-                let trend_text = match trend_info {
-                    Some((_, trend)) => {
-                        // Determine visual indicator and color based on trend
-                        match trend.as_str() {
-                            "Skyrocketing" => "▲▲▲", // Triple up arrow
-                            "Rising" => "▲▲",       // Double up arrow
-                            "Increasing" => "▲",    // Single up arrow
-                            "Stable" => "◆",        // Diamond
-                            "Decreasing" => "▼",    // Single down arrow
-                            "Falling" => "▼▼",      // Double down arrow
-                            "Plummeting" => "▼▼▼",  // Triple down arrow
-                            _ => "◆",               // Default diamond
-                        }
+                // Arrow, color and percent from the market's recent price history
+                let trend = market.as_ref()
+                    .and_then(|market| market.get_price_trend(&item.name));
+                let trend_cell = match trend {
+                    Some((percent, label)) => {
+                        let arrow = match label.as_str() {
+                            "Skyrocketing" => "▲▲▲",
+                            "Rising" => "▲▲",
+                            "Increasing" => "▲",
+                            "Decreasing" => "▼",
+                            "Falling" => "▼▼",
+                            "Plummeting" => "▼▼▼",
+                            _ => "◆",
+                        };
+                        let color = if percent > 1.0 {
+                            colors::SUCCESS
+                        } else if percent < -1.0 {
+                            colors::DANGER
+                        } else {
+                            colors::DIM
+                        };
+                        Cell::from(format!("{} {:+.1}%", arrow, percent)).style(Style::default().fg(color))
                     },
-                    None => "◆" // Default if no trend
+                    // Not enough price history yet
+                    None => Cell::from("—").style(Style::default().fg(colors::DIM)),
                 };
                 
-                Row::new(vec![
-                    format!("{}", i + 1),
-                    item.name.clone(),
-                    format!("{}", quantity),
-                    format!("{} cr", price),
-                    trend_text.to_string(),
-                ])
+                let history = market.as_ref()
+                    .map(|market| sparkline(&market.get_price_history(&item.name)))
+                    .unwrap_or_default();
+                
+                let row = Row::new(vec![
+                    Cell::from(format!("{}", i + 1)),
+                    Cell::from(item.name.clone()),
+                    Cell::from(format!("{}", quantity)),
+                    Cell::from(format!("{} cr", price)),
+                    trend_cell,
+                    Cell::from(history).style(Style::default().fg(colors::INFO)),
+                ]);
+                
+                if i == game.trading_system.get_inspected_item() {
+                    row.style(Style::default().fg(colors::HIGHLIGHT))
+                } else {
+                    row
+                }
             } else {
                 // Simpler row for sell mode (player inventory)
-                Row::new(vec![
+                let row = Row::new(vec![
                     format!("{}", i + 1),
-                    item.name.clone(),
+                    item.display_name(),
                     format!("{}", quantity),
                     format!("{} cr", price),
-                ])
+                ]);
+                
+                if i == game.trading_system.get_inspected_item() {
+                    row.style(Style::default().fg(colors::HIGHLIGHT))
+                } else {
+                    row
+                }
             }
         }).collect();
 
         let widths = if game.trading_system.is_buy_mode() {
             [
                 Constraint::Length(3),         // #
-                Constraint::Percentage(45),    // Item name
-                Constraint::Percentage(15),    // Quantity
-                Constraint::Percentage(20),    // Price
-                Constraint::Percentage(15),    // Trend
+                Constraint::Percentage(30),    // Item name
+                Constraint::Percentage(12),    // Quantity
+                Constraint::Percentage(15),    // Price
+                Constraint::Percentage(18),    // Trend
+                Constraint::Percentage(20),    // History
             ]
         } else {
             [
@@ -186,7 +285,8 @@ fn draw_market_items<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Constraint::Percentage(55),    // Item name
                 Constraint::Percentage(20),    // Quantity
                 Constraint::Percentage(20),    // Price
-                Constraint::Percentage(0),     // Hidden column (to match the 5-column structure)
+                Constraint::Percentage(0),     // Hidden columns (to match the 6-column structure)
+                Constraint::Percentage(0),
             ]
         };
 
@@ -199,20 +299,158 @@ fn draw_market_items<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     }
 }
 
-fn draw_player_market_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
-    let block = style_utils::create_info_block("FINANCIAL STATUS");
+// Price history of the item picked with the arrow keys
+fn draw_item_detail<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let block = style_utils::create_info_block("ITEM DETAIL");
 
-    let text = vec![
-        Spans::from(vec![
-            Span::raw("Credits: "),
-            Span::styled(format!("{} cr", game.player.credits), Style::default().fg(colors::INFO)),
-            Span::raw("    Cargo: "),
+    let items = game.universe.get_market_items_for_system(game.player.current_system.id.clone());
+    let market = game.universe.get_market(&game.player.current_system.id);
+    let inspected = items.get(game.trading_system.get_inspected_item());
+
+    let mut text = match (inspected, market) {
+        (Some((item, _)), Some(market)) if game.trading_system.is_buy_mode() => {
+            let history = market.get_price_history(&item.name);
+            let low = history.iter().min().copied().unwrap_or(item.value);
+            let high = history.iter().max().copied().unwrap_or(item.value);
+            vec![
+                Spans::from(vec![
+                    Span::styled(format!("{}  ", item.name), Style::default().fg(colors::HIGHLIGHT)),
+                    Span::styled(sparkline(&history), Style::default().fg(colors::INFO)),
+                    Span::styled(
+                        format!("  low {} / high {} cr over {} updates", low, high, history.len()),
+                        Style::default().fg(colors::DIM)
+                    ),
+                ]),
+                alert_line(game, &item.name),
+            ]
+        },
+        // Sell mode offers bulk sales of the highlighted cargo instead
+        _ if !game.trading_system.is_buy_mode() => vec![
+            Spans::from(vec![
+                Span::raw("["),
+                Span::styled("A", Style::default().fg(colors::PRIMARY)),
+                Span::raw("] Sell all of selected  ["),
+                Span::styled("T", Style::default().fg(colors::PRIMARY)),
+                Span::raw("] Sell all of its type  ["),
+                Span::styled("J", Style::default().fg(colors::PRIMARY)),
+                Span::raw(format!("] Sell junk (under {} cr/unit)", JUNK_VALUE_THRESHOLD)),
+            ]),
+            Spans::from(Span::styled("Press [↑/↓] to select cargo", Style::default().fg(colors::DIM))),
+        ],
+        _ => vec![Spans::from(Span::styled("No price history to show", Style::default().fg(colors::DIM)))],
+    };
+
+    if let Some(estimate) = game.trade_estimate.as_ref().filter(|e| e.is_buy == game.trading_system.is_buy_mode()) {
+        text.push(estimate_line(estimate));
+    }
+
+    let paragraph = Paragraph::new(text).block(block);
+    f.render_widget(paragraph, area);
+}
+
+// Price alert prompt while one is being set up, otherwise the detail
+// panel's key hints and how many alerts watch the inspected item
+fn alert_line(game: &Game, item_name: &str) -> Spans<'static> {
+    if let Some((direction, threshold)) = &game.alert_input {
+        return Spans::from(vec![
+            Span::styled(format!("Alert when price goes {} ", direction.label()), Style::default().fg(colors::INFO)),
+            Span::styled(format!("{}_ cr", threshold), Style::default().fg(colors::WARNING)),
             Span::styled(
-                format!("{}/{}", game.player.inventory.used_capacity(), game.player.ship.cargo_capacity),
-                Style::default().fg(colors::INFO)
+                format!("  [Tab] above/below  [R] repeat: {}  [Enter] set  [Esc] cancel",
+                    if game.alert_repeat { "on" } else { "off" }),
+                Style::default().fg(colors::DIM),
             ),
-        ]),
+        ]);
+    }
+
+    let system_id = &game.player.current_system.id;
+    let alerts = game.player.price_alerts.iter()
+        .filter(|alert| &alert.system_id == system_id && alert.item_name == item_name)
+        .count();
+    let mut spans = vec![
+        Span::raw("Press ["),
+        Span::styled("↑/↓", Style::default().fg(colors::PRIMARY)),
+        Span::raw("] to inspect another item, ["),
+        Span::styled("Enter", Style::default().fg(colors::PRIMARY)),
+        Span::raw("] to examine it, ["),
+        Span::styled("L", Style::default().fg(colors::PRIMARY)),
+        Span::raw("] to set a price alert"),
     ];
+    if alerts > 0 {
+        spans.push(Span::styled(
+            format!("  ({} set, [Shift+L] clears)", alerts),
+            Style::default().fg(colors::WARNING),
+        ));
+    }
+    Spans::from(spans)
+}
+
+// One-line summary of what the trade under the cursor costs and leaves behind
+fn estimate_line(estimate: &TradeEstimate) -> Spans<'static> {
+    let verb = if estimate.is_buy { "Buy" } else { "Sell" };
+    let mut spans = vec![
+        Span::styled(
+            format!("{} {} {}: ", verb, estimate.quantity, estimate.item_name),
+            Style::default().fg(colors::NORMAL),
+        ),
+        Span::styled(format!("{} cr", estimate.total), Style::default().fg(colors::HIGHLIGHT)),
+    ];
+    if estimate.tax > 0 {
+        spans.push(Span::styled(format!(" ({} tax)", estimate.tax), Style::default().fg(colors::DIM)));
+    }
+    spans.push(Span::styled(
+        format!(" -> {} cr, {} t", estimate.credits_after, estimate.cargo_after),
+        Style::default().fg(colors::DIM),
+    ));
+
+    match estimate.limit {
+        Some(limit) => spans.push(Span::styled(
+            format!("  max {} ({})", estimate.max_quantity, limit.describe()),
+            Style::default().fg(colors::DANGER),
+        )),
+        None if estimate.is_buy => spans.push(Span::styled(
+            format!("  max {}", estimate.max_quantity),
+            Style::default().fg(colors::DIM),
+        )),
+        None => {},
+    }
+
+    if let Some(break_even) = estimate.break_even_price {
+        spans.push(Span::styled(format!("  break-even {} cr", break_even), Style::default().fg(colors::INFO)));
+    }
+    if let (Some((system_id, price)), Some(profit)) = (&estimate.best_price_elsewhere, estimate.profit_elsewhere()) {
+        let color = if profit > 0 { colors::SUCCESS } else { colors::DANGER };
+        spans.push(Span::styled(
+            format!(", best {} cr at {} ({:+})", price, system_id, profit),
+            Style::default().fg(color),
+        ));
+    }
+
+    Spans::from(spans)
+}
+
+fn draw_player_market_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let block = style_utils::create_info_block("FINANCIAL STATUS");
+
+    let mut spans = vec![
+        Span::raw("Credits: "),
+        Span::styled(format!("{} cr", game.player.credits), Style::default().fg(colors::INFO)),
+    ];
+    // Prices here are in the local scrip; show what the player holds of it
+    if let Some(scrip) = game.local_scrip() {
+        let currency = scrip.currency();
+        spans.push(Span::raw("    Prices in: "));
+        spans.push(Span::styled(
+            format!("{} ({} held)", currency.symbol(), game.player.balance(&currency)),
+            Style::default().fg(colors::WARNING),
+        ));
+    }
+    spans.push(Span::raw("    Cargo: "));
+    spans.push(Span::styled(
+        format!("{}/{}", game.player.inventory.used_capacity(), game.player.ship.cargo_capacity),
+        Style::default().fg(colors::INFO)
+    ));
+    let text = vec![Spans::from(spans)];
 
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, area);
@@ -148,7 +148,7 @@ fn draw_player_mining_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect
     let text = vec![
         Spans::from(vec![
             Span::raw("Mining Power: "),
-            Span::styled(format!("{}", game.player.ship.mining_power), Style::default().fg(colors::INFO)),
+            Span::styled(format!("{}", game.player.ship.mining_rating()), Style::default().fg(colors::INFO)),
             Span::raw(" | Mining Level: "),
             Span::styled(format!("{}", game.player.skills.get_mining_level()), Style::default().fg(colors::INFO)),
             Span::raw(" | Press "),
@@ -11,3 +11,5 @@ pub mod character_creation;
 pub mod character_info;
 pub mod orders;
 pub mod station_services;
+pub mod start_menu;
+pub mod news;
@@ -8,8 +8,10 @@ use tui::{
 };
 
 use crate::game::Game;
+use crate::systems::economy::system_specialization;
 use crate::ui::colors;
 use crate::ui::widgets::starmap::draw_starmap;
+use crate::ui::widgets::system_map::draw_system_map;
 
 pub fn draw_navigation_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     // Split the screen into two parts: starmap and info panel
@@ -21,8 +23,17 @@ pub fn draw_navigation_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: R
         ])
         .split(area);
 
-    // Draw the starmap
-    draw_starmap(f, game, chunks[0]);
+    // Starmap above, the current system's own map below
+    let map_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
+        ])
+        .split(chunks[0]);
+
+    draw_starmap(f, game, map_chunks[0]);
+    draw_system_map(f, game, map_chunks[1]);
 
     // Draw the navigation info panel
     draw_navigation_info(f, game, chunks[1]);
@@ -71,23 +82,40 @@ fn draw_navigation_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
             let distance = game.navigation_system.calculate_distance(&current_system, system);
             let travel_time = game.navigation_system.calculate_travel_time(distance);
             
-            let in_range = game.navigation_system.is_in_range(&game.player, distance);
+            // Jumps need a lane within range, or a gate link
+            let nav = &game.navigation_system;
+            let is_gate = nav.is_gate_link(&current_system, system);
+            let in_range = is_gate
+                || (nav.has_lane(&current_system, system) && nav.is_in_range(&game.player, distance));
             let style = if in_range {
                 Style::default().fg(colors::NORMAL)
             } else {
                 Style::default().fg(colors::DIM)
             };
             
-            text.push(Spans::from(vec![
+            // Same fuel the jump itself would burn
+            let fuel = nav.jump_fuel_cost(&game.player, system);
+            let mut spans = vec![
                 Span::styled(
                     format!("[{}] {} - ", i + 1, system.name),
                     style,
                 ),
                 Span::styled(
-                    format!("{:.1} LY, {} mins", distance, travel_time.as_secs() / 60),
+                    format!("{:.1} LY, {} mins, {} fuel{}", distance, travel_time.as_secs() / 60, fuel,
+                        if is_gate { " (gate)" } else { "" }),
                     style,
                 ),
-            ]));
+            ];
+            if fuel > game.player.ship.current_fuel {
+                spans.push(Span::styled(" NOT ENOUGH FUEL", Style::default().fg(colors::DANGER)));
+            }
+            text.push(Spans::from(spans));
+            // What the system's market deals in
+            let market = game.universe.get_market(&system.id);
+            text.push(Spans::from(Span::styled(
+                format!("    {}", system_specialization(system, market.as_ref())),
+                Style::default().fg(colors::DIM),
+            )));
         }
     }
 
@@ -129,6 +157,25 @@ fn draw_navigation_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     }
 
     text.push(Spans::from(""));
+
+    // Show the remaining hops of a plotted course
+    let course = game.navigation_system.get_course();
+    if !course.is_empty() {
+        let names: Vec<String> = course.iter()
+            .map(|id| game.universe.get_system(id).map(|s| s.name.clone()).unwrap_or_else(|| id.clone()))
+            .collect();
+        text.push(Spans::from(vec![
+            Span::raw("Course: "),
+            Span::styled(names.join(" > "), Style::default().fg(colors::INFO)),
+        ]));
+    }
+
+    text.push(Spans::from(vec![
+        Span::raw("["),
+        Span::styled("F", Style::default().fg(colors::WARNING)),
+        Span::raw("] Find Fuel"),
+    ]));
+    text.extend(routine_lines(game));
     text.push(Spans::from(vec![
         Span::raw("["),
         Span::styled("M", Style::default().fg(colors::WARNING)),
@@ -138,3 +185,33 @@ fn draw_navigation_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, area);
 }
+
+// Keys for recording and running a trade routine, with what each is doing
+fn routine_lines(game: &Game) -> Vec<Spans<'static>> {
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(colors::WARNING));
+    let record = match &game.routine_recording {
+        Some(routine) => Spans::from(vec![
+            Span::raw("["), key("R"), Span::raw("] Save Routine "),
+            Span::styled(format!("(recording, {} steps)", routine.steps.len()), Style::default().fg(colors::DANGER)),
+        ]),
+        None => Spans::from(vec![Span::raw("["), key("R"), Span::raw("] Record Routine")]),
+    };
+    let run = match (&game.routine_run, &game.routine) {
+        (Some(run), _) => Spans::from(vec![
+            Span::raw("["), key("P"), Span::raw("] Stop Routine "),
+            Span::styled(
+                format!(
+                    "(step {}/{}: {})",
+                    run.step + 1, run.routine.steps.len(),
+                    run.current().map(|step| step.describe()).unwrap_or_default(),
+                ),
+                Style::default().fg(colors::SUCCESS),
+            ),
+        ]),
+        (None, Some(routine)) => Spans::from(vec![
+            Span::raw("["), key("P"), Span::raw(format!("] Run Routine ({} steps)", routine.steps.len())),
+        ]),
+        (None, None) => return vec![record],
+    };
+    vec![record, run]
+}
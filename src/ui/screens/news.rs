@@ -0,0 +1,84 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::game::Game;
+use crate::ui::colors;
+use crate::ui::screens::style_utils;
+
+// Market news: every reported event, newest first. Running events come with
+// when they end; events in the player's current system stand out.
+pub fn draw_news_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(5),     // Reports
+            Constraint::Length(3),  // Controls
+        ])
+        .split(area);
+
+    let now = game.time_system.game_time_seconds();
+    let news = game.universe.market_news();
+
+    let items: Vec<ListItem> = if news.is_empty() {
+        vec![ListItem::new(Spans::from(Span::styled(
+            "No market news yet",
+            Style::default().fg(colors::DIM),
+        )))]
+    } else {
+        news.iter().rev()
+            .map(|report| {
+                let system_name = report.system_id.as_deref().map(|system_id| {
+                    game.universe.get_system(system_id)
+                        .map(|system| system.name.clone())
+                        .unwrap_or_else(|| system_id.to_string())
+                });
+                let running = report.is_active(now);
+                let nearby = report.affects(&game.player.current_system.id);
+                let headline_color = match (running, nearby) {
+                    (false, _) => colors::DIM,
+                    (true, true) => colors::WARNING,
+                    (true, false) => colors::DEFAULT_TEXT,
+                };
+                let status = if running {
+                    format!("until {}", game.time_system.clock_string_at(report.ends_at))
+                } else {
+                    "ended".to_string()
+                };
+                let affects = report.item_name().unwrap_or("all goods").to_string();
+
+                ListItem::new(vec![
+                    Spans::from(vec![
+                        Span::styled(
+                            format!("{:<13}", game.time_system.clock_string_at(report.started_at)),
+                            Style::default().fg(colors::INFO),
+                        ),
+                        Span::styled(
+                            report.headline(system_name.as_deref()),
+                            Style::default().fg(headline_color).add_modifier(Modifier::BOLD),
+                        ),
+                    ]),
+                    Spans::from(vec![
+                        Span::raw(" ".repeat(13)),
+                        Span::styled(format!("{} | {}", affects, status), Style::default().fg(colors::DIM)),
+                    ]),
+                ])
+            })
+            .collect()
+    };
+
+    let list = List::new(items).block(style_utils::create_primary_block("MARKET NEWS"));
+    f.render_widget(list, chunks[0]);
+
+    let controls = Paragraph::new(Spans::from(vec![
+        Span::styled("[M]", Style::default().fg(colors::PRIMARY)),
+        Span::raw(" Main Menu"),
+    ]))
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(colors::DIM)));
+    f.render_widget(controls, chunks[1]);
+}
@@ -9,7 +9,7 @@ use tui::{
 
 use crate::{
     game::Game,
-    models::market::{OrderStatus, OrderType},
+    models::market::{OrderKind, OrderStatus, OrderType},
     ui::colors,
 };
 
@@ -76,10 +76,7 @@ pub fn draw_orders_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
                     Style::default().fg(colors::DEFAULT_TEXT)
                 };
                 
-                let order_type_text = match order.order_type {
-                    OrderType::Buy => "BUY",
-                    OrderType::Sell => "SELL",
-                };
+                let order_type_text = order.kind_label();
                 
                 let order_status_text = match order.status {
                     OrderStatus::Active => "ACTIVE",
@@ -166,9 +163,16 @@ pub fn draw_orders_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
                 OrderStatus::Expired => colors::DIM,
             };
             
-            let order_type = match order.order_type {
-                OrderType::Buy => "Buy",
-                OrderType::Sell => "Sell",
+            let order_type = match (order.kind, &order.order_type) {
+                (OrderKind::Limit, OrderType::Buy) => "Buy".to_string(),
+                (OrderKind::Limit, OrderType::Sell) => "Sell".to_string(),
+                (OrderKind::StopLoss, _) => "Stop-loss sell".to_string(),
+                (OrderKind::TakeProfit, _) => "Take-profit sell".to_string(),
+                (OrderKind::TrailingStop { percent }, _) => format!(
+                    "Trailing stop, {}% below peak {}",
+                    percent,
+                    order.peak_price.unwrap_or(order.target_price),
+                ),
             };
             
             let order_type_color = match order.order_type {
@@ -176,6 +180,20 @@ pub fn draw_orders_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
                 OrderType::Sell => colors::SUCCESS,
             };
             
+            let mut price_spans = vec![
+                Span::styled("Quantity: ", Style::default().fg(colors::DIM)),
+                Span::styled(order.quantity.to_string(), Style::default().fg(colors::DEFAULT_TEXT)),
+                Span::raw("  |  "),
+                Span::styled("Target Price: ", Style::default().fg(colors::DIM)),
+                Span::styled(order.target_price.to_string(), Style::default().fg(colors::DEFAULT_TEXT)),
+            ];
+            // Filled orders also show what they actually filled at
+            if let Some(price) = order.executed_price {
+                price_spans.push(Span::raw("  |  "));
+                price_spans.push(Span::styled("Filled At: ", Style::default().fg(colors::DIM)));
+                price_spans.push(Span::styled(price.to_string(), Style::default().fg(colors::DEFAULT_TEXT)));
+            }
+            
             vec![
                 Spans::from(vec![
                     Span::styled("Type: ", Style::default().fg(colors::DIM)),
@@ -188,13 +206,7 @@ pub fn draw_orders_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
                     Span::styled("Item: ", Style::default().fg(colors::DIM)),
                     Span::styled(&order.item_name, Style::default().fg(colors::DEFAULT_TEXT)),
                 ]),
-                Spans::from(vec![
-                    Span::styled("Quantity: ", Style::default().fg(colors::DIM)),
-                    Span::styled(order.quantity.to_string(), Style::default().fg(colors::DEFAULT_TEXT)),
-                    Span::raw("  |  "),
-                    Span::styled("Target Price: ", Style::default().fg(colors::DIM)),
-                    Span::styled(order.target_price.to_string(), Style::default().fg(colors::DEFAULT_TEXT)),
-                ]),
+                Spans::from(price_spans),
                 Spans::from(vec![
                     Span::styled("Total Value: ", Style::default().fg(colors::DIM)),
                     Span::styled(
@@ -240,6 +252,12 @@ pub fn draw_orders_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
                 Span::raw("uy  "),
                 Span::styled("[S]", Style::default().fg(colors::PRIMARY)),
                 Span::raw("ell  "),
+                Span::styled("[L]", Style::default().fg(colors::PRIMARY)),
+                Span::raw(" Stop-loss  "),
+                Span::styled("[P]", Style::default().fg(colors::PRIMARY)),
+                Span::raw("rofit  "),
+                Span::styled("[F]", Style::default().fg(colors::PRIMARY)),
+                Span::raw(" Trailing  "),
                 Span::styled("[C]", Style::default().fg(colors::PRIMARY)),
                 Span::raw("ancel  "),
                 Span::styled("[Tab]", Style::default().fg(colors::PRIMARY)),
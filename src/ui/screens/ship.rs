@@ -1,7 +1,7 @@
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Style},
+    style::{Color, Style},
     text::{Span, Spans},
     widgets::{Paragraph},
     Frame,
@@ -25,8 +25,50 @@ pub fn draw_ship_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     // Draw ship ASCII art and basic info
     draw_ship_visual(f, game, chunks[0]);
 
+    let right_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(10),    // Ship stats
+            Constraint::Length(game.player.ship.crew_capacity() as u16 + 4), // Crew roster
+        ])
+        .split(chunks[1]);
+
     // Draw ship stats
-    draw_ship_stats(f, game, chunks[1]);
+    draw_ship_stats(f, game, right_chunks[0]);
+
+    // Draw crew roster
+    draw_crew_roster(f, game, right_chunks[1]);
+}
+
+fn draw_crew_roster<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let ship = &game.player.ship;
+    let title = format!("CREW {}/{}", ship.crew.len(), ship.crew_capacity());
+    let block = style_utils::create_info_block(&title);
+
+    let mut text = Vec::new();
+    if ship.crew.is_empty() {
+        text.push(Spans::from(vec![
+            Span::styled("No crew aboard. Recruit at Habitation stations.", Style::default().fg(colors::DIM)),
+        ]));
+    } else {
+        for (i, member) in ship.crew.iter().enumerate() {
+            text.push(Spans::from(vec![
+                Span::raw(format!("[{}] ", i + 1)),
+                Span::styled(member.name.clone(), Style::default().fg(colors::PRIMARY)),
+                Span::raw(format!(" - {} (lvl {}) ", member.role.to_string(), member.skill_level)),
+                Span::styled(member.bonus_description(), Style::default().fg(colors::INFO)),
+            ]));
+        }
+    }
+
+    text.push(Spans::from(vec![
+        Span::styled("Wages: ", Style::default().fg(colors::DIM)),
+        Span::styled(format!("{} cr/day", ship.crew_daily_upkeep()), Style::default().fg(colors::WARNING)),
+        Span::raw("  |  Press [1-4] to dismiss crew"),
+    ]));
+
+    let paragraph = Paragraph::new(text).block(block);
+    f.render_widget(paragraph, area);
 }
 
 fn draw_ship_visual<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
@@ -62,6 +104,17 @@ fn draw_ship_visual<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
+// Green when healthy, yellow once damaged, red when close to breaking up
+fn integrity_color(integrity: f32) -> Color {
+    if integrity > 60.0 {
+        colors::SUCCESS
+    } else if integrity > 25.0 {
+        colors::WARNING
+    } else {
+        colors::DANGER
+    }
+}
+
 fn draw_ship_stats<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     let block = style_utils::create_info_block("TECHNICAL SPECIFICATIONS");
 
@@ -75,6 +128,13 @@ fn draw_ship_stats<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::PRIMARY)
             ),
         ]),
+        Spans::from(vec![
+            Span::styled("Hull Integrity: ", Style::default().fg(colors::DIM)),
+            Span::styled(
+                format!("{:.0}%", ship.hull_integrity()),
+                Style::default().fg(integrity_color(ship.hull_integrity()))
+            ),
+        ]),
         Spans::from(vec![
             Span::styled("Shield: ", Style::default().fg(colors::DIM)),
             Span::styled(
@@ -104,6 +164,13 @@ fn draw_ship_stats<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::NORMAL)
             ),
         ]),
+        Spans::from(vec![
+            Span::styled("Fuel Efficiency: ", Style::default().fg(colors::DIM)),
+            Span::styled(
+                format!("{:.0}%", ship.effective_fuel_efficiency() * 100.0),
+                Style::default().fg(colors::NORMAL)
+            ),
+        ]),
         Spans::from(""),
         Spans::from(vec![
             Span::styled("Weapons: ", Style::default().fg(colors::DIM)),
@@ -112,10 +179,17 @@ fn draw_ship_stats<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
                 Style::default().fg(colors::DANGER)
             ),
         ]),
+        Spans::from(vec![
+            Span::styled("Attack / Defense: ", Style::default().fg(colors::DIM)),
+            Span::styled(
+                format!("{} / {} ({}/{} modules)", ship.attack_rating(), ship.defense_rating(), ship.modules.len(), ship.module_slots()),
+                Style::default().fg(colors::NORMAL)
+            ),
+        ]),
         Spans::from(vec![
             Span::styled("Mining Power: ", Style::default().fg(colors::DIM)),
             Span::styled(
-                format!("{}", ship.mining_power),
+                format!("{}", ship.mining_rating()),
                 Style::default().fg(colors::WARNING)
             ),
         ]),
@@ -0,0 +1,109 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect, Alignment},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, BorderType},
+    Frame,
+};
+use crate::game::{Game, StartOption};
+use crate::ui::{colors, ascii_art};
+use crate::utils::save_load::{MetaSync, SaveMeta};
+
+pub fn draw_start_menu<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let menu = &game.start_menu;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8),      // Title
+            Constraint::Min(6),         // Options
+            Constraint::Length(1),      // Key hints
+        ])
+        .split(area);
+
+    let title = Paragraph::new(ascii_art::get_title_art())
+        .style(Style::default().fg(colors::PRIMARY))
+        .alignment(Alignment::Center);
+    f.render_widget(title, chunks[0]);
+
+    let mut lines = Vec::new();
+    for (i, entry) in menu.entries.iter().enumerate() {
+        let label = match &entry.option {
+            StartOption::Continue => "Continue".to_string(),
+            StartOption::NewGame => "New Game".to_string(),
+            StartOption::Load(slot) => format!("Load: {}", slot),
+        };
+        let selected = i == menu.selected;
+        let marker = if selected { "> " } else { "  " };
+
+        // Options that can't be chosen stay listed, dimmed, with the reason
+        let mut spans = match &entry.unavailable {
+            Some(reason) => vec![
+                Span::styled(format!("{}{}", marker, label), Style::default().fg(colors::DIM)),
+                Span::styled(format!("  ({})", reason), Style::default().fg(colors::DIM)),
+            ],
+            None if selected => vec![Span::styled(
+                format!("{}{}", marker, label),
+                Style::default().fg(colors::HIGHLIGHT).add_modifier(Modifier::BOLD),
+            )],
+            None => vec![Span::styled(format!("{}{}", marker, label), Style::default().fg(colors::NORMAL))],
+        };
+        if selected && entry.option == StartOption::NewGame {
+            if let Some(seed) = &menu.seed_input {
+                spans.push(Span::styled(
+                    format!("  Seed: {}_", seed),
+                    Style::default().fg(colors::WARNING),
+                ));
+            }
+        }
+        lines.push(Spans::from(spans));
+        if let Some(meta) = &entry.meta {
+            lines.push(preview_line(meta));
+        }
+    }
+
+    let options = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default()
+            .title(Span::styled(" FLIGHT RECORDER ", Style::default().fg(colors::PRIMARY)))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(colors::SECONDARY)));
+    f.render_widget(options, centered(chunks[1], 60));
+
+    let hint = if menu.seed_input.is_some() {
+        "Type a seed, or leave blank for a random one. [Enter] Start  [Esc] Back"
+    } else {
+        "[↑/↓] Select  [Enter] Choose  [Q] Quit"
+    };
+    let hint = Paragraph::new(hint)
+        .style(Style::default().fg(colors::INFO))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[2]);
+}
+
+// Who the save belongs to and how far along it is, from its metadata alone
+fn preview_line(meta: &SaveMeta) -> Spans<'static> {
+    let style = Style::default().fg(colors::DIM);
+    if meta.sync == MetaSync::Missing {
+        return Spans::from(Span::styled("    no details saved", style));
+    }
+    let played = meta.summary.play_time_secs;
+    let mut spans = vec![Span::styled(
+        format!(
+            "    {} · {} cr · {}h {:02}m played",
+            meta.summary.character_name, meta.summary.credits, played / 3600, played / 60 % 60,
+        ),
+        style,
+    )];
+    if meta.sync == MetaSync::Stale {
+        spans.push(Span::styled(" (details may be out of date)", Style::default().fg(colors::WARNING)));
+    }
+    Spans::from(spans)
+}
+
+// A column `width` wide in the middle of `area`
+fn centered(area: Rect, width: u16) -> Rect {
+    let width = width.min(area.width);
+    Rect { x: area.x + (area.width - width) / 2, width, ..area }
+}
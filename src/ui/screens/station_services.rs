@@ -7,10 +7,13 @@ use tui::{
     Frame,
 };
 
-use crate::game::Game;
+use crate::game::{Game, EXCHANGE_LOT};
 use crate::ui::colors;
 use crate::ui::screens::style_utils;
 use crate::ui::ascii_art;
+use crate::models::crew;
+use crate::models::insurance::{InsurancePolicy, POLICY_TERM_DAYS};
+use crate::systems::crafting::{CraftingSystem, RESEARCH_COST, RESEARCH_HOURS};
 
 pub fn draw_station_services_screen<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
     // Check if player is docked at a station
@@ -153,6 +156,20 @@ fn draw_station_services<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
             ]));
         }
         
+        // Hull repairs are offered at every station
+        let ship = &game.player.ship;
+        let repair_status = if ship.hull_damage() > 0 {
+            format!("Available ({:.0}%)", ship.hull_integrity())
+        } else {
+            "Hull Intact".to_string()
+        };
+        
+        rows.push(Row::new(vec![
+            "R".to_string(),
+            "Hull Repair".to_string(),
+            repair_status,
+        ]));
+        
         // Add other services based on station type
         let mut service_idx = 3;
         for service in &station.services {
@@ -180,7 +197,7 @@ fn draw_station_services<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
         f.render_widget(services_table, chunks[0]);
         
         // Draw service details
-        let details_text = vec![
+        let mut details_text = vec![
             Spans::from(vec![
                 Span::styled("Select a service by number:", Style::default().fg(colors::INFO)),
             ]),
@@ -191,14 +208,105 @@ fn draw_station_services<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect)
                 Span::raw("] Refuel your ship - "),
                 Span::styled("25 credits per unit".to_string(), Style::default().fg(colors::PRIMARY)),
             ]),
-            Spans::from(""),
             Spans::from(vec![
                 Span::raw("["),
-                Span::styled("M", Style::default().fg(colors::WARNING)),
-                Span::raw("] Main Menu"),
+                Span::styled("R", Style::default().fg(colors::WARNING)),
+                Span::raw("] Repair hull - "),
+                Span::styled("15 credits per hull point".to_string(), Style::default().fg(colors::PRIMARY)),
             ]),
         ];
         
+        // Insurance: the current policy, or what a new one would cost
+        let today = game.time_system.get_game_day();
+        match game.player.insurance.as_ref().filter(|policy| policy.is_active(today)) {
+            Some(policy) => details_text.push(Spans::from(vec![
+                Span::styled("Insured", Style::default().fg(colors::SUCCESS)),
+                Span::raw(format!(" until day {} ({} days left) - deductible ", policy.expires_on_day, policy.days_left(today))),
+                Span::styled(format!("{} cr", policy.deductible), Style::default().fg(colors::PRIMARY)),
+            ])),
+            None => {
+                let quote = InsurancePolicy::quote(&game.player.ship, today);
+                details_text.push(Spans::from(vec![
+                    Span::raw("["),
+                    Span::styled("I", Style::default().fg(colors::WARNING)),
+                    Span::raw(format!("] Insure ship for {} days - ", POLICY_TERM_DAYS)),
+                    Span::styled(format!("{} cr", quote.premium), Style::default().fg(colors::PRIMARY)),
+                    Span::raw(format!(" (covers {} cr, {} cr deductible)", quote.insured_value, quote.deductible)),
+                ]));
+            },
+        }
+        
+        // Waiting lets orders, markets and crafting jobs catch up
+        match &game.wait_input {
+            Some(hours) => details_text.push(Spans::from(vec![
+                Span::styled("Wait how many hours? ", Style::default().fg(colors::INFO)),
+                Span::styled(format!("{}_", hours), Style::default().fg(colors::WARNING)),
+                Span::raw(" (Enter to wait, Esc to cancel)"),
+            ])),
+            None => details_text.push(Spans::from(vec![
+                Span::raw("["),
+                Span::styled("W", Style::default().fg(colors::WARNING)),
+                Span::raw(format!("] Wait - pass time at the station (now {})", game.time_system.get_formatted_time())),
+            ])),
+        }
+        
+        // Stations in a faction's territory change credits into its scrip
+        if let Some(scrip) = game.local_scrip() {
+            let currency = scrip.currency();
+            details_text.push(Spans::from(vec![
+                Span::raw("["),
+                Span::styled("X", Style::default().fg(colors::WARNING)),
+                Span::raw(format!("] Exchange {} cr for ", EXCHANGE_LOT)),
+                Span::styled(currency.format(scrip.buy_quote(EXCHANGE_LOT)), Style::default().fg(colors::PRIMARY)),
+                Span::raw(format!(" - [Shift+X] sell your {} ({} held)", currency.symbol(), game.player.balance(&currency))),
+            ]));
+        }
+        
+        // Research laboratories sell attempts at new blueprints
+        if station.services.contains(&"Blueprint Research".to_string()) {
+            let unknown = game.crafting_system.unknown_blueprints(&game.player).len();
+            details_text.push(Spans::from(vec![
+                Span::raw("["),
+                Span::styled("E", Style::default().fg(colors::WARNING)),
+                Span::raw(format!("] Research a blueprint for {} hours - ", RESEARCH_HOURS)),
+                Span::styled(format!("{} cr", RESEARCH_COST), Style::default().fg(colors::PRIMARY)),
+                Span::raw(format!(" ({:.0}% chance, {} left to find)",
+                    CraftingSystem::research_chance(&game.player) * 100.0, unknown)),
+            ]));
+        }
+        details_text.push(Spans::from(""));
+        
+        // Crew for hire at stations with a recruitment office
+        if station.services.contains(&"Crew Recruitment".to_string()) {
+            let candidates = crew::recruitment_pool(&station.id, game.time_system.get_game_day());
+            details_text.push(Spans::from(vec![
+                Span::styled(
+                    format!("Crew for hire ({}/{} aboard):", game.player.ship.crew.len(), game.player.ship.crew_capacity()),
+                    Style::default().fg(colors::INFO)
+                ),
+            ]));
+            for (i, candidate) in candidates.iter().enumerate() {
+                let key = (b'A' + i as u8) as char;
+                details_text.push(Spans::from(vec![
+                    Span::raw("["),
+                    Span::styled(key.to_string(), Style::default().fg(colors::WARNING)),
+                    Span::raw(format!("] {} - {} lvl {} ({}) - ", 
+                        candidate.name, candidate.role.to_string(), candidate.skill_level, candidate.bonus_description())),
+                    Span::styled(
+                        format!("{} cr + {} cr/day", candidate.hire_cost, candidate.daily_upkeep),
+                        Style::default().fg(colors::PRIMARY)
+                    ),
+                ]));
+            }
+            details_text.push(Spans::from(""));
+        }
+        
+        details_text.push(Spans::from(vec![
+            Span::raw("["),
+            Span::styled("M", Style::default().fg(colors::WARNING)),
+            Span::raw("] Main Menu"),
+        ]));
+        
         let paragraph = Paragraph::new(details_text);
         f.render_widget(paragraph, chunks[1]);
     } else {
@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::ui::colors;
+
+// Chat lines from other players, newest at the bottom, with the input line
+// underneath while the player is typing
+pub fn draw_chat_pane<B: Backend>(f: &mut Frame<B>, log: &VecDeque<String>, input: Option<&str>, area: Rect) {
+    let border_color = if input.is_some() { colors::PRIMARY } else { colors::DIM };
+    let block = Block::default()
+        .title(Span::styled(" COMMS ", Style::default().fg(colors::INFO)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color));
+
+    let inner_height = block.inner(area).height as usize;
+    let history_lines = if input.is_some() { inner_height.saturating_sub(1) } else { inner_height };
+
+    let mut text: Vec<Spans> = log.iter()
+        .skip(log.len().saturating_sub(history_lines))
+        .map(|line| Spans::from(Span::styled(line.as_str(), Style::default().fg(colors::NORMAL))))
+        .collect();
+
+    match input {
+        Some(input) => text.push(Spans::from(vec![
+            Span::styled("> ", Style::default().fg(colors::PRIMARY)),
+            Span::raw(input),
+            Span::styled("_", Style::default().fg(colors::PRIMARY)),
+        ])),
+        None if log.is_empty() => text.push(Spans::from(Span::styled(
+            "Press / to chat",
+            Style::default().fg(colors::DIM),
+        ))),
+        None => {},
+    }
+
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
@@ -0,0 +1,62 @@
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::game::PendingAction;
+use crate::ui::colors;
+
+// A yes/no prompt shown over the current screen. The action only runs once
+// the player confirms it.
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    pub message: String,
+    pub action: PendingAction,
+}
+
+impl ConfirmDialog {
+    pub fn new(message: String, action: PendingAction) -> Self {
+        ConfirmDialog { message, action }
+    }
+}
+
+pub fn draw_confirm_dialog<B: Backend>(f: &mut Frame<B>, dialog: &ConfirmDialog, area: Rect) {
+    // Centered box, clamped to the available area
+    let width = 60.min(area.width);
+    let height = 7.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .title(Span::styled(" CONFIRM ", Style::default().fg(colors::WARNING)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::WARNING));
+
+    let text = vec![
+        Spans::from(Span::styled(dialog.message.as_str(), Style::default().fg(colors::NORMAL))),
+        Spans::from(""),
+        Spans::from(vec![
+            Span::raw("["),
+            Span::styled("Y", Style::default().fg(colors::PRIMARY)),
+            Span::raw("] Confirm    ["),
+            Span::styled("N", Style::default().fg(colors::DANGER)),
+            Span::raw("] Cancel"),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
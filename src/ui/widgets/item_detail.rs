@@ -0,0 +1,147 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::game::Game;
+use crate::models::item::Item;
+use crate::ui::colors;
+
+// Look up an item by name in the player's hold, then in every market
+fn find_item(game: &Game, name: &str) -> Option<Item> {
+    if let Some((item, _)) = game.player.inventory.items.iter().find(|(item, _)| item.name == name) {
+        return Some(item.clone());
+    }
+
+    game.universe.get_all_system_ids().into_iter()
+        .flat_map(|system_id| game.universe.get_market_items_for_system(system_id))
+        .map(|(item, _)| item)
+        .find(|item| item.name == name)
+}
+
+fn system_name(game: &Game, system_id: &str) -> String {
+    game.universe.get_system(system_id)
+        .map(|system| system.name.clone())
+        .unwrap_or_else(|| system_id.to_string())
+}
+
+fn label(text: &str) -> Span<'static> {
+    Span::styled(format!("{:<10}", text), Style::default().fg(colors::DIM))
+}
+
+// Popup with everything known about one item: what it is, where it trades
+// cheapest and dearest, and which recipes make or use it
+pub fn draw_item_detail<B: Backend>(f: &mut Frame<B>, game: &Game, item_name: &str, area: Rect) {
+    let width = 64.min(area.width);
+    let height = 21.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let mut text = Vec::new();
+    match find_item(game, item_name) {
+        Some(item) => {
+            text.push(Spans::from(Span::styled(
+                item.display_name(),
+                Style::default().fg(colors::HIGHLIGHT).add_modifier(Modifier::BOLD),
+            )));
+            text.push(Spans::from(vec![label("Type"), Span::raw(item.item_type.to_string())]));
+            text.push(Spans::from(vec![label("Rarity"), Span::raw(item.rarity.to_string())]));
+            text.push(Spans::from(vec![
+                label("Value"),
+                Span::raw(format!("{} cr, {} t each", item.value, item.weight)),
+            ]));
+            text.push(Spans::from(vec![
+                label("In cargo"),
+                Span::raw(game.player.inventory.get_item_quantity(item_name).to_string()),
+            ]));
+            // Equipment and modules describe themselves
+            let attributes = &item.attributes;
+            if let Some(module) = &attributes.module {
+                text.push(Spans::from(vec![
+                    label("Fits as"),
+                    Span::raw(format!("{:?} module, rating {}", module.module_type, module.rating)),
+                ]));
+            }
+            if let Some(durability) = attributes.durability {
+                text.push(Spans::from(vec![label("Condition"), Span::raw(durability.to_string())]));
+            }
+            if let Some(charges) = attributes.charges {
+                text.push(Spans::from(vec![label("Charges"), Span::raw(charges.to_string())]));
+            }
+        },
+        None => {
+            text.push(Spans::from(Span::styled(item_name, Style::default().fg(colors::HIGHLIGHT))));
+        },
+    }
+    text.push(Spans::from(""));
+
+    // Prices come back cheapest first
+    let prices = game.universe.get_price_comparison(item_name);
+    match (prices.first(), prices.last()) {
+        (Some(cheapest), Some(dearest)) => {
+            text.push(Spans::from(vec![
+                label("Buy at"),
+                Span::styled(
+                    format!("{} ({} cr)", system_name(game, &cheapest.0), cheapest.1),
+                    Style::default().fg(colors::SUCCESS),
+                ),
+            ]));
+            text.push(Spans::from(vec![
+                label("Sell at"),
+                Span::styled(
+                    format!("{} ({} cr)", system_name(game, &dearest.0), dearest.1),
+                    Style::default().fg(colors::WARNING),
+                ),
+            ]));
+        },
+        _ => text.push(Spans::from(Span::styled(
+            "Not traded in any known market",
+            Style::default().fg(colors::DIM),
+        ))),
+    }
+    text.push(Spans::from(""));
+
+    let recipe_names = |recipes: Vec<&crate::systems::crafting::CraftingRecipe>| {
+        if recipes.is_empty() {
+            "—".to_string()
+        } else {
+            recipes.iter().map(|recipe| recipe.name.as_str()).collect::<Vec<_>>().join(", ")
+        }
+    };
+    text.push(Spans::from(vec![
+        label("Made by"),
+        Span::raw(recipe_names(game.crafting_system.recipes_producing(item_name))),
+    ]));
+    text.push(Spans::from(vec![
+        label("Used in"),
+        Span::raw(recipe_names(game.crafting_system.recipes_using(item_name))),
+    ]));
+    text.push(Spans::from(""));
+    text.push(Spans::from(vec![
+        Span::raw("["),
+        Span::styled("Enter", Style::default().fg(colors::PRIMARY)),
+        Span::raw("/"),
+        Span::styled("Esc", Style::default().fg(colors::PRIMARY)),
+        Span::raw("] Close"),
+    ]));
+
+    let block = Block::default()
+        .title(Span::styled(" EXAMINE ", Style::default().fg(colors::INFO)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::INFO));
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
@@ -1,3 +1,10 @@
 pub mod starmap;
 pub mod status_bar;
 pub mod menu;
+pub mod sparkline;
+pub mod confirm_dialog;
+pub mod travel_animation;
+pub mod system_map;
+pub mod item_detail;
+pub mod chat_pane;
+pub mod tutorial_hint;
@@ -0,0 +1,42 @@
+// Tiny inline charts drawn with Unicode block characters
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Render `values` as one bar per value, scaled between their min and max.
+// A flat series (or a single point) is drawn at mid height.
+pub fn sparkline(values: &[u32]) -> String {
+    let min = match values.iter().min() {
+        Some(min) => *min,
+        None => return String::new(),
+    };
+    let max = *values.iter().max().unwrap();
+
+    if min == max {
+        return BARS[BARS.len() / 2 - 1].to_string().repeat(values.len());
+    }
+
+    let range = (max - min) as f32;
+    values.iter()
+        .map(|value| {
+            let level = ((value - min) as f32 / range * (BARS.len() - 1) as f32).round() as usize;
+            BARS[level]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_to_range() {
+        assert_eq!(sparkline(&[10, 20, 30]), "▁▅█");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_flat_and_single_point_history() {
+        assert_eq!(sparkline(&[50, 50, 50]), "▄▄▄");
+        assert_eq!(sparkline(&[7]), "▄");
+    }
+}
@@ -1,6 +1,6 @@
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::Rect,
     style::Style,
     text::{Span, Spans},
     widgets::Paragraph,
@@ -8,77 +8,85 @@ use tui::{
 };
 
 use crate::game::Game;
+use crate::network::connection::ConnectionStatus;
 use crate::ui::colors;
 
+// Cells in the fuel gauge
+const FUEL_BAR_WIDTH: usize = 6;
+// Fuel below this share of capacity shows in red
+const LOW_FUEL_FRACTION: f32 = 0.2;
+
+// One line of the stats a pilot needs at a glance: where they are, money,
+// fuel, cargo, hull and the clock. Segments are ordered by importance so a
+// narrow terminal cuts off the least useful ones.
 pub fn draw_status_bar<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(25),  // Player info
-            Constraint::Percentage(25),  // Ship info
-            Constraint::Percentage(25),  // Location
-            Constraint::Percentage(25),  // Time
-        ])
-        .split(area);
+    let player = &game.player;
+    let ship = &player.ship;
+    let separator = || Span::styled(" │ ", Style::default().fg(colors::DIM));
 
-    draw_player_info(f, game, chunks[0]);
-    draw_ship_info(f, game, chunks[1]);
-    draw_location_info(f, game, chunks[2]);
-    draw_time_info(f, game, chunks[3]);
-}
+    let (dock_state, dock_color) = if game.navigation_system.is_docked(player) {
+        ("Docked", colors::SUCCESS)
+    } else {
+        ("In space", colors::DIM)
+    };
 
-fn draw_player_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
-    let text = Spans::from(vec![
-        Span::raw(format!("{}  ", game.player.character.name)),
+    let mut spans = vec![
+        Span::styled(player.current_system.name.clone(), Style::default().fg(colors::INFO)),
+        Span::styled(format!(" [{}]", dock_state), Style::default().fg(dock_color)),
+        separator(),
+        Span::styled(format!("{} cr", player.credits), Style::default().fg(colors::PRIMARY)),
+        separator(),
+    ];
+    spans.extend(fuel_spans(ship.current_fuel, ship.fuel_capacity));
+    spans.extend([
+        separator(),
         Span::styled(
-            format!("Credits: {} cr", game.player.credits),
-            Style::default().fg(colors::INFO),
+            format!("Cargo {}/{}", player.inventory.used_capacity(), ship.cargo_capacity),
+            Style::default().fg(colors::NORMAL),
         ),
+        separator(),
+        Span::styled(format!("Hull {}/{}", ship.hull, ship.max_hull), Style::default().fg(colors::HULL)),
+        separator(),
+        Span::styled(game.time_system.get_clock_string(), Style::default().fg(colors::NORMAL)),
     ]);
+    if game.paused {
+        spans.push(Span::styled("  PAUSED", Style::default().fg(colors::WARNING)));
+    }
 
-    let paragraph = Paragraph::new(text);
-    f.render_widget(paragraph, area);
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
 }
 
-fn draw_ship_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
-    let ship = &game.player.ship;
-    let text = Spans::from(vec![
-        Span::raw(format!("{}  ", ship.name)),
-        Span::styled(
-            format!("Hull: {}/{}", ship.hull, ship.max_hull),
-            Style::default().fg(colors::PRIMARY),
-        ),
+// One line saying whether the client can reach the server: green when
+// online, yellow while reconnecting, red once it has given up
+pub fn draw_connection_status<B: Backend>(f: &mut Frame<B>, status: &ConnectionStatus, area: Rect) {
+    let color = match status {
+        ConnectionStatus::Connected => colors::SUCCESS,
+        ConnectionStatus::Reconnecting { .. } => colors::WARNING,
+        ConnectionStatus::Offline { .. } => colors::DANGER,
+    };
+    let line = Spans::from(vec![
+        Span::styled("Server: ", Style::default().fg(colors::DIM)),
+        Span::styled(status.describe(), Style::default().fg(color)),
     ]);
-
-    let paragraph = Paragraph::new(text);
-    f.render_widget(paragraph, area);
+    f.render_widget(Paragraph::new(line), area);
 }
 
-fn draw_location_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
-    let system = &game.player.current_system;
-    let location = if game.navigation_system.is_docked(&game.player) {
-        format!("{} (Docked)", system.name)
+// Fuel as a gauge plus current/capacity, red when running low
+fn fuel_spans(current: u32, capacity: u32) -> Vec<Span<'static>> {
+    let fraction = if capacity == 0 { 0.0 } else { current as f32 / capacity as f32 };
+    let color = if fraction < LOW_FUEL_FRACTION {
+        colors::DANGER
+    } else if fraction < 0.5 {
+        colors::WARNING
     } else {
-        format!("{} (Space)", system.name)
+        colors::SUCCESS
     };
+    let filled = ((fraction * FUEL_BAR_WIDTH as f32).ceil() as usize).min(FUEL_BAR_WIDTH);
 
-    let text = Spans::from(vec![
-        Span::raw("Location: "),
-        Span::styled(location, Style::default().fg(colors::INFO)),
-    ]);
-
-    let paragraph = Paragraph::new(text);
-    f.render_widget(paragraph, area);
-}
-
-fn draw_time_info<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
-    let game_time = game.time_system.get_formatted_time();
-    
-    let text = Spans::from(vec![
-        Span::raw("Time: "),
-        Span::styled(game_time, Style::default().fg(colors::NORMAL)),
-    ]);
-
-    let paragraph = Paragraph::new(text);
-    f.render_widget(paragraph, area);
+    vec![
+        Span::raw("Fuel "),
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled("░".repeat(FUEL_BAR_WIDTH - filled), Style::default().fg(colors::DIM)),
+        Span::styled(format!(" {}/{}", current, capacity), Style::default().fg(color)),
+    ]
 }
@@ -0,0 +1,122 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tui::style::Color;
+
+use crate::game::Game;
+use crate::models::universe::{CelestialBodyType, StarSystem};
+use crate::ui::colors;
+
+// Something plotted on the system map
+struct MapEntry {
+    glyph: char,
+    color: Color,
+    x: f32,
+    y: f32,
+    details: String,
+}
+
+fn body_glyph(body_type: &CelestialBodyType) -> (char, Color) {
+    match body_type {
+        CelestialBodyType::Star => ('★', colors::WARNING),
+        CelestialBodyType::Planet => ('●', colors::INFO),
+        CelestialBodyType::Moon => ('○', colors::NORMAL),
+        CelestialBodyType::Asteroid => ('∙', colors::HULL),
+        CelestialBodyType::BlackHole => ('✖', colors::DANGER),
+        CelestialBodyType::JumpGate => ('◆', colors::ENERGY),
+    }
+}
+
+// Bodies first, then resource fields; the inspect cursor walks this order
+fn map_entries(system: &StarSystem) -> Vec<MapEntry> {
+    let bodies = system.celestial_bodies.iter().map(|body| {
+        let (glyph, color) = body_glyph(&body.body_type);
+        MapEntry {
+            glyph,
+            color,
+            x: body.position_x,
+            y: body.position_y,
+            details: format!("{} ({:?}, size {:.1}) at ({:.0}, {:.0})",
+                body.name, body.body_type, body.size, body.position_x, body.position_y),
+        }
+    });
+
+    let fields = system.resource_fields.iter().map(|field| MapEntry {
+        glyph: '※',
+        color: colors::SUCCESS,
+        x: field.position_x,
+        y: field.position_y,
+        details: format!("{} (size {}, danger {}) at ({:.0}, {:.0})",
+            field.field_type.to_string(), field.size, field.field_type.danger_level(),
+            field.position_x, field.position_y),
+    });
+
+    bodies.chain(fields).collect()
+}
+
+// Number of things the inspect cursor can land on in `system`
+pub fn inspectable_count(system: &StarSystem) -> usize {
+    system.celestial_bodies.len() + system.resource_fields.len()
+}
+
+// Plot the current system's bodies and resource fields on a character grid,
+// with a details line for the one under the inspect cursor
+pub fn draw_system_map<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let system = &game.player.current_system;
+    let block = Block::default()
+        .title(Span::styled(format!(" {} SYSTEM MAP ", system.name.to_uppercase()), Style::default().fg(colors::PRIMARY)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::SECONDARY));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let entries = map_entries(system);
+    if entries.is_empty() || inner.width < 3 || inner.height < 2 {
+        f.render_widget(Paragraph::new(Span::styled("Nothing charted", Style::default().fg(colors::DIM))), inner);
+        return;
+    }
+
+    // Last row holds the details line
+    let width = inner.width as usize;
+    let height = (inner.height - 1) as usize;
+
+    let min_x = entries.iter().map(|e| e.x).fold(f32::MAX, f32::min);
+    let max_x = entries.iter().map(|e| e.x).fold(f32::MIN, f32::max);
+    let min_y = entries.iter().map(|e| e.y).fold(f32::MAX, f32::min);
+    let max_y = entries.iter().map(|e| e.y).fold(f32::MIN, f32::max);
+    let scale = |value: f32, min: f32, max: f32, cells: usize| -> usize {
+        if max - min < f32::EPSILON {
+            cells / 2
+        } else {
+            (((value - min) / (max - min)) * (cells - 1) as f32).round() as usize
+        }
+    };
+
+    let selected = game.inspected_body % entries.len();
+    let mut grid: Vec<Vec<Span>> = (0..height)
+        .map(|_| (0..width).map(|_| Span::raw(" ")).collect())
+        .collect();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let col = scale(entry.x, min_x, max_x, width);
+        let row = scale(entry.y, min_y, max_y, height);
+        let mut style = Style::default().fg(entry.color);
+        if i == selected {
+            style = style.bg(colors::DIM).add_modifier(Modifier::BOLD);
+        }
+        grid[row][col] = Span::styled(entry.glyph.to_string(), style);
+    }
+
+    let mut text: Vec<Spans> = grid.into_iter().map(Spans::from).collect();
+    text.push(Spans::from(vec![
+        Span::styled("◀▶ ", Style::default().fg(colors::PRIMARY)),
+        Span::styled(entries[selected].details.clone(), Style::default().fg(colors::NORMAL)),
+    ]));
+
+    f.render_widget(Paragraph::new(text), inner);
+}
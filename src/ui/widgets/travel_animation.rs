@@ -0,0 +1,33 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::game::Game;
+use crate::ui::{ascii_art, colors};
+
+// Ship crossing a star field while a jump gets under way
+pub fn draw_travel_animation<B: Backend>(f: &mut Frame<B>, game: &Game, area: Rect) {
+    let (progress, destination) = match game.travel_animation_progress() {
+        Some(state) => state,
+        None => return,
+    };
+
+    let block = Block::default()
+        .title(Span::styled(format!(" JUMPING TO {} ", destination.to_uppercase()), Style::default().fg(colors::PRIMARY)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::SECONDARY));
+    let inner = block.inner(area);
+
+    let text: Vec<Spans> = ascii_art::get_travel_frame(inner.width, inner.height, game.animation_frame, progress)
+        .into_iter()
+        .map(|line| Spans::from(Span::styled(line, Style::default().fg(colors::INFO))))
+        .collect();
+
+    let paragraph = Paragraph::new(text).block(block);
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,30 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::ui::colors;
+
+// The tutorial's next instruction, boxed along the bottom edge of `area`
+// so it sits over the screen it refers to without hiding its controls
+pub fn draw_tutorial_hint<B: Backend>(f: &mut Frame<B>, hint: &str, area: Rect) {
+    let height = 3.min(area.height);
+    let popup = Rect::new(area.x, area.bottom() - height, area.width, height);
+
+    let block = Block::default()
+        .title(Span::styled(" TUTORIAL ", Style::default().fg(colors::ENERGY)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::ENERGY));
+
+    let text = Spans::from(vec![
+        Span::styled(hint, Style::default().fg(colors::NORMAL)),
+        Span::styled("  [Ctrl+T] Skip tutorial", Style::default().fg(colors::DIM)),
+    ]);
+
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(text).block(block), popup);
+}
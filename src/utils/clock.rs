@@ -0,0 +1,38 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Serialize, Deserialize};
+
+/// A point in simulated game time, handed to market and economy code so
+/// timestamps never depend on the wall clock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GameClock {
+    now: u64, // Game seconds since the game epoch
+}
+
+#[allow(dead_code)]
+impl GameClock {
+    /// Clock fixed at the given game time (in seconds)
+    pub fn at(seconds: u64) -> Self {
+        GameClock { now: seconds }
+    }
+
+    /// Shim for top-level entry points that have no `TimeSystem` to draw
+    /// from (e.g. the standalone server loop). Game logic should never
+    /// call this directly.
+    pub fn wall_clock() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::from_secs(0))
+            .as_secs();
+        GameClock { now }
+    }
+
+    /// Current game time in seconds
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Timestamp a number of game hours after this clock's time
+    pub fn hours_from_now(&self, hours: u64) -> u64 {
+        self.now + hours * 3600
+    }
+}
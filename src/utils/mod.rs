@@ -1,2 +1,3 @@
 pub mod serde;
-pub mod save_load;
\ No newline at end of file
+pub mod save_load;
+pub mod clock;
\ No newline at end of file
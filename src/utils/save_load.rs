@@ -1,41 +1,445 @@
-use std::fs::File;
-use std::io::{self, Read, Write};
+use std::fs;
+use std::io::{self, Read};
 use std::error::Error;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Serialize, Deserialize};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use crate::models::universe::Universe;
+use crate::systems::economy::EconomySystem;
+use crate::utils::clock::GameClock;
 
 const SAVE_FILE: &str = "savegame.json";
+const ECONOMY_FILE: &str = "economy.json";
+const SAVE_DIR: &str = "saves";
+// Extension of the metadata file written beside each save
+const META_EXTENSION: &str = "meta";
+// First bytes of every gzip stream; files without them are plain JSON
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+// Bumped whenever the save format changes, with a migration added below
+pub const SAVE_VERSION: u32 = 1;
+
+// Upgrades a save body by one version; entry i takes version i to i + 1
+type Migration = fn(&mut serde_json::Value);
+const MIGRATIONS: [Migration; SAVE_VERSION as usize] = [
+    // Version 0 saves predate metadata; their bodies are already current
+    |_| {},
+];
+
+// What a game reports about itself for the save's metadata
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SaveSummary {
+    pub character_name: String,
+    pub credits: u32,
+    pub play_time_secs: u64,
+}
+
+pub trait Summarize {
+    fn save_summary(&self) -> SaveSummary;
+}
+
+// Whether a save's metadata still describes its body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetaSync {
+    #[default]
+    InSync,
+    // The body was written or changed without its metadata
+    Stale,
+    // No readable metadata; only the slot name is known
+    Missing,
+}
+
+// Small header kept beside each save so a load menu can preview it
+// without deserializing the whole game
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SaveMeta {
+    #[serde(skip)]
+    pub slot: String,
+    #[serde(flatten)]
+    pub summary: SaveSummary,
+    // Seconds since the Unix epoch
+    pub saved_at: u64,
+    pub version: u32,
+    // Size and checksum of the body this header was written with
+    pub body_len: u64,
+    pub body_checksum: u64,
+    #[serde(skip)]
+    pub sync: MetaSync,
+}
+
+pub fn save_game<T: Serialize + Summarize>(game: &T) -> Result<(), Box<dyn Error>> {
+    write_save(game, Path::new(SAVE_FILE))
+}
+
+// Whether a saved game is on disk
+pub fn save_exists() -> bool {
+    Path::new(SAVE_FILE).exists()
+}
+
+pub fn load_game<T>() -> Result<T, Box<dyn Error>> 
+where
+    T: for<'de> Deserialize<'de>,
+{
+    read_save(Path::new(SAVE_FILE))
+}
+
+// Named save slots live in their own directory beside the default save
+fn slot_path(slot: &str) -> PathBuf {
+    Path::new(SAVE_DIR).join(format!("{}.json", slot))
+}
+
+pub fn save_game_to<T: Serialize + Summarize>(game: &T, slot: &str) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(SAVE_DIR)?;
+    write_save(game, &slot_path(slot))
+}
+
+pub fn load_game_from<T>(slot: &str) -> Result<T, Box<dyn Error>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    read_save(&slot_path(slot))
+}
 
-pub fn save_game<T: Serialize>(game: &T) -> Result<(), Box<dyn Error>> {
-    // Serialize game state to JSON
-    let serialized = serde_json::to_string_pretty(game)?;
+pub fn slot_exists(slot: &str) -> bool {
+    slot_path(slot).exists()
+}
+
+// Names of every save slot, sorted
+pub fn list_saves() -> Vec<String> {
+    let mut slots: Vec<String> = fs::read_dir(SAVE_DIR)
+        .map(|entries| {
+            entries.filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+                .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    slots.sort();
+    slots
+}
+
+// Metadata of every save slot, in slot order. Slots whose metadata is
+// missing or no longer matches the body are still listed, marked as such.
+pub fn list_save_metadata() -> Vec<SaveMeta> {
+    list_saves().into_iter()
+        .map(|slot| {
+            let mut meta = read_meta(&slot_path(&slot));
+            meta.slot = slot;
+            meta
+        })
+        .collect()
+}
+
+// Metadata of the default save, if there is one
+pub fn save_metadata() -> Option<SaveMeta> {
+    let path = Path::new(SAVE_FILE);
+    if !path.exists() {
+        return None;
+    }
+    Some(read_meta(path))
+}
+
+// A save and its metadata bundled into one file to back up or share
+#[derive(Serialize, Deserialize)]
+struct SaveExport {
+    meta: SaveMeta,
+    game: serde_json::Value,
+}
+
+// Copy a save slot into a single shareable file at `path`
+pub fn export_save(slot: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+    let save_path = slot_path(slot);
+    let content = read_json(&save_path)
+        .map_err(|e| format!("Can't read save slot '{}': {}", slot, e))?;
+    
+    // Without metadata the save's age is unknown, so it is treated as the
+    // oldest version and migrated in full on import
+    let mut meta = read_meta(&save_path);
+    if meta.sync == MetaSync::Missing {
+        meta.version = 0;
+    }
+    let export = SaveExport { meta, game: serde_json::from_slice(&content)? };
+    fs::write(path, encode(&export)?)?;
+    Ok(())
+}
+
+// Bring an exported save in as a new slot, upgrading it from the version
+// it was made with. The game is fully loaded before anything is written.
+pub fn import_save<T>(path: &Path, slot: &str) -> Result<(), Box<dyn Error>>
+where
+    T: Serialize + Summarize + for<'de> Deserialize<'de>,
+{
+    if slot_exists(slot) {
+        return Err(format!("Save slot '{}' already exists", slot).into());
+    }
+    let content = read_json(path)?;
+    let SaveExport { meta, mut game } = serde_json::from_slice(&content)
+        .map_err(|e| format!("Not an exported save: {}", e))?;
+    
+    migrate(&mut game, meta.version)?;
+    let game: T = serde_json::from_value(game)
+        .map_err(|e| format!("Exported save is damaged: {}", e))?;
+    save_game_to(&game, slot)
+}
+
+// Upgrade a save body made with `version` to SAVE_VERSION
+fn migrate(body: &mut serde_json::Value, version: u32) -> Result<(), String> {
+    if version > SAVE_VERSION {
+        return Err(format!(
+            "Save was made by a newer version of the game (save format {}, this game reads up to {})",
+            version, SAVE_VERSION
+        ));
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(body);
+    }
+    Ok(())
+}
+
+// A slot name made from `label` (e.g. a character name) that no save uses yet
+pub fn unused_slot_name(label: &str) -> String {
+    let base: String = label.trim().chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let base = if base.is_empty() { "save".to_string() } else { base };
+    
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while slot_exists(&name) {
+        name = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+fn meta_path(save_path: &Path) -> PathBuf {
+    save_path.with_extension(META_EXTENSION)
+}
+
+// FNV-1a; stable across builds, unlike the std hashers
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// Saves are gzipped unless SPACE_TRADER_PLAIN_SAVES is set, which keeps
+// them as readable JSON for debugging
+fn compression_enabled() -> bool {
+    std::env::var("SPACE_TRADER_PLAIN_SAVES").is_err()
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !compression_enabled() {
+        return Ok(serde_json::to_vec_pretty(value)?);
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, value)?;
+    Ok(encoder.finish()?)
+}
+
+// JSON in `bytes`, inflating it first if it was written compressed
+fn decode(bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(bytes);
+    }
+    let mut json = Vec::new();
+    GzDecoder::new(&bytes[..]).read_to_end(&mut json)?;
+    Ok(json)
+}
+
+fn read_json(path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
+    decode(fs::read(path)?)
+}
+
+fn write_save<T: Serialize + Summarize>(game: &T, path: &Path) -> Result<(), Box<dyn Error>> {
+    // Serialize game state, compressed unless turned off
+    let serialized = encode(game)?;
     
     // Write to file
-    let mut file = File::create(SAVE_FILE)?;
-    file.write_all(serialized.as_bytes())?;
+    fs::write(path, &serialized)?;
+    
+    // The body goes first, so a crash in between leaves metadata that no
+    // longer matches rather than metadata for a body that was never written
+    let meta = SaveMeta {
+        summary: game.save_summary(),
+        saved_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        version: SAVE_VERSION,
+        body_len: serialized.len() as u64,
+        body_checksum: checksum(&serialized),
+        ..Default::default()
+    };
+    fs::write(meta_path(path), serde_json::to_string_pretty(&meta)?)?;
     
     Ok(())
 }
 
-pub fn load_game<T>() -> Result<T, Box<dyn Error>> 
+// The metadata beside the save at `path`, checked against the body
+fn read_meta(path: &Path) -> SaveMeta {
+    let meta = fs::read_to_string(meta_path(path)).ok()
+        .and_then(|content| serde_json::from_str::<SaveMeta>(&content).ok());
+    let mut meta = match meta {
+        Some(meta) => meta,
+        None => return SaveMeta { sync: MetaSync::Missing, ..Default::default() },
+    };
+    
+    // Checking the body means reading it, but not deserializing it
+    let matches = fs::read(path).map_or(false, |body| {
+        body.len() as u64 == meta.body_len && checksum(&body) == meta.body_checksum
+    });
+    if !matches {
+        meta.sync = MetaSync::Stale;
+    }
+    meta
+}
+
+fn read_save<T>(path: &Path) -> Result<T, Box<dyn Error>>
 where
     T: for<'de> Deserialize<'de>,
 {
     // Check if save file exists
-    if !Path::new(SAVE_FILE).exists() {
+    if !path.exists() {
         return Err(Box::new(io::Error::new(
             io::ErrorKind::NotFound,
             "Save file not found"
         )));
     }
     
-    // Read file content
-    let mut file = File::open(SAVE_FILE)?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
+    // Read file content; saves from before compression are plain JSON
+    let content = read_json(path)?;
     
     // Deserialize from JSON
-    let deserialized = serde_json::from_str(&content)?;
+    let deserialized = serde_json::from_slice(&content)?;
     
     Ok(deserialized)
-}
\ No newline at end of file
+}
+
+// Economy state is kept apart from the game save so the server's markets,
+// orders, listings and price trends survive a restart on their own
+pub fn save_economy(economy: &EconomySystem) -> Result<(), Box<dyn Error>> {
+    save_economy_to(economy, Path::new(ECONOMY_FILE))
+}
+
+pub fn save_economy_to(economy: &EconomySystem, path: &Path) -> Result<(), Box<dyn Error>> {
+    fs::write(path, encode(economy)?)?;
+    
+    Ok(())
+}
+
+// Load the saved economy, or start fresh markets from the universe when
+// there's no economy file yet
+pub fn load_economy(universe: &Universe) -> Result<EconomySystem, Box<dyn Error>> {
+    load_economy_from(universe, Path::new(ECONOMY_FILE))
+}
+
+pub fn load_economy_from(universe: &Universe, path: &Path) -> Result<EconomySystem, Box<dyn Error>> {
+    if !path.exists() {
+        let mut economy = EconomySystem::from_universe(universe);
+        economy.apply_market_definition(universe.market_definition(), &GameClock::default());
+        return Ok(economy);
+    }
+    
+    let content = read_json(path)?;
+    let mut economy: EconomySystem = serde_json::from_slice(&content)?;
+    economy.restore_rng();
+    
+    Ok(economy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::player_market::ListingVisibility;
+    use crate::models::item::{Item, ItemType};
+
+    #[test]
+    fn test_economy_round_trips_through_file() {
+        let universe = Universe::new();
+        let mut economy = EconomySystem::from_universe(&universe);
+        economy.trade_route_tariffs.insert(("a".to_string(), "b".to_string()), 0.15);
+        economy.simulation_step = 7;
+        let system_id = economy.system_markets.keys().min().unwrap().clone();
+        let item_name = economy.system_markets[&system_id].items.keys().next().unwrap().clone();
+        economy.system_markets.get_mut(&system_id).unwrap().items.get_mut(&item_name).unwrap().current_price = 4321;
+        economy.player_market.create_listing(
+            "seller", "Seller", Item::new("Relic", 500, 1, ItemType::Product), 2, 900,
+            &system_id, &system_id, None, 0, ListingVisibility::Public, false, "", Vec::new(),
+            &GameClock::default(),
+        );
+
+        let path = std::env::temp_dir().join(format!("economy_test_{}.json", std::process::id()));
+        save_economy_to(&economy, &path).unwrap();
+        let loaded = load_economy_from(&universe, &path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.system_markets[&system_id].items[&item_name].current_price, 4321);
+        assert_eq!(loaded.trade_route_tariffs.get(&("a".to_string(), "b".to_string())), Some(&0.15));
+        assert_eq!(loaded.player_market.listings.len(), 1);
+        assert_eq!(loaded.simulation_step, 7);
+
+        // No file yet: fresh markets from the universe
+        let missing = std::env::temp_dir().join("economy_test_missing.json");
+        let fresh = load_economy_from(&universe, &missing).unwrap();
+        assert!(fresh.system_markets.contains_key(&system_id));
+        assert!(fresh.player_market.listings.is_empty());
+    }
+
+    #[derive(Serialize)]
+    struct Dummy {
+        credits: u32,
+    }
+
+    impl Summarize for Dummy {
+        fn save_summary(&self) -> SaveSummary {
+            SaveSummary { character_name: "Dummy".to_string(), credits: self.credits, play_time_secs: 90 }
+        }
+    }
+
+    #[test]
+    fn test_metadata_notices_body_written_without_it() {
+        let path = std::env::temp_dir().join(format!("save_meta_test_{}.json", std::process::id()));
+        write_save(&Dummy { credits: 250 }, &path).unwrap();
+        let meta = read_meta(&path);
+        assert_eq!(meta.sync, MetaSync::InSync);
+        assert_eq!(meta.summary.credits, 250);
+        assert_eq!(meta.version, SAVE_VERSION);
+
+        // The body changes but the metadata doesn't
+        fs::write(&path, serde_json::to_string(&Dummy { credits: 999 }).unwrap()).unwrap();
+        assert_eq!(read_meta(&path).sync, MetaSync::Stale);
+
+        let _ = fs::remove_file(meta_path(&path));
+        assert_eq!(read_meta(&path).sync, MetaSync::Missing);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_migrate_rejects_saves_from_newer_versions() {
+        let mut body = serde_json::json!({ "credits": 5 });
+        assert!(migrate(&mut body, 0).is_ok());
+        assert!(migrate(&mut body, SAVE_VERSION).is_ok());
+        let err = migrate(&mut body, SAVE_VERSION + 1).unwrap_err();
+        assert!(err.contains("newer version"));
+    }
+
+    #[test]
+    fn test_compressed_and_plain_saves_both_load() {
+        let path = std::env::temp_dir().join(format!("save_gzip_test_{}.json", std::process::id()));
+        write_save(&Dummy { credits: 42 }, &path).unwrap();
+        if compression_enabled() {
+            assert!(fs::read(&path).unwrap().starts_with(&GZIP_MAGIC));
+        }
+        let loaded: serde_json::Value = read_save(&path).unwrap();
+        assert_eq!(loaded["credits"], 42);
+
+        // A save written before compression existed
+        fs::write(&path, "{\n  \"credits\": 7\n}").unwrap();
+        let loaded: serde_json::Value = read_save(&path).unwrap();
+        assert_eq!(loaded["credits"], 7);
+
+        let _ = fs::remove_file(meta_path(&path));
+        let _ = fs::remove_file(&path);
+    }
+}
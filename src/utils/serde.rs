@@ -93,4 +93,91 @@ pub mod option_instant_serde {
         let opt_serializable: Option<SerializableInstant> = Option::deserialize(deserializer)?;
         Ok(opt_serializable.map(|s| s.into()))
     }
-}
\ No newline at end of file
+}
+
+/// Module for serializing/deserializing maps whose keys JSON can't use as
+/// object keys (e.g. enums with data), as a list of entries
+pub mod entry_list_map_serde {
+    use super::*;
+    use serde::{Serializer, Deserializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<S, K, V>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        K: Serialize,
+        V: Serialize,
+    {
+        let entries: Vec<(&K, &V)> = map.iter().collect();
+        entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, K, V>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        D: Deserializer<'de>,
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+    {
+        let entries: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+/// Module for serializing/deserializing counted stacks (e.g. inventory
+/// items) as a list of entries. Entries whose keys compare equal are
+/// merged by adding their counts.
+pub mod stack_list_serde {
+    use super::*;
+    use serde::{Serializer, Deserializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<S, K>(map: &HashMap<K, u32>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        K: Serialize,
+    {
+        super::entry_list_map_serde::serialize(map, serializer)
+    }
+
+    pub fn deserialize<'de, D, K>(deserializer: D) -> Result<HashMap<K, u32>, D::Error>
+    where
+        D: Deserializer<'de>,
+        K: Deserialize<'de> + Eq + Hash,
+    {
+        let entries: Vec<(K, u32)> = Vec::deserialize(deserializer)?;
+        let mut map = HashMap::new();
+        for (key, count) in entries {
+            *map.entry(key).or_insert(0) += count;
+        }
+        Ok(map)
+    }
+}
+
+/// Module for serializing/deserializing maps keyed by a pair of strings,
+/// which JSON can't use as object keys, as a list of entries
+pub mod pair_key_map_serde {
+    use super::*;
+    use serde::{Serializer, Deserializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S, V>(map: &HashMap<(String, String), V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        V: Serialize,
+    {
+        let mut entries: Vec<(&(String, String), &V)> = map.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, V>(deserializer: D) -> Result<HashMap<(String, String), V>, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Deserialize<'de>,
+    {
+        let entries: Vec<((String, String), V)> = Vec::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}